@@ -0,0 +1,13 @@
+use std::env;
+
+// generates the gRPC service from `proto/catalog.proto`. The protoc that is shipped with
+// `protoc-bin-vendored` is used unless `PROTOC` points at another one
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if env::var_os("PROTOC").is_none() {
+        env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    }
+
+    tonic_prost_build::compile_protos("proto/catalog.proto")?;
+
+    Ok(())
+}
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS audit_logs_entity_idx;
+DROP TABLE audit_logs;
@@ -0,0 +1,36 @@
+CREATE TABLE IF NOT EXISTS product_revisions (
+    id SERIAL PRIMARY KEY,
+    product_id INTEGER NOT NULL,
+    revision INTEGER NOT NULL,
+    name VARCHAR NOT NULL,
+    cost DOUBLE PRECISION NOT NULL,
+    active BOOLEAN NOT NULL,
+    variants JSONB NOT NULL DEFAULT '[]',
+    actor VARCHAR NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE,
+    UNIQUE (product_id, revision)
+);
+
+-- every existing product starts out at its first revision
+INSERT INTO product_revisions (product_id, revision, name, cost, active, variants, actor)
+SELECT
+    products.id,
+    1,
+    products.name,
+    products.cost,
+    products.active,
+    COALESCE(
+        (
+            SELECT jsonb_agg(
+                jsonb_build_object('variant_id', variants.id, 'name', variants.name, 'value', product_variants.value)
+                ORDER BY product_variants.id
+            )
+            FROM product_variants
+            INNER JOIN variants ON variants.id = product_variants.variant_id
+            WHERE product_variants.product_id = products.id
+        ),
+        '[]'::jsonb
+    ),
+    'system'
+FROM products;
@@ -0,0 +1 @@
+ALTER TABLE products ADD COLUMN IF NOT EXISTS external_key VARCHAR UNIQUE;
@@ -0,0 +1,13 @@
+ALTER TABLE products ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+-- existing products were last changed when their latest revision was recorded
+UPDATE products
+SET updated_at = latest_revisions.created_at
+FROM (
+    SELECT product_id, MAX(created_at) AS created_at
+    FROM product_revisions
+    GROUP BY product_id
+) AS latest_revisions
+WHERE latest_revisions.product_id = products.id;
+
+CREATE INDEX IF NOT EXISTS products_updated_at_idx ON products (updated_at);
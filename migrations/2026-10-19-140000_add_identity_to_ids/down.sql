@@ -0,0 +1,3 @@
+ALTER TABLE product_variants ALTER COLUMN id DROP IDENTITY IF EXISTS;
+ALTER TABLE variants ALTER COLUMN id DROP IDENTITY IF EXISTS;
+ALTER TABLE products ALTER COLUMN id DROP IDENTITY IF EXISTS;
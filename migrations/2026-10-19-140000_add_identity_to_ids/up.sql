@@ -0,0 +1,10 @@
+-- ids of the original tables were never generated by the database, so inserts without an id fail.
+-- The identities start after the highest id already in use
+ALTER TABLE products ALTER COLUMN id ADD GENERATED BY DEFAULT AS IDENTITY;
+SELECT setval(pg_get_serial_sequence('products', 'id'), COALESCE(MAX(id), 0) + 1, false) FROM products;
+
+ALTER TABLE variants ALTER COLUMN id ADD GENERATED BY DEFAULT AS IDENTITY;
+SELECT setval(pg_get_serial_sequence('variants', 'id'), COALESCE(MAX(id), 0) + 1, false) FROM variants;
+
+ALTER TABLE product_variants ALTER COLUMN id ADD GENERATED BY DEFAULT AS IDENTITY;
+SELECT setval(pg_get_serial_sequence('product_variants', 'id'), COALESCE(MAX(id), 0) + 1, false) FROM product_variants;
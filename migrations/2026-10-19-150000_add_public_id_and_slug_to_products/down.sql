@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS product_slug_redirects;
+DROP INDEX IF EXISTS products_slug_idx;
+DROP INDEX IF EXISTS products_public_id_idx;
+ALTER TABLE products DROP COLUMN IF EXISTS slug;
+ALTER TABLE products DROP COLUMN IF EXISTS public_id;
@@ -0,0 +1,65 @@
+-- products get a public identifier and a slug so that URLs do not expose their sequential ids.
+-- Existing products get a UUIDv7 built from the time they were last updated, and a slug made from
+-- their name the same way the application makes them, with a `-2` style suffix where names collide
+ALTER TABLE products ADD COLUMN IF NOT EXISTS public_id UUID;
+ALTER TABLE products ADD COLUMN IF NOT EXISTS slug VARCHAR;
+
+UPDATE products
+SET public_id = encode(
+    set_bit(
+        set_bit(
+            overlay(
+                uuid_send(gen_random_uuid())
+                PLACING substring(int8send(floor(extract(epoch FROM updated_at) * 1000)::BIGINT) FROM 3)
+                FROM 1 FOR 6
+            ),
+            52, 1
+        ),
+        53, 1
+    ),
+    'hex'
+)::UUID
+WHERE public_id IS NULL;
+
+DO $$
+DECLARE
+    product RECORD;
+    base VARCHAR;
+    candidate VARCHAR;
+    suffix INTEGER;
+BEGIN
+    FOR product IN SELECT id, name FROM products WHERE slug IS NULL ORDER BY id LOOP
+        base := trim(BOTH '-' FROM left(trim(BOTH '-' FROM regexp_replace(lower(product.name), '[^a-z0-9]+', '-', 'g')), 80));
+
+        IF base = '' THEN
+            base := 'product';
+        ELSIF base ~ '^[0-9]+$'
+            OR base ~ '^[0-9a-f]{32}$'
+            OR base ~ '^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$' THEN
+            base := 'product-' || base;
+        END IF;
+
+        candidate := base;
+        suffix := 1;
+        WHILE EXISTS (SELECT 1 FROM products WHERE slug = candidate) LOOP
+            suffix := suffix + 1;
+            candidate := base || '-' || suffix;
+        END LOOP;
+
+        UPDATE products SET slug = candidate WHERE id = product.id;
+    END LOOP;
+END $$;
+
+ALTER TABLE products ALTER COLUMN public_id SET NOT NULL;
+ALTER TABLE products ALTER COLUMN slug SET NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS products_public_id_idx ON products (public_id);
+CREATE UNIQUE INDEX IF NOT EXISTS products_slug_idx ON products (slug);
+
+-- slugs a product went by before it was renamed, so that links to them keep working
+CREATE TABLE IF NOT EXISTS product_slug_redirects (
+    slug VARCHAR PRIMARY KEY,
+    product_id INTEGER NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS product_slug_redirects_product_id_idx ON product_slug_redirects (product_id);
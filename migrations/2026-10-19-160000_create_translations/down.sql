@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS variant_value_translations;
+DROP TABLE IF EXISTS variant_translations;
+DROP TABLE IF EXISTS product_translations;
+ALTER TABLE products DROP COLUMN IF EXISTS description;
@@ -0,0 +1,28 @@
+-- the fields on products and variants hold the text in the default locale, translations into other
+-- locales are kept per entity and locale
+ALTER TABLE products ADD COLUMN IF NOT EXISTS description TEXT;
+
+CREATE TABLE IF NOT EXISTS product_translations (
+    product_id INTEGER NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    locale VARCHAR NOT NULL,
+    name VARCHAR NOT NULL,
+    description TEXT,
+    PRIMARY KEY (product_id, locale)
+);
+
+CREATE TABLE IF NOT EXISTS variant_translations (
+    variant_id INTEGER NOT NULL REFERENCES variants (id) ON DELETE CASCADE,
+    locale VARCHAR NOT NULL,
+    display_name VARCHAR NOT NULL,
+    PRIMARY KEY (variant_id, locale)
+);
+
+-- values are translated once per variant rather than per product, so `red` reads `rot` on every
+-- product that has it
+CREATE TABLE IF NOT EXISTS variant_value_translations (
+    variant_id INTEGER NOT NULL REFERENCES variants (id) ON DELETE CASCADE,
+    value VARCHAR NOT NULL,
+    locale VARCHAR NOT NULL,
+    display_value VARCHAR NOT NULL,
+    PRIMARY KEY (variant_id, value, locale)
+);
@@ -0,0 +1,10 @@
+-- structured key/value pairs shown next to a product's description, e.g. `Weight: 1.2 kg`. They are
+-- kept in the order they were given in
+CREATE TABLE IF NOT EXISTS product_specifications (
+    product_id INTEGER NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    name VARCHAR NOT NULL,
+    value VARCHAR NOT NULL,
+    PRIMARY KEY (product_id, position),
+    UNIQUE (product_id, name)
+);
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS products_attributes_idx;
+DROP INDEX IF EXISTS products_product_type_id_idx;
+ALTER TABLE products DROP COLUMN IF EXISTS attributes;
+ALTER TABLE products DROP COLUMN IF EXISTS product_type_id;
+DROP TABLE IF EXISTS product_types;
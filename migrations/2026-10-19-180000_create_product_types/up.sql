@@ -0,0 +1,15 @@
+-- a product type describes the attributes its products carry beyond their variants, e.g. `material`
+-- for shoes or `wattage` for lamps. The schema is a JSON array of attribute definitions
+CREATE TABLE IF NOT EXISTS product_types (
+    id INTEGER GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    name VARCHAR NOT NULL UNIQUE,
+    attribute_schema JSONB NOT NULL DEFAULT '[]',
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+ALTER TABLE products ADD COLUMN IF NOT EXISTS product_type_id INTEGER REFERENCES product_types (id);
+ALTER TABLE products ADD COLUMN IF NOT EXISTS attributes JSONB NOT NULL DEFAULT '{}';
+
+CREATE INDEX IF NOT EXISTS products_product_type_id_idx ON products (product_type_id);
+-- serves containment filters such as `attributes @> '{"material": "leather"}'`
+CREATE INDEX IF NOT EXISTS products_attributes_idx ON products USING GIN (attributes jsonb_path_ops);
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS bundle_components;
+DROP TABLE IF EXISTS product_bundles;
+ALTER TABLE products DROP COLUMN IF EXISTS stock_quantity;
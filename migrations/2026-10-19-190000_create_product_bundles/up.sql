@@ -0,0 +1,21 @@
+-- the number of units on hand, NULL for products whose stock is not tracked
+ALTER TABLE products ADD COLUMN IF NOT EXISTS stock_quantity INTEGER CHECK (stock_quantity >= 0);
+
+-- a bundle is a product made of other products, e.g. a starter kit. Its price is either the
+-- bundle product's own cost or derived from its components with a discount
+CREATE TABLE IF NOT EXISTS product_bundles (
+    product_id INTEGER PRIMARY KEY REFERENCES products (id) ON DELETE CASCADE,
+    pricing VARCHAR NOT NULL CHECK (pricing IN ('fixed', 'derived')),
+    discount_percent DOUBLE PRECISION CHECK (discount_percent BETWEEN 0 AND 100),
+    on_component_deactivated VARCHAR NOT NULL CHECK (on_component_deactivated IN ('deactivate', 'warn'))
+);
+
+-- components cannot be deleted while a bundle still uses them
+CREATE TABLE IF NOT EXISTS bundle_components (
+    bundle_id INTEGER NOT NULL REFERENCES product_bundles (product_id) ON DELETE CASCADE,
+    component_id INTEGER NOT NULL REFERENCES products (id),
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    PRIMARY KEY (bundle_id, component_id)
+);
+
+CREATE INDEX IF NOT EXISTS bundle_components_component_id_idx ON bundle_components (component_id);
@@ -0,0 +1,13 @@
+-- typed links from a product to others, e.g. the accessories shown next to it or the product that
+-- replaces it. Within a type, links are shown in order of their position
+CREATE TABLE IF NOT EXISTS product_relations (
+    product_id INTEGER NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    relation_type VARCHAR NOT NULL
+        CHECK (relation_type IN ('related', 'cross_sell', 'up_sell', 'accessory', 'replacement')),
+    related_product_id INTEGER NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (product_id, relation_type, related_product_id),
+    CHECK (product_id <> related_product_id)
+);
+
+CREATE INDEX IF NOT EXISTS product_relations_related_product_id_idx ON product_relations (related_product_id);
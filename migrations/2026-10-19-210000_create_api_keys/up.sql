@@ -0,0 +1,11 @@
+-- keys that API clients authenticate with. Only the SHA-256 hash of a key is stored, the key itself
+-- is shown once when it is created
+CREATE TABLE IF NOT EXISTS api_keys (
+    id INTEGER GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    name VARCHAR NOT NULL UNIQUE,
+    key_hash VARCHAR NOT NULL UNIQUE,
+    scopes TEXT[] NOT NULL DEFAULT '{}',
+    expires_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,14 @@
+-- named sets of permissions. A client that has the name of a role among its scopes is granted the
+-- permissions of the role. Permissions can also be granted directly as scopes, `admin` included
+CREATE TABLE IF NOT EXISTS roles (
+    id INTEGER GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    name VARCHAR NOT NULL UNIQUE,
+    permissions TEXT[] NOT NULL DEFAULT '{}',
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+INSERT INTO roles (name, permissions)
+VALUES ('merchandiser', '{catalog:read,catalog:write,inventory:adjust}'),
+       ('pricing', '{catalog:read,pricing:write}'),
+       ('partner', '{catalog:read}')
+ON CONFLICT (name) DO NOTHING;
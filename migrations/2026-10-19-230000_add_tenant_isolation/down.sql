@@ -0,0 +1,33 @@
+ALTER TABLE api_keys DROP COLUMN IF EXISTS tenant_id;
+
+DROP INDEX IF EXISTS variants_tenant_name_idx;
+ALTER TABLE idempotency_keys DROP CONSTRAINT IF EXISTS idempotency_keys_pkey;
+ALTER TABLE idempotency_keys ADD PRIMARY KEY (key);
+DROP INDEX IF EXISTS product_types_tenant_name_idx;
+ALTER TABLE product_types ADD CONSTRAINT product_types_name_key UNIQUE (name);
+ALTER TABLE product_slug_redirects DROP CONSTRAINT IF EXISTS product_slug_redirects_pkey;
+ALTER TABLE product_slug_redirects ADD PRIMARY KEY (slug);
+DROP INDEX IF EXISTS products_tenant_slug_idx;
+CREATE UNIQUE INDEX IF NOT EXISTS products_slug_idx ON products (slug);
+DROP INDEX IF EXISTS products_tenant_external_key_idx;
+ALTER TABLE products ADD CONSTRAINT products_external_key_key UNIQUE (external_key);
+
+DO $$
+DECLARE
+    scoped_table VARCHAR;
+BEGIN
+    FOREACH scoped_table IN ARRAY ARRAY[
+        'products', 'variants', 'product_variants', 'product_images', 'product_revisions',
+        'product_slug_redirects', 'product_translations', 'variant_translations',
+        'variant_value_translations', 'product_specifications', 'product_types', 'product_bundles',
+        'bundle_components', 'product_relations', 'idempotency_keys', 'audit_logs'
+    ] LOOP
+        EXECUTE format('DROP POLICY IF EXISTS tenant_isolation ON %I', scoped_table);
+        EXECUTE format('ALTER TABLE %I NO FORCE ROW LEVEL SECURITY', scoped_table);
+        EXECUTE format('ALTER TABLE %I DISABLE ROW LEVEL SECURITY', scoped_table);
+        EXECUTE format('ALTER TABLE %I DROP COLUMN IF EXISTS tenant_id', scoped_table);
+    END LOOP;
+END $$;
+
+DROP FUNCTION IF EXISTS enable_tenant_isolation(REGCLASS);
+DROP FUNCTION IF EXISTS current_tenant_id();
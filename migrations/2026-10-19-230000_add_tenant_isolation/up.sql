@@ -0,0 +1,59 @@
+-- the tenant a connection works for, as set in `app.tenant_id` by the application for each request.
+-- Connections that do not set one work for the default tenant
+CREATE OR REPLACE FUNCTION current_tenant_id() RETURNS VARCHAR AS $$
+    SELECT coalesce(nullif(current_setting('app.tenant_id', true), ''), 'default')
+$$ LANGUAGE sql STABLE;
+
+-- scopes a catalog table by tenant: rows get the tenant of the connection that writes them and a
+-- row level security policy hides the rows of other tenants. Tables created by later migrations
+-- are scoped by calling this on them too
+CREATE OR REPLACE FUNCTION enable_tenant_isolation(scoped_table REGCLASS) RETURNS VOID AS $$
+BEGIN
+    EXECUTE format(
+        'ALTER TABLE %s ADD COLUMN IF NOT EXISTS tenant_id VARCHAR NOT NULL DEFAULT current_tenant_id()',
+        scoped_table
+    );
+    EXECUTE format('ALTER TABLE %s ENABLE ROW LEVEL SECURITY', scoped_table);
+    EXECUTE format('ALTER TABLE %s FORCE ROW LEVEL SECURITY', scoped_table);
+    EXECUTE format('DROP POLICY IF EXISTS tenant_isolation ON %s', scoped_table);
+    EXECUTE format(
+        'CREATE POLICY tenant_isolation ON %s USING (tenant_id = current_tenant_id()) '
+            'WITH CHECK (tenant_id = current_tenant_id())',
+        scoped_table
+    );
+END
+$$ LANGUAGE plpgsql;
+
+SELECT enable_tenant_isolation('products');
+SELECT enable_tenant_isolation('variants');
+SELECT enable_tenant_isolation('product_variants');
+SELECT enable_tenant_isolation('product_images');
+SELECT enable_tenant_isolation('product_revisions');
+SELECT enable_tenant_isolation('product_slug_redirects');
+SELECT enable_tenant_isolation('product_translations');
+SELECT enable_tenant_isolation('variant_translations');
+SELECT enable_tenant_isolation('variant_value_translations');
+SELECT enable_tenant_isolation('product_specifications');
+SELECT enable_tenant_isolation('product_types');
+SELECT enable_tenant_isolation('product_bundles');
+SELECT enable_tenant_isolation('bundle_components');
+SELECT enable_tenant_isolation('product_relations');
+SELECT enable_tenant_isolation('idempotency_keys');
+SELECT enable_tenant_isolation('audit_logs');
+
+-- names that had to be unique across the catalog only have to be unique within a tenant now
+ALTER TABLE products DROP CONSTRAINT IF EXISTS products_external_key_key;
+CREATE UNIQUE INDEX IF NOT EXISTS products_tenant_external_key_idx ON products (tenant_id, external_key);
+DROP INDEX IF EXISTS products_slug_idx;
+CREATE UNIQUE INDEX IF NOT EXISTS products_tenant_slug_idx ON products (tenant_id, slug);
+ALTER TABLE product_slug_redirects DROP CONSTRAINT IF EXISTS product_slug_redirects_pkey;
+ALTER TABLE product_slug_redirects ADD PRIMARY KEY (tenant_id, slug);
+ALTER TABLE product_types DROP CONSTRAINT IF EXISTS product_types_name_key;
+CREATE UNIQUE INDEX IF NOT EXISTS product_types_tenant_name_idx ON product_types (tenant_id, name);
+ALTER TABLE idempotency_keys DROP CONSTRAINT IF EXISTS idempotency_keys_pkey;
+ALTER TABLE idempotency_keys ADD PRIMARY KEY (tenant_id, key);
+CREATE INDEX IF NOT EXISTS variants_tenant_name_idx ON variants (tenant_id, name);
+
+-- API keys are looked up before the tenant of a request is known, so they are not hidden from
+-- other tenants. The tenant of a key is the tenant its requests work for
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS tenant_id VARCHAR NOT NULL DEFAULT current_tenant_id();
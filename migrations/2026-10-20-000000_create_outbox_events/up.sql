@@ -0,0 +1,17 @@
+-- domain events written in the same transaction as the catalog changes they describe, waiting to
+-- be delivered by the relay. Events of every tenant are relayed by the same worker, so the table is
+-- not hidden from other tenants and each event records the tenant it happened in
+CREATE TABLE IF NOT EXISTS outbox_events (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    event_id UUID NOT NULL UNIQUE,
+    tenant_id VARCHAR NOT NULL DEFAULT current_tenant_id(),
+    event_type VARCHAR NOT NULL,
+    product_id INTEGER NOT NULL,
+    payload JSONB NOT NULL,
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    delivered_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS outbox_events_pending_idx ON outbox_events (id) WHERE delivered_at IS NULL;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS product_changes;
+DROP SEQUENCE IF EXISTS product_change_sequence;
@@ -0,0 +1,23 @@
+-- the latest change to every product, for consumers that keep a copy of the catalog in sync. Each
+-- write to a product moves its row to the end of the feed with the next number of the change
+-- sequence and the ID of the transaction that wrote it. Rows outlive their products, a row whose
+-- product is gone is the tombstone that tells consumers to drop it
+CREATE SEQUENCE IF NOT EXISTS product_change_sequence;
+
+CREATE TABLE IF NOT EXISTS product_changes (
+    product_id INTEGER PRIMARY KEY,
+    public_id UUID NOT NULL,
+    sequence BIGINT NOT NULL DEFAULT nextval('product_change_sequence'),
+    transaction_id BIGINT NOT NULL DEFAULT pg_current_xact_id()::TEXT::BIGINT,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    tenant_id VARCHAR NOT NULL DEFAULT current_tenant_id()
+);
+
+-- products written before the feed existed are in it from the start
+INSERT INTO product_changes (product_id, public_id, tenant_id)
+SELECT id, public_id, tenant_id FROM products ORDER BY id
+ON CONFLICT (product_id) DO NOTHING;
+
+SELECT enable_tenant_isolation('product_changes');
+
+CREATE INDEX IF NOT EXISTS product_changes_feed_idx ON product_changes (tenant_id, transaction_id, sequence);
@@ -0,0 +1,5 @@
+DELETE FROM product_revisions WHERE product_id NOT IN (SELECT id FROM products);
+
+ALTER TABLE product_revisions
+    ADD CONSTRAINT product_revisions_product_id_fkey
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE;
@@ -0,0 +1,3 @@
+-- revisions are the history of a product and stay behind when the product is deleted, like its
+-- audit records and change feed tombstones do
+ALTER TABLE product_revisions DROP CONSTRAINT IF EXISTS product_revisions_product_id_fkey;
@@ -0,0 +1,8 @@
+DROP POLICY IF EXISTS tenant_isolation ON roles;
+ALTER TABLE roles NO FORCE ROW LEVEL SECURITY;
+ALTER TABLE roles DISABLE ROW LEVEL SECURITY;
+
+DELETE FROM roles WHERE tenant_id <> 'default';
+DROP INDEX IF EXISTS roles_tenant_name_idx;
+ALTER TABLE roles DROP COLUMN IF EXISTS tenant_id;
+ALTER TABLE roles ADD CONSTRAINT roles_name_key UNIQUE (name);
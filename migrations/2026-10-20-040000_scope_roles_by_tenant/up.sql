@@ -0,0 +1,16 @@
+-- roles are part of a tenant's setup like the rest of its catalog, so editing a role only changes
+-- the permissions of that tenant's clients. Tenants that already have API keys keep the roles that
+-- were shared until now
+ALTER TABLE roles ADD COLUMN IF NOT EXISTS tenant_id VARCHAR NOT NULL DEFAULT current_tenant_id();
+ALTER TABLE roles DROP CONSTRAINT IF EXISTS roles_name_key;
+CREATE UNIQUE INDEX IF NOT EXISTS roles_tenant_name_idx ON roles (tenant_id, name);
+
+INSERT INTO roles (tenant_id, name, permissions)
+SELECT tenants.tenant_id, roles.name, roles.permissions
+FROM (SELECT DISTINCT tenant_id FROM api_keys) AS tenants
+CROSS JOIN roles
+WHERE roles.tenant_id = 'default'
+  AND tenants.tenant_id <> 'default'
+ON CONFLICT (tenant_id, name) DO NOTHING;
+
+SELECT enable_tenant_isolation('roles');
@@ -0,0 +1,10 @@
+-- an event that cannot be delivered is attempted again later instead of holding back the events
+-- after it, and is dead once it has failed too often. Dead events are kept with their last error.
+-- A relay claims the events it is about to deliver by moving their next attempt past the time it
+-- needs for them
+ALTER TABLE outbox_events ADD COLUMN IF NOT EXISTS next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT now();
+ALTER TABLE outbox_events ADD COLUMN IF NOT EXISTS dead_at TIMESTAMPTZ;
+
+DROP INDEX IF EXISTS outbox_events_pending_idx;
+CREATE INDEX IF NOT EXISTS outbox_events_due_idx ON outbox_events (next_attempt_at, id)
+    WHERE delivered_at IS NULL AND dead_at IS NULL;
@@ -0,0 +1,6 @@
+-- keys that several clients of a tenant share cannot be kept apart without the principal, and they
+-- only hold responses for replays, so they are all dropped
+DELETE FROM idempotency_keys;
+ALTER TABLE idempotency_keys DROP CONSTRAINT IF EXISTS idempotency_keys_pkey;
+ALTER TABLE idempotency_keys DROP COLUMN IF EXISTS principal;
+ALTER TABLE idempotency_keys ADD PRIMARY KEY (tenant_id, key);
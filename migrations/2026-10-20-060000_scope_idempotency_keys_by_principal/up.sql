@@ -0,0 +1,6 @@
+-- a key only replays the responses of the client that sent it, so two clients of a tenant that
+-- happen to pick the same key do not get each other's responses. Keys stored until now belong to
+-- no client and are left to expire
+ALTER TABLE idempotency_keys ADD COLUMN IF NOT EXISTS principal VARCHAR NOT NULL DEFAULT '';
+ALTER TABLE idempotency_keys DROP CONSTRAINT IF EXISTS idempotency_keys_pkey;
+ALTER TABLE idempotency_keys ADD PRIMARY KEY (tenant_id, principal, key);
@@ -0,0 +1,197 @@
+use crate::DbPool;
+use crate::api::errors::ApiError;
+use crate::core::entities::api_key::hash_api_key;
+use crate::core::authorization::Authorization;
+use crate::core::entities::principal::{AuthMethod, Principal};
+use crate::core::entities::tenant::Tenant;
+use crate::core::ports::database::api_key_database::ApiKeyDatastore;
+use crate::core::ports::database::role_database::RoleDatastore;
+use crate::datastore::repositories::api_key_repository::ApiKeyRepository;
+use crate::datastore::repositories::role_repository::RoleRepository;
+use actix_web::body::MessageBody;
+use actix_web::dev::{Payload, ServiceRequest, ServiceResponse};
+use actix_web::http::header::{self, HeaderMap};
+use actix_web::middleware::Next;
+use actix_web::{web, FromRequest, HttpMessage, HttpRequest};
+use diesel::PgConnection;
+use anyhow::{Context, Result as AnyResult};
+use chrono::Utc;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::future::{ready, Ready};
+
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+// the keys that bearer tokens are verified against. Tokens are accepted for each algorithm that
+// has a key configured, and none are accepted when neither is
+#[derive(Clone)]
+pub struct AuthConfig {
+    hs256_key: Option<DecodingKey>,
+    rs256_key: Option<DecodingKey>,
+    issuer: Option<String>,
+    audience: Option<String>,
+}
+
+impl AuthConfig {
+    pub fn from_env() -> AnyResult<AuthConfig> {
+        let hs256_key = env::var("JWT_HS256_SECRET")
+            .ok()
+            .filter(|secret| !secret.is_empty())
+            .map(|secret| DecodingKey::from_secret(secret.as_bytes()));
+        let rs256_key = match env::var("JWT_RS256_PUBLIC_KEY_FILE") {
+            Ok(path) if !path.is_empty() => {
+                let pem = fs::read(&path).with_context(|| format!("Error reading JWT public key {}", path))?;
+
+                Some(DecodingKey::from_rsa_pem(&pem).with_context(|| format!("Invalid JWT public key {}", path))?)
+            }
+            _ => None,
+        };
+
+        Ok(AuthConfig {
+            hs256_key,
+            rs256_key,
+            issuer: env::var("JWT_ISSUER").ok().filter(|issuer| !issuer.is_empty()),
+            audience: env::var("JWT_AUDIENCE").ok().filter(|audience| !audience.is_empty()),
+        })
+    }
+}
+
+// the claims read from a bearer token. `scope` holds the granted scopes separated by spaces and
+// `tenant` the tenant the client belongs to, the default tenant when it is left out
+#[derive(Debug, Deserialize)]
+struct Claims {
+    sub: String,
+    #[serde(default)]
+    scope: String,
+    #[serde(default)]
+    tenant: Tenant,
+}
+
+fn invalid_token(reason: impl Into<String>) -> ApiError {
+    ApiError::Unauthorized(format!("The bearer token is not valid: {}", reason.into()))
+}
+
+pub(crate) fn verify_bearer_token(config: &AuthConfig, token: &str) -> Result<Principal, ApiError> {
+    let algorithm = decode_header(token).map_err(|error| invalid_token(error.to_string()))?.alg;
+    let key = match algorithm {
+        Algorithm::HS256 => config.hs256_key.as_ref(),
+        Algorithm::RS256 => config.rs256_key.as_ref(),
+        _ => None,
+    }
+    .ok_or_else(|| invalid_token(format!("tokens signed with {:?} are not accepted", algorithm)))?;
+
+    let mut validation = Validation::new(algorithm);
+    if let Some(issuer) = &config.issuer {
+        validation.set_issuer(&[issuer]);
+    }
+    match &config.audience {
+        Some(audience) => validation.set_audience(&[audience]),
+        None => validation.validate_aud = false,
+    }
+
+    let claims = decode::<Claims>(token, key, &validation)
+        .map_err(|error| invalid_token(error.to_string()))?
+        .claims;
+
+    Ok(Principal::new(
+        claims.sub,
+        AuthMethod::Jwt,
+        claims.scope.split_whitespace().map(String::from).collect(),
+    )
+    .with_tenant(claims.tenant))
+}
+
+pub(crate) async fn verify_api_key(pool: web::Data<DbPool>, key: String) -> Result<Principal, ApiError> {
+    let api_key = web::block(move || {
+        let mut connection = pool.get()?;
+
+        ApiKeyRepository::new(&mut connection).find_api_key(&hash_api_key(&key))
+    })
+    .await??
+    .ok_or_else(|| ApiError::Unauthorized("The API key is not valid".to_string()))?;
+
+    if !api_key.is_usable_at(Utc::now()) {
+        return Err(ApiError::Unauthorized("The API key has expired or been revoked".to_string()));
+    }
+
+    Ok(
+        Principal::new(api_key.name().to_string(), AuthMethod::ApiKey, api_key.scopes().to_vec())
+            .with_tenant(api_key.tenant().clone()),
+    )
+}
+
+// whether a request carries credentials, which are either turned away or make it authenticated
+pub(crate) fn has_credentials(headers: &HeaderMap) -> bool {
+    headers.contains_key(API_KEY_HEADER) || headers.contains_key(header::AUTHORIZATION)
+}
+
+// the principal a request authenticates as with an `X-API-Key` header or an
+// `Authorization: Bearer` token, if it carries either
+async fn authenticate_request(request: &ServiceRequest) -> Result<Option<Principal>, ApiError> {
+    if let Some(value) = request.headers().get(API_KEY_HEADER) {
+        let key = value
+            .to_str()
+            .map_err(|_| ApiError::Unauthorized("The API key is not valid".to_string()))?
+            .to_string();
+        let pool = request
+            .app_data::<web::Data<DbPool>>()
+            .cloned()
+            .ok_or_else(|| ApiError::Internal(anyhow::anyhow!("No connection pool is configured")))?;
+
+        return verify_api_key(pool, key).await.map(Some);
+    }
+
+    if let Some(value) = request.headers().get(header::AUTHORIZATION) {
+        let token = value
+            .to_str()
+            .ok()
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| invalid_token("only bearer tokens are accepted"))?;
+        let config = request
+            .app_data::<web::Data<AuthConfig>>()
+            .ok_or_else(|| ApiError::Internal(anyhow::anyhow!("No authentication is configured")))?;
+
+        return verify_bearer_token(config, token.trim()).map(Some);
+    }
+
+    Ok(None)
+}
+
+// authenticates requests that carry credentials and makes their principal available to handlers.
+// Requests with credentials that are not valid are turned away, requests without any go on
+// anonymously and are turned away by the handlers that need a principal
+pub async fn authenticate(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    if let Some(principal) = authenticate_request(&request).await? {
+        request.extensions_mut().insert(principal);
+    }
+
+    next.call(request).await
+}
+
+// handlers that take a principal can only be called by authenticated clients
+impl FromRequest for Principal {
+    type Error = ApiError;
+    type Future = Ready<Result<Principal, ApiError>>;
+
+    fn from_request(request: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(request.extensions().get::<Principal>().cloned().ok_or_else(|| {
+            ApiError::Unauthorized(format!(
+                "This request needs an {} header or a bearer token",
+                API_KEY_HEADER
+            ))
+        }))
+    }
+}
+
+// the permissions of a principal, with the roles named in its scopes looked up as they are now so
+// that changes to a role apply to the next request
+pub(crate) fn authorize(connection: &mut PgConnection, principal: &Principal) -> AnyResult<Authorization> {
+    let roles = RoleRepository::new(connection).find_roles(principal.scopes())?;
+
+    Ok(Authorization::new(principal, &roles))
+}
@@ -0,0 +1,229 @@
+use crate::DbPool;
+use crate::api::auth::authorize;
+use crate::api::errors::{ApiError, ErrorBody};
+use crate::api::with_connection;
+use crate::core::entities::bundle::{Bundle, BundleDefinition, BundlePricing, ComponentDeactivation, ComponentQuantity};
+use crate::core::entities::ids::ProductId;
+use crate::core::entities::principal::Principal;
+use crate::core::entities::product_reference::ProductReference;
+use crate::core::entities::role::Permission;
+use crate::core::entities::tenant::Tenant;
+use crate::core::ports::database::bundle_database::BundleDatastore;
+use crate::core::ports::database::errors::DatastoreError;
+use crate::core::ports::database::product_database::ProductDatastore;
+use crate::datastore::repositories::bundle_repository::BundleRepository;
+use crate::datastore::repositories::product_repository::ProductRepository;
+use actix_web::{web, HttpResponse};
+use diesel::{Connection, PgConnection};
+use serde::Deserialize;
+use utoipa::{OpenApi, ToSchema};
+
+// a component is referred to like any other product, or by its SKU
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentReference {
+    Product(ProductReference),
+    Sku(String),
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BundleComponentPayload {
+    #[serde(flatten)]
+    pub reference: ComponentReference,
+    pub quantity: u32,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BundlePayload {
+    pub pricing: BundlePricing,
+    #[serde(default = "default_component_deactivation")]
+    pub on_component_deactivated: ComponentDeactivation,
+    pub components: Vec<BundleComponentPayload>,
+}
+
+fn default_component_deactivation() -> ComponentDeactivation {
+    ComponentDeactivation::Deactivate
+}
+
+// the pricing of a bundle sets what it sells for, so writes that set or change it need
+// `pricing:write` on top of `catalog:write`. A fixed bundle sells for the product's own cost, so
+// turning one back into a plain product leaves its price as it is
+fn changes_pricing(current: Option<BundlePricing>, new: Option<BundlePricing>) -> bool {
+    match (current, new) {
+        (Some(BundlePricing::Fixed), None) => false,
+        (current, new) => current != new,
+    }
+}
+
+// the pricing of the bundle a product is, or none when it is not a bundle
+fn current_pricing(connection: &mut PgConnection, id: ProductId) -> anyhow::Result<Option<BundlePricing>> {
+    match BundleRepository::new(connection).get_bundle(id) {
+        Ok(bundle) => Ok(Some(bundle.pricing())),
+        Err(error)
+            if matches!(
+                error.downcast_ref::<DatastoreError>(),
+                Some(DatastoreError::NotFound { entity: "Bundle", .. })
+            ) =>
+        {
+            Ok(None)
+        }
+        Err(error) => Err(error),
+    }
+}
+
+pub fn configure(config: &mut web::ServiceConfig) {
+    config.service(
+        web::resource("/products/{reference}/bundle")
+            .route(web::get().to(get_bundle))
+            .route(web::put().to(set_bundle))
+            .route(web::delete().to(delete_bundle)),
+    );
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(get_bundle, set_bundle, delete_bundle))]
+pub struct BundlesApi;
+
+#[utoipa::path(
+    get,
+    path = "/products/{reference}/bundle",
+    tag = "bundles",
+    params(("reference" = ProductReference, Path, description = "The product's ID, public ID or slug")),
+    responses(
+        (status = 200, description = "The bundle with its price and availability", body = Bundle),
+        (status = 404, description = "There is no such product or it is not a bundle", body = ErrorBody),
+    )
+)]
+async fn get_bundle(pool: web::Data<DbPool>, tenant: Tenant, path: web::Path<ProductReference>) -> Result<HttpResponse, ApiError> {
+    let reference = path.into_inner();
+
+    let bundle = with_connection(pool, tenant, move |connection| {
+        let id = ProductRepository::new(connection).resolve_product(&reference)?.id();
+
+        BundleRepository::new(connection).get_bundle(id)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(bundle))
+}
+
+#[utoipa::path(
+    put,
+    path = "/products/{reference}/bundle",
+    tag = "bundles",
+    params(("reference" = ProductReference, Path, description = "The product's ID, public ID or slug")),
+    request_body = BundlePayload,
+    responses(
+        (status = 200, description = "The saved bundle", body = Bundle),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client may not write the catalog, or the pricing when it is set or changed", body = ErrorBody),
+        (status = 404, description = "There is no such product", body = ErrorBody),
+        (status = 422, description = "The components do not make up a valid bundle", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn set_bundle(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    path: web::Path<ProductReference>,
+    payload: web::Json<BundlePayload>,
+) -> Result<HttpResponse, ApiError> {
+    let reference = path.into_inner();
+    let payload = payload.into_inner();
+
+    let bundle = with_connection(pool, tenant, move |connection| {
+        connection.transaction::<_, anyhow::Error, _>(|connection| {
+            let authorization = authorize(connection, &principal)?;
+            authorization.require(Permission::CatalogWrite)?;
+
+            let mut product_repository = ProductRepository::new(connection);
+            let id = product_repository.resolve_product(&reference)?.id();
+            let components = payload
+                .components
+                .into_iter()
+                .map(|component| {
+                    let product_id = match component.reference {
+                        ComponentReference::Product(product) => product_repository.resolve_product(&product)?.id(),
+                        ComponentReference::Sku(sku) => product_repository.find_product_by_external_key(&sku)?,
+                    };
+
+                    Ok(ComponentQuantity::new(product_id, component.quantity))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            if changes_pricing(current_pricing(connection, id)?, Some(payload.pricing)) {
+                authorization.require(Permission::PricingWrite)?;
+            }
+
+            BundleRepository::new(connection).with_actor(principal.actor()).set_bundle(
+                id,
+                BundleDefinition::new(payload.pricing, payload.on_component_deactivated, components),
+            )
+        })
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(bundle))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/products/{reference}/bundle",
+    tag = "bundles",
+    params(("reference" = ProductReference, Path, description = "The product's ID, public ID or slug")),
+    responses(
+        (status = 204, description = "The product is no longer a bundle"),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client may not write the catalog, or the pricing of a derived bundle", body = ErrorBody),
+        (status = 404, description = "There is no such product or it is not a bundle", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn delete_bundle(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    path: web::Path<ProductReference>,
+) -> Result<HttpResponse, ApiError> {
+    let reference = path.into_inner();
+
+    with_connection(pool, tenant, move |connection| {
+        connection.transaction::<_, anyhow::Error, _>(|connection| {
+            let authorization = authorize(connection, &principal)?;
+            authorization.require(Permission::CatalogWrite)?;
+
+            let id = ProductRepository::new(connection).resolve_product(&reference)?.id();
+            if changes_pricing(current_pricing(connection, id)?, None) {
+                authorization.require(Permission::PricingWrite)?;
+            }
+
+            BundleRepository::new(connection)
+                .with_actor(principal.actor())
+                .delete_bundle(id)
+        })
+    })
+    .await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod bundles_tests {
+    use crate::api::bundles::changes_pricing;
+    use crate::core::entities::bundle::BundlePricing;
+
+    #[test]
+    fn test_setting_or_changing_bundle_pricing_changes_pricing() {
+        let derived = |discount_percent| BundlePricing::Derived { discount_percent };
+
+        assert!(changes_pricing(None, Some(BundlePricing::Fixed)));
+        assert!(changes_pricing(None, Some(derived(10.0))));
+        assert!(changes_pricing(Some(BundlePricing::Fixed), Some(derived(10.0))));
+        assert!(changes_pricing(Some(derived(10.0)), Some(derived(15.0))));
+        assert!(changes_pricing(Some(derived(10.0)), None));
+        assert!(!changes_pricing(Some(derived(10.0)), Some(derived(10.0))));
+        assert!(!changes_pricing(Some(BundlePricing::Fixed), Some(BundlePricing::Fixed)));
+        assert!(!changes_pricing(Some(BundlePricing::Fixed), None));
+    }
+}
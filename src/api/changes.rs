@@ -0,0 +1,74 @@
+use crate::DbPool;
+use crate::api::errors::{ApiError, ErrorBody};
+use crate::api::with_connection;
+use crate::core::entities::product_change::{ChangeToken, ProductChange};
+use crate::core::entities::tenant::Tenant;
+use crate::core::ports::database::change_database::ChangeFeedDatastore;
+use crate::datastore::repositories::change_repository::ChangeRepository;
+use actix_web::{web, HttpResponse};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+const DEFAULT_PAGE_SIZE: i64 = 100;
+const MAX_PAGE_SIZE: i64 = 1000;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ChangesQuery {
+    // the `next_token` of the previous page, the feed starts from the beginning without it
+    pub since: Option<String>,
+    pub limit: Option<i64>,
+}
+
+// a page of the changes feed. `next_token` is where the next request picks up, and stays the same
+// when there was nothing new
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChangesPage {
+    pub changes: Vec<ProductChange>,
+    pub next_token: Option<ChangeToken>,
+}
+
+pub fn configure(config: &mut web::ServiceConfig) {
+    config.service(web::resource("/changes").route(web::get().to(list_changes)));
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list_changes))]
+pub struct ChangesApi;
+
+// lets consumers keep a copy of the catalog in sync by polling for what changed since they last
+// asked, instead of reading the whole catalog every time. Starting without a token lists every
+// product there is, along with tombstones of the ones that were deleted
+#[utoipa::path(
+    get,
+    path = "/changes",
+    tag = "changes",
+    params(ChangesQuery),
+    responses(
+        (status = 200, description = "The changes after the given token", body = ChangesPage),
+        (status = 400, description = "The token is not one the feed handed out", body = ErrorBody),
+    )
+)]
+async fn list_changes(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    query: web::Query<ChangesQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let query = query.into_inner();
+    let since = query
+        .since
+        .map(|since| since.parse::<ChangeToken>())
+        .transpose()
+        .map_err(|error| ApiError::BadRequest(error.to_string()))?;
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    let changes = with_connection(pool, tenant, move |connection| {
+        ChangeRepository::new(connection).list_product_changes(since, limit)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(ChangesPage {
+        next_token: changes.last().map(ProductChange::token).or(since),
+        changes,
+    }))
+}
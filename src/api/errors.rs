@@ -0,0 +1,156 @@
+use crate::api::etag::version_etag;
+use crate::api::rate_limit::{rate_limit_headers, whole_seconds};
+use crate::core::entities::rate_limit::RateLimitDecision;
+use crate::core::ports::database::errors::DatastoreError;
+use actix_web::error::BlockingError;
+use actix_web::http::{header, StatusCode};
+use actix_web::{HttpResponse, ResponseError};
+use serde::Serialize;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use utoipa::ToSchema;
+
+#[derive(Debug)]
+pub enum ApiError {
+    // a conditional write was made without an `If-Match` header
+    PreconditionRequired,
+    // a request came without credentials where they are needed, or with credentials that are not valid
+    Unauthorized(String),
+    // a client tried to work for a tenant other than its own
+    Forbidden(String),
+    BadRequest(String),
+    // a client made more requests than its rate limit lets through
+    TooManyRequests(RateLimitDecision),
+    Conflict(String),
+    UnprocessableEntity(String),
+    Datastore(DatastoreError),
+    Internal(anyhow::Error),
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ApiError::PreconditionRequired => {
+                write!(f, "This request must be made conditional with an If-Match header")
+            }
+            ApiError::Unauthorized(message)
+            | ApiError::Forbidden(message)
+            | ApiError::BadRequest(message)
+            | ApiError::Conflict(message)
+            | ApiError::UnprocessableEntity(message) => write!(f, "{}", message),
+            ApiError::TooManyRequests(decision) => write!(
+                f,
+                "Too many requests, the limit is {} requests per {} seconds",
+                decision.limit().requests(),
+                decision.limit().period().num_seconds()
+            ),
+            ApiError::Datastore(error) => write!(f, "{}", error),
+            ApiError::Internal(_) => write!(f, "Internal server error"),
+        }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        match error.downcast::<DatastoreError>() {
+            Ok(datastore_error) => ApiError::Datastore(datastore_error),
+            Err(error) => ApiError::Internal(error),
+        }
+    }
+}
+
+impl From<BlockingError> for ApiError {
+    fn from(error: BlockingError) -> Self {
+        ApiError::Internal(anyhow::Error::new(error))
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::PreconditionRequired => StatusCode::PRECONDITION_REQUIRED,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::Datastore(DatastoreError::NotFound { .. })
+            | ApiError::Datastore(DatastoreError::UnknownReference { .. }) => StatusCode::NOT_FOUND,
+            ApiError::Datastore(DatastoreError::AlreadyExists { .. }) => StatusCode::CONFLICT,
+            ApiError::Datastore(DatastoreError::VersionConflict { .. }) => {
+                StatusCode::PRECONDITION_FAILED
+            }
+            ApiError::Datastore(DatastoreError::InvalidAttributes { .. })
+            | ApiError::Datastore(DatastoreError::InvalidBundle { .. })
+            | ApiError::Datastore(DatastoreError::InvalidRelations { .. }) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::Datastore(DatastoreError::InUse { .. }) => StatusCode::CONFLICT,
+            ApiError::Datastore(DatastoreError::PermissionDenied { .. }) => StatusCode::FORBIDDEN,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut response = HttpResponse::build(self.status_code());
+
+        match self {
+            // a stale write reports the version it lost against so the client can re-read and retry
+            ApiError::Datastore(DatastoreError::VersionConflict { current_version, .. }) => {
+                response.insert_header(version_etag(*current_version));
+            }
+            ApiError::Unauthorized(_) => {
+                response.insert_header((header::WWW_AUTHENTICATE, "Bearer"));
+            }
+            ApiError::TooManyRequests(decision) => {
+                for rate_limit_header in rate_limit_headers(decision) {
+                    response.insert_header(rate_limit_header);
+                }
+                response.insert_header((header::RETRY_AFTER, whole_seconds(decision.retry_after()).to_string()));
+            }
+            ApiError::Internal(error) => log::error!("Request failed: {:?}", error),
+            _ => {}
+        }
+
+        response.json(ErrorBody::from(self))
+    }
+}
+
+// the body errors are answered with. The fields after `error` are only there for the errors that
+// have them to tell
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorBody {
+    pub error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_version: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub problems: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required_permission: Option<String>,
+}
+
+impl From<&ApiError> for ErrorBody {
+    fn from(error: &ApiError) -> Self {
+        let mut body = ErrorBody {
+            error: error.to_string(),
+            current_version: None,
+            problems: None,
+            required_permission: None,
+        };
+
+        match error {
+            ApiError::Datastore(DatastoreError::VersionConflict { current_version, .. }) => {
+                body.current_version = Some(*current_version);
+            }
+            ApiError::Datastore(
+                DatastoreError::InvalidAttributes { problems, .. }
+                | DatastoreError::InvalidBundle { problems, .. }
+                | DatastoreError::InvalidRelations { problems, .. },
+            ) => body.problems = Some(problems.clone()),
+            ApiError::Datastore(DatastoreError::PermissionDenied { permission, .. }) => {
+                body.required_permission = Some(permission.to_string());
+            }
+            _ => {}
+        }
+
+        body
+    }
+}
@@ -0,0 +1,88 @@
+use crate::api::errors::ApiError;
+use crate::core::entities::locale::LocaleFallback;
+use actix_web::http::header::{self, ETag, EntityTag, IfMatch, IfNoneMatch};
+use actix_web::{HttpMessage, HttpRequest};
+
+// the ETag of a product is its version, so any write to the product changes it
+pub fn version_etag(version: u32) -> ETag {
+    ETag(EntityTag::new_strong(version.to_string()))
+}
+
+// the ETag of a product as it is read in the locales of `fallback`, e.g. `"3;de-AT;de;en"`. Reads in
+// other locales are other representations of the same version, so they get a tag of their own
+pub fn representation_etag(version: u32, fallback: &LocaleFallback) -> ETag {
+    let locales = fallback.locales().iter().map(|locale| locale.as_str()).collect::<Vec<_>>();
+
+    ETag(EntityTag::new_strong(format!("{};{}", version, locales.join(";"))))
+}
+
+// the version an ETag stands for, whether it is the tag of a write or of a read in some locales
+fn tag_version(tag: &EntityTag) -> Option<u32> {
+    tag.tag().split(';').next()?.parse::<u32>().ok()
+}
+
+// reads the version a write is conditional on from the `If-Match` header. Writes must name the
+// exact version they were based on, so `*` and lists of tags are rejected
+pub fn expected_version(request: &HttpRequest) -> Result<u32, ApiError> {
+    if !request.headers().contains_key(header::IF_MATCH) {
+        return Err(ApiError::PreconditionRequired);
+    }
+
+    let invalid = || {
+        ApiError::BadRequest(String::from(
+            "If-Match must hold the single strong ETag returned when the product was read",
+        ))
+    };
+
+    match request.get_header::<IfMatch>() {
+        Some(IfMatch::Items(tags)) => match tags.as_slice() {
+            [tag] if !tag.weak => tag_version(tag).ok_or_else(invalid),
+            _ => Err(invalid()),
+        },
+        _ => Err(invalid()),
+    }
+}
+
+// whether the client already holds the representation tagged `current`, per its `If-None-Match` header
+pub fn is_not_modified(request: &HttpRequest, current: &ETag) -> bool {
+    match request.get_header::<IfNoneMatch>() {
+        Some(IfNoneMatch::Any) => true,
+        Some(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(&current.0)),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod etag_tests {
+    use crate::api::etag::{expected_version, is_not_modified, representation_etag, version_etag};
+    use crate::core::entities::locale::LocaleFallback;
+    use actix_web::http::header;
+    use actix_web::test::TestRequest;
+
+    fn fallback(preferred: &[&str]) -> LocaleFallback {
+        let preferred = preferred.iter().map(|tag| tag.parse().unwrap()).collect::<Vec<_>>();
+
+        LocaleFallback::new(&preferred, "en".parse().unwrap())
+    }
+
+    #[test]
+    fn test_reads_in_other_locales_are_not_modified_only_for_their_own_tag() {
+        let german = representation_etag(3, &fallback(&["de-AT"]));
+        let english = representation_etag(3, &fallback(&[]));
+        assert_eq!("\"3;de-AT;de;en\"", german.to_string());
+
+        let request = TestRequest::get().insert_header((header::IF_NONE_MATCH, german.to_string())).to_http_request();
+        assert!(is_not_modified(&request, &german));
+        assert!(!is_not_modified(&request, &english));
+        assert!(!is_not_modified(&request, &representation_etag(4, &fallback(&["de-AT"]))));
+    }
+
+    #[test]
+    fn test_writes_accept_the_tags_of_reads_and_of_writes() {
+        for etag in [representation_etag(3, &fallback(&["de-AT"])), version_etag(3)] {
+            let request = TestRequest::put().insert_header((header::IF_MATCH, etag.to_string())).to_http_request();
+
+            assert_eq!(3, expected_version(&request).unwrap());
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use crate::DbPool;
+use crate::api::errors::{ApiError, ErrorBody};
+use crate::core::entities::tenant::Tenant;
+use crate::datastore::repositories::export_repository::ExportRepository;
+use crate::datastore::tenancy::set_current_tenant;
+use crate::export::merchant_feed::MerchantFeedConfig;
+use crate::export::{export_products, ExportFormat, ExportOptions};
+use actix_web::body::{BodySize, MessageBody};
+use actix_web::http::header;
+use actix_web::web::Bytes;
+use actix_web::{rt, web, HttpResponse};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::io::{self, BufWriter, Write};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+use utoipa::{IntoParams, OpenApi};
+
+// how many written chunks may wait for the client before the export pauses
+const EXPORT_CHANNEL_CAPACITY: usize = 16;
+const EXPORT_CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportQuery {
+    pub format: String,
+    pub changed_since: Option<DateTime<Utc>>,
+}
+
+pub fn configure(config: &mut web::ServiceConfig) {
+    config.service(web::resource("/product-exports").route(web::get().to(export_product_feed)));
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(export_product_feed))]
+pub struct ExportsApi;
+
+// the response is streamed while the export runs on the blocking thread pool, so a failure halfway
+// through can no longer change the status and aborts the response instead
+#[utoipa::path(
+    get,
+    path = "/product-exports",
+    tag = "exports",
+    params(ExportQuery),
+    responses(
+        (
+            status = 200,
+            description = "The products as a feed in the given format",
+            content(
+                (String = "text/csv"),
+                (String = "application/x-ndjson"),
+                (String = "application/rss+xml"),
+            )
+        ),
+        (status = 400, description = "The format is not one products can be exported to", body = ErrorBody),
+    )
+)]
+async fn export_product_feed(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    merchant_feed: web::Data<MerchantFeedConfig>,
+    query: web::Query<ExportQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let query = query.into_inner();
+    let options = ExportOptions {
+        format: query
+            .format
+            .parse::<ExportFormat>()
+            .map_err(|error| ApiError::BadRequest(error.to_string()))?,
+        changed_since: query.changed_since,
+        merchant_feed: merchant_feed.get_ref().clone(),
+    };
+    let content_type = options.format.content_type();
+    let (sender, receiver) = mpsc::channel(EXPORT_CHANNEL_CAPACITY);
+
+    rt::task::spawn_blocking(move || {
+        let failure_sender = sender.clone();
+        let writer = BufWriter::with_capacity(EXPORT_CHUNK_SIZE, ChannelWriter { sender });
+
+        let exported = pool.get().map_err(anyhow::Error::from).and_then(|mut connection| {
+            set_current_tenant(&mut connection, &tenant)?;
+            export_products(writer, &options, &mut ExportRepository::new(&mut connection))
+        });
+
+        match exported {
+            Ok(count) => log::info!("exported {} products", count),
+            Err(error) => {
+                log::error!("product export failed: {:#}", error);
+                let _ = failure_sender.blocking_send(Err(io::Error::other(error.to_string())));
+            }
+        }
+    });
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, content_type))
+        .body(ChannelBody { receiver }))
+}
+
+// hands what the export writes over to the response body
+struct ChannelWriter {
+    sender: mpsc::Sender<io::Result<Bytes>>,
+}
+
+impl Write for ChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.sender
+            .blocking_send(Ok(Bytes::copy_from_slice(buf)))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "the client went away"))?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// a response body fed by a `ChannelWriter`, it ends once every sender is dropped
+struct ChannelBody {
+    receiver: mpsc::Receiver<io::Result<Bytes>>,
+}
+
+impl MessageBody for ChannelBody {
+    type Error = io::Error;
+
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<io::Result<Bytes>>> {
+        self.get_mut().receiver.poll_recv(cx)
+    }
+}
@@ -0,0 +1,727 @@
+use crate::DbPool;
+use crate::api::errors::ApiError;
+use crate::api::{with_connection, with_product_repository};
+use crate::core::entities::complete_product::CompleteProduct;
+use crate::core::entities::ids::{ProductId, ProductTypeId, VariantId};
+use crate::core::entities::principal::Principal;
+use crate::core::entities::product::Product;
+use crate::core::entities::product_reference::ProductReference;
+use crate::core::entities::product_type::ProductType;
+use crate::core::entities::product_variant::ProductVariant;
+use crate::core::entities::tenant::Tenant;
+use crate::core::entities::variant::Variant;
+use crate::core::entities::variant_value::VariantValue;
+use crate::core::ports::database::errors::DatastoreError;
+use crate::core::ports::database::product_database::ProductDatastore;
+use crate::core::ports::database::product_type_database::ProductTypeDatastore;
+use crate::core::ports::database::utils::ListQueryParams;
+use crate::datastore::repositories::product_repository::ProductRepository;
+use crate::datastore::repositories::product_type_repository::ProductTypeRepository;
+use actix_web::http::header;
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, ResponseError};
+use async_graphql::dataloader::{DataLoader, Loader};
+use async_graphql::http::GraphiQLSource;
+use async_graphql::{
+    Context, EmptySubscription, Error as GraphqlError, ErrorExtensions, InputObject, Json, Object, Schema,
+    SimpleObject, ID,
+};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+const DEFAULT_PAGE_SIZE: i32 = 20;
+
+// listings are capped so that a single query cannot ask for the whole catalog along with the
+// variants of every product in it
+const MAX_PAGE_SIZE: i32 = 100;
+
+// deep enough for every query the schema can answer, products do not nest any further than this
+const MAX_QUERY_DEPTH: usize = 10;
+
+pub type CatalogSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+pub fn schema() -> CatalogSchema {
+    Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .limit_depth(MAX_QUERY_DEPTH)
+        .finish()
+}
+
+pub fn configure(config: &mut web::ServiceConfig) {
+    config.app_data(web::Data::new(schema())).service(
+        web::resource("/graphql")
+            .route(web::post().to(execute_graphql))
+            .route(web::get().to(graphiql)),
+    );
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(execute_graphql, graphiql))]
+pub struct GraphqlApi;
+
+// errors are reported in the `errors` of the response like GraphQL clients expect, with the status
+// the REST API would have answered with in their extensions
+fn graphql_error(error: ApiError) -> GraphqlError {
+    if let ApiError::Internal(internal_error) = &error {
+        log::error!("GraphQL request failed: {:?}", internal_error);
+    }
+
+    GraphqlError::new(error.to_string()).extend_with(|_, extensions| {
+        extensions.set("status", error.status_code().as_u16());
+        if let ApiError::Datastore(
+            DatastoreError::InvalidAttributes { problems, .. }
+            | DatastoreError::InvalidBundle { problems, .. }
+            | DatastoreError::InvalidRelations { problems, .. },
+        ) = &error
+        {
+            extensions.set("problems", problems.clone());
+        }
+    })
+}
+
+fn parse_id<T: std::str::FromStr<Err = E>, E: std::fmt::Display>(id: &ID) -> Result<T, GraphqlError> {
+    id.parse::<T>()
+        .map_err(|error| graphql_error(ApiError::BadRequest(error.to_string())))
+}
+
+pub struct ProductObject(Product);
+
+#[Object(name = "Product")]
+impl ProductObject {
+    async fn id(&self) -> Option<ID> {
+        self.0.id().map(ID::from)
+    }
+
+    async fn public_id(&self) -> Option<Uuid> {
+        self.0.public_id()
+    }
+
+    async fn slug(&self) -> Option<&str> {
+        self.0.slug()
+    }
+
+    async fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    async fn description(&self) -> Option<&str> {
+        self.0.description()
+    }
+
+    // the key the product goes by in the systems it was imported from
+    async fn sku(&self) -> Option<&str> {
+        self.0.external_key()
+    }
+
+    async fn cost(&self) -> f64 {
+        self.0.cost()
+    }
+
+    async fn active(&self) -> bool {
+        self.0.active()
+    }
+
+    async fn version(&self) -> u32 {
+        self.0.version()
+    }
+
+    async fn stock_quantity(&self) -> Option<u32> {
+        self.0.stock_quantity()
+    }
+
+    async fn attributes(&self) -> Json<Map<String, Value>> {
+        Json(self.0.attributes().clone())
+    }
+
+    async fn product_type(&self, context: &Context<'_>) -> Result<Option<ProductTypeObject>, GraphqlError> {
+        let Some(product_type_id) = self.0.product_type_id() else {
+            return Ok(None);
+        };
+
+        context
+            .data_unchecked::<DataLoader<ProductTypeLoader>>()
+            .load_one(product_type_id)
+            .await
+    }
+
+    async fn variants(&self, context: &Context<'_>) -> Result<Vec<VariantValueObject>, GraphqlError> {
+        let Some(product_id) = self.0.id() else {
+            return Ok(Vec::new());
+        };
+
+        Ok(context
+            .data_unchecked::<DataLoader<ProductVariantsLoader>>()
+            .load_one(product_id)
+            .await?
+            .unwrap_or_default())
+    }
+}
+
+#[derive(Clone, SimpleObject)]
+#[graphql(name = "ProductType")]
+pub struct ProductTypeObject {
+    id: Option<ID>,
+    name: String,
+}
+
+impl From<ProductType> for ProductTypeObject {
+    fn from(product_type: ProductType) -> Self {
+        ProductTypeObject {
+            id: product_type.id().map(ID::from),
+            name: product_type.name().to_string(),
+        }
+    }
+}
+
+#[derive(Clone, SimpleObject)]
+#[graphql(name = "Variant")]
+pub struct VariantObject {
+    id: Option<ID>,
+    name: String,
+}
+
+// a variant of a product along with the values the product comes in, e.g. `size` in `40` and `41`
+#[derive(Clone, SimpleObject)]
+#[graphql(name = "VariantValue")]
+pub struct VariantValueObject {
+    variant: VariantObject,
+    values: Vec<Option<String>>,
+}
+
+// the variant values of products, keyed by product. The variants of every product in a response are
+// loaded with one query instead of one query per product
+pub struct ProductVariantsLoader {
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+}
+
+impl Loader<ProductId> for ProductVariantsLoader {
+    type Value = Vec<VariantValueObject>;
+    type Error = GraphqlError;
+
+    async fn load(&self, keys: &[ProductId]) -> Result<HashMap<ProductId, Self::Value>, Self::Error> {
+        let product_ids = keys.to_vec();
+        let product_variants = with_connection(self.pool.clone(), self.tenant.clone(), move |connection| {
+            ProductRepository::new(connection).list_variants_of_products(&product_ids)
+        })
+        .await
+        .map_err(graphql_error)?;
+
+        Ok(group_variant_values(product_variants))
+    }
+}
+
+// groups the values of each product by variant, keeping variants in the order they were attached
+fn group_variant_values(
+    product_variants: Vec<(ProductVariant, Variant)>,
+) -> HashMap<ProductId, Vec<VariantValueObject>> {
+    let mut variant_values = HashMap::<ProductId, Vec<(VariantId, VariantValueObject)>>::new();
+
+    for (product_variant, variant) in product_variants {
+        let product_values = variant_values.entry(product_variant.product_id()).or_default();
+
+        match product_values
+            .iter_mut()
+            .find(|(variant_id, _)| *variant_id == product_variant.variant_id())
+        {
+            Some((_, variant_value)) => variant_value.values.push(product_variant.value().clone()),
+            None => product_values.push((
+                product_variant.variant_id(),
+                VariantValueObject {
+                    variant: VariantObject {
+                        id: variant.id().map(ID::from),
+                        name: variant.name().to_string(),
+                    },
+                    values: vec![product_variant.value().clone()],
+                },
+            )),
+        }
+    }
+
+    variant_values
+        .into_iter()
+        .map(|(product_id, values)| (product_id, values.into_iter().map(|(_, value)| value).collect()))
+        .collect()
+}
+
+// the product types of products, keyed by ID and loaded with one query per response
+pub struct ProductTypeLoader {
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+}
+
+impl Loader<ProductTypeId> for ProductTypeLoader {
+    type Value = ProductTypeObject;
+    type Error = GraphqlError;
+
+    async fn load(&self, keys: &[ProductTypeId]) -> Result<HashMap<ProductTypeId, Self::Value>, Self::Error> {
+        let product_type_ids = keys.to_vec();
+        let product_types = with_connection(self.pool.clone(), self.tenant.clone(), move |connection| {
+            ProductTypeRepository::new(connection).find_product_types(&product_type_ids)
+        })
+        .await
+        .map_err(graphql_error)?;
+
+        Ok(product_types
+            .into_iter()
+            .filter_map(|product_type| Some((product_type.id()?, ProductTypeObject::from(product_type))))
+            .collect())
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    // a product by its ID, public ID or slug, or null when there is none. Slugs the product had
+    // before it was renamed lead to it as well
+    async fn product(&self, context: &Context<'_>, reference: String) -> Result<Option<ProductObject>, GraphqlError> {
+        let reference = ProductReference::from(reference);
+
+        let product = with_connection(
+            context.data_unchecked::<web::Data<DbPool>>().clone(),
+            context.data_unchecked::<Tenant>().clone(),
+            move |connection| {
+                let mut repository = ProductRepository::new(connection);
+                let resolved_product = repository.resolve_product(&reference)?;
+
+                repository.get_product(resolved_product.id())
+            },
+        )
+        .await;
+
+        match product {
+            Ok(product) => Ok(Some(ProductObject(product))),
+            Err(ApiError::Datastore(DatastoreError::NotFound { .. } | DatastoreError::UnknownReference { .. })) => {
+                Ok(None)
+            }
+            Err(error) => Err(graphql_error(error)),
+        }
+    }
+
+    async fn products(
+        &self,
+        context: &Context<'_>,
+        #[graphql(default_with = "DEFAULT_PAGE_SIZE")] limit: i32,
+        #[graphql(default = 0)] offset: i32,
+    ) -> Result<Vec<ProductObject>, GraphqlError> {
+        if !(1..=MAX_PAGE_SIZE).contains(&limit) || offset < 0 {
+            return Err(graphql_error(ApiError::BadRequest(format!(
+                "limit must be between 1 and {} and offset must not be negative",
+                MAX_PAGE_SIZE
+            ))));
+        }
+        let params = ListQueryParams {
+            limit: i64::from(limit),
+            offset: i64::from(offset),
+        };
+
+        let products = with_connection(
+            context.data_unchecked::<web::Data<DbPool>>().clone(),
+            context.data_unchecked::<Tenant>().clone(),
+            move |connection| ProductRepository::new(connection).list_products(params),
+        )
+        .await
+        .map_err(graphql_error)?;
+
+        Ok(products.into_iter().map(ProductObject).collect())
+    }
+}
+
+#[derive(InputObject)]
+pub struct VariantInput {
+    name: String,
+    values: Vec<Option<String>>,
+}
+
+#[derive(InputObject)]
+pub struct CreateProductInput {
+    name: String,
+    description: Option<String>,
+    cost: f64,
+    active: bool,
+    product_type_id: Option<ID>,
+    attributes: Option<Json<Map<String, Value>>>,
+    stock_quantity: Option<u32>,
+    #[graphql(default)]
+    variants: Vec<VariantInput>,
+}
+
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    // creates a product along with its variants, like `POST /complete-products` does
+    async fn create_product(
+        &self,
+        context: &Context<'_>,
+        input: CreateProductInput,
+    ) -> Result<ProductObject, GraphqlError> {
+        let principal = context.data_opt::<Principal>().cloned().ok_or_else(|| {
+            graphql_error(ApiError::Unauthorized(
+                "Creating products needs an API key or a bearer token".to_string(),
+            ))
+        })?;
+        let product_type_id = input.product_type_id.as_ref().map(parse_id::<ProductTypeId, _>).transpose()?;
+        let complete_product = CompleteProduct::new(
+            Product::new(input.name, input.cost, input.active, None)
+                .with_description(input.description)
+                .with_attributes(product_type_id, input.attributes.map(|attributes| attributes.0).unwrap_or_default())
+                .with_stock_quantity(input.stock_quantity),
+            input
+                .variants
+                .into_iter()
+                .map(|variant| VariantValue::new(Variant::new(variant.name, None), variant.values))
+                .collect(),
+        );
+
+        let created_product = with_product_repository(
+            context.data_unchecked::<web::Data<DbPool>>().clone(),
+            context.data_unchecked::<Tenant>().clone(),
+            principal,
+            move |repository| {
+                let created_product_id = repository.create_complete_product(complete_product)?;
+
+                repository.get_product(created_product_id)
+            },
+        )
+        .await
+        .map_err(graphql_error)?;
+
+        Ok(ProductObject(created_product))
+    }
+}
+
+// hands a request what its resolvers work with. The data loaders are made for each request, so that
+// nothing loaded for one request, or one tenant, is handed out in another
+fn with_request_data(
+    graphql_request: async_graphql::Request,
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Option<Principal>,
+) -> async_graphql::Request {
+    let mut graphql_request = graphql_request
+        .data(DataLoader::new(
+            ProductVariantsLoader {
+                pool: pool.clone(),
+                tenant: tenant.clone(),
+            },
+            actix_web::rt::spawn,
+        ))
+        .data(DataLoader::new(
+            ProductTypeLoader {
+                pool: pool.clone(),
+                tenant: tenant.clone(),
+            },
+            actix_web::rt::spawn,
+        ))
+        .data(pool)
+        .data(tenant);
+    if let Some(principal) = principal {
+        graphql_request = graphql_request.data(principal);
+    }
+
+    graphql_request
+}
+
+// runs a query or mutation for the tenant of the request, as the principal it authenticated as
+#[utoipa::path(
+    post,
+    path = "/graphql",
+    tag = "graphql",
+    request_body(content = Object, description = "A GraphQL request with its `query`, `operationName` and `variables`"),
+    responses(
+        (status = 200, description = "The GraphQL response, errors included", body = Object),
+    )
+)]
+async fn execute_graphql(
+    request: HttpRequest,
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    schema: web::Data<CatalogSchema>,
+    graphql_request: web::Json<async_graphql::Request>,
+) -> HttpResponse {
+    let principal = request.extensions().get::<Principal>().cloned();
+    let graphql_request = with_request_data(graphql_request.into_inner(), pool, tenant, principal);
+
+    HttpResponse::Ok().json(schema.execute(graphql_request).await)
+}
+
+#[utoipa::path(
+    get,
+    path = "/graphql",
+    tag = "graphql",
+    responses(
+        (status = 200, description = "A GraphiQL page to try queries in", content_type = "text/html"),
+    )
+)]
+async fn graphiql() -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, "text/html; charset=utf-8"))
+        .body(GraphiQLSource::build().endpoint("/graphql").finish())
+}
+
+#[cfg(test)]
+mod graphql_tests {
+    use crate::api::graphql::{group_variant_values, schema, with_request_data, ProductVariantsLoader};
+    use crate::core::entities::complete_product::CompleteProduct;
+    use crate::core::entities::ids::{ProductId, VariantId};
+    use crate::core::entities::principal::{AuthMethod, Principal};
+    use crate::core::entities::product::Product;
+    use crate::core::entities::product_type::ProductType;
+    use crate::core::entities::product_variant::ProductVariant;
+    use crate::core::entities::tenant::Tenant;
+    use crate::core::entities::variant::Variant;
+    use crate::core::entities::variant_value::VariantValue;
+    use crate::core::ports::database::product_database::ProductDatastore;
+    use crate::core::ports::database::product_type_database::ProductTypeDatastore;
+    use crate::create_connection_pool_test;
+    use crate::datastore::repositories::product_repository::ProductRepository;
+    use crate::datastore::repositories::product_type_repository::ProductTypeRepository;
+    use crate::datastore::tenancy::set_current_tenant;
+    use crate::DbPool;
+    use actix_web::web;
+    use async_graphql::dataloader::Loader;
+    use async_graphql::{Request, Response};
+    use diesel::r2d2::{ConnectionManager, Pool};
+    use diesel::PgConnection;
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn tenant() -> Tenant {
+        "graphql".parse().unwrap()
+    }
+
+    fn writer(scopes: &[&str]) -> Principal {
+        Principal::new(
+            "storefront".to_string(),
+            AuthMethod::ApiKey,
+            scopes.iter().map(|scope| scope.to_string()).collect(),
+        )
+        .with_tenant(tenant())
+    }
+
+    // two products of one type, boots in two sizes and one colour and sandals in one size
+    fn create_products(pool: &web::Data<DbPool>) -> (ProductId, ProductId) {
+        let mut connection = pool.get().unwrap();
+        set_current_tenant(&mut connection, &tenant()).expect("Error setting tenant");
+
+        let product_type_id = ProductTypeRepository::new(&mut connection)
+            .create_product_type(ProductType::new("Footwear".to_string(), vec![], None))
+            .expect("Error creating product type")
+            .id();
+        let mut product_repository = ProductRepository::new(&mut connection);
+        let mut create = |name: &str, variant_values| {
+            product_repository
+                .create_complete_product(CompleteProduct::new(
+                    Product::new(name.to_string(), 60.0, true, None)
+                        .with_attributes(product_type_id, Default::default()),
+                    variant_values,
+                ))
+                .expect("Error creating product")
+        };
+        let boots_id = create(
+            "Boots",
+            vec![
+                VariantValue::new(
+                    Variant::new("graphql size".to_string(), None),
+                    vec![Some("40".to_string()), Some("41".to_string())],
+                ),
+                VariantValue::new(Variant::new("graphql colour".to_string(), None), vec![Some("red".to_string())]),
+            ],
+        );
+        let sandals_id = create(
+            "Sandals",
+            vec![VariantValue::new(Variant::new("graphql size".to_string(), None), vec![Some("38".to_string())])],
+        );
+
+        (boots_id, sandals_id)
+    }
+
+    async fn execute(pool: &web::Data<DbPool>, principal: Option<Principal>, query: &str) -> Response {
+        schema()
+            .execute(with_request_data(Request::new(query), pool.clone(), tenant(), principal))
+            .await
+    }
+
+    fn error_status(response: &Response) -> Option<async_graphql::Value> {
+        response.errors[0].extensions.as_ref().and_then(|extensions| extensions.get("status")).cloned()
+    }
+
+    #[actix_web::test]
+    async fn test_products_are_listed_with_their_types_and_variants() {
+        let pool = web::Data::new(create_connection_pool_test());
+        create_products(&pool);
+
+        let response = execute(
+            &pool,
+            None,
+            "{ products { name productType { name } variants { variant { name } values } } }",
+        )
+        .await;
+
+        assert_eq!(Vec::<async_graphql::ServerError>::new(), response.errors);
+        assert_eq!(
+            json!({"products": [
+                {
+                    "name": "Boots",
+                    "productType": {"name": "Footwear"},
+                    "variants": [
+                        {"variant": {"name": "graphql size"}, "values": ["40", "41"]},
+                        {"variant": {"name": "graphql colour"}, "values": ["red"]},
+                    ],
+                },
+                {
+                    "name": "Sandals",
+                    "productType": {"name": "Footwear"},
+                    "variants": [{"variant": {"name": "graphql size"}, "values": ["38"]}],
+                },
+            ]}),
+            response.data.into_json().unwrap()
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_products_are_found_by_reference() {
+        let pool = web::Data::new(create_connection_pool_test());
+        let (boots_id, _) = create_products(&pool);
+
+        let query = format!(r#"{{ product(reference: "{}") {{ name slug }} }}"#, boots_id);
+        let response = execute(&pool, None, &query).await;
+        let data = response.data.into_json().unwrap();
+        assert_eq!("Boots", data["product"]["name"]);
+
+        let slug = data["product"]["slug"].as_str().unwrap().to_string();
+        let response = execute(&pool, None, &format!(r#"{{ product(reference: "{}") {{ id }} }}"#, slug)).await;
+        assert_eq!(json!({"product": {"id": boots_id.to_string()}}), response.data.into_json().unwrap());
+
+        let response = execute(&pool, None, r#"{ product(reference: "no-such-product") { id } }"#).await;
+        assert_eq!(json!({"product": null}), response.data.into_json().unwrap());
+    }
+
+    #[actix_web::test]
+    async fn test_variants_of_all_products_are_loaded_at_once() {
+        let pool = web::Data::new(create_connection_pool_test());
+        let (boots_id, sandals_id) = create_products(&pool);
+
+        let variant_values = ProductVariantsLoader {
+            pool: pool.clone(),
+            tenant: tenant(),
+        }
+        .load(&[boots_id, sandals_id])
+        .await
+        .expect("Error loading variants");
+
+        assert_eq!(2, variant_values[&boots_id].len());
+        assert_eq!(vec![Some("40".to_string()), Some("41".to_string())], variant_values[&boots_id][0].values);
+        assert_eq!(vec![Some("38".to_string())], variant_values[&sandals_id][0].values);
+    }
+
+    #[test]
+    fn test_variant_values_are_grouped_by_product_and_variant() {
+        let boots_id = ProductId::try_from(1).unwrap();
+        let sandals_id = ProductId::try_from(2).unwrap();
+        let size_id = VariantId::try_from(1).unwrap();
+        let colour_id = VariantId::try_from(2).unwrap();
+        let size = Variant::new("size".to_string(), Some(size_id));
+        let colour = Variant::new("colour".to_string(), Some(colour_id));
+
+        let variant_values = group_variant_values(vec![
+            (ProductVariant::new(boots_id, size_id, Some("40".to_string())), size.clone()),
+            (ProductVariant::new(sandals_id, size_id, Some("38".to_string())), size.clone()),
+            (ProductVariant::new(boots_id, colour_id, Some("red".to_string())), colour),
+            (ProductVariant::new(boots_id, size_id, Some("41".to_string())), size),
+        ]);
+
+        let boots_values = variant_values[&boots_id]
+            .iter()
+            .map(|variant_value| (variant_value.variant.name.as_str(), variant_value.values.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("size", vec![Some("40".to_string()), Some("41".to_string())]),
+                ("colour", vec![Some("red".to_string())]),
+            ],
+            boots_values
+        );
+        assert_eq!(vec![Some("38".to_string())], variant_values[&sandals_id][0].values);
+    }
+
+    #[actix_web::test]
+    async fn test_page_sizes_and_query_depth_are_capped() {
+        let pool = web::Data::new(create_connection_pool_test());
+
+        let response = execute(&pool, None, "{ products(limit: 101) { name } }").await;
+        assert_eq!(Some(async_graphql::Value::from(400)), error_status(&response));
+
+        let response = execute(&pool, None, "{ products(offset: -1) { name } }").await;
+        assert_eq!(Some(async_graphql::Value::from(400)), error_status(&response));
+
+        let response = execute(
+            &pool,
+            None,
+            "{ __schema { types { fields { type { ofType { ofType { ofType { ofType { ofType { ofType { ofType { \
+             name } } } } } } } } } } } }",
+        )
+        .await;
+        assert_eq!(1, response.errors.len());
+        assert!(response.errors[0].message.contains("nested too deep"), "{}", response.errors[0].message);
+    }
+
+    #[actix_web::test]
+    async fn test_creating_products_needs_a_principal_allowed_to_write_the_catalog() {
+        let pool = web::Data::new(create_connection_pool_test());
+        let mutation = r#"mutation {
+            createProduct(input: {
+                name: "Clogs", cost: 35.0, active: true, variants: [{name: "graphql width", values: ["wide"]}]
+            }) {
+                name
+                version
+                variants { variant { name } values }
+            }
+        }"#;
+
+        let response = execute(&pool, None, mutation).await;
+        assert_eq!(Some(async_graphql::Value::from(401)), error_status(&response));
+
+        let response = execute(&pool, Some(writer(&["pricing:write"])), mutation).await;
+        assert_eq!(Some(async_graphql::Value::from(403)), error_status(&response));
+
+        let response = execute(&pool, Some(writer(&["catalog:write"])), mutation).await;
+        assert_eq!(Vec::<async_graphql::ServerError>::new(), response.errors);
+        assert_eq!(
+            json!({"createProduct": {
+                "name": "Clogs",
+                "version": 1,
+                "variants": [{"variant": {"name": "graphql width"}, "values": ["wide"]}],
+            }}),
+            response.data.into_json().unwrap()
+        );
+
+        let response = execute(&pool, None, "{ products { name } }").await;
+        assert_eq!(json!({"products": [{"name": "Clogs"}]}), response.data.into_json().unwrap());
+    }
+
+    #[actix_web::test]
+    async fn test_failing_product_listing_is_answered_with_an_error() {
+        // a pool whose database cannot be reached, so that listing products fails
+        let pool = Pool::builder()
+            .connection_timeout(Duration::from_millis(200))
+            .build_unchecked(ConnectionManager::<PgConnection>::new("postgres://nobody@127.0.0.1:1/nothing"));
+
+        let response = schema()
+            .execute(
+                Request::new("{ products { name } }")
+                    .data(web::Data::new(pool))
+                    .data(Tenant::default()),
+            )
+            .await;
+
+        assert_eq!(1, response.errors.len());
+        assert_eq!("Internal server error", response.errors[0].message);
+        assert_eq!(
+            Some(&async_graphql::Value::from(500)),
+            response.errors[0].extensions.as_ref().and_then(|extensions| extensions.get("status"))
+        );
+    }
+}
@@ -0,0 +1,277 @@
+use crate::DbPool;
+use crate::api::auth::authorize;
+use crate::api::errors::ApiError;
+use crate::api::with_product_repository;
+use crate::core::authorization::AuthorizedProductDatastore;
+use crate::core::entities::idempotency_record::{IdempotencyRecord, IdempotentResponse};
+use crate::core::entities::principal::Principal;
+use crate::core::entities::tenant::Tenant;
+use crate::core::ports::database::idempotency_database::IdempotencyDatastore;
+use crate::datastore::repositories::idempotency_repository::IdempotencyRepository;
+use crate::datastore::repositories::product_repository::ProductRepository;
+use crate::datastore::tenancy::set_current_tenant;
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpRequest, HttpResponse};
+use anyhow::Result as AnyResult;
+use chrono::{Duration, Utc};
+use diesel::Connection;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::env;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "Idempotent-Replayed";
+
+const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+const DEFAULT_IDEMPOTENCY_KEY_TTL_SECONDS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone)]
+pub struct IdempotencyConfig {
+    // how long a key and its response are kept for replays
+    pub ttl: Duration,
+}
+
+impl IdempotencyConfig {
+    pub fn from_env() -> IdempotencyConfig {
+        let ttl_seconds = env::var("IDEMPOTENCY_KEY_TTL_SECONDS")
+            .ok()
+            .and_then(|value| value.parse::<i64>().ok())
+            .unwrap_or(DEFAULT_IDEMPOTENCY_KEY_TTL_SECONDS);
+
+        IdempotencyConfig {
+            ttl: Duration::seconds(ttl_seconds),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum IdempotencyOutcome {
+    Completed(IdempotentResponse),
+    Replayed(IdempotentResponse),
+    InProgress,
+    KeyReused,
+}
+
+// what a request with the same key as an earlier one is answered with: the earlier response once
+// there is one, as long as the request is the same as the earlier one
+fn existing_key_outcome(record: &IdempotencyRecord, fingerprint: &str) -> IdempotencyOutcome {
+    if record.fingerprint() != fingerprint {
+        return IdempotencyOutcome::KeyReused;
+    }
+
+    match record.response() {
+        Some(response) => IdempotencyOutcome::Replayed(response.clone()),
+        None => IdempotencyOutcome::InProgress,
+    }
+}
+
+// fingerprints a request by its method, path and deserialized body, so that formatting
+// differences in the body do not make a retry look like a different request
+pub fn request_fingerprint(request: &HttpRequest, payload: &impl Serialize) -> AnyResult<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(request.method().as_str());
+    hasher.update(b" ");
+    hasher.update(request.path());
+    hasher.update(b"\n");
+    hasher.update(serde_json::to_vec(payload)?);
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+// runs a create operation. When the request carries an `Idempotency-Key` the key is reserved in the
+// same transaction as the create and the response is stored with it, so a retry with the same key
+// and body gets the original response back instead of creating a duplicate. Keys are kept apart by
+// tenant and by principal, and the create is made for `tenant` and audited as made by `principal`
+pub(crate) async fn idempotent_create<F>(
+    request: &HttpRequest,
+    pool: web::Data<DbPool>,
+    config: &IdempotencyConfig,
+    tenant: Tenant,
+    principal: Principal,
+    payload: &impl Serialize,
+    operation: F,
+) -> Result<HttpResponse, ApiError>
+where
+    F: FnOnce(&mut AuthorizedProductDatastore<ProductRepository<'_>>) -> AnyResult<IdempotentResponse>
+        + Send
+        + 'static,
+{
+    let key = match request.headers().get(IDEMPOTENCY_KEY_HEADER) {
+        None => {
+            let response = with_product_repository(pool, tenant, principal, operation).await?;
+
+            return Ok(to_http_response(&response, false));
+        }
+        Some(value) => value
+            .to_str()
+            .ok()
+            .filter(|key| !key.is_empty() && key.len() <= MAX_IDEMPOTENCY_KEY_LENGTH)
+            .map(String::from)
+            .ok_or_else(|| {
+                ApiError::BadRequest(format!(
+                    "{} must be between 1 and {} visible ASCII characters",
+                    IDEMPOTENCY_KEY_HEADER, MAX_IDEMPOTENCY_KEY_LENGTH
+                ))
+            })?,
+    };
+    let fingerprint = request_fingerprint(request, payload)?;
+    let expires_at = Utc::now() + config.ttl;
+    let actor = principal.actor();
+
+    let outcome = web::block(move || {
+        let mut connection = pool.get()?;
+        set_current_tenant(&mut connection, &tenant)?;
+
+        connection.transaction::<_, anyhow::Error, _>(|connection| {
+            let existing_key = IdempotencyRepository::new(connection).reserve_idempotency_key(
+                &actor,
+                &key,
+                &fingerprint,
+                expires_at,
+            )?;
+
+            match existing_key {
+                Some(record) => Ok(existing_key_outcome(&record, &fingerprint)),
+                None => {
+                    let authorization = authorize(connection, &principal)?;
+                    let response = operation(&mut AuthorizedProductDatastore::new(
+                        ProductRepository::new(connection).with_actor(actor.as_str()),
+                        authorization,
+                    ))?;
+                    IdempotencyRepository::new(connection).complete_idempotency_key(&actor, &key, &response)?;
+
+                    Ok(IdempotencyOutcome::Completed(response))
+                }
+            }
+        })
+    })
+    .await??;
+
+    match outcome {
+        IdempotencyOutcome::Completed(response) => Ok(to_http_response(&response, false)),
+        IdempotencyOutcome::Replayed(response) => Ok(to_http_response(&response, true)),
+        IdempotencyOutcome::InProgress => Err(ApiError::Conflict(format!(
+            "A request with this {} is still being processed",
+            IDEMPOTENCY_KEY_HEADER
+        ))),
+        IdempotencyOutcome::KeyReused => Err(ApiError::UnprocessableEntity(format!(
+            "This {} was already used for a different request",
+            IDEMPOTENCY_KEY_HEADER
+        ))),
+    }
+}
+
+fn to_http_response(response: &IdempotentResponse, replayed: bool) -> HttpResponse {
+    let status =
+        StatusCode::from_u16(response.status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let mut builder = HttpResponse::build(status);
+
+    for (name, value) in response.headers() {
+        builder.insert_header((name.as_str(), value.as_str()));
+    }
+
+    if replayed {
+        builder.insert_header((IDEMPOTENT_REPLAYED_HEADER, "true"));
+    }
+
+    builder.json(response.body())
+}
+
+#[cfg(test)]
+mod idempotency_tests {
+    use crate::api::errors::ApiError;
+    use crate::api::idempotency::{
+        existing_key_outcome, idempotent_create, IdempotencyConfig, IdempotencyOutcome, IDEMPOTENCY_KEY_HEADER,
+        IDEMPOTENT_REPLAYED_HEADER,
+    };
+    use crate::core::entities::idempotency_record::{IdempotencyRecord, IdempotentResponse};
+    use crate::core::entities::principal::{AuthMethod, Principal};
+    use crate::core::entities::product::Product;
+    use crate::core::entities::tenant::Tenant;
+    use crate::core::ports::database::product_database::ProductDatastore;
+    use crate::create_connection_pool_test;
+    use crate::datastore::models::schema::products;
+    use actix_web::body::to_bytes;
+    use actix_web::test::TestRequest;
+    use actix_web::{web, HttpRequest, HttpResponse};
+    use chrono::{Duration, Utc};
+    use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+    use serde_json::{json, Value};
+
+    fn writer(subject: &str) -> Principal {
+        Principal::new(subject.to_string(), AuthMethod::ApiKey, vec!["catalog:write".to_string()])
+    }
+
+    fn request_with_key(key: &str) -> HttpRequest {
+        TestRequest::post().uri("/products").insert_header((IDEMPOTENCY_KEY_HEADER, key)).to_http_request()
+    }
+
+    async fn create(
+        pool: &web::Data<crate::DbPool>,
+        principal: Principal,
+        key: &str,
+        payload: Value,
+    ) -> Result<HttpResponse, ApiError> {
+        let config = IdempotencyConfig { ttl: Duration::hours(1) };
+        let name = payload["name"].as_str().unwrap().to_string();
+
+        let request = request_with_key(key);
+
+        idempotent_create(&request, pool.clone(), &config, Tenant::default(), principal, &payload, move |repository| {
+            let product = repository.create_product(Product::new(name, 80.0, true, None))?;
+
+            Ok(IdempotentResponse::new(201, vec![], serde_json::to_value(&product)?))
+        })
+        .await
+    }
+
+    async fn body(response: HttpResponse) -> Value {
+        serde_json::from_slice(&to_bytes(response.into_body()).await.unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_existing_keys_replay_only_finished_requests_with_the_same_fingerprint() {
+        let response = IdempotentResponse::new(201, vec![], json!({"name": "Clogs"}));
+        let finished = IdempotencyRecord::new(
+            "key".to_string(),
+            "fingerprint".to_string(),
+            Some(response.clone()),
+            Utc::now(),
+        );
+        let running = IdempotencyRecord::new("key".to_string(), "fingerprint".to_string(), None, Utc::now());
+
+        assert_eq!(IdempotencyOutcome::Replayed(response), existing_key_outcome(&finished, "fingerprint"));
+        assert_eq!(IdempotencyOutcome::KeyReused, existing_key_outcome(&finished, "other fingerprint"));
+        assert_eq!(IdempotencyOutcome::InProgress, existing_key_outcome(&running, "fingerprint"));
+    }
+
+    #[actix_web::test]
+    async fn test_retried_creates_replay_the_response_of_the_first_one() {
+        let pool = web::Data::new(create_connection_pool_test());
+        let payload = json!({"name": "Idempotent clogs"});
+
+        let first = create(&pool, writer("storefront"), "retry-1", payload.clone()).await.unwrap();
+        assert!(first.headers().get(IDEMPOTENT_REPLAYED_HEADER).is_none());
+        let first_body = body(first).await;
+
+        let retry = create(&pool, writer("storefront"), "retry-1", payload.clone()).await.unwrap();
+        assert_eq!(201, retry.status().as_u16());
+        assert_eq!("true", retry.headers().get(IDEMPOTENT_REPLAYED_HEADER).unwrap());
+        assert_eq!(first_body, body(retry).await);
+
+        let reused = create(&pool, writer("storefront"), "retry-1", json!({"name": "Other clogs"})).await;
+        assert!(matches!(reused, Err(ApiError::UnprocessableEntity(_))));
+
+        // another client of the tenant that picks the same key makes its own product
+        let other_client = create(&pool, writer("warehouse"), "retry-1", payload).await.unwrap();
+        assert!(other_client.headers().get(IDEMPOTENT_REPLAYED_HEADER).is_none());
+        assert_ne!(first_body["id"], body(other_client).await["id"]);
+
+        let created_products = products::table
+            .filter(products::name.eq("Idempotent clogs"))
+            .count()
+            .get_result::<i64>(&mut pool.get().unwrap())
+            .unwrap();
+        assert_eq!(2, created_products);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::DbPool;
+use crate::api::auth::authorize;
+use crate::api::errors::{ApiError, ErrorBody};
+use crate::api::with_connection;
+use crate::core::entities::principal::Principal;
+use crate::core::entities::product_image::ProductImage;
+use crate::core::entities::product_reference::ProductReference;
+use crate::core::entities::role::Permission;
+use crate::core::entities::tenant::Tenant;
+use crate::core::ports::database::image_database::ImageDatastore;
+use crate::core::ports::database::product_database::ProductDatastore;
+use crate::datastore::repositories::image_repository::ImageRepository;
+use crate::datastore::repositories::product_repository::ProductRepository;
+use actix_web::{web, HttpResponse};
+use std::collections::HashSet;
+use utoipa::OpenApi;
+
+pub fn configure(config: &mut web::ServiceConfig) {
+    config.service(
+        web::resource("/products/{reference}/images")
+            .route(web::get().to(list_product_images))
+            .route(web::put().to(set_product_images)),
+    );
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list_product_images, set_product_images))]
+pub struct ImagesApi;
+
+// images are linked from exports and feeds, so each must be an absolute http(s) URL and given once
+fn validate_images(images: &[ProductImage]) -> Result<(), ApiError> {
+    let mut urls = HashSet::new();
+
+    for image in images {
+        let url = image.url();
+        let has_host = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("http://"))
+            .is_some_and(|rest| !rest.is_empty() && !rest.starts_with('/'));
+        if !has_host || url.chars().any(char::is_whitespace) {
+            return Err(ApiError::BadRequest(format!("{} is not an http or https URL", url)));
+        }
+        if !urls.insert(url) {
+            return Err(ApiError::BadRequest(format!("Image {} is given more than once", url)));
+        }
+    }
+
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/products/{reference}/images",
+    tag = "images",
+    params(("reference" = ProductReference, Path, description = "The product's ID, public ID or slug")),
+    responses(
+        (status = 200, description = "The images of the product", body = Vec<ProductImage>),
+        (status = 404, description = "There is no such product", body = ErrorBody),
+    )
+)]
+async fn list_product_images(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    path: web::Path<ProductReference>,
+) -> Result<HttpResponse, ApiError> {
+    let reference = path.into_inner();
+
+    let images = with_connection(pool, tenant, move |connection| {
+        let id = ProductRepository::new(connection).resolve_product(&reference)?.id();
+
+        ImageRepository::new(connection).list_product_images(id)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(images))
+}
+
+#[utoipa::path(
+    put,
+    path = "/products/{reference}/images",
+    tag = "images",
+    params(("reference" = ProductReference, Path, description = "The product's ID, public ID or slug")),
+    request_body = Vec<ProductImage>,
+    responses(
+        (status = 200, description = "The saved images, which replace the ones the product had", body = Vec<ProductImage>),
+        (status = 400, description = "An image is not valid", body = ErrorBody),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client may not write the catalog", body = ErrorBody),
+        (status = 404, description = "There is no such product", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn set_product_images(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    path: web::Path<ProductReference>,
+    payload: web::Json<Vec<ProductImage>>,
+) -> Result<HttpResponse, ApiError> {
+    let reference = path.into_inner();
+    let images = payload.into_inner();
+    validate_images(&images)?;
+
+    let saved_images = with_connection(pool, tenant, move |connection| {
+        authorize(connection, &principal)?.require(Permission::CatalogWrite)?;
+
+        let id = ProductRepository::new(connection).resolve_product(&reference)?.id();
+
+        ImageRepository::new(connection).with_actor(principal.actor()).set_product_images(id, images)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(saved_images))
+}
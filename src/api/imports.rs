@@ -0,0 +1,77 @@
+use crate::DbPool;
+use crate::api::errors::{ApiError, ErrorBody};
+use crate::api::with_product_repository;
+use crate::core::entities::principal::Principal;
+use crate::core::entities::tenant::Tenant;
+use crate::import::{ImportFormat, ImportOptions, ImportReport, import_products};
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi};
+
+// import files are uploaded whole, so they get a much larger limit than regular payloads
+const IMPORT_PAYLOAD_LIMIT: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImportQuery {
+    pub format: String,
+    pub dry_run: Option<bool>,
+    pub batch_size: Option<usize>,
+}
+
+pub fn configure(config: &mut web::ServiceConfig) {
+    config.service(
+        web::resource("/product-imports")
+            .app_data(web::PayloadConfig::new(IMPORT_PAYLOAD_LIMIT))
+            .route(web::post().to(import_product_file)),
+    );
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(import_product_file))]
+pub struct ImportsApi;
+
+#[utoipa::path(
+    post,
+    path = "/product-imports",
+    tag = "imports",
+    params(ImportQuery),
+    request_body(
+        description = "A file of products in the given format, `csv` or `jsonl`",
+        content(
+            (String = "text/csv"),
+            (String = "application/x-ndjson"),
+        )
+    ),
+    responses(
+        (status = 200, description = "What the import did, and the rows it could not import", body = ImportReport),
+        (status = 400, description = "The format is not one products can be imported from", body = ErrorBody),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client may not write the catalog", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn import_product_file(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    query: web::Query<ImportQuery>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ApiError> {
+    let format = query
+        .format
+        .parse::<ImportFormat>()
+        .map_err(|error| ApiError::BadRequest(error.to_string()))?;
+    let mut options = ImportOptions::new(format);
+    options.dry_run = query.dry_run.unwrap_or(false);
+    if let Some(batch_size) = query.batch_size {
+        options.batch_size = batch_size;
+    }
+
+    let report = with_product_repository(pool, tenant, principal, move |repository| {
+        import_products(body.as_ref(), &options, repository)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(report))
+}
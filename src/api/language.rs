@@ -0,0 +1,37 @@
+use crate::core::entities::locale::{parse_accept_language, Locale, LocaleFallback};
+use actix_web::http::header;
+use actix_web::HttpRequest;
+use std::env;
+
+const DEFAULT_LOCALE: &str = "en";
+
+#[derive(Debug, Clone)]
+pub struct LocaleConfig {
+    // the locale that the fields of products and variants are written in. Every fallback chain
+    // ends here
+    pub default_locale: Locale,
+}
+
+impl LocaleConfig {
+    pub fn from_env() -> LocaleConfig {
+        let default_locale = env::var("DEFAULT_LOCALE")
+            .ok()
+            .and_then(|value| value.parse::<Locale>().ok())
+            .unwrap_or_else(|| DEFAULT_LOCALE.parse().expect("the default locale is a valid locale"));
+
+        LocaleConfig { default_locale }
+    }
+}
+
+// the locales to read a request's content in, from its `Accept-Language` header. Requests without
+// one read the default locale
+pub fn request_locale_fallback(request: &HttpRequest, config: &LocaleConfig) -> LocaleFallback {
+    let preferred = request
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .map(parse_accept_language)
+        .unwrap_or_default();
+
+    LocaleFallback::new(&preferred, config.default_locale.clone())
+}
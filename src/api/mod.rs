@@ -0,0 +1,91 @@
+pub mod auth;
+pub mod bundles;
+pub mod changes;
+pub mod errors;
+pub mod etag;
+pub mod exports;
+pub mod graphql;
+pub mod idempotency;
+pub mod images;
+pub mod imports;
+pub mod language;
+pub mod openapi;
+pub mod product_types;
+pub mod products;
+pub mod rate_limit;
+pub mod relations;
+pub mod roles;
+pub mod specifications;
+pub mod tenancy;
+pub mod translations;
+pub mod webhooks;
+
+use crate::DbPool;
+use crate::api::auth::authorize;
+use crate::api::errors::ApiError;
+use crate::core::authorization::AuthorizedProductDatastore;
+use crate::core::entities::principal::Principal;
+use crate::core::entities::tenant::Tenant;
+use crate::datastore::repositories::product_repository::ProductRepository;
+use crate::datastore::tenancy::set_current_tenant;
+use diesel::PgConnection;
+use actix_web::web;
+use anyhow::Result as AnyResult;
+
+pub fn configure(config: &mut web::ServiceConfig) {
+    products::configure(config);
+    product_types::configure(config);
+    imports::configure(config);
+    exports::configure(config);
+    translations::configure(config);
+    specifications::configure(config);
+    images::configure(config);
+    bundles::configure(config);
+    relations::configure(config);
+    roles::configure(config);
+    webhooks::configure(config);
+    changes::configure(config);
+    graphql::configure(config);
+    openapi::configure(config);
+}
+
+// runs a datastore operation on the blocking thread pool, with a pooled connection that works for
+// `tenant`, for operations that go through more than one repository
+pub(crate) async fn with_connection<F, R>(pool: web::Data<DbPool>, tenant: Tenant, operation: F) -> Result<R, ApiError>
+where
+    F: FnOnce(&mut PgConnection) -> AnyResult<R> + Send + 'static,
+    R: Send + 'static,
+{
+    web::block(move || {
+        let mut connection = pool.get()?;
+        set_current_tenant(&mut connection, &tenant)?;
+
+        operation(&mut connection)
+    })
+    .await?
+    .map_err(ApiError::from)
+}
+
+// runs a datastore operation on the blocking thread pool, with a product repository over a pooled
+// connection that works for `tenant`, only does what `principal` is allowed to and audits writes as
+// made by it
+pub(crate) async fn with_product_repository<F, R>(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    operation: F,
+) -> Result<R, ApiError>
+where
+    F: FnOnce(&mut AuthorizedProductDatastore<ProductRepository<'_>>) -> AnyResult<R> + Send + 'static,
+    R: Send + 'static,
+{
+    with_connection(pool, tenant, move |connection| {
+        let authorization = authorize(connection, &principal)?;
+
+        operation(&mut AuthorizedProductDatastore::new(
+            ProductRepository::new(connection).with_actor(principal.actor()),
+            authorization,
+        ))
+    })
+    .await
+}
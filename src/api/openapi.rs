@@ -0,0 +1,223 @@
+use crate::api::auth::API_KEY_HEADER;
+use crate::api::{
+    bundles, changes, exports, graphql, images, imports, product_types, products, relations, roles,
+    specifications, translations, webhooks,
+};
+use actix_web::{web, HttpResponse};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+use utoipa_scalar::{Scalar, Servable};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Product Store",
+        description = "Products, their variants and everything attached to them. Authenticated clients work \
+                       for their own tenant, anonymous ones pick one with an `X-Tenant-ID` header."
+    ),
+    modifiers(&Credentials)
+)]
+struct ApiDoc;
+
+// the two ways a client can authenticate, see `auth::authenticate`
+struct Credentials;
+
+impl Modify for Credentials {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(API_KEY_HEADER))),
+        );
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build()),
+        );
+    }
+}
+
+// the OpenAPI document of everything `api::configure` serves, put together from the operations each
+// module documents next to its own `configure`
+pub fn api_doc() -> utoipa::openapi::OpenApi {
+    let mut doc = ApiDoc::openapi();
+    doc.merge(products::ProductsApi::openapi());
+    doc.merge(product_types::ProductTypesApi::openapi());
+    doc.merge(imports::ImportsApi::openapi());
+    doc.merge(exports::ExportsApi::openapi());
+    doc.merge(translations::TranslationsApi::openapi());
+    doc.merge(specifications::SpecificationsApi::openapi());
+    doc.merge(images::ImagesApi::openapi());
+    doc.merge(bundles::BundlesApi::openapi());
+    doc.merge(relations::RelationsApi::openapi());
+    doc.merge(roles::RolesApi::openapi());
+    doc.merge(webhooks::WebhooksApi::openapi());
+    doc.merge(changes::ChangesApi::openapi());
+    doc.merge(graphql::GraphqlApi::openapi());
+
+    doc
+}
+
+pub fn configure(config: &mut web::ServiceConfig) {
+    let doc = api_doc();
+
+    config
+        .service(
+            web::resource("/openapi.json")
+                .app_data(web::Data::new(doc.clone()))
+                .route(web::get().to(get_openapi_document)),
+        )
+        .service(Scalar::with_url("/docs", doc));
+}
+
+async fn get_openapi_document(doc: web::Data<utoipa::openapi::OpenApi>) -> HttpResponse {
+    HttpResponse::Ok().json(doc.get_ref())
+}
+
+#[cfg(test)]
+mod openapi_tests {
+    use crate::api::configure;
+    use crate::api::openapi::api_doc;
+    use actix_web::http::{Method, StatusCode};
+    use actix_web::test::{call_service, init_service, TestRequest};
+    use actix_web::{web, App, HttpResponse};
+    use std::collections::BTreeSet;
+
+    // the modules that register routes in `api::configure`, by name and as they are written
+    const API_SOURCES: [(&str, &str); 13] = [
+        ("products", include_str!("products.rs")),
+        ("product_types", include_str!("product_types.rs")),
+        ("imports", include_str!("imports.rs")),
+        ("exports", include_str!("exports.rs")),
+        ("translations", include_str!("translations.rs")),
+        ("specifications", include_str!("specifications.rs")),
+        ("images", include_str!("images.rs")),
+        ("bundles", include_str!("bundles.rs")),
+        ("relations", include_str!("relations.rs")),
+        ("roles", include_str!("roles.rs")),
+        ("webhooks", include_str!("webhooks.rs")),
+        ("changes", include_str!("changes.rs")),
+        ("graphql", include_str!("graphql.rs")),
+    ];
+
+    // the operations the OpenAPI document describes, e.g. `("GET", "/products/{reference}")`
+    fn documented_operations() -> BTreeSet<(String, String)> {
+        let doc = serde_json::to_value(api_doc()).unwrap();
+
+        doc["paths"]
+            .as_object()
+            .unwrap()
+            .iter()
+            .flat_map(|(path, operations)| {
+                operations
+                    .as_object()
+                    .unwrap()
+                    .keys()
+                    .map(move |method| (method.to_uppercase(), path.clone()))
+            })
+            .collect()
+    }
+
+    // the operations the API modules register, read off the `web::resource("...")` and
+    // `.route(web::get()...)` calls in their sources in the order they are written
+    fn registered_operations() -> BTreeSet<(String, String)> {
+        const RESOURCE: &str = "web::resource(\"";
+        const ROUTE: &str = ".route(web::";
+        let mut operations = BTreeSet::new();
+
+        for (_, source) in API_SOURCES {
+            let mut resource = None;
+            let mut rest = source;
+
+            loop {
+                let next_resource = rest.find(RESOURCE);
+                let next_route = rest.find(ROUTE);
+                match (next_resource, next_route) {
+                    (Some(at), route) if route.is_none_or(|route| at < route) => {
+                        let path = &rest[at + RESOURCE.len()..];
+                        let end = path.find('"').unwrap();
+                        resource = Some(path[..end].to_string());
+                        rest = &path[end..];
+                    }
+                    (_, Some(at)) => {
+                        let method = &rest[at + ROUTE.len()..];
+                        let end = method.find('(').unwrap();
+                        let path = resource.clone().expect("a route is registered outside of a resource");
+                        operations.insert((method[..end].to_uppercase(), path));
+                        rest = &method[end..];
+                    }
+                    (_, None) => break,
+                }
+            }
+        }
+
+        operations
+    }
+
+    // the modules `api::configure` wires in, read off its `module::configure(config)` calls
+    fn configured_modules() -> BTreeSet<&'static str> {
+        const SOURCE: &str = include_str!("mod.rs");
+        let body = &SOURCE[SOURCE.find("pub fn configure(").unwrap()..];
+        let body = &body[..body.find("\n}").unwrap()];
+
+        body.lines()
+            .filter_map(|line| line.trim().strip_suffix("::configure(config);"))
+            .collect()
+    }
+
+    #[test]
+    fn test_every_configured_module_is_checked_against_the_document() {
+        let configured = configured_modules();
+        let checked = API_SOURCES.iter().map(|(module, _)| *module).collect::<BTreeSet<_>>();
+
+        // the document is served next to the API it describes rather than being part of it
+        let unchecked = configured
+            .iter()
+            .filter(|module| **module != "openapi" && !checked.contains(*module))
+            .collect::<Vec<_>>();
+        let unconfigured = checked.difference(&configured).collect::<Vec<_>>();
+
+        assert!(configured.contains("products"), "no modules were read off `api::configure`");
+        assert!(unchecked.is_empty(), "modules missing from API_SOURCES: {:?}", unchecked);
+        assert!(unconfigured.is_empty(), "modules in API_SOURCES that are not configured: {:?}", unconfigured);
+    }
+
+    #[test]
+    fn test_openapi_document_describes_every_route() {
+        let documented = documented_operations();
+        let registered = registered_operations();
+
+        let undocumented = registered.difference(&documented).collect::<Vec<_>>();
+        let unregistered = documented.difference(&registered).collect::<Vec<_>>();
+
+        assert!(undocumented.is_empty(), "routes missing from the OpenAPI document: {:?}", undocumented);
+        assert!(unregistered.is_empty(), "documented operations that are not routed: {:?}", unregistered);
+    }
+
+    #[actix_web::test]
+    async fn test_documented_operations_are_routed() {
+        let app = init_service(
+            App::new()
+                .configure(configure)
+                .default_service(web::to(|| async { HttpResponse::ImATeapot().finish() })),
+        )
+        .await;
+
+        for (method, path) in documented_operations() {
+            // path parameters are filled in with a value every one of them parses
+            let uri = path
+                .split('/')
+                .map(|segment| if segment.starts_with('{') { "1" } else { segment })
+                .collect::<Vec<_>>()
+                .join("/");
+            let request = TestRequest::default()
+                .method(Method::from_bytes(method.as_bytes()).unwrap())
+                .uri(&uri)
+                .to_request();
+
+            let status = call_service(&app, request).await.status();
+
+            assert_ne!(StatusCode::IM_A_TEAPOT, status, "{} {} is not routed", method, path);
+            assert_ne!(StatusCode::METHOD_NOT_ALLOWED, status, "{} {} is not routed", method, path);
+        }
+    }
+}
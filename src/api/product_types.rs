@@ -0,0 +1,152 @@
+use crate::DbPool;
+use crate::api::auth::authorize;
+use crate::api::errors::{ApiError, ErrorBody};
+use crate::api::with_connection;
+use crate::core::entities::ids::ProductTypeId;
+use crate::core::entities::principal::Principal;
+use crate::core::entities::product_type::{AttributeDefinition, ProductType};
+use crate::core::entities::role::Permission;
+use crate::core::entities::tenant::Tenant;
+use crate::core::ports::database::product_type_database::ProductTypeDatastore;
+use crate::datastore::repositories::product_type_repository::ProductTypeRepository;
+use actix_web::http::header;
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use utoipa::{OpenApi, ToSchema};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ProductTypePayload {
+    pub name: String,
+    #[serde(default)]
+    pub attributes: Vec<AttributeDefinition>,
+}
+
+pub fn configure(config: &mut web::ServiceConfig) {
+    config
+        .service(
+            web::resource("/product-types")
+                .route(web::get().to(list_product_types))
+                .route(web::post().to(create_product_type)),
+        )
+        .service(
+            web::resource("/product-types/{id}")
+                .route(web::get().to(get_product_type))
+                .route(web::put().to(update_product_type)),
+        );
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list_product_types, create_product_type, get_product_type, update_product_type))]
+pub struct ProductTypesApi;
+
+#[utoipa::path(
+    get,
+    path = "/product-types",
+    tag = "product types",
+    responses(
+        (status = 200, description = "Every product type", body = Vec<ProductType>),
+    )
+)]
+async fn list_product_types(pool: web::Data<DbPool>, tenant: Tenant) -> Result<HttpResponse, ApiError> {
+    let product_types =
+        with_connection(pool, tenant, move |connection| ProductTypeRepository::new(connection).list_product_types()).await?;
+
+    Ok(HttpResponse::Ok().json(product_types))
+}
+
+#[utoipa::path(
+    post,
+    path = "/product-types",
+    tag = "product types",
+    request_body = ProductTypePayload,
+    responses(
+        (status = 201, description = "The created product type", body = ProductType, headers(("Location" = String))),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client may not write the catalog", body = ErrorBody),
+        (status = 409, description = "There is a product type with the name already", body = ErrorBody),
+        (status = 422, description = "The attribute definitions are not valid", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn create_product_type(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    payload: web::Json<ProductTypePayload>,
+) -> Result<HttpResponse, ApiError> {
+    let payload = payload.into_inner();
+    let product_type = ProductType::new(payload.name, payload.attributes, None);
+
+    let created_product_type = with_connection(pool, tenant, move |connection| {
+        authorize(connection, &principal)?.require(Permission::CatalogWrite)?;
+
+        ProductTypeRepository::new(connection).with_actor(principal.actor()).create_product_type(product_type)
+    })
+    .await?;
+
+    Ok(HttpResponse::Created()
+        .insert_header((
+            header::LOCATION,
+            format!("/product-types/{}", created_product_type.id().map(|id| id.to_string()).unwrap_or_default()),
+        ))
+        .json(created_product_type))
+}
+
+#[utoipa::path(
+    get,
+    path = "/product-types/{id}",
+    tag = "product types",
+    params(("id" = ProductTypeId, Path, description = "The product type's ID")),
+    responses(
+        (status = 200, description = "The product type", body = ProductType),
+        (status = 404, description = "There is no such product type", body = ErrorBody),
+    )
+)]
+async fn get_product_type(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    path: web::Path<ProductTypeId>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+
+    let product_type =
+        with_connection(pool, tenant, move |connection| ProductTypeRepository::new(connection).get_product_type(id)).await?;
+
+    Ok(HttpResponse::Ok().json(product_type))
+}
+
+#[utoipa::path(
+    put,
+    path = "/product-types/{id}",
+    tag = "product types",
+    params(("id" = ProductTypeId, Path, description = "The product type's ID")),
+    request_body = ProductTypePayload,
+    responses(
+        (status = 200, description = "The updated product type", body = ProductType),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client may not write the catalog", body = ErrorBody),
+        (status = 404, description = "There is no such product type", body = ErrorBody),
+        (status = 422, description = "The attribute definitions are not valid, or products of the type would no longer fit them", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn update_product_type(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    path: web::Path<ProductTypeId>,
+    payload: web::Json<ProductTypePayload>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+    let payload = payload.into_inner();
+    let product_type = ProductType::new(payload.name, payload.attributes, Some(id));
+
+    let updated_product_type = with_connection(pool, tenant, move |connection| {
+        authorize(connection, &principal)?.require(Permission::CatalogWrite)?;
+
+        ProductTypeRepository::new(connection).with_actor(principal.actor()).update_product_type(id, product_type)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(updated_product_type))
+}
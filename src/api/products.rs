@@ -0,0 +1,515 @@
+use crate::DbPool;
+use crate::api::errors::{ApiError, ErrorBody};
+use crate::api::etag::{expected_version, is_not_modified, representation_etag, version_etag};
+use crate::api::idempotency::{idempotent_create, IdempotencyConfig};
+use crate::api::language::{request_locale_fallback, LocaleConfig};
+use crate::api::{with_connection, with_product_repository};
+use crate::core::entities::complete_product::CompleteProduct;
+use crate::core::entities::idempotency_record::IdempotentResponse;
+use crate::core::entities::ids::ProductTypeId;
+use crate::core::entities::principal::Principal;
+use crate::core::entities::product::Product;
+use crate::core::entities::product_detail::ProductDetail;
+use crate::core::entities::product_reference::ProductReference;
+use crate::core::entities::tenant::Tenant;
+use crate::core::entities::variant::Variant;
+use crate::core::entities::variant_value::VariantValue;
+use crate::core::ports::database::product_database::ProductDatastore;
+use crate::core::ports::database::translation_database::TranslationDatastore;
+use crate::core::ports::database::utils::{ListQueryParams, ProductFilter};
+use crate::datastore::repositories::product_repository::ProductRepository;
+use crate::datastore::repositories::translation_repository::TranslationRepository;
+use actix_web::http::{header, StatusCode};
+use actix_web::{web, HttpRequest, HttpResponse};
+use anyhow::Result as AnyResult;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+const DEFAULT_PAGE_SIZE: i64 = 20;
+
+// pages are capped like GraphQL listings are, so that a single request cannot ask for the whole catalog
+const MAX_PAGE_SIZE: i64 = 100;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListProductsQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub product_type: Option<ProductTypeId>,
+    // a JSON object the attributes of listed products must contain, e.g. `{"material":"leather"}`
+    pub attributes: Option<String>,
+}
+
+impl ListProductsQuery {
+    // the page asked for, which must hold at least one and at most `MAX_PAGE_SIZE` products and
+    // must not start before the first product
+    fn list_params(&self) -> Result<ListQueryParams, ApiError> {
+        let limit = self.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        let offset = self.offset.unwrap_or(0);
+        if !(1..=MAX_PAGE_SIZE).contains(&limit) || offset < 0 {
+            return Err(ApiError::BadRequest(format!(
+                "limit must be between 1 and {} and offset must not be negative",
+                MAX_PAGE_SIZE
+            )));
+        }
+
+        Ok(ListQueryParams { limit, offset })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProductPayload {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub cost: f64,
+    pub active: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub product_type_id: Option<ProductTypeId>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub attributes: Map<String, Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stock_quantity: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct VariantPayload {
+    pub name: String,
+    pub values: Vec<Option<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CompleteProductPayload {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub cost: f64,
+    pub active: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub product_type_id: Option<ProductTypeId>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub attributes: Map<String, Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stock_quantity: Option<u32>,
+    pub variants: Vec<VariantPayload>,
+}
+
+// the fields of a product to change, fields that are left out keep their current value
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ProductPatchPayload {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub cost: Option<f64>,
+    pub active: Option<bool>,
+    pub product_type_id: Option<ProductTypeId>,
+    pub attributes: Option<Map<String, Value>>,
+    pub stock_quantity: Option<u32>,
+}
+
+pub fn configure(config: &mut web::ServiceConfig) {
+    config
+        .service(
+            web::resource("/products")
+                .route(web::get().to(list_products))
+                .route(web::post().to(create_product)),
+        )
+        .service(web::resource("/complete-products").route(web::post().to(create_complete_product)))
+        .service(
+            web::resource("/products/{reference}")
+                .route(web::get().to(get_product))
+                .route(web::put().to(update_product))
+                .route(web::patch().to(patch_product))
+                .route(web::delete().to(delete_product)),
+        );
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_products,
+    create_product,
+    create_complete_product,
+    get_product,
+    update_product,
+    patch_product,
+    delete_product
+))]
+pub struct ProductsApi;
+
+#[utoipa::path(
+    get,
+    path = "/products",
+    tag = "products",
+    params(
+        ListProductsQuery,
+        ("Accept-Language" = Option<String>, Header, description = "The locales to show names and descriptions in"),
+    ),
+    responses(
+        (status = 200, description = "A page of products", body = Vec<Product>),
+        (status = 400, description = "The page is out of range or attributes is not a JSON object", body = ErrorBody),
+    )
+)]
+async fn list_products(
+    request: HttpRequest,
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    locale_config: web::Data<LocaleConfig>,
+    query: web::Query<ListProductsQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let query = query.into_inner();
+    let params = query.list_params()?;
+    let filter = ProductFilter {
+        product_type_id: query.product_type,
+        attributes: query
+            .attributes
+            .map(|attributes| serde_json::from_str::<Map<String, Value>>(&attributes))
+            .transpose()
+            .map_err(|error| ApiError::BadRequest(format!("attributes must be a JSON object: {}", error)))?,
+    };
+    let fallback = request_locale_fallback(&request, &locale_config);
+
+    let product_records = with_connection(pool, tenant, move |connection| {
+        TranslationRepository::new(connection).list_localized_products(params, &filter, &fallback)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::VARY, header::ACCEPT_LANGUAGE.as_str()))
+        .json(product_records))
+}
+
+// the response to a product being created, in the form it is stored in for idempotent replays
+fn created_product_response(created_product: &Product) -> AnyResult<IdempotentResponse> {
+    Ok(IdempotentResponse::new(
+        StatusCode::CREATED.as_u16(),
+        vec![
+            (
+                header::ETAG.to_string(),
+                version_etag(created_product.version()).to_string(),
+            ),
+            (
+                header::LOCATION.to_string(),
+                format!("/products/{}", created_product.public_id().map(|id| id.to_string()).unwrap_or_default()),
+            ),
+        ],
+        serde_json::to_value(created_product)?,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/products",
+    tag = "products",
+    params(("Idempotency-Key" = Option<String>, Header, description = "Makes a retried request answer like the first one")),
+    request_body = ProductPayload,
+    responses(
+        (status = 201, description = "The created product", body = Product, headers(("ETag" = String), ("Location" = String))),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client may not write the catalog", body = ErrorBody),
+        (status = 422, description = "The attributes do not fit the product type", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn create_product(
+    request: HttpRequest,
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    idempotency: web::Data<IdempotencyConfig>,
+    payload: web::Json<ProductPayload>,
+) -> Result<HttpResponse, ApiError> {
+    let payload = payload.into_inner();
+    let product = Product::new(payload.name.clone(), payload.cost, payload.active, None)
+        .with_description(payload.description.clone())
+        .with_attributes(payload.product_type_id, payload.attributes.clone())
+        .with_stock_quantity(payload.stock_quantity);
+
+    idempotent_create(&request, pool, &idempotency, tenant, principal, &payload, move |repository| {
+        let created_product = repository.create_product(product)?;
+
+        created_product_response(&created_product)
+    })
+    .await
+}
+
+#[utoipa::path(
+    post,
+    path = "/complete-products",
+    tag = "products",
+    params(("Idempotency-Key" = Option<String>, Header, description = "Makes a retried request answer like the first one")),
+    request_body = CompleteProductPayload,
+    responses(
+        (status = 201, description = "The created product", body = Product, headers(("ETag" = String), ("Location" = String))),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client may not write the catalog", body = ErrorBody),
+        (status = 422, description = "The attributes do not fit the product type", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn create_complete_product(
+    request: HttpRequest,
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    idempotency: web::Data<IdempotencyConfig>,
+    payload: web::Json<CompleteProductPayload>,
+) -> Result<HttpResponse, ApiError> {
+    let payload = payload.into_inner();
+    let complete_product = CompleteProduct::new(
+        Product::new(payload.name.clone(), payload.cost, payload.active, None)
+            .with_description(payload.description.clone())
+            .with_attributes(payload.product_type_id, payload.attributes.clone())
+            .with_stock_quantity(payload.stock_quantity),
+        payload
+            .variants
+            .iter()
+            .map(|variant| {
+                VariantValue::new(Variant::new(variant.name.clone(), None), variant.values.clone())
+            })
+            .collect(),
+    );
+
+    idempotent_create(&request, pool, &idempotency, tenant, principal, &payload, move |repository| {
+        let created_product_id = repository.create_complete_product(complete_product)?;
+        let created_product = repository.get_product(created_product_id)?;
+
+        created_product_response(&created_product)
+    })
+    .await
+}
+
+#[utoipa::path(
+    get,
+    path = "/products/{reference}",
+    tag = "products",
+    params(
+        ("reference" = ProductReference, Path, description = "The product's ID, public ID or slug"),
+        ("Accept-Language" = Option<String>, Header, description = "The locales to show names and descriptions in"),
+        ("If-None-Match" = Option<String>, Header, description = "The ETag of a copy the client already has"),
+    ),
+    responses(
+        (status = 200, description = "The product with its variants", body = ProductDetail, headers(("ETag" = String))),
+        (status = 301, description = "The slug is one the product had before it was renamed", headers(("Location" = String))),
+        (status = 304, description = "The client's copy is up to date"),
+        (status = 404, description = "There is no such product", body = ErrorBody),
+    )
+)]
+async fn get_product(
+    request: HttpRequest,
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    locale_config: web::Data<LocaleConfig>,
+    path: web::Path<ProductReference>,
+) -> Result<HttpResponse, ApiError> {
+    let reference = path.into_inner();
+    let fallback = request_locale_fallback(&request, &locale_config);
+    let read_fallback = fallback.clone();
+
+    let (resolved_product, product_detail) = with_connection(pool, tenant, move |connection| {
+        let resolved_product = ProductRepository::new(connection).resolve_product(&reference)?;
+        if resolved_product.redirected() {
+            return Ok((resolved_product, None));
+        }
+
+        let product_detail =
+            TranslationRepository::new(connection).get_product_detail(resolved_product.id(), &read_fallback)?;
+
+        Ok((resolved_product, Some(product_detail)))
+    })
+    .await?;
+
+    // a slug the product had before it was renamed sends clients on to the one it has now
+    let Some(product_detail) = product_detail else {
+        return Ok(HttpResponse::MovedPermanently()
+            .insert_header((header::LOCATION, format!("/products/{}", resolved_product.slug())))
+            .finish());
+    };
+
+    let etag = representation_etag(product_detail.product().version(), &fallback);
+
+    if is_not_modified(&request, &etag) {
+        return Ok(HttpResponse::NotModified()
+            .insert_header(etag)
+            .insert_header((header::VARY, header::ACCEPT_LANGUAGE.as_str()))
+            .finish());
+    }
+
+    Ok(HttpResponse::Ok()
+        .insert_header(etag)
+        .insert_header((header::VARY, header::ACCEPT_LANGUAGE.as_str()))
+        .insert_header((header::CONTENT_LANGUAGE, product_detail.locale().as_str()))
+        .json(product_detail))
+}
+
+#[utoipa::path(
+    put,
+    path = "/products/{reference}",
+    tag = "products",
+    params(
+        ("reference" = ProductReference, Path, description = "The product's ID, public ID or slug"),
+        ("If-Match" = String, Header, description = "The ETag of the version being replaced"),
+    ),
+    request_body = ProductPayload,
+    responses(
+        (status = 200, description = "The updated product", body = Product, headers(("ETag" = String))),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client may not write the catalog", body = ErrorBody),
+        (status = 404, description = "There is no such product", body = ErrorBody),
+        (status = 412, description = "The product changed since the given version", body = ErrorBody),
+        (status = 422, description = "The attributes do not fit the product type", body = ErrorBody),
+        (status = 428, description = "The If-Match header is missing", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn update_product(
+    request: HttpRequest,
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    path: web::Path<ProductReference>,
+    payload: web::Json<ProductPayload>,
+) -> Result<HttpResponse, ApiError> {
+    let reference = path.into_inner();
+    let version = expected_version(&request)?;
+    let payload = payload.into_inner();
+
+    let updated_product = with_product_repository(pool, tenant, principal, move |repository| {
+        let id = repository.resolve_product(&reference)?.id();
+        let product = Product::new(payload.name, payload.cost, payload.active, Some(id))
+            .with_description(payload.description)
+            .with_attributes(payload.product_type_id, payload.attributes)
+            .with_stock_quantity(payload.stock_quantity);
+
+        repository.update_product(id, product, version)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(version_etag(updated_product.version()))
+        .json(updated_product))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/products/{reference}",
+    tag = "products",
+    params(
+        ("reference" = ProductReference, Path, description = "The product's ID, public ID or slug"),
+        ("If-Match" = String, Header, description = "The ETag of the version being changed"),
+    ),
+    request_body = ProductPatchPayload,
+    responses(
+        (status = 200, description = "The updated product", body = Product, headers(("ETag" = String))),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client may not write the catalog", body = ErrorBody),
+        (status = 404, description = "There is no such product", body = ErrorBody),
+        (status = 412, description = "The product changed since the given version", body = ErrorBody),
+        (status = 422, description = "The attributes do not fit the product type", body = ErrorBody),
+        (status = 428, description = "The If-Match header is missing", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn patch_product(
+    request: HttpRequest,
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    path: web::Path<ProductReference>,
+    payload: web::Json<ProductPatchPayload>,
+) -> Result<HttpResponse, ApiError> {
+    let reference = path.into_inner();
+    let version = expected_version(&request)?;
+    let payload = payload.into_inner();
+
+    // the patch is applied on top of whatever is stored, the version check in `update_product`
+    // rejects it if the stored product moved on from the version the client patched
+    let updated_product = with_product_repository(pool, tenant, principal, move |repository| {
+        let id = repository.resolve_product(&reference)?.id();
+        let existing_product = repository.get_product(id)?;
+        let product = Product::new(
+            payload.name.unwrap_or_else(|| existing_product.name().to_string()),
+            payload.cost.unwrap_or(existing_product.cost()),
+            payload.active.unwrap_or(existing_product.active()),
+            Some(id),
+        )
+        .with_description(payload.description.or_else(|| existing_product.description().map(String::from)))
+        .with_attributes(
+            payload.product_type_id.or(existing_product.product_type_id()),
+            payload.attributes.unwrap_or_else(|| existing_product.attributes().clone()),
+        )
+        .with_stock_quantity(payload.stock_quantity.or(existing_product.stock_quantity()));
+
+        repository.update_product(id, product, version)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(version_etag(updated_product.version()))
+        .json(updated_product))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/products/{reference}",
+    tag = "products",
+    params(
+        ("reference" = ProductReference, Path, description = "The product's ID, public ID or slug"),
+        ("If-Match" = String, Header, description = "The ETag of the version being deleted"),
+    ),
+    responses(
+        (status = 204, description = "The product was deleted"),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client may not write the catalog", body = ErrorBody),
+        (status = 404, description = "There is no such product", body = ErrorBody),
+        (status = 412, description = "The product changed since the given version", body = ErrorBody),
+        (status = 428, description = "The If-Match header is missing", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn delete_product(
+    request: HttpRequest,
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    path: web::Path<ProductReference>,
+) -> Result<HttpResponse, ApiError> {
+    let reference = path.into_inner();
+    let version = expected_version(&request)?;
+
+    with_product_repository(pool, tenant, principal, move |repository| {
+        let id = repository.resolve_product(&reference)?.id();
+
+        repository.delete_product(id, version)
+    })
+    .await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod products_tests {
+    use crate::api::errors::ApiError;
+    use crate::api::products::ListProductsQuery;
+
+    fn query(limit: Option<i64>, offset: Option<i64>) -> ListProductsQuery {
+        ListProductsQuery {
+            limit,
+            offset,
+            product_type: None,
+            attributes: None,
+        }
+    }
+
+    #[test]
+    fn test_pages_out_of_range_are_rejected() {
+        let params = query(None, None).list_params().unwrap();
+        assert_eq!((20, 0), (params.limit, params.offset));
+
+        let params = query(Some(100), Some(40)).list_params().unwrap();
+        assert_eq!((100, 40), (params.limit, params.offset));
+
+        for (limit, offset) in [(Some(-1), None), (Some(0), None), (Some(101), None), (None, Some(-1))] {
+            assert!(matches!(query(limit, offset).list_params(), Err(ApiError::BadRequest(_))));
+        }
+    }
+}
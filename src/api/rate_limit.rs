@@ -0,0 +1,363 @@
+use crate::api::auth::has_credentials;
+use crate::api::errors::ApiError;
+use crate::core::entities::principal::Principal;
+use crate::core::entities::rate_limit::{RateLimit, RateLimitDecision};
+use crate::core::entities::tenant::Tenant;
+use crate::core::ports::rate_limit_store::RateLimitStore;
+use crate::datastore::in_memory_rate_limit_store::InMemoryRateLimitStore;
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::http::{Method, StatusCode};
+use actix_web::middleware::Next;
+use actix_web::{web, HttpMessage, HttpRequest};
+use anyhow::{anyhow, Context, Result as AnyResult};
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
+use std::env;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::Arc;
+
+const DEFAULT_RATE_LIMITS: &str = "reads=300/60,writes=60/60,transfers=10/60,failed_authentications=10/60";
+
+// the routes that share a limit. Imports and exports are limited apart from other requests since
+// each of them is much more work. Requests whose credentials are turned away are limited on their
+// own as well, by address, so that guessing API keys or tokens is slowed down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RouteGroup {
+    Reads,
+    Writes,
+    Transfers,
+    FailedAuthentications,
+}
+
+impl RouteGroup {
+    pub fn of(method: &Method, path: &str) -> RouteGroup {
+        if path.starts_with("/product-imports") || path.starts_with("/product-exports") {
+            RouteGroup::Transfers
+        } else if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+            RouteGroup::Reads
+        } else {
+            RouteGroup::Writes
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RouteGroup::Reads => "reads",
+            RouteGroup::Writes => "writes",
+            RouteGroup::Transfers => "transfers",
+            RouteGroup::FailedAuthentications => "failed_authentications",
+        }
+    }
+}
+
+impl FromStr for RouteGroup {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "reads" => Ok(RouteGroup::Reads),
+            "writes" => Ok(RouteGroup::Writes),
+            "transfers" => Ok(RouteGroup::Transfers),
+            "failed_authentications" => Ok(RouteGroup::FailedAuthentications),
+            _ => Err(anyhow!(
+                "{} is not a route group, route groups are reads, writes, transfers and failed_authentications",
+                value
+            )),
+        }
+    }
+}
+
+impl Display for RouteGroup {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+// the limits of each route group, and the limits of clients that get more or less than others.
+// Route groups without a limit are not limited
+#[derive(Debug, Clone, Default)]
+pub struct RateLimitConfig {
+    limits: HashMap<RouteGroup, RateLimit>,
+    client_limits: HashMap<(String, RouteGroup), RateLimit>,
+}
+
+impl RateLimitConfig {
+    pub fn from_env() -> AnyResult<RateLimitConfig> {
+        let limits = env::var("RATE_LIMITS").unwrap_or_else(|_| DEFAULT_RATE_LIMITS.to_string());
+
+        limits.parse().context("Invalid RATE_LIMITS")
+    }
+
+    // the limit of a client in a route group, its own one if it has one
+    pub fn limit(&self, client: &str, group: RouteGroup) -> Option<&RateLimit> {
+        self.client_limits
+            .get(&(client.to_string(), group))
+            .or_else(|| self.limits.get(&group))
+    }
+}
+
+// limits separated by commas, each a route group and a rate limit optionally preceded by the client
+// it is for, e.g. `reads=300/60,writes=60/60,api_key:partner@reads=30/60`
+impl FromStr for RateLimitConfig {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut config = RateLimitConfig::default();
+
+        for entry in value.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (target, limit) = entry
+                .split_once('=')
+                .ok_or_else(|| anyhow!("{} is not a limit, limits look like `reads=300/60`", entry))?;
+            let limit = limit.parse::<RateLimit>()?;
+
+            match target.split_once('@') {
+                Some((client, group)) => {
+                    config
+                        .client_limits
+                        .insert((client.trim().to_string(), group.trim().parse()?), limit);
+                }
+                None => {
+                    config.limits.insert(target.trim().parse()?, limit);
+                }
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+// limits requests by client and route group, keeping the buckets in a store that can be swapped for
+// one shared by several servers
+#[derive(Clone)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    store: Arc<dyn RateLimitStore>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig, store: Arc<dyn RateLimitStore>) -> RateLimiter {
+        RateLimiter { config, store }
+    }
+
+    pub fn in_memory(config: RateLimitConfig) -> RateLimiter {
+        RateLimiter::new(config, Arc::new(InMemoryRateLimitStore::new()))
+    }
+
+    // takes a token for a request, if its route group is limited. Requests are let through when the
+    // store fails, an outage of the store should not take the catalog down with it
+    fn take_token(&self, request: &ServiceRequest) -> Option<RateLimitDecision> {
+        let group = RouteGroup::of(request.method(), request.path());
+        let (tenant, client) = request_client(request);
+        let limit = self.config.limit(&client, group)?;
+        let bucket = match tenant {
+            Some(tenant) => format!("{} {} {}", group, tenant.as_str(), client),
+            None => format!("{} {}", group, client),
+        };
+
+        match self.store.take_token(&bucket, limit, Utc::now()) {
+            Ok(decision) => Some(decision),
+            Err(error) => {
+                log::warn!("Not rate limiting {} for {}: {:#}", group, client, error);
+                None
+            }
+        }
+    }
+}
+
+impl RateLimiter {
+    // whether the address of a request has failed to authenticate too often to try again yet. Its
+    // bucket is only looked at, tokens are taken for the attempts that fail
+    fn check_failed_authentications(&self, request: &HttpRequest) -> Option<RateLimitDecision> {
+        self.failed_authentications(request, |store, bucket, limit| store.peek_token(bucket, limit, Utc::now()))
+    }
+
+    fn count_failed_authentication(&self, request: &HttpRequest) {
+        self.failed_authentications(request, |store, bucket, limit| store.take_token(bucket, limit, Utc::now()));
+    }
+
+    fn failed_authentications(
+        &self,
+        request: &HttpRequest,
+        operation: impl FnOnce(&dyn RateLimitStore, &str, &RateLimit) -> AnyResult<RateLimitDecision>,
+    ) -> Option<RateLimitDecision> {
+        let group = RouteGroup::FailedAuthentications;
+        let client = peer_client(request);
+        let limit = self.config.limit(&client, group)?;
+
+        match operation(self.store.as_ref(), &format!("{} {}", group, client), limit) {
+            Ok(decision) => Some(decision),
+            Err(error) => {
+                log::warn!("Not rate limiting {} for {}: {:#}", group, client, error);
+                None
+            }
+        }
+    }
+}
+
+// the address a request came from, e.g. `ip:10.0.0.7`
+fn peer_client(request: &HttpRequest) -> String {
+    format!(
+        "ip:{}",
+        request
+            .peer_addr()
+            .map(|address| address.ip().to_string())
+            .unwrap_or_else(|| "unknown".to_string())
+    )
+}
+
+// the client a request is counted against: the actor it was authenticated as within its tenant, or
+// the address it came from for anonymous requests. Clients of different tenants can have the same
+// name, e.g. API keys named `partner`, and do not share a limit. This is the address of the peer,
+// so anonymous requests that come through a proxy share a limit
+fn request_client(request: &ServiceRequest) -> (Option<Tenant>, String) {
+    match request.extensions().get::<Principal>() {
+        Some(principal) => (Some(principal.tenant().clone()), principal.actor()),
+        None => (None, peer_client(request.request())),
+    }
+}
+
+// durations in headers are whole seconds, rounded up so that clients do not come back too early
+pub fn whole_seconds(duration: Duration) -> i64 {
+    (duration.num_milliseconds() + 999).div_euclid(1000)
+}
+
+// the `RateLimit-*` headers telling a client what it has left
+pub fn rate_limit_headers(decision: &RateLimitDecision) -> Vec<(HeaderName, HeaderValue)> {
+    let header = |name: &'static str, value: String| {
+        (
+            HeaderName::from_static(name),
+            HeaderValue::from_str(&value).expect("rate limit headers are numbers"),
+        )
+    };
+
+    vec![
+        header("ratelimit-limit", decision.limit().requests().to_string()),
+        header("ratelimit-remaining", decision.remaining().to_string()),
+        header("ratelimit-reset", whole_seconds(decision.reset_after()).to_string()),
+        header(
+            "ratelimit-policy",
+            format!("{};w={}", decision.limit().requests(), decision.limit().period().num_seconds()),
+        ),
+    ]
+}
+
+// turns away requests of clients that are over their limit with `429 Too Many Requests`, and tells
+// the others what they have left. This has to run after `authenticate`, since authenticated
+// clients are limited by who they are rather than where they come from
+pub async fn limit_rate(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let decision = request
+        .app_data::<web::Data<RateLimiter>>()
+        .and_then(|rate_limiter| rate_limiter.take_token(&request));
+
+    match decision {
+        Some(decision) if !decision.allowed() => Err(ApiError::TooManyRequests(decision).into()),
+        Some(decision) => {
+            let mut response = next.call(request).await?;
+            for (name, value) in rate_limit_headers(&decision) {
+                response.headers_mut().insert(name, value);
+            }
+
+            Ok(response)
+        }
+        None => next.call(request).await,
+    }
+}
+
+// turns away requests with credentials from addresses whose credentials were turned away too often
+// with `429 Too Many Requests`, before the credentials are looked up. This has to run before
+// `authenticate`, which is the one that turns credentials away
+pub async fn limit_failed_authentications(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let rate_limiter = request.app_data::<web::Data<RateLimiter>>().cloned();
+    let Some(rate_limiter) = rate_limiter.filter(|_| has_credentials(request.headers())) else {
+        return next.call(request).await;
+    };
+
+    let http_request = request.request().clone();
+    if let Some(decision) = rate_limiter.check_failed_authentications(&http_request) {
+        if !decision.allowed() {
+            return Err(ApiError::TooManyRequests(decision).into());
+        }
+    }
+
+    let response = next.call(request).await;
+    let status = match &response {
+        Ok(response) => response.status(),
+        Err(error) => error.as_response_error().status_code(),
+    };
+    if status == StatusCode::UNAUTHORIZED {
+        rate_limiter.count_failed_authentication(&http_request);
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod rate_limit_tests {
+    use crate::api::auth::authenticate;
+    use crate::api::rate_limit::{limit_failed_authentications, RateLimiter};
+    use crate::core::entities::principal::{AuthMethod, Principal};
+    use crate::core::entities::tenant::Tenant;
+    use actix_web::http::{header, StatusCode};
+    use actix_web::middleware::from_fn;
+    use actix_web::test::{init_service, try_call_service, TestRequest};
+    use actix_web::{web, App, HttpMessage, HttpResponse};
+
+    #[test]
+    fn test_clients_of_different_tenants_do_not_share_a_limit() {
+        let rate_limiter = RateLimiter::in_memory("writes=1/60".parse().unwrap());
+        let take_token = |tenant: &str| {
+            let request = TestRequest::post().uri("/products").to_srv_request();
+            request.extensions_mut().insert(
+                Principal::new("partner".to_string(), AuthMethod::ApiKey, Vec::new())
+                    .with_tenant(tenant.parse::<Tenant>().unwrap()),
+            );
+
+            rate_limiter.take_token(&request).unwrap().allowed()
+        };
+
+        assert!(take_token("acme"));
+        assert!(take_token("globex"));
+        assert!(!take_token("acme"));
+    }
+
+    #[actix_web::test]
+    async fn test_addresses_that_keep_failing_to_authenticate_are_turned_away() {
+        let rate_limiter = RateLimiter::in_memory("failed_authentications=2/60".parse().unwrap());
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(rate_limiter))
+                .wrap(from_fn(authenticate))
+                .wrap(from_fn(limit_failed_authentications))
+                .route("/products", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+        let status = |authorization: Option<&str>| {
+            let mut request = TestRequest::get().uri("/products").peer_addr("10.0.0.7:4000".parse().unwrap());
+            if let Some(authorization) = authorization {
+                request = request.insert_header((header::AUTHORIZATION, authorization));
+            }
+
+            let response = try_call_service(&app, request.to_request());
+            async move {
+                match response.await {
+                    Ok(response) => response.status(),
+                    Err(error) => error.as_response_error().status_code(),
+                }
+            }
+        };
+
+        assert_eq!(StatusCode::UNAUTHORIZED, status(Some("Basic guess")).await);
+        assert_eq!(StatusCode::UNAUTHORIZED, status(Some("Basic guess")).await);
+        assert_eq!(StatusCode::TOO_MANY_REQUESTS, status(Some("Basic guess")).await);
+        assert_eq!(StatusCode::OK, status(None).await);
+    }
+}
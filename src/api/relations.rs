@@ -0,0 +1,109 @@
+use crate::DbPool;
+use crate::api::auth::authorize;
+use crate::api::errors::{ApiError, ErrorBody};
+use crate::api::with_connection;
+use crate::core::entities::principal::Principal;
+use crate::core::entities::product_reference::ProductReference;
+use crate::core::entities::product_relation::{ProductRelation, RelationType};
+use crate::core::entities::role::Permission;
+use crate::core::entities::tenant::Tenant;
+use crate::core::ports::database::product_database::ProductDatastore;
+use crate::core::ports::database::relation_database::RelationDatastore;
+use crate::datastore::repositories::product_repository::ProductRepository;
+use crate::datastore::repositories::relation_repository::RelationRepository;
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use utoipa::{OpenApi, ToSchema};
+
+// a link to another product, which is referred to like any other product
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ProductRelationPayload {
+    pub relation_type: RelationType,
+    pub product: ProductReference,
+    pub position: i32,
+}
+
+pub fn configure(config: &mut web::ServiceConfig) {
+    config.service(
+        web::resource("/products/{reference}/relations")
+            .route(web::get().to(list_product_relations))
+            .route(web::put().to(set_product_relations)),
+    );
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list_product_relations, set_product_relations))]
+pub struct RelationsApi;
+
+#[utoipa::path(
+    get,
+    path = "/products/{reference}/relations",
+    tag = "relations",
+    params(("reference" = ProductReference, Path, description = "The product's ID, public ID or slug")),
+    responses(
+        (status = 200, description = "The products linked to the product", body = Vec<ProductRelation>),
+        (status = 404, description = "There is no such product", body = ErrorBody),
+    )
+)]
+async fn list_product_relations(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    path: web::Path<ProductReference>,
+) -> Result<HttpResponse, ApiError> {
+    let reference = path.into_inner();
+
+    let relations = with_connection(pool, tenant, move |connection| {
+        let id = ProductRepository::new(connection).resolve_product(&reference)?.id();
+
+        RelationRepository::new(connection).list_product_relations(id)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(relations))
+}
+
+#[utoipa::path(
+    put,
+    path = "/products/{reference}/relations",
+    tag = "relations",
+    params(("reference" = ProductReference, Path, description = "The product's ID, public ID or slug")),
+    request_body = Vec<ProductRelationPayload>,
+    responses(
+        (status = 200, description = "The saved links, which replace the ones the product had", body = Vec<ProductRelation>),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client may not write the catalog", body = ErrorBody),
+        (status = 404, description = "There is no such product", body = ErrorBody),
+        (status = 422, description = "The links are not valid, e.g. a product is linked to itself", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn set_product_relations(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    path: web::Path<ProductReference>,
+    payload: web::Json<Vec<ProductRelationPayload>>,
+) -> Result<HttpResponse, ApiError> {
+    let reference = path.into_inner();
+    let payload = payload.into_inner();
+
+    let saved_relations = with_connection(pool, tenant, move |connection| {
+        authorize(connection, &principal)?.require(Permission::CatalogWrite)?;
+
+        let mut product_repository = ProductRepository::new(connection);
+        let id = product_repository.resolve_product(&reference)?.id();
+        let relations = payload
+            .into_iter()
+            .map(|relation| {
+                let product_id = product_repository.resolve_product(&relation.product)?.id();
+
+                Ok(ProductRelation::new(relation.relation_type, product_id, relation.position))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        RelationRepository::new(connection).with_actor(principal.actor()).set_product_relations(id, relations)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(saved_relations))
+}
@@ -0,0 +1,158 @@
+use crate::DbPool;
+use crate::api::auth::authorize;
+use crate::api::errors::{ApiError, ErrorBody};
+use crate::api::with_connection;
+use crate::core::entities::principal::Principal;
+use crate::core::entities::role::{Permission, Role};
+use crate::core::entities::tenant::Tenant;
+use crate::core::ports::database::role_database::RoleDatastore;
+use crate::datastore::repositories::role_repository::RoleRepository;
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use utoipa::{OpenApi, ToSchema};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RolePayload {
+    pub permissions: Vec<Permission>,
+}
+
+// roles are managed by admins only, reading them included
+pub fn configure(config: &mut web::ServiceConfig) {
+    config
+        .service(web::resource("/roles").route(web::get().to(list_roles)))
+        .service(
+            web::resource("/roles/{name}")
+                .route(web::get().to(get_role))
+                .route(web::put().to(save_role))
+                .route(web::delete().to(delete_role)),
+        );
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list_roles, get_role, save_role, delete_role))]
+pub struct RolesApi;
+
+#[utoipa::path(
+    get,
+    path = "/roles",
+    tag = "roles",
+    responses(
+        (status = 200, description = "Every role", body = Vec<Role>),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client is not an admin", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn list_roles(pool: web::Data<DbPool>, tenant: Tenant, principal: Principal) -> Result<HttpResponse, ApiError> {
+    let roles = with_connection(pool, tenant, move |connection| {
+        authorize(connection, &principal)?.require(Permission::Admin)?;
+
+        RoleRepository::new(connection).list_roles()
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(roles))
+}
+
+#[utoipa::path(
+    get,
+    path = "/roles/{name}",
+    tag = "roles",
+    params(("name" = String, Path, description = "The role's name, as API key scopes and token claims refer to it")),
+    responses(
+        (status = 200, description = "The role", body = Role),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client is not an admin", body = ErrorBody),
+        (status = 404, description = "There is no such role", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn get_role(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    path: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let name = path.into_inner();
+
+    let role = with_connection(pool, tenant, move |connection| {
+        authorize(connection, &principal)?.require(Permission::Admin)?;
+
+        RoleRepository::new(connection).get_role(&name)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(role))
+}
+
+#[utoipa::path(
+    put,
+    path = "/roles/{name}",
+    tag = "roles",
+    params(("name" = String, Path, description = "The role's name, as API key scopes and token claims refer to it")),
+    request_body = RolePayload,
+    responses(
+        (status = 200, description = "The created or replaced role", body = Role),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client is not an admin", body = ErrorBody),
+        (status = 422, description = "The name is not one a role can have", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn save_role(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    path: web::Path<String>,
+    payload: web::Json<RolePayload>,
+) -> Result<HttpResponse, ApiError> {
+    let name = path.into_inner();
+    // a role named like a permission could never be granted, the scope would grant the permission
+    if name.parse::<Permission>().is_ok() {
+        return Err(ApiError::UnprocessableEntity(format!(
+            "{} is a permission and cannot be used as the name of a role",
+            name
+        )));
+    }
+    let role = Role::new(name, payload.into_inner().permissions);
+
+    let saved_role = with_connection(pool, tenant, move |connection| {
+        authorize(connection, &principal)?.require(Permission::Admin)?;
+
+        RoleRepository::new(connection).with_actor(principal.actor()).save_role(role)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(saved_role))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/roles/{name}",
+    tag = "roles",
+    params(("name" = String, Path, description = "The role's name, as API key scopes and token claims refer to it")),
+    responses(
+        (status = 204, description = "The role was deleted"),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client is not an admin", body = ErrorBody),
+        (status = 404, description = "There is no such role", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn delete_role(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    path: web::Path<String>,
+) -> Result<HttpResponse, ApiError> {
+    let name = path.into_inner();
+
+    with_connection(pool, tenant, move |connection| {
+        authorize(connection, &principal)?.require(Permission::Admin)?;
+
+        RoleRepository::new(connection).with_actor(principal.actor()).delete_role(&name)
+    })
+    .await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
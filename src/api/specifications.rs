@@ -0,0 +1,110 @@
+use crate::DbPool;
+use crate::api::auth::authorize;
+use crate::api::errors::{ApiError, ErrorBody};
+use crate::api::with_connection;
+use crate::core::entities::principal::Principal;
+use crate::core::entities::product_reference::ProductReference;
+use crate::core::entities::role::Permission;
+use crate::core::entities::specification::Specification;
+use crate::core::entities::tenant::Tenant;
+use crate::core::ports::database::product_database::ProductDatastore;
+use crate::core::ports::database::specification_database::SpecificationDatastore;
+use crate::datastore::repositories::product_repository::ProductRepository;
+use crate::datastore::repositories::specification_repository::SpecificationRepository;
+use actix_web::{web, HttpResponse};
+use std::collections::HashSet;
+use utoipa::OpenApi;
+
+pub fn configure(config: &mut web::ServiceConfig) {
+    config.service(
+        web::resource("/products/{reference}/specifications")
+            .route(web::get().to(list_product_specifications))
+            .route(web::put().to(set_product_specifications)),
+    );
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list_product_specifications, set_product_specifications))]
+pub struct SpecificationsApi;
+
+// a specification table shows one row per name, so names must be present and distinct
+fn validate_specifications(specifications: &[Specification]) -> Result<(), ApiError> {
+    let mut names = HashSet::new();
+
+    for specification in specifications {
+        let name = specification.name().trim();
+        if name.is_empty() {
+            return Err(ApiError::BadRequest("Specification names must not be empty".to_string()));
+        }
+        if !names.insert(name) {
+            return Err(ApiError::BadRequest(format!("Specification {} is given more than once", name)));
+        }
+    }
+
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/products/{reference}/specifications",
+    tag = "specifications",
+    params(("reference" = ProductReference, Path, description = "The product's ID, public ID or slug")),
+    responses(
+        (status = 200, description = "The specifications of the product", body = Vec<Specification>),
+        (status = 404, description = "There is no such product", body = ErrorBody),
+    )
+)]
+async fn list_product_specifications(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    path: web::Path<ProductReference>,
+) -> Result<HttpResponse, ApiError> {
+    let reference = path.into_inner();
+
+    let specifications = with_connection(pool, tenant, move |connection| {
+        let id = ProductRepository::new(connection).resolve_product(&reference)?.id();
+
+        SpecificationRepository::new(connection).list_product_specifications(id)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(specifications))
+}
+
+#[utoipa::path(
+    put,
+    path = "/products/{reference}/specifications",
+    tag = "specifications",
+    params(("reference" = ProductReference, Path, description = "The product's ID, public ID or slug")),
+    request_body = Vec<Specification>,
+    responses(
+        (status = 200, description = "The saved specifications, which replace the ones the product had", body = Vec<Specification>),
+        (status = 400, description = "A specification is not valid", body = ErrorBody),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client may not write the catalog", body = ErrorBody),
+        (status = 404, description = "There is no such product", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn set_product_specifications(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    path: web::Path<ProductReference>,
+    payload: web::Json<Vec<Specification>>,
+) -> Result<HttpResponse, ApiError> {
+    let reference = path.into_inner();
+    let specifications = payload.into_inner();
+    validate_specifications(&specifications)?;
+
+    let saved_specifications = with_connection(pool, tenant, move |connection| {
+        authorize(connection, &principal)?.require(Permission::CatalogWrite)?;
+
+        let id = ProductRepository::new(connection).resolve_product(&reference)?.id();
+
+        SpecificationRepository::new(connection).with_actor(principal.actor()).set_product_specifications(id, specifications)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(saved_specifications))
+}
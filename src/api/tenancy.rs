@@ -0,0 +1,98 @@
+use crate::api::errors::ApiError;
+use crate::core::entities::principal::Principal;
+use crate::core::entities::tenant::Tenant;
+use actix_web::body::MessageBody;
+use actix_web::dev::{Payload, ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::{web, FromRequest, HttpMessage, HttpRequest};
+use std::env;
+use std::future::{ready, Ready};
+
+pub const TENANT_HEADER: &str = "X-Tenant-ID";
+
+#[derive(Debug, Clone)]
+pub struct TenantConfig {
+    // the tenant of anonymous requests that do not name one
+    pub default_tenant: Tenant,
+}
+
+impl TenantConfig {
+    pub fn from_env() -> TenantConfig {
+        let default_tenant = env::var("DEFAULT_TENANT")
+            .ok()
+            .and_then(|value| value.parse::<Tenant>().ok())
+            .unwrap_or_default();
+
+        TenantConfig { default_tenant }
+    }
+}
+
+// the tenant a request works for. Authenticated clients belong to a tenant and can only work for
+// that one, anonymous clients pick one with an `X-Tenant-ID` header
+fn request_tenant(request: &ServiceRequest) -> Result<Tenant, ApiError> {
+    let requested_tenant = request
+        .headers()
+        .get(TENANT_HEADER)
+        .map(|value| {
+            value
+                .to_str()
+                .map_err(|_| ApiError::BadRequest(format!("{} is not valid", TENANT_HEADER)))?
+                .parse::<Tenant>()
+                .map_err(|error| ApiError::BadRequest(error.to_string()))
+        })
+        .transpose()?;
+
+    tenant_for(
+        request.extensions().get::<Principal>(),
+        requested_tenant,
+        request.app_data::<web::Data<TenantConfig>>().map(|config| config.get_ref()),
+    )
+}
+
+// the tenant a client works for, given who it authenticated as and the tenant it asked for
+pub(crate) fn tenant_for(
+    principal: Option<&Principal>,
+    requested_tenant: Option<Tenant>,
+    config: Option<&TenantConfig>,
+) -> Result<Tenant, ApiError> {
+    match (principal, requested_tenant) {
+        (Some(principal), Some(requested_tenant)) if principal.tenant() != &requested_tenant => {
+            Err(ApiError::Forbidden(format!(
+                "{} belongs to tenant {} and cannot work for tenant {}",
+                principal.actor(),
+                principal.tenant(),
+                requested_tenant
+            )))
+        }
+        (Some(principal), _) => Ok(principal.tenant().clone()),
+        (None, Some(requested_tenant)) => Ok(requested_tenant),
+        (None, None) => Ok(config.map(|config| config.default_tenant.clone()).unwrap_or_default()),
+    }
+}
+
+// resolves the tenant of every request and makes it available to handlers. This has to run after
+// `authenticate`, since the tenant of an authenticated request is the one of its principal
+pub async fn resolve_tenant(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let tenant = request_tenant(&request)?;
+    request.extensions_mut().insert(tenant);
+
+    next.call(request).await
+}
+
+impl FromRequest for Tenant {
+    type Error = ApiError;
+    type Future = Ready<Result<Tenant, ApiError>>;
+
+    fn from_request(request: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(
+            request
+                .extensions()
+                .get::<Tenant>()
+                .cloned()
+                .ok_or_else(|| ApiError::Internal(anyhow::anyhow!("No tenant was resolved for this request"))),
+        )
+    }
+}
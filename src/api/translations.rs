@@ -0,0 +1,211 @@
+use crate::DbPool;
+use crate::api::auth::authorize;
+use crate::api::errors::{ApiError, ErrorBody};
+use crate::api::language::LocaleConfig;
+use crate::api::with_connection;
+use crate::core::entities::ids::VariantId;
+use crate::core::entities::locale::Locale;
+use crate::core::entities::principal::Principal;
+use crate::core::entities::product_reference::ProductReference;
+use crate::core::entities::role::Permission;
+use crate::core::entities::tenant::Tenant;
+use crate::core::entities::translation::{ProductTranslation, VariantTranslation};
+use crate::core::ports::database::product_database::ProductDatastore;
+use crate::core::ports::database::translation_database::TranslationDatastore;
+use crate::datastore::repositories::product_repository::ProductRepository;
+use crate::datastore::repositories::translation_repository::TranslationRepository;
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use utoipa::{OpenApi, ToSchema};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ProductTranslationPayload {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct VariantTranslationPayload {
+    pub display_name: String,
+    #[serde(default)]
+    pub values: BTreeMap<String, String>,
+}
+
+pub fn configure(config: &mut web::ServiceConfig) {
+    config
+        .service(
+            web::resource("/products/{reference}/translations").route(web::get().to(list_product_translations)),
+        )
+        .service(
+            web::resource("/products/{reference}/translations/{locale}")
+                .route(web::put().to(set_product_translation))
+                .route(web::delete().to(delete_product_translation)),
+        )
+        .service(
+            web::resource("/variants/{id}/translations/{locale}").route(web::put().to(set_variant_translation)),
+        );
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list_product_translations, set_product_translation, delete_product_translation, set_variant_translation))]
+pub struct TranslationsApi;
+
+// the text of the default locale lives on the product itself and is changed by updating the product
+fn reject_default_locale(locale: &Locale, config: &LocaleConfig) -> Result<(), ApiError> {
+    if locale == &config.default_locale {
+        return Err(ApiError::BadRequest(format!(
+            "{} is the default locale, update the entity itself to change its text",
+            locale
+        )));
+    }
+
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/products/{reference}/translations",
+    tag = "translations",
+    params(("reference" = ProductReference, Path, description = "The product's ID, public ID or slug")),
+    responses(
+        (status = 200, description = "The translations of the product", body = Vec<ProductTranslation>),
+        (status = 404, description = "There is no such product", body = ErrorBody),
+    )
+)]
+async fn list_product_translations(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    path: web::Path<ProductReference>,
+) -> Result<HttpResponse, ApiError> {
+    let reference = path.into_inner();
+
+    let translations = with_connection(pool, tenant, move |connection| {
+        let id = ProductRepository::new(connection).resolve_product(&reference)?.id();
+
+        TranslationRepository::new(connection).list_product_translations(id)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(translations))
+}
+
+#[utoipa::path(
+    put,
+    path = "/products/{reference}/translations/{locale}",
+    tag = "translations",
+    params(
+        ("reference" = ProductReference, Path, description = "The product's ID, public ID or slug"),
+        ("locale" = Locale, Path, description = "A BCP 47 language tag other than the default locale"),
+    ),
+    request_body = ProductTranslationPayload,
+    responses(
+        (status = 200, description = "The saved translation", body = ProductTranslation),
+        (status = 400, description = "The locale is the default one, whose text lives on the entity itself", body = ErrorBody),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client may not write the catalog", body = ErrorBody),
+        (status = 404, description = "There is no such product", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn set_product_translation(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    locale_config: web::Data<LocaleConfig>,
+    path: web::Path<(ProductReference, Locale)>,
+    payload: web::Json<ProductTranslationPayload>,
+) -> Result<HttpResponse, ApiError> {
+    let (reference, locale) = path.into_inner();
+    reject_default_locale(&locale, &locale_config)?;
+    let payload = payload.into_inner();
+    let translation = ProductTranslation::new(locale, payload.name, payload.description);
+
+    let saved_translation = with_connection(pool, tenant, move |connection| {
+        authorize(connection, &principal)?.require(Permission::CatalogWrite)?;
+
+        let id = ProductRepository::new(connection).resolve_product(&reference)?.id();
+
+        TranslationRepository::new(connection).with_actor(principal.actor()).set_product_translation(id, translation)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(saved_translation))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/products/{reference}/translations/{locale}",
+    tag = "translations",
+    params(
+        ("reference" = ProductReference, Path, description = "The product's ID, public ID or slug"),
+        ("locale" = Locale, Path, description = "A BCP 47 language tag other than the default locale"),
+    ),
+    responses(
+        (status = 204, description = "The translation was deleted"),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client may not write the catalog", body = ErrorBody),
+        (status = 404, description = "There is no such product or translation", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn delete_product_translation(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    path: web::Path<(ProductReference, Locale)>,
+) -> Result<HttpResponse, ApiError> {
+    let (reference, locale) = path.into_inner();
+
+    with_connection(pool, tenant, move |connection| {
+        authorize(connection, &principal)?.require(Permission::CatalogWrite)?;
+
+        let id = ProductRepository::new(connection).resolve_product(&reference)?.id();
+
+        TranslationRepository::new(connection).with_actor(principal.actor()).delete_product_translation(id, &locale)
+    })
+    .await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    put,
+    path = "/variants/{id}/translations/{locale}",
+    tag = "translations",
+    params(
+        ("id" = VariantId, Path, description = "The variant's ID"),
+        ("locale" = Locale, Path, description = "A BCP 47 language tag other than the default locale"),
+    ),
+    request_body = VariantTranslationPayload,
+    responses(
+        (status = 200, description = "The saved translation", body = VariantTranslation),
+        (status = 400, description = "The locale is the default one, whose text lives on the entity itself", body = ErrorBody),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client may not write the catalog", body = ErrorBody),
+        (status = 404, description = "There is no such variant", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn set_variant_translation(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    locale_config: web::Data<LocaleConfig>,
+    path: web::Path<(VariantId, Locale)>,
+    payload: web::Json<VariantTranslationPayload>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, locale) = path.into_inner();
+    reject_default_locale(&locale, &locale_config)?;
+    let payload = payload.into_inner();
+    let translation = VariantTranslation::new(locale, payload.display_name, payload.values);
+
+    let saved_translation = with_connection(pool, tenant, move |connection| {
+        authorize(connection, &principal)?.require(Permission::CatalogWrite)?;
+
+        TranslationRepository::new(connection).with_actor(principal.actor()).set_variant_translation(id, translation)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(saved_translation))
+}
@@ -0,0 +1,369 @@
+use crate::DbPool;
+use crate::api::auth::authorize;
+use crate::api::errors::{ApiError, ErrorBody};
+use crate::api::with_connection;
+use crate::core::entities::domain_event::EVENT_TYPES;
+use crate::core::entities::ids::WebhookSubscriptionId;
+use crate::core::entities::principal::Principal;
+use crate::core::entities::role::Permission;
+use crate::core::entities::tenant::Tenant;
+use crate::core::entities::webhook::{generate_webhook_secret, DeliveryStatus, WebhookDelivery, WebhookSubscription};
+use crate::core::ports::database::utils::ListQueryParams;
+use crate::core::ports::database::webhook_database::WebhookDatastore;
+use crate::datastore::repositories::webhook_repository::WebhookRepository;
+use crate::webhooks::receivers::ReceiverPolicy;
+use actix_web::http::header;
+use actix_web::{web, HttpResponse};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+const DEFAULT_PAGE_SIZE: i64 = 20;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct WebhookSubscriptionPayload {
+    pub url: String,
+    // the types of events to get, every type when this is empty
+    #[serde(default)]
+    pub event_types: Vec<String>,
+    // the secret to sign payloads with, one is generated when this is not set
+    #[serde(default)]
+    pub secret: Option<String>,
+    #[serde(default = "active_by_default")]
+    pub active: bool,
+}
+
+fn active_by_default() -> bool {
+    true
+}
+
+// a subscription as it is created, the only time its secret is shown
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedWebhookSubscription {
+    #[serde(flatten)]
+    pub subscription: WebhookSubscription,
+    pub secret: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListDeliveriesQuery {
+    pub status: Option<DeliveryStatus>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+// webhooks are managed by admins only, reading them and their delivery log included
+pub fn configure(config: &mut web::ServiceConfig) {
+    config
+        .service(
+            web::resource("/webhooks")
+                .route(web::get().to(list_webhook_subscriptions))
+                .route(web::post().to(create_webhook_subscription)),
+        )
+        .service(
+            web::resource("/webhooks/{id}")
+                .route(web::get().to(get_webhook_subscription))
+                .route(web::put().to(update_webhook_subscription))
+                .route(web::delete().to(delete_webhook_subscription)),
+        )
+        .service(web::resource("/webhooks/{id}/deliveries").route(web::get().to(list_webhook_deliveries)))
+        .service(
+            web::resource("/webhooks/{id}/deliveries/{delivery_id}/retry")
+                .route(web::post().to(retry_webhook_delivery)),
+        );
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list_webhook_subscriptions, create_webhook_subscription, get_webhook_subscription, update_webhook_subscription, delete_webhook_subscription, list_webhook_deliveries, retry_webhook_delivery))]
+pub struct WebhooksApi;
+
+// receivers are reached over HTTP on hosts `receivers` lets them be on, and get only the events there
+// are. Checking the receiver resolves its host, so this blocks
+fn validate_webhook_subscription(
+    receivers: &ReceiverPolicy,
+    payload: WebhookSubscriptionPayload,
+) -> Result<WebhookSubscriptionPayload, ApiError> {
+    let mut problems = Vec::new();
+
+    if let Err(error) = receivers.check_url(&payload.url) {
+        problems.push(error.to_string());
+    }
+    for event_type in &payload.event_types {
+        if !EVENT_TYPES.contains(&event_type.as_str()) {
+            problems.push(format!(
+                "'{}' is not an event type, event types are {}",
+                event_type,
+                EVENT_TYPES.join(", ")
+            ));
+        }
+    }
+    if payload.secret.as_ref().is_some_and(|secret| secret.is_empty()) {
+        problems.push("secret must not be empty".to_string());
+    }
+
+    if problems.is_empty() {
+        Ok(payload)
+    } else {
+        Err(ApiError::UnprocessableEntity(problems.join("; ")))
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhooks",
+    tag = "webhooks",
+    responses(
+        (status = 200, description = "Every subscription", body = Vec<WebhookSubscription>),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client is not an admin", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn list_webhook_subscriptions(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+) -> Result<HttpResponse, ApiError> {
+    let subscriptions = with_connection(pool, tenant, move |connection| {
+        authorize(connection, &principal)?.require(Permission::Admin)?;
+
+        WebhookRepository::new(connection).list_webhook_subscriptions()
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(subscriptions))
+}
+
+#[utoipa::path(
+    post,
+    path = "/webhooks",
+    tag = "webhooks",
+    request_body = WebhookSubscriptionPayload,
+    responses(
+        (status = 201, description = "The created subscription along with its secret, which is not shown again", body = CreatedWebhookSubscription, headers(("Location" = String))),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client is not an admin", body = ErrorBody),
+        (status = 422, description = "The URL or event types are not valid, or the URL points to an internal address", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn create_webhook_subscription(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    receivers: web::Data<ReceiverPolicy>,
+    payload: web::Json<WebhookSubscriptionPayload>,
+) -> Result<HttpResponse, ApiError> {
+    let payload = payload.into_inner();
+    let payload = web::block(move || validate_webhook_subscription(&receivers, payload)).await??;
+    let secret = payload.secret.unwrap_or_else(generate_webhook_secret);
+
+    let created_subscription = with_connection(pool, tenant, move |connection| {
+        authorize(connection, &principal)?.require(Permission::Admin)?;
+
+        let subscription = WebhookRepository::new(connection)
+            .with_actor(principal.actor())
+            .create_webhook_subscription(&payload.url, &payload.event_types, &secret)?;
+        if !payload.active {
+            return WebhookRepository::new(connection)
+                .with_actor(principal.actor())
+                .update_webhook_subscription(subscription.id(), &payload.url, &payload.event_types, false);
+        }
+
+        Ok(subscription)
+    })
+    .await?;
+
+    Ok(HttpResponse::Created()
+        .insert_header((header::LOCATION, format!("/webhooks/{}", created_subscription.id())))
+        .json(CreatedWebhookSubscription {
+            secret: created_subscription.secret().to_string(),
+            subscription: created_subscription,
+        }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = WebhookSubscriptionId, Path, description = "The subscription's ID")),
+    responses(
+        (status = 200, description = "The subscription", body = WebhookSubscription),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client is not an admin", body = ErrorBody),
+        (status = 404, description = "There is no such subscription", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn get_webhook_subscription(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    path: web::Path<WebhookSubscriptionId>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+
+    let subscription = with_connection(pool, tenant, move |connection| {
+        authorize(connection, &principal)?.require(Permission::Admin)?;
+
+        WebhookRepository::new(connection).get_webhook_subscription(id)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(subscription))
+}
+
+// replaces where a subscription's events go and which ones it gets, and turns it on or off. The
+// secret cannot be changed, a subscription with a new secret is created instead
+#[utoipa::path(
+    put,
+    path = "/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = WebhookSubscriptionId, Path, description = "The subscription's ID")),
+    request_body = WebhookSubscriptionPayload,
+    responses(
+        (status = 200, description = "The updated subscription", body = WebhookSubscription),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client is not an admin", body = ErrorBody),
+        (status = 404, description = "There is no such subscription", body = ErrorBody),
+        (status = 422, description = "The URL or event types are not valid, or the URL points to an internal address", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn update_webhook_subscription(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    receivers: web::Data<ReceiverPolicy>,
+    path: web::Path<WebhookSubscriptionId>,
+    payload: web::Json<WebhookSubscriptionPayload>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+    let payload = payload.into_inner();
+    let payload = web::block(move || validate_webhook_subscription(&receivers, payload)).await??;
+    if payload.secret.is_some() {
+        return Err(ApiError::UnprocessableEntity(
+            "the secret of a subscription cannot be changed".to_string(),
+        ));
+    }
+
+    let updated_subscription = with_connection(pool, tenant, move |connection| {
+        authorize(connection, &principal)?.require(Permission::Admin)?;
+
+        WebhookRepository::new(connection)
+            .with_actor(principal.actor())
+            .update_webhook_subscription(id, &payload.url, &payload.event_types, payload.active)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(updated_subscription))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = WebhookSubscriptionId, Path, description = "The subscription's ID")),
+    responses(
+        (status = 204, description = "The subscription was deleted"),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client is not an admin", body = ErrorBody),
+        (status = 404, description = "There is no such subscription", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn delete_webhook_subscription(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    path: web::Path<WebhookSubscriptionId>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+
+    with_connection(pool, tenant, move |connection| {
+        authorize(connection, &principal)?.require(Permission::Admin)?;
+
+        WebhookRepository::new(connection)
+            .with_actor(principal.actor())
+            .delete_webhook_subscription(id)
+    })
+    .await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+// the delivery log of a subscription, newest first. `?status=dead` lists the deliveries that gave up
+#[utoipa::path(
+    get,
+    path = "/webhooks/{id}/deliveries",
+    tag = "webhooks",
+    params(
+        ("id" = WebhookSubscriptionId, Path, description = "The subscription's ID"),
+        ListDeliveriesQuery,
+    ),
+    responses(
+        (status = 200, description = "The latest deliveries to the subscription", body = Vec<WebhookDelivery>),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client is not an admin", body = ErrorBody),
+        (status = 404, description = "There is no such subscription", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn list_webhook_deliveries(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    path: web::Path<WebhookSubscriptionId>,
+    query: web::Query<ListDeliveriesQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+    let query = query.into_inner();
+    let params = ListQueryParams {
+        limit: query.limit.unwrap_or(DEFAULT_PAGE_SIZE),
+        offset: query.offset.unwrap_or(0),
+    };
+
+    let deliveries = with_connection(pool, tenant, move |connection| {
+        authorize(connection, &principal)?.require(Permission::Admin)?;
+
+        WebhookRepository::new(connection).list_webhook_deliveries(id, query.status, params)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(deliveries))
+}
+
+// attempts a delivery again from scratch, typically a dead one once its receiver was fixed
+#[utoipa::path(
+    post,
+    path = "/webhooks/{id}/deliveries/{delivery_id}/retry",
+    tag = "webhooks",
+    params(
+        ("id" = WebhookSubscriptionId, Path, description = "The subscription's ID"),
+        ("delivery_id" = i64, Path, description = "The delivery's ID"),
+    ),
+    responses(
+        (status = 202, description = "The delivery is due to be attempted again", body = WebhookDelivery),
+        (status = 401, description = "No valid credentials were given", body = ErrorBody),
+        (status = 403, description = "The client is not an admin", body = ErrorBody),
+        (status = 404, description = "There is no such delivery", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+async fn retry_webhook_delivery(
+    pool: web::Data<DbPool>,
+    tenant: Tenant,
+    principal: Principal,
+    path: web::Path<(WebhookSubscriptionId, i64)>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, delivery_id) = path.into_inner();
+
+    let delivery = with_connection(pool, tenant, move |connection| {
+        authorize(connection, &principal)?.require(Permission::Admin)?;
+
+        WebhookRepository::new(connection).retry_webhook_delivery(id, delivery_id)
+    })
+    .await?;
+
+    Ok(HttpResponse::Accepted().json(delivery))
+}
@@ -0,0 +1,13 @@
+use clap::Parser;
+use product_store::cli::{run, Cli};
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {:#}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
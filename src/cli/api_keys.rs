@@ -0,0 +1,99 @@
+use crate::cli::output::{print_output, OutputFormat, Table};
+use crate::core::entities::api_key::{generate_api_key, hash_api_key, ApiKey};
+use crate::core::ports::database::api_key_database::ApiKeyDatastore;
+use anyhow::{anyhow, Result as AnyResult};
+use chrono::{Duration, Utc};
+use clap::Subcommand;
+use serde::Serialize;
+
+#[derive(Debug, Subcommand)]
+pub enum ApiKeyCommand {
+    /// List API keys, revoked and expired ones included
+    List,
+    /// Create an API key and print it. The key cannot be shown again
+    Create {
+        /// A unique name for the client the key is for, which its writes are audited as
+        name: String,
+        /// A scope to grant the key, can be given more than once
+        #[arg(long = "scope")]
+        scopes: Vec<String>,
+        /// Let the key expire after this many days instead of never
+        #[arg(long)]
+        expires_in_days: Option<i64>,
+    },
+    /// Revoke an API key so that it cannot be used anymore
+    Revoke {
+        /// The name of the key
+        name: String,
+    },
+}
+
+#[derive(Serialize)]
+struct CreatedApiKey {
+    #[serde(flatten)]
+    api_key: ApiKey,
+    key: String,
+}
+
+fn api_key_row(api_key: &ApiKey) -> Vec<String> {
+    vec![
+        api_key.name().to_string(),
+        api_key.tenant().to_string(),
+        api_key.scopes().join(" "),
+        api_key.expires_at().map(|at| at.to_rfc3339()).unwrap_or_default(),
+        api_key.revoked_at().map(|at| at.to_rfc3339()).unwrap_or_default(),
+    ]
+}
+
+pub fn run_api_key_command(
+    command: ApiKeyCommand,
+    datastore: &mut impl ApiKeyDatastore,
+    output: OutputFormat,
+) -> AnyResult<()> {
+    match command {
+        ApiKeyCommand::List => {
+            let api_keys = datastore.list_api_keys()?;
+
+            print_output(output, &api_keys, |api_keys| {
+                let mut table = Table::new(vec!["NAME", "TENANT", "SCOPES", "EXPIRES AT", "REVOKED AT"]);
+                for api_key in api_keys {
+                    table.add_row(api_key_row(api_key));
+                }
+                table
+            })
+        }
+        ApiKeyCommand::Create {
+            name,
+            scopes,
+            expires_in_days,
+        } => {
+            let expires_at = match expires_in_days {
+                Some(days) if days <= 0 => return Err(anyhow!("--expires-in-days must be at least 1")),
+                Some(days) => Some(Utc::now() + Duration::days(days)),
+                None => None,
+            };
+            let key = generate_api_key();
+            let created_api_key = CreatedApiKey {
+                api_key: datastore.create_api_key(&name, &hash_api_key(&key), &scopes, expires_at)?,
+                key,
+            };
+
+            print_output(output, &created_api_key, |created_api_key| {
+                let mut table = Table::new(vec!["NAME", "TENANT", "SCOPES", "EXPIRES AT", "REVOKED AT", "KEY"]);
+                let mut row = api_key_row(&created_api_key.api_key);
+                row.push(created_api_key.key.clone());
+                table.add_row(row);
+                table
+            })
+        }
+        ApiKeyCommand::Revoke { name } => {
+            let revoked_api_key = datastore.revoke_api_key(&name)?;
+
+            print_output(output, &revoked_api_key, |revoked_api_key| {
+                let mut table = Table::new(vec!["NAME", "TENANT", "SCOPES", "EXPIRES AT", "REVOKED AT"]);
+                table.add_row(api_key_row(revoked_api_key));
+                table
+            })
+        }
+    }
+}
@@ -0,0 +1,110 @@
+pub mod api_keys;
+pub mod output;
+pub mod products;
+pub mod transfers;
+pub mod variants;
+
+use crate::cli::api_keys::{run_api_key_command, ApiKeyCommand};
+use crate::cli::output::{print_output, OutputFormat, Table};
+use crate::cli::products::{run_product_command, ProductCommand};
+use crate::cli::transfers::{run_export, run_import, ExportArgs, ImportArgs};
+use crate::cli::variants::{run_variant_command, VariantCommand};
+use crate::core::entities::tenant::Tenant;
+use crate::datastore::migrations::{ensure_schema_version, run_pending_migrations};
+use crate::datastore::repositories::api_key_repository::ApiKeyRepository;
+use crate::datastore::repositories::export_repository::ExportRepository;
+use crate::datastore::repositories::product_repository::ProductRepository;
+use crate::datastore::repositories::variant_repository::VariantRepository;
+use crate::datastore::tenancy::set_current_tenant;
+use crate::establish_connection;
+use anyhow::Result as AnyResult;
+use clap::{Parser, Subcommand};
+use std::env;
+
+/// Inspect and fix the product catalog in the database at `DATABASE_URL`
+#[derive(Debug, Parser)]
+#[command(name = "product-admin", version)]
+pub struct Cli {
+    /// How results are printed
+    #[arg(long, short, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    output: OutputFormat,
+    /// Apply pending migrations before running the command instead of refusing to run
+    #[arg(long, global = true, env = "RUN_MIGRATIONS")]
+    migrate: bool,
+    /// The tenant whose catalog to work on. API keys are created for it
+    #[arg(long, global = true, env = "DEFAULT_TENANT", default_value_t = Tenant::default())]
+    tenant: Tenant,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Manage products
+    #[command(subcommand)]
+    Product(ProductCommand),
+    /// Manage variants
+    #[command(subcommand)]
+    Variant(VariantCommand),
+    /// Import products from a CSV or JSON Lines file
+    Import(ImportArgs),
+    /// Export active products as CSV, JSON Lines or a Google Merchant feed
+    Export(ExportArgs),
+    /// Manage the API keys that clients authenticate with
+    #[command(subcommand)]
+    ApiKey(ApiKeyCommand),
+    /// Run the migrations that have not been applied to the database yet
+    Migrate,
+}
+
+// writes made from the command line are audited as the user who ran it
+fn cli_actor() -> String {
+    format!("cli:{}", env::var("USER").unwrap_or_else(|_| String::from("unknown")))
+}
+
+pub fn run(cli: Cli) -> AnyResult<()> {
+    let mut connection = establish_connection();
+
+    // `migrate` is the one command that may run against a schema at another version
+    if !matches!(cli.command, Command::Migrate) {
+        for version in ensure_schema_version(&mut connection, cli.migrate)? {
+            eprintln!("applied migration {}", version);
+        }
+    }
+    set_current_tenant(&mut connection, &cli.tenant)?;
+
+    match cli.command {
+        Command::Product(command) => run_product_command(
+            command,
+            &mut ProductRepository::new(&mut connection).with_actor(cli_actor()),
+            cli.output,
+        ),
+        Command::Variant(command) => run_variant_command(
+            command,
+            &mut VariantRepository::new(&mut connection).with_actor(cli_actor()),
+            cli.output,
+        ),
+        Command::Import(args) => run_import(
+            args,
+            &mut ProductRepository::new(&mut connection).with_actor(cli_actor()),
+            cli.output,
+        ),
+        Command::Export(args) => run_export(args, &mut ExportRepository::new(&mut connection)),
+        Command::ApiKey(command) => run_api_key_command(
+            command,
+            &mut ApiKeyRepository::new(&mut connection).with_actor(cli_actor()),
+            cli.output,
+        ),
+        Command::Migrate => {
+            let applied_versions = run_pending_migrations(&mut connection)?;
+
+            print_output(cli.output, &applied_versions, |applied_versions| {
+                let mut table = Table::new(vec!["APPLIED MIGRATION"]);
+                for version in applied_versions {
+                    table.add_row(vec![version.clone()]);
+                }
+                table
+            })
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use anyhow::Result as AnyResult;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::io::{self, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+}
+
+// rows of plain text printed under a header, with every column padded to its widest cell
+pub struct Table {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: Vec<&'static str>) -> Table {
+        Table {
+            headers,
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn add_row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        let mut widths = self.headers.iter().map(|header| header.len()).collect::<Vec<_>>();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let header = self.headers.iter().map(|header| header.to_string()).collect::<Vec<_>>();
+        for row in std::iter::once(&header).chain(&self.rows) {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ");
+
+            writeln!(writer, "{}", line.trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+// prints a command's result to stdout, either as pretty JSON or as the table `to_table` builds
+pub fn print_output<T: Serialize>(
+    format: OutputFormat,
+    value: &T,
+    to_table: impl FnOnce(&T) -> Table,
+) -> AnyResult<()> {
+    let mut stdout = io::stdout().lock();
+
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut stdout, value)?;
+            writeln!(stdout)?;
+        }
+        OutputFormat::Table => to_table(value).write_to(&mut stdout)?,
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod output_tests {
+    use crate::cli::output::Table;
+
+    #[test]
+    fn test_table_pads_columns() {
+        let mut table = Table::new(vec!["ID", "NAME"]);
+        table.add_row(vec!["1".to_string(), "boots".to_string()]);
+        table.add_row(vec!["12".to_string(), "sneakers".to_string()]);
+
+        let mut output = Vec::new();
+        table.write_to(&mut output).unwrap();
+
+        assert_eq!(
+            "ID  NAME\n1   boots\n12  sneakers\n",
+            String::from_utf8(output).unwrap()
+        );
+    }
+}
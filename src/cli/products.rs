@@ -0,0 +1,216 @@
+use crate::cli::output::{print_output, OutputFormat, Table};
+use crate::core::entities::complete_product::CompleteProduct;
+use crate::core::entities::product::Product;
+use crate::core::entities::product_reference::ProductReference;
+use crate::core::entities::variant::Variant;
+use crate::core::entities::variant_value::VariantValue;
+use crate::core::ports::database::product_database::ProductDatastore;
+use crate::core::ports::database::utils::ListQueryParams;
+use anyhow::{anyhow, Result as AnyResult};
+use clap::{Args, Subcommand};
+use serde::Serialize;
+
+#[derive(Debug, Subcommand)]
+pub enum ProductCommand {
+    /// List products
+    List {
+        #[arg(long, default_value_t = 20)]
+        limit: i64,
+        #[arg(long, default_value_t = 0)]
+        offset: i64,
+    },
+    /// Show a product along with its variant values
+    Get {
+        /// The ID, public ID or slug of the product
+        product: ProductReference,
+    },
+    /// Create a product
+    Create(CreateProductArgs),
+    /// Change the fields of a product, fields that are left out keep their current value
+    Update(UpdateProductArgs),
+    /// Deactivate a product so that it is no longer sold or exported
+    Archive {
+        /// The ID, public ID or slug of the product
+        product: ProductReference,
+        /// Fail unless the product is still at this version
+        #[arg(long)]
+        expected_version: Option<u32>,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct CreateProductArgs {
+    #[arg(long)]
+    name: String,
+    #[arg(long)]
+    cost: f64,
+    #[arg(long)]
+    description: Option<String>,
+    /// Create the product deactivated
+    #[arg(long)]
+    inactive: bool,
+    #[arg(long)]
+    external_key: Option<String>,
+    /// A variant and its values, e.g. `size=40|41|42`. Can be repeated
+    #[arg(long = "variant", value_name = "NAME=VALUES", value_parser = parse_variant_value)]
+    variants: Vec<VariantValue>,
+}
+
+#[derive(Debug, Args)]
+pub struct UpdateProductArgs {
+    /// The ID, public ID or slug of the product
+    product: ProductReference,
+    #[arg(long)]
+    name: Option<String>,
+    #[arg(long)]
+    cost: Option<f64>,
+    #[arg(long)]
+    description: Option<String>,
+    #[arg(long)]
+    active: Option<bool>,
+    /// Fail unless the product is still at this version
+    #[arg(long)]
+    expected_version: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct ProductVariantValue {
+    name: String,
+    value: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ProductWithVariants {
+    product: Product,
+    variants: Vec<ProductVariantValue>,
+}
+
+pub fn run_product_command(
+    command: ProductCommand,
+    datastore: &mut impl ProductDatastore,
+    output: OutputFormat,
+) -> AnyResult<()> {
+    match command {
+        ProductCommand::List { limit, offset } => {
+            let listed_products = datastore.list_products(ListQueryParams { limit, offset })?;
+
+            print_output(output, &listed_products, |listed_products| products_table(listed_products))
+        }
+        ProductCommand::Get { product } => {
+            let id = datastore.resolve_product(&product)?.id();
+            let (product, product_variants) = datastore.get_product_with_variants(id)?;
+            let product_with_variants = ProductWithVariants {
+                product,
+                variants: product_variants
+                    .into_iter()
+                    .map(|(product_variant, variant)| ProductVariantValue {
+                        name: variant.name().to_string(),
+                        value: product_variant.value().clone(),
+                    })
+                    .collect(),
+            };
+
+            print_output(output, &product_with_variants, |product_with_variants| {
+                let mut table = products_table(std::slice::from_ref(&product_with_variants.product));
+                for variant in &product_with_variants.variants {
+                    table.add_row(vec![
+                        String::new(),
+                        format!("{}: {}", variant.name, variant.value.clone().unwrap_or_default()),
+                    ]);
+                }
+                table
+            })
+        }
+        ProductCommand::Create(args) => {
+            let product = Product::new(args.name, args.cost, !args.inactive, None)
+                .with_external_key(args.external_key)
+                .with_description(args.description);
+            let created_product = if args.variants.is_empty() {
+                datastore.create_product(product)?
+            } else {
+                let created_product_id = datastore.create_complete_product(CompleteProduct::new(product, args.variants))?;
+                datastore.get_product(created_product_id)?
+            };
+
+            print_output(output, &created_product, |created_product| {
+                products_table(std::slice::from_ref(created_product))
+            })
+        }
+        ProductCommand::Update(args) => {
+            let id = datastore.resolve_product(&args.product)?.id();
+            let existing_product = datastore.get_product(id)?;
+            let product = Product::new(
+                args.name.unwrap_or_else(|| existing_product.name().to_string()),
+                args.cost.unwrap_or(existing_product.cost()),
+                args.active.unwrap_or(existing_product.active()),
+                Some(id),
+            )
+            .with_description(args.description.or_else(|| existing_product.description().map(str::to_string)))
+            .with_attributes(existing_product.product_type_id(), existing_product.attributes().clone())
+            .with_stock_quantity(existing_product.stock_quantity());
+            let expected_version = args.expected_version.unwrap_or(existing_product.version());
+            let updated_product = datastore.update_product(id, product, expected_version)?;
+
+            print_output(output, &updated_product, |updated_product| {
+                products_table(std::slice::from_ref(updated_product))
+            })
+        }
+        ProductCommand::Archive { product, expected_version } => {
+            let id = datastore.resolve_product(&product)?.id();
+            let existing_product = datastore.get_product(id)?;
+            let product = Product::new(
+                existing_product.name().to_string(),
+                existing_product.cost(),
+                false,
+                Some(id),
+            )
+            .with_description(existing_product.description().map(str::to_string))
+            .with_attributes(existing_product.product_type_id(), existing_product.attributes().clone())
+            .with_stock_quantity(existing_product.stock_quantity());
+            let expected_version = expected_version.unwrap_or(existing_product.version());
+            let archived_product = datastore.update_product(id, product, expected_version)?;
+
+            print_output(output, &archived_product, |archived_product| {
+                products_table(std::slice::from_ref(archived_product))
+            })
+        }
+    }
+}
+
+fn products_table(listed_products: &[Product]) -> Table {
+    let mut table = Table::new(vec!["ID", "SLUG", "NAME", "COST", "ACTIVE", "VERSION", "EXTERNAL KEY"]);
+
+    for product in listed_products {
+        table.add_row(vec![
+            product.id().map(|id| id.to_string()).unwrap_or_default(),
+            product.slug().unwrap_or_default().to_string(),
+            product.name().to_string(),
+            format!("{:.2}", product.cost()),
+            product.active().to_string(),
+            product.version().to_string(),
+            product.external_key().unwrap_or_default().to_string(),
+        ]);
+    }
+
+    table
+}
+
+// parses a `--variant` argument of the form `name=value|value`
+fn parse_variant_value(argument: &str) -> AnyResult<VariantValue> {
+    let (name, values) = argument
+        .split_once('=')
+        .ok_or_else(|| anyhow!("expected NAME=VALUES, got '{}'", argument))?;
+    let name = name.trim();
+    let values = values
+        .split('|')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| Some(value.to_string()))
+        .collect::<Vec<_>>();
+
+    if name.is_empty() || values.is_empty() {
+        return Err(anyhow!("expected NAME=VALUES, got '{}'", argument));
+    }
+
+    Ok(VariantValue::new(Variant::new(name.to_string(), None), values))
+}
@@ -0,0 +1,101 @@
+use crate::cli::output::{print_output, OutputFormat, Table};
+use crate::core::ports::database::export_database::ProductExportDatastore;
+use crate::core::ports::database::product_database::ProductDatastore;
+use crate::export::merchant_feed::MerchantFeedConfig;
+use crate::export::{export_products, ExportFormat, ExportOptions};
+use crate::import::{import_products, ImportFormat, ImportOptions, DEFAULT_BATCH_SIZE};
+use anyhow::{anyhow, Result as AnyResult};
+use chrono::{DateTime, Utc};
+use clap::Args;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+#[derive(Debug, Args)]
+pub struct ImportArgs {
+    /// The CSV or JSON Lines file to import
+    file: PathBuf,
+    /// `csv` or `jsonl`, guessed from the file extension when left out
+    #[arg(long)]
+    format: Option<String>,
+    /// Validate and apply the file without committing anything
+    #[arg(long)]
+    dry_run: bool,
+    #[arg(long, default_value_t = DEFAULT_BATCH_SIZE)]
+    batch_size: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// `csv`, `jsonl` or `google-merchant`
+    #[arg(long)]
+    format: String,
+    /// Only export products changed after this RFC 3339 timestamp
+    #[arg(long)]
+    changed_since: Option<DateTime<Utc>>,
+    /// The file to write the export to, stdout when left out
+    file: Option<PathBuf>,
+}
+
+pub fn run_import(args: ImportArgs, datastore: &mut impl ProductDatastore, output: OutputFormat) -> AnyResult<()> {
+    let format = match &args.format {
+        Some(format) => format.parse::<ImportFormat>()?,
+        None => match args.file.extension().and_then(|extension| extension.to_str()) {
+            Some("csv") => ImportFormat::Csv,
+            Some("jsonl") | Some("ndjson") => ImportFormat::JsonLines,
+            _ => return Err(anyhow!("Cannot tell the format of {}, pass --format", args.file.display())),
+        },
+    };
+    let options = ImportOptions {
+        format,
+        dry_run: args.dry_run,
+        batch_size: args.batch_size,
+    };
+
+    let report = import_products(BufReader::new(File::open(&args.file)?), &options, datastore)?;
+
+    print_output(output, &report, |report| {
+        let mut table = Table::new(vec!["LINE", "EXTERNAL KEY", "ERROR"]);
+        for failed_row in &report.failed {
+            table.add_row(vec![
+                failed_row.line.to_string(),
+                failed_row.external_key.clone().unwrap_or_default(),
+                failed_row.message.clone(),
+            ]);
+        }
+        table
+    })?;
+
+    if output == OutputFormat::Table {
+        eprintln!(
+            "{} rows read: {} created, {} updated, {} unchanged, {} failed{}",
+            report.rows_read,
+            report.created,
+            report.updated,
+            report.unchanged,
+            report.failed.len(),
+            if report.dry_run { " (dry run, nothing was committed)" } else { "" },
+        );
+    }
+
+    Ok(())
+}
+
+// the export is written as is, so the output format only applies to the summary on stderr
+pub fn run_export(args: ExportArgs, datastore: &mut impl ProductExportDatastore) -> AnyResult<()> {
+    let options = ExportOptions {
+        format: args.format.parse::<ExportFormat>()?,
+        changed_since: args.changed_since,
+        merchant_feed: MerchantFeedConfig::from_env(),
+    };
+
+    let writer: Box<dyn Write> = match &args.file {
+        Some(file) => Box::new(BufWriter::new(File::create(file)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    let exported = export_products(writer, &options, datastore)?;
+    eprintln!("{} products exported", exported);
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use crate::cli::output::{print_output, OutputFormat, Table};
+use crate::core::entities::ids::{ProductId, VariantId};
+use crate::core::ports::database::variant_database::VariantDatastore;
+use anyhow::Result as AnyResult;
+use clap::Subcommand;
+use serde::Serialize;
+
+#[derive(Debug, Subcommand)]
+pub enum VariantCommand {
+    /// List variants and how many product values use each of them
+    List,
+    /// Merge a variant into another one, moving its values over and deleting it
+    Merge {
+        /// The variant to merge and delete
+        source_id: VariantId,
+        /// The variant that takes over the values
+        #[arg(long = "into")]
+        target_id: VariantId,
+    },
+}
+
+#[derive(Serialize)]
+struct VariantUsage {
+    id: Option<VariantId>,
+    name: String,
+    values: u32,
+}
+
+#[derive(Serialize)]
+struct VariantMerge {
+    source_id: VariantId,
+    target_id: VariantId,
+    updated_products: Vec<ProductId>,
+}
+
+pub fn run_variant_command(
+    command: VariantCommand,
+    datastore: &mut impl VariantDatastore,
+    output: OutputFormat,
+) -> AnyResult<()> {
+    match command {
+        VariantCommand::List => {
+            let listed_variants = datastore
+                .list_variants()?
+                .into_iter()
+                .map(|(variant, values)| VariantUsage {
+                    id: variant.id(),
+                    name: variant.name().to_string(),
+                    values,
+                })
+                .collect::<Vec<_>>();
+
+            print_output(output, &listed_variants, |listed_variants| {
+                let mut table = Table::new(vec!["ID", "NAME", "VALUES"]);
+                for variant in listed_variants {
+                    table.add_row(vec![
+                        variant.id.map(|id| id.to_string()).unwrap_or_default(),
+                        variant.name.clone(),
+                        variant.values.to_string(),
+                    ]);
+                }
+                table
+            })
+        }
+        VariantCommand::Merge { source_id, target_id } => {
+            let variant_merge = VariantMerge {
+                source_id,
+                target_id,
+                updated_products: datastore.merge_variants(source_id, target_id)?,
+            };
+
+            print_output(output, &variant_merge, |variant_merge| {
+                let mut table = Table::new(vec!["UPDATED PRODUCT ID"]);
+                for product_id in &variant_merge.updated_products {
+                    table.add_row(vec![product_id.to_string()]);
+                }
+                table
+            })
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use crate::core::entities::complete_product::CompleteProduct;
+use crate::core::entities::ids::ProductId;
+use crate::core::entities::principal::Principal;
+use crate::core::entities::product::Product;
+use crate::core::entities::product_import::ImportedProduct;
+use crate::core::entities::product_reference::{ProductReference, ResolvedProduct};
+use crate::core::entities::product_variant::ProductVariant;
+use crate::core::entities::role::{Permission, Role};
+use crate::core::entities::variant::Variant;
+use crate::core::ports::database::errors::DatastoreError;
+use crate::core::ports::database::product_database::{ProductDatastore, ProductWithVariants};
+use crate::core::ports::database::utils::ListQueryParams;
+use anyhow::Result as AnyResult;
+use std::collections::BTreeSet;
+
+// the permissions a principal holds. Each of its scopes grants either the permission it names or
+// the permissions of the role it names, and scopes that name neither grant nothing. `admin` allows
+// everything
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Authorization {
+    subject: String,
+    permissions: BTreeSet<Permission>,
+}
+
+impl Authorization {
+    pub fn new(principal: &Principal, roles: &[Role]) -> Authorization {
+        let mut permissions = BTreeSet::new();
+
+        for scope in principal.scopes() {
+            match scope.parse::<Permission>() {
+                Ok(permission) => {
+                    permissions.insert(permission);
+                }
+                Err(_) => {
+                    if let Some(role) = roles.iter().find(|role| role.name() == scope) {
+                        permissions.extend(role.permissions().iter().copied());
+                    }
+                }
+            }
+        }
+
+        Authorization {
+            subject: principal.actor(),
+            permissions,
+        }
+    }
+
+    pub fn permissions(&self) -> &BTreeSet<Permission> {
+        &self.permissions
+    }
+
+    pub fn allows(&self, permission: Permission) -> bool {
+        self.permissions.contains(&Permission::Admin)
+            || self.permissions.contains(&permission)
+    }
+
+    // fails with a permission denied error unless the principal holds `permission`
+    pub fn require(&self, permission: Permission) -> AnyResult<()> {
+        if self.allows(permission) {
+            Ok(())
+        } else {
+            Err(DatastoreError::PermissionDenied {
+                subject: self.subject.clone(),
+                permission: permission.as_str(),
+            }
+            .into())
+        }
+    }
+}
+
+// a product datastore that only lets a principal do what its permissions allow. Reads are open to
+// anyone, as they are over HTTP, GraphQL and gRPC. Writes need `catalog:write`, except that changing
+// the cost of a product needs `pricing:write` and changing its stock needs `inventory:adjust`
+// instead. Imports replace costs, so they need both `catalog:write` and `pricing:write`
+pub struct AuthorizedProductDatastore<D> {
+    datastore: D,
+    authorization: Authorization,
+}
+
+impl<D: ProductDatastore> AuthorizedProductDatastore<D> {
+    pub fn new(datastore: D, authorization: Authorization) -> AuthorizedProductDatastore<D> {
+        AuthorizedProductDatastore {
+            datastore,
+            authorization,
+        }
+    }
+
+    pub fn authorization(&self) -> &Authorization {
+        &self.authorization
+    }
+}
+
+// the permissions needed to turn `existing` into `updated`
+fn update_permissions(existing: &Product, updated: &Product) -> Vec<Permission> {
+    let mut permissions = Vec::new();
+
+    if existing.cost() != updated.cost() {
+        permissions.push(Permission::PricingWrite);
+    }
+    if existing.stock_quantity() != updated.stock_quantity() {
+        permissions.push(Permission::InventoryAdjust);
+    }
+    if existing.name() != updated.name()
+        || existing.description() != updated.description()
+        || existing.active() != updated.active()
+        || existing.product_type_id() != updated.product_type_id()
+        || existing.attributes() != updated.attributes()
+    {
+        permissions.push(Permission::CatalogWrite);
+    }
+
+    permissions
+}
+
+impl<D: ProductDatastore> ProductDatastore for AuthorizedProductDatastore<D> {
+    fn create_product(&mut self, product: Product) -> AnyResult<Product> {
+        self.authorization.require(Permission::CatalogWrite)?;
+        self.datastore.create_product(product)
+    }
+
+    fn create_complete_product(&mut self, complete_product: CompleteProduct) -> AnyResult<ProductId> {
+        self.authorization.require(Permission::CatalogWrite)?;
+        self.datastore.create_complete_product(complete_product)
+    }
+
+    fn upsert_complete_products(
+        &mut self,
+        complete_products: Vec<CompleteProduct>,
+        dry_run: bool,
+    ) -> AnyResult<Vec<AnyResult<ImportedProduct>>> {
+        self.authorization.require(Permission::CatalogWrite)?;
+        self.authorization.require(Permission::PricingWrite)?;
+        self.datastore.upsert_complete_products(complete_products, dry_run)
+    }
+
+    fn update_product(&mut self, id: ProductId, product: Product, expected_version: u32) -> AnyResult<Product> {
+        let existing_product = self.datastore.get_product(id)?;
+        for permission in update_permissions(&existing_product, &product) {
+            self.authorization.require(permission)?;
+        }
+
+        self.datastore.update_product(id, product, expected_version)
+    }
+
+    fn delete_product(&mut self, id: ProductId, expected_version: u32) -> AnyResult<()> {
+        self.authorization.require(Permission::CatalogWrite)?;
+        self.datastore.delete_product(id, expected_version)
+    }
+
+    fn resolve_product(&mut self, reference: &ProductReference) -> AnyResult<ResolvedProduct> {
+        self.datastore.resolve_product(reference)
+    }
+
+    fn find_product_by_external_key(&mut self, external_key: &str) -> AnyResult<ProductId> {
+        self.datastore.find_product_by_external_key(external_key)
+    }
+
+    fn get_product(&mut self, id: ProductId) -> AnyResult<Product> {
+        self.datastore.get_product(id)
+    }
+
+    fn get_product_with_variants(&mut self, id: ProductId) -> AnyResult<(Product, Vec<(ProductVariant, Variant)>)> {
+        self.datastore.get_product_with_variants(id)
+    }
+
+    fn list_products(&mut self, params: ListQueryParams) -> AnyResult<Vec<Product>> {
+        self.datastore.list_products(params)
+    }
+
+    fn list_variants_of_products(&mut self, ids: &[ProductId]) -> AnyResult<Vec<(ProductVariant, Variant)>> {
+        self.datastore.list_variants_of_products(ids)
+    }
+
+    fn list_products_with_variants(
+        &mut self,
+        after: Option<ProductId>,
+        limit: i64,
+    ) -> AnyResult<Vec<ProductWithVariants>> {
+        self.datastore.list_products_with_variants(after, limit)
+    }
+}
+
+#[cfg(test)]
+mod authorization_tests {
+    use crate::core::authorization::{Authorization, AuthorizedProductDatastore};
+    use crate::core::entities::principal::{AuthMethod, Principal};
+    use crate::core::entities::product::Product;
+    use crate::core::entities::role::{Permission, Role};
+    use crate::core::ports::database::errors::DatastoreError;
+    use crate::core::ports::database::product_database::ProductDatastore;
+    use crate::datastore::repositories::product_repository::ProductRepository;
+    use crate::establish_connection_test;
+    use diesel::Connection;
+
+    fn principal(scopes: &[&str]) -> Principal {
+        Principal::new(
+            "storefront".to_string(),
+            AuthMethod::ApiKey,
+            scopes.iter().map(|scope| scope.to_string()).collect(),
+        )
+    }
+
+    fn roles() -> Vec<Role> {
+        vec![
+            Role::new("partner".to_string(), vec![Permission::CatalogRead]),
+            Role::new("pricing".to_string(), vec![Permission::CatalogRead, Permission::PricingWrite]),
+        ]
+    }
+
+    #[test]
+    fn test_scopes_grant_permissions_and_roles() {
+        let authorization = Authorization::new(&principal(&["pricing", "inventory:adjust", "unknown"]), &roles());
+
+        assert_eq!(
+            vec![Permission::CatalogRead, Permission::PricingWrite, Permission::InventoryAdjust],
+            authorization.permissions().iter().copied().collect::<Vec<_>>()
+        );
+        assert!(!authorization.allows(Permission::CatalogWrite));
+        assert!(Authorization::new(&principal(&["admin"]), &roles()).allows(Permission::CatalogWrite));
+    }
+
+    #[test]
+    fn test_cost_changes_need_pricing_permission() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let product_id = ProductRepository::new(conn)
+                .create_product(Product::new("Desk lamp".to_string(), 30.0, true, None))
+                .expect("Error creating product")
+                .id()
+                .unwrap();
+
+            let mut partner = AuthorizedProductDatastore::new(
+                ProductRepository::new(conn),
+                Authorization::new(&principal(&["partner"]), &roles()),
+            );
+            assert_eq!(30.0, partner.get_product(product_id).expect("Error reading product").cost());
+            let error = partner
+                .update_product(product_id, Product::new("Desk lamp".to_string(), 25.0, true, Some(product_id)), 1)
+                .expect_err("A partner changed a cost");
+            assert_eq!(
+                Some(&DatastoreError::PermissionDenied {
+                    subject: "api_key:storefront".to_string(),
+                    permission: "pricing:write",
+                }),
+                error.downcast_ref::<DatastoreError>()
+            );
+
+            let mut pricing = AuthorizedProductDatastore::new(
+                ProductRepository::new(conn),
+                Authorization::new(&principal(&["pricing"]), &roles()),
+            );
+            let updated_product = pricing
+                .update_product(product_id, Product::new("Desk lamp".to_string(), 25.0, true, Some(product_id)), 1)
+                .expect("Error changing cost");
+            assert_eq!(25.0, updated_product.cost());
+
+            let error = pricing
+                .update_product(product_id, Product::new("Reading lamp".to_string(), 25.0, true, Some(product_id)), 2)
+                .expect_err("Pricing renamed a product");
+            assert!(matches!(
+                error.downcast_ref::<DatastoreError>(),
+                Some(DatastoreError::PermissionDenied { permission: "catalog:write", .. })
+            ));
+
+            Ok(())
+        })
+    }
+}
@@ -1,5 +1,28 @@
+pub mod api_key;
+pub mod audit_record;
+pub mod bundle;
 pub mod complete_product;
+pub mod domain_event;
+pub mod ids;
+pub mod idempotency_record;
+pub mod locale;
+pub mod principal;
 pub mod product;
+pub mod product_change;
+pub mod product_detail;
+pub mod product_export;
+pub mod product_image;
+pub mod product_import;
+pub mod product_reference;
+pub mod product_relation;
+pub mod product_revision;
+pub mod product_type;
 pub mod product_variant;
+pub mod rate_limit;
+pub mod role;
+pub mod specification;
+pub mod tenant;
+pub mod translation;
 pub mod variant;
 pub mod variant_value;
+pub mod webhook;
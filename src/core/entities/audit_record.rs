@@ -0,0 +1,220 @@
+use anyhow::{anyhow, Error as AnyError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::str::FromStr;
+
+// actor recorded when a write is made without an explicit actor
+pub const SYSTEM_ACTOR: &str = "system";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEntityType {
+    Product,
+    Variant,
+    ProductVariant,
+    ApiKey,
+    Bundle,
+    ProductImages,
+    ProductRelations,
+    ProductSpecifications,
+    ProductType,
+    ProductTranslation,
+    Role,
+    VariantTranslation,
+    WebhookSubscription,
+}
+
+impl AuditEntityType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEntityType::Product => "product",
+            AuditEntityType::Variant => "variant",
+            AuditEntityType::ProductVariant => "product_variant",
+            AuditEntityType::ApiKey => "api_key",
+            AuditEntityType::Bundle => "bundle",
+            AuditEntityType::ProductImages => "product_images",
+            AuditEntityType::ProductRelations => "product_relations",
+            AuditEntityType::ProductSpecifications => "product_specifications",
+            AuditEntityType::ProductType => "product_type",
+            AuditEntityType::ProductTranslation => "product_translation",
+            AuditEntityType::Role => "role",
+            AuditEntityType::VariantTranslation => "variant_translation",
+            AuditEntityType::WebhookSubscription => "webhook_subscription",
+        }
+    }
+}
+
+impl FromStr for AuditEntityType {
+    type Err = AnyError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "product" => Ok(AuditEntityType::Product),
+            "variant" => Ok(AuditEntityType::Variant),
+            "product_variant" => Ok(AuditEntityType::ProductVariant),
+            "api_key" => Ok(AuditEntityType::ApiKey),
+            "bundle" => Ok(AuditEntityType::Bundle),
+            "product_images" => Ok(AuditEntityType::ProductImages),
+            "product_relations" => Ok(AuditEntityType::ProductRelations),
+            "product_specifications" => Ok(AuditEntityType::ProductSpecifications),
+            "product_type" => Ok(AuditEntityType::ProductType),
+            "product_translation" => Ok(AuditEntityType::ProductTranslation),
+            "role" => Ok(AuditEntityType::Role),
+            "variant_translation" => Ok(AuditEntityType::VariantTranslation),
+            "webhook_subscription" => Ok(AuditEntityType::WebhookSubscription),
+            other => Err(anyhow!("Unknown audit entity type: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Created,
+    Updated,
+    Deleted,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Created => "created",
+            AuditAction::Updated => "updated",
+            AuditAction::Deleted => "deleted",
+        }
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = AnyError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "created" => Ok(AuditAction::Created),
+            "updated" => Ok(AuditAction::Updated),
+            "deleted" => Ok(AuditAction::Deleted),
+            other => Err(anyhow!("Unknown audit action: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditRecord {
+    id: i32,
+    actor: String,
+    occurred_at: DateTime<Utc>,
+    entity_type: AuditEntityType,
+    entity_id: i32,
+    action: AuditAction,
+    changes: Value,
+}
+
+impl AuditRecord {
+    pub fn new(
+        id: i32,
+        actor: String,
+        occurred_at: DateTime<Utc>,
+        entity_type: AuditEntityType,
+        entity_id: i32,
+        action: AuditAction,
+        changes: Value,
+    ) -> AuditRecord {
+        AuditRecord {
+            id,
+            actor,
+            occurred_at,
+            entity_type,
+            entity_id,
+            action,
+            changes,
+        }
+    }
+
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn actor(&self) -> &str {
+        self.actor.as_str()
+    }
+
+    pub fn occurred_at(&self) -> DateTime<Utc> {
+        self.occurred_at
+    }
+
+    pub fn entity_type(&self) -> AuditEntityType {
+        self.entity_type
+    }
+
+    pub fn entity_id(&self) -> i32 {
+        self.entity_id
+    }
+
+    pub fn action(&self) -> AuditAction {
+        self.action
+    }
+
+    pub fn changes(&self) -> &Value {
+        &self.changes
+    }
+
+    // computes the fields that differ between two snapshots of an entity. Each changed field maps
+    // to an object holding its `before` and `after` values, a missing snapshot counts as null fields
+    pub fn diff(before: Option<&Value>, after: Option<&Value>) -> Value {
+        let empty = Map::new();
+        let before_fields = before.and_then(Value::as_object).unwrap_or(&empty);
+        let after_fields = after.and_then(Value::as_object).unwrap_or(&empty);
+
+        let mut changes = Map::new();
+
+        for field in before_fields.keys().chain(after_fields.keys()) {
+            if changes.contains_key(field) {
+                continue;
+            }
+
+            let old_value = before_fields.get(field).cloned().unwrap_or(Value::Null);
+            let new_value = after_fields.get(field).cloned().unwrap_or(Value::Null);
+
+            if old_value != new_value {
+                let mut change = Map::new();
+                change.insert("before".to_string(), old_value);
+                change.insert("after".to_string(), new_value);
+                changes.insert(field.clone(), Value::Object(change));
+            }
+        }
+
+        Value::Object(changes)
+    }
+}
+
+#[cfg(test)]
+mod audit_record_tests {
+    use crate::core::entities::audit_record::AuditRecord;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_only_contains_changed_fields() {
+        let before = json!({"id": 1, "name": "boots", "cost": 13.23});
+        let after = json!({"id": 1, "name": "boots", "cost": 15.0});
+
+        let actual = AuditRecord::diff(Some(&before), Some(&after));
+
+        assert_eq!(actual, json!({"cost": {"before": 13.23, "after": 15.0}}));
+    }
+
+    #[test]
+    fn test_diff_of_created_entity() {
+        let after = json!({"id": 1, "name": "boots"});
+
+        let actual = AuditRecord::diff(None, Some(&after));
+
+        assert_eq!(
+            actual,
+            json!({
+                "id": {"before": null, "after": 1},
+                "name": {"before": null, "after": "boots"}
+            })
+        );
+    }
+}
@@ -0,0 +1,348 @@
+use crate::core::entities::ids::ProductId;
+use crate::core::entities::product::Product;
+use anyhow::{anyhow, Error as AnyError};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+// how the price of a bundle is set: the bundle product's own cost, or the cost of its components
+// with a discount taken off
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BundlePricing {
+    Fixed,
+    Derived { discount_percent: f64 },
+}
+
+// what happens to a bundle when one of its components is deactivated: it is deactivated along
+// with it, or it stays active and reports a warning while it cannot be sold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentDeactivation {
+    Deactivate,
+    Warn,
+}
+
+impl ComponentDeactivation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ComponentDeactivation::Deactivate => "deactivate",
+            ComponentDeactivation::Warn => "warn",
+        }
+    }
+}
+
+impl FromStr for ComponentDeactivation {
+    type Err = AnyError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "deactivate" => Ok(ComponentDeactivation::Deactivate),
+            "warn" => Ok(ComponentDeactivation::Warn),
+            other => Err(anyhow!("Unknown component deactivation: {}", other)),
+        }
+    }
+}
+
+// a product that goes into a bundle, and how many units of it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ComponentQuantity {
+    product_id: ProductId,
+    quantity: u32,
+}
+
+impl ComponentQuantity {
+    pub fn new(product_id: ProductId, quantity: u32) -> ComponentQuantity {
+        ComponentQuantity { product_id, quantity }
+    }
+
+    pub fn product_id(&self) -> ProductId {
+        self.product_id
+    }
+
+    pub fn quantity(&self) -> u32 {
+        self.quantity
+    }
+}
+
+// what a bundle is made of, as it is written
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BundleDefinition {
+    pricing: BundlePricing,
+    on_component_deactivated: ComponentDeactivation,
+    components: Vec<ComponentQuantity>,
+}
+
+impl BundleDefinition {
+    pub fn new(
+        pricing: BundlePricing,
+        on_component_deactivated: ComponentDeactivation,
+        components: Vec<ComponentQuantity>,
+    ) -> BundleDefinition {
+        BundleDefinition {
+            pricing,
+            on_component_deactivated,
+            components,
+        }
+    }
+
+    pub fn pricing(&self) -> BundlePricing {
+        self.pricing
+    }
+
+    pub fn on_component_deactivated(&self) -> ComponentDeactivation {
+        self.on_component_deactivated
+    }
+
+    pub fn components(&self) -> &[ComponentQuantity] {
+        &self.components
+    }
+
+    // checks the definition on its own: it has components, each is listed once with at least one
+    // unit and a discount is a percentage. Returns every problem found
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+
+        if self.components.is_empty() {
+            problems.push("A bundle needs at least one component".to_string());
+        }
+
+        for (index, component) in self.components.iter().enumerate() {
+            if component.quantity == 0 {
+                problems.push(format!("Component {} needs a quantity of at least 1", component.product_id));
+            }
+            if self.components[..index]
+                .iter()
+                .any(|earlier| earlier.product_id == component.product_id)
+            {
+                problems.push(format!("Component {} is listed more than once", component.product_id));
+            }
+        }
+
+        if let BundlePricing::Derived { discount_percent } = self.pricing {
+            if !(0.0..=100.0).contains(&discount_percent) {
+                problems.push("The discount must be between 0 and 100 percent".to_string());
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+}
+
+// a component of a stored bundle along with what its price and availability depend on
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct BundleComponent {
+    product_id: ProductId,
+    name: String,
+    quantity: u32,
+    cost: f64,
+    active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stock_quantity: Option<u32>,
+}
+
+impl BundleComponent {
+    pub fn new(product: &Product, product_id: ProductId, quantity: u32) -> BundleComponent {
+        BundleComponent {
+            product_id,
+            name: product.name().to_string(),
+            quantity,
+            cost: product.cost(),
+            active: product.active(),
+            stock_quantity: product.stock_quantity(),
+        }
+    }
+
+    pub fn product_id(&self) -> ProductId {
+        self.product_id
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn quantity(&self) -> u32 {
+        self.quantity
+    }
+
+    pub fn active(&self) -> bool {
+        self.active
+    }
+}
+
+// a stored bundle with its price and availability worked out from its components.
+// `available_quantity` is how many bundles the component stock makes up, `None` when none of the
+// components has its stock tracked. A bundle with an inactive component cannot be sold, which
+// `warnings` explains
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct Bundle {
+    product_id: ProductId,
+    pricing: BundlePricing,
+    on_component_deactivated: ComponentDeactivation,
+    components: Vec<BundleComponent>,
+    price: f64,
+    available_quantity: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<String>,
+}
+
+impl Bundle {
+    pub fn new(
+        bundle_product: &Product,
+        product_id: ProductId,
+        pricing: BundlePricing,
+        on_component_deactivated: ComponentDeactivation,
+        components: Vec<BundleComponent>,
+    ) -> Bundle {
+        let price = match pricing {
+            BundlePricing::Fixed => bundle_product.cost(),
+            BundlePricing::Derived { discount_percent } => {
+                let components_cost = components
+                    .iter()
+                    .map(|component| component.cost * f64::from(component.quantity))
+                    .sum::<f64>();
+
+                (components_cost * (100.0 - discount_percent)).round() / 100.0
+            }
+        };
+
+        let warnings = components
+            .iter()
+            .filter(|component| !component.active)
+            .map(|component| format!("Component {} ({}) is inactive", component.name, component.product_id))
+            .collect::<Vec<_>>();
+
+        let available_quantity = if !bundle_product.active() || !warnings.is_empty() {
+            Some(0)
+        } else {
+            components
+                .iter()
+                .filter_map(|component| {
+                    component
+                        .stock_quantity
+                        .map(|stock_quantity| stock_quantity / component.quantity.max(1))
+                })
+                .min()
+        };
+
+        Bundle {
+            product_id,
+            pricing,
+            on_component_deactivated,
+            components,
+            price,
+            available_quantity,
+            warnings,
+        }
+    }
+
+    pub fn product_id(&self) -> ProductId {
+        self.product_id
+    }
+
+    pub fn pricing(&self) -> BundlePricing {
+        self.pricing
+    }
+
+    pub fn on_component_deactivated(&self) -> ComponentDeactivation {
+        self.on_component_deactivated
+    }
+
+    pub fn components(&self) -> &[BundleComponent] {
+        &self.components
+    }
+
+    pub fn price(&self) -> f64 {
+        self.price
+    }
+
+    pub fn available_quantity(&self) -> Option<u32> {
+        self.available_quantity
+    }
+
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+}
+
+#[cfg(test)]
+mod bundle_tests {
+    use crate::core::entities::bundle::{
+        Bundle, BundleComponent, BundleDefinition, BundlePricing, ComponentDeactivation, ComponentQuantity,
+    };
+    use crate::core::entities::ids::ProductId;
+    use crate::core::entities::product::Product;
+
+    fn id(id: i32) -> ProductId {
+        ProductId::try_from(id).unwrap()
+    }
+
+    fn component(id_value: i32, cost: f64, quantity: u32, active: bool, stock_quantity: Option<u32>) -> BundleComponent {
+        let product = Product::new(format!("part {}", id_value), cost, active, None).with_stock_quantity(stock_quantity);
+
+        BundleComponent::new(&product, id(id_value), quantity)
+    }
+
+    #[test]
+    fn test_derived_price_and_availability() {
+        let kit = Product::new("Starter kit".to_string(), 99.0, true, None);
+        let bundle = Bundle::new(
+            &kit,
+            id(1),
+            BundlePricing::Derived { discount_percent: 10.0 },
+            ComponentDeactivation::Warn,
+            vec![component(2, 20.0, 2, true, Some(9)), component(3, 15.5, 1, true, None)],
+        );
+
+        assert_eq!(49.95, bundle.price());
+        assert_eq!(Some(4), bundle.available_quantity());
+        assert!(bundle.warnings().is_empty());
+
+        let fixed = Bundle::new(
+            &kit,
+            id(1),
+            BundlePricing::Fixed,
+            ComponentDeactivation::Warn,
+            vec![component(3, 15.5, 1, true, None)],
+        );
+
+        assert_eq!(99.0, fixed.price());
+        assert_eq!(None, fixed.available_quantity());
+    }
+
+    #[test]
+    fn test_inactive_component_makes_bundle_unavailable() {
+        let bundle = Bundle::new(
+            &Product::new("Starter kit".to_string(), 99.0, true, None),
+            id(1),
+            BundlePricing::Fixed,
+            ComponentDeactivation::Warn,
+            vec![component(2, 20.0, 1, false, Some(5))],
+        );
+
+        assert_eq!(Some(0), bundle.available_quantity());
+        assert_eq!(vec!["Component part 2 (2) is inactive".to_string()], bundle.warnings());
+    }
+
+    #[test]
+    fn test_validate_definition() {
+        let definition = BundleDefinition::new(
+            BundlePricing::Derived { discount_percent: 120.0 },
+            ComponentDeactivation::Deactivate,
+            vec![ComponentQuantity::new(id(2), 0), ComponentQuantity::new(id(2), 1)],
+        );
+
+        assert_eq!(
+            Err(vec![
+                "Component 2 needs a quantity of at least 1".to_string(),
+                "Component 2 is listed more than once".to_string(),
+                "The discount must be between 0 and 100 percent".to_string(),
+            ]),
+            definition.validate()
+        );
+    }
+}
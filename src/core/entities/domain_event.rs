@@ -0,0 +1,147 @@
+use crate::core::entities::ids::{ProductId, VariantId};
+use crate::core::entities::tenant::Tenant;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// the types of every event, which is what webhook subscriptions filter on
+pub const EVENT_TYPES: [&str; 6] = [
+    "ProductCreated",
+    "ProductUpdated",
+    "PriceChanged",
+    "ProductDeleted",
+    "VariantAdded",
+    "VariantRemoved",
+];
+
+// a change to the catalog that other services may want to react to. Events are written along with
+// the change they describe, so there is one for every change that was committed and none for
+// changes that were rolled back
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum DomainEvent {
+    ProductCreated {
+        product_id: ProductId,
+        public_id: Uuid,
+        name: String,
+        slug: String,
+        cost: f64,
+        active: bool,
+    },
+    // any change to a product's fields, along with its fields after the change
+    ProductUpdated {
+        product_id: ProductId,
+        public_id: Uuid,
+        name: String,
+        slug: String,
+        cost: f64,
+        active: bool,
+        version: u32,
+    },
+    // a change to a product's cost, written next to the product's `ProductUpdated`
+    PriceChanged {
+        product_id: ProductId,
+        public_id: Uuid,
+        old_cost: f64,
+        new_cost: f64,
+    },
+    ProductDeleted {
+        product_id: ProductId,
+        public_id: Uuid,
+    },
+    VariantAdded {
+        product_id: ProductId,
+        variant_id: VariantId,
+        variant: String,
+        value: Option<String>,
+    },
+    VariantRemoved {
+        product_id: ProductId,
+        variant_id: VariantId,
+        value: Option<String>,
+    },
+}
+
+impl DomainEvent {
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            DomainEvent::ProductCreated { .. } => "ProductCreated",
+            DomainEvent::ProductUpdated { .. } => "ProductUpdated",
+            DomainEvent::PriceChanged { .. } => "PriceChanged",
+            DomainEvent::ProductDeleted { .. } => "ProductDeleted",
+            DomainEvent::VariantAdded { .. } => "VariantAdded",
+            DomainEvent::VariantRemoved { .. } => "VariantRemoved",
+        }
+    }
+
+    pub fn product_id(&self) -> ProductId {
+        match self {
+            DomainEvent::ProductCreated { product_id, .. }
+            | DomainEvent::ProductUpdated { product_id, .. }
+            | DomainEvent::PriceChanged { product_id, .. }
+            | DomainEvent::ProductDeleted { product_id, .. }
+            | DomainEvent::VariantAdded { product_id, .. }
+            | DomainEvent::VariantRemoved { product_id, .. } => *product_id,
+        }
+    }
+}
+
+// an event as it was written to the outbox. `id` orders events by when they were written, and
+// `event_id` identifies an event across deliveries so that receivers can drop the duplicates that
+// at-least-once delivery brings
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutboxEvent {
+    id: i64,
+    event_id: Uuid,
+    tenant: Tenant,
+    occurred_at: DateTime<Utc>,
+    #[serde(skip)]
+    attempts: u32,
+    #[serde(flatten)]
+    event: DomainEvent,
+}
+
+impl OutboxEvent {
+    pub fn new(
+        id: i64,
+        event_id: Uuid,
+        tenant: Tenant,
+        occurred_at: DateTime<Utc>,
+        attempts: u32,
+        event: DomainEvent,
+    ) -> OutboxEvent {
+        OutboxEvent {
+            id,
+            event_id,
+            tenant,
+            occurred_at,
+            attempts,
+            event,
+        }
+    }
+
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn event_id(&self) -> Uuid {
+        self.event_id
+    }
+
+    pub fn tenant(&self) -> &Tenant {
+        &self.tenant
+    }
+
+    pub fn occurred_at(&self) -> DateTime<Utc> {
+        self.occurred_at
+    }
+
+    // the deliveries of the event that failed so far
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn event(&self) -> &DomainEvent {
+        &self.event
+    }
+}
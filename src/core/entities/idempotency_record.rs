@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// the response sent for a request made with an idempotency key, kept so that it can be replayed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IdempotentResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Value,
+}
+
+impl IdempotentResponse {
+    pub fn new(status: u16, headers: Vec<(String, String)>, body: Value) -> IdempotentResponse {
+        IdempotentResponse {
+            status,
+            headers,
+            body,
+        }
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    pub fn body(&self) -> &Value {
+        &self.body
+    }
+}
+
+#[derive(Debug)]
+pub struct IdempotencyRecord {
+    key: String,
+    fingerprint: String,
+    response: Option<IdempotentResponse>,
+    expires_at: DateTime<Utc>,
+}
+
+impl IdempotencyRecord {
+    pub fn new(
+        key: String,
+        fingerprint: String,
+        response: Option<IdempotentResponse>,
+        expires_at: DateTime<Utc>,
+    ) -> IdempotencyRecord {
+        IdempotencyRecord {
+            key,
+            fingerprint,
+            response,
+            expires_at,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        self.key.as_str()
+    }
+
+    pub fn fingerprint(&self) -> &str {
+        self.fingerprint.as_str()
+    }
+
+    // the stored response, missing while the request that reserved the key is still running
+    pub fn response(&self) -> Option<&IdempotentResponse> {
+        self.response.as_ref()
+    }
+
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+}
@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+// a value that cannot be an ID. IDs are generated by the database as positive 32 bit integers, so
+// anything outside of that range is rejected instead of being truncated into a different ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidId {
+    pub entity: &'static str,
+    pub value: String,
+}
+
+impl Display for InvalidId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is not a valid {} ID", self.value, self.entity)
+    }
+}
+
+impl Error for InvalidId {}
+
+macro_rules! define_id {
+    ($name:ident, $entity:literal) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema)]
+        #[serde(try_from = "i64", into = "i32")]
+        pub struct $name(i32);
+
+        impl $name {
+            pub fn get(self) -> i32 {
+                self.0
+            }
+        }
+
+        impl TryFrom<i64> for $name {
+            type Error = InvalidId;
+
+            fn try_from(value: i64) -> Result<Self, Self::Error> {
+                match i32::try_from(value) {
+                    Ok(id) if id > 0 => Ok($name(id)),
+                    _ => Err(InvalidId {
+                        entity: $entity,
+                        value: value.to_string(),
+                    }),
+                }
+            }
+        }
+
+        impl TryFrom<i32> for $name {
+            type Error = InvalidId;
+
+            fn try_from(value: i32) -> Result<Self, Self::Error> {
+                $name::try_from(i64::from(value))
+            }
+        }
+
+        impl TryFrom<u32> for $name {
+            type Error = InvalidId;
+
+            fn try_from(value: u32) -> Result<Self, Self::Error> {
+                $name::try_from(i64::from(value))
+            }
+        }
+
+        impl From<$name> for i32 {
+            fn from(id: $name) -> i32 {
+                id.0
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = InvalidId;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                value
+                    .parse::<i64>()
+                    .map_err(|_| InvalidId {
+                        entity: $entity,
+                        value: value.to_string(),
+                    })
+                    .and_then($name::try_from)
+            }
+        }
+    };
+}
+
+define_id!(ApiKeyId, "API key");
+define_id!(ProductId, "product");
+define_id!(ProductTypeId, "product type");
+define_id!(VariantId, "variant");
+define_id!(WebhookSubscriptionId, "webhook subscription");
+
+#[cfg(test)]
+mod ids_tests {
+    use crate::core::entities::ids::{ProductId, VariantId};
+
+    #[test]
+    fn test_ids_reject_out_of_range_values() {
+        assert_eq!(12, ProductId::try_from(12_i64).unwrap().get());
+        assert!(ProductId::try_from(0_i32).is_err());
+        assert!(ProductId::try_from(-4_i64).is_err());
+        assert!(VariantId::try_from(u32::MAX).is_err());
+        assert!(VariantId::try_from(i64::from(i32::MAX) + 1).is_err());
+    }
+
+    #[test]
+    fn test_ids_parse_and_deserialize() {
+        assert_eq!(ProductId::try_from(7_i32).unwrap(), "7".parse::<ProductId>().unwrap());
+        assert!("seven".parse::<ProductId>().is_err());
+
+        assert_eq!(
+            VariantId::try_from(3_i32).unwrap(),
+            serde_json::from_str::<VariantId>("3").unwrap()
+        );
+        assert!(serde_json::from_str::<VariantId>("4294967296").is_err());
+        assert_eq!("3", serde_json::to_string(&VariantId::try_from(3_i32).unwrap()).unwrap());
+    }
+}
@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+// a value that is not a language tag, e.g. `de_AT` or `german`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidLocale {
+    pub value: String,
+}
+
+impl Display for InvalidLocale {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is not a valid locale", self.value)
+    }
+}
+
+impl Error for InvalidLocale {}
+
+// a BCP 47 language tag such as `de` or `de-AT`, kept in its canonical case so that `DE-at` and
+// `de-AT` are the same locale
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(try_from = "String", into = "String")]
+pub struct Locale(String);
+
+impl Locale {
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    // the locale with its last subtag removed, `de-AT` for `de-AT-1996` and `de` for `de-AT`
+    pub fn parent(&self) -> Option<Locale> {
+        self.0
+            .rsplit_once('-')
+            .map(|(parent, _)| Locale(parent.to_string()))
+    }
+}
+
+impl FromStr for Locale {
+    type Err = InvalidLocale;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidLocale {
+            value: value.to_string(),
+        };
+        let mut subtags = value.split('-');
+
+        let language = subtags.next().ok_or_else(invalid)?;
+        if !(2..=3).contains(&language.len()) || !language.bytes().all(|byte| byte.is_ascii_alphabetic()) {
+            return Err(invalid());
+        }
+
+        let mut canonical = language.to_ascii_lowercase();
+        for subtag in subtags {
+            if !(2..=8).contains(&subtag.len()) || !subtag.bytes().all(|byte| byte.is_ascii_alphanumeric()) {
+                return Err(invalid());
+            }
+
+            canonical.push('-');
+            // regions are upper case (`AT`) and scripts title case (`Latn`), as the registry has them
+            match subtag.len() {
+                2 if subtag.bytes().all(|byte| byte.is_ascii_alphabetic()) => {
+                    canonical.push_str(&subtag.to_ascii_uppercase())
+                }
+                4 if subtag.bytes().all(|byte| byte.is_ascii_alphabetic()) => {
+                    canonical.push_str(&subtag[..1].to_ascii_uppercase());
+                    canonical.push_str(&subtag[1..].to_ascii_lowercase());
+                }
+                _ => canonical.push_str(&subtag.to_ascii_lowercase()),
+            }
+        }
+
+        Ok(Locale(canonical))
+    }
+}
+
+impl TryFrom<String> for Locale {
+    type Error = InvalidLocale;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Locale> for String {
+    fn from(locale: Locale) -> String {
+        locale.0
+    }
+}
+
+impl Display for Locale {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+// the locales to look for a translation in, most preferred first. Every preferred locale is
+// followed by its parents and the chain ends at the default locale, whose text is held by the
+// entity itself, e.g. `de-AT → de → en`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocaleFallback {
+    locales: Vec<Locale>,
+}
+
+impl LocaleFallback {
+    pub fn new(preferred: &[Locale], default_locale: Locale) -> LocaleFallback {
+        let mut locales = Vec::new();
+
+        for locale in preferred {
+            let mut next = Some(locale.clone());
+            while let Some(locale) = next {
+                next = locale.parent();
+                if locale == default_locale {
+                    break;
+                }
+                if !locales.contains(&locale) {
+                    locales.push(locale);
+                }
+            }
+        }
+
+        locales.push(default_locale);
+
+        LocaleFallback { locales }
+    }
+
+    pub fn locales(&self) -> &[Locale] {
+        &self.locales
+    }
+
+    pub fn default_locale(&self) -> &Locale {
+        self.locales.last().expect("a fallback chain always ends at the default locale")
+    }
+
+    // the locales that translations are stored for, i.e. every one but the default
+    pub fn translated_locales(&self) -> &[Locale] {
+        &self.locales[..self.locales.len() - 1]
+    }
+
+    // the first text along the chain, together with the locale it is in. `translated` looks up
+    // the stored translation for a locale and `default_text` is the entity's own text
+    pub fn resolve<'a>(
+        &'a self,
+        default_text: Option<&'a str>,
+        translated: impl Fn(&Locale) -> Option<&'a str>,
+    ) -> Option<(&'a Locale, &'a str)> {
+        let (default_locale, translated_locales) = self.locales.split_last()?;
+
+        translated_locales
+            .iter()
+            .find_map(|locale| translated(locale).map(|text| (locale, text)))
+            .or_else(|| default_text.map(|text| (default_locale, text)))
+    }
+}
+
+// the locales of an `Accept-Language` header, most preferred first. Tags that are not valid
+// locales, the `*` wildcard and anything with a quality of 0 are left out
+pub fn parse_accept_language(header: &str) -> Vec<Locale> {
+    let mut weighted_locales = header
+        .split(',')
+        .filter_map(|range| {
+            let mut parameters = range.split(';').map(str::trim);
+            let locale = parameters.next()?.parse::<Locale>().ok()?;
+            let quality = parameters
+                .find_map(|parameter| parameter.strip_prefix("q="))
+                .map_or(Some(1.0), |quality| quality.parse::<f32>().ok())?;
+
+            (quality > 0.0).then_some((locale, quality))
+        })
+        .collect::<Vec<_>>();
+
+    // the sort is stable, so ranges with the same quality keep the order the client sent them in
+    weighted_locales.sort_by(|(_, left), (_, right)| right.total_cmp(left));
+
+    weighted_locales.into_iter().map(|(locale, _)| locale).collect()
+}
+
+#[cfg(test)]
+mod locale_tests {
+    use crate::core::entities::locale::{parse_accept_language, Locale, LocaleFallback};
+
+    fn locales(tags: &[&str]) -> Vec<Locale> {
+        tags.iter().map(|tag| tag.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_parse_locale() {
+        assert_eq!("de-AT", "DE-at".parse::<Locale>().unwrap().as_str());
+        assert_eq!("sr-Latn-RS", "sr-latn-rs".parse::<Locale>().unwrap().as_str());
+        assert!("de_AT".parse::<Locale>().is_err());
+        assert!("german".parse::<Locale>().is_err());
+        assert!("".parse::<Locale>().is_err());
+    }
+
+    #[test]
+    fn test_fallback_chain() {
+        let fallback = LocaleFallback::new(&locales(&["de-AT", "fr", "de"]), "en".parse().unwrap());
+
+        assert_eq!(locales(&["de-AT", "de", "fr", "en"]), fallback.locales());
+        assert_eq!(locales(&["de-AT", "de", "fr"]), fallback.translated_locales());
+
+        let english = LocaleFallback::new(&locales(&["en-GB"]), "en".parse().unwrap());
+
+        assert_eq!(locales(&["en-GB", "en"]), english.locales());
+    }
+
+    #[test]
+    fn test_resolve_along_chain() {
+        let fallback = LocaleFallback::new(&locales(&["de-AT"]), "en".parse().unwrap());
+        let german = "de".parse::<Locale>().unwrap();
+
+        let resolved = fallback.resolve(Some("Boots"), |locale| (locale == &german).then_some("Stiefel"));
+        assert_eq!(Some(("de", "Stiefel")), resolved.map(|(locale, text)| (locale.as_str(), text)));
+
+        let resolved = fallback.resolve(Some("Boots"), |_| None);
+        assert_eq!(Some(("en", "Boots")), resolved.map(|(locale, text)| (locale.as_str(), text)));
+
+        assert_eq!(None, fallback.resolve(None, |_| None));
+    }
+
+    #[test]
+    fn test_parse_accept_language() {
+        assert_eq!(
+            locales(&["de-AT", "de", "en"]),
+            parse_accept_language("en;q=0.5, de-AT, *;q=0.1, de;q=0.8, fr;q=0")
+        );
+        assert_eq!(locales(&["fr", "it"]), parse_accept_language("fr, not a tag, it"));
+        assert!(parse_accept_language("").is_empty());
+    }
+}
@@ -0,0 +1,71 @@
+use crate::core::entities::tenant::Tenant;
+use serde::Serialize;
+
+// how a principal proved who it is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthMethod {
+    ApiKey,
+    Jwt,
+}
+
+impl AuthMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthMethod::ApiKey => "api_key",
+            AuthMethod::Jwt => "jwt",
+        }
+    }
+}
+
+// the client a request was authenticated as: the name of its API key or the subject of its token,
+// along with the scopes it was granted and the tenant it belongs to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Principal {
+    subject: String,
+    method: AuthMethod,
+    scopes: Vec<String>,
+    tenant: Tenant,
+}
+
+impl Principal {
+    pub fn new(subject: String, method: AuthMethod, scopes: Vec<String>) -> Principal {
+        Principal {
+            subject,
+            method,
+            scopes,
+            tenant: Tenant::default(),
+        }
+    }
+
+    pub fn with_tenant(mut self, tenant: Tenant) -> Principal {
+        self.tenant = tenant;
+        self
+    }
+
+    pub fn subject(&self) -> &str {
+        self.subject.as_str()
+    }
+
+    pub fn method(&self) -> AuthMethod {
+        self.method
+    }
+
+    pub fn scopes(&self) -> &[String] {
+        &self.scopes
+    }
+
+    pub fn tenant(&self) -> &Tenant {
+        &self.tenant
+    }
+
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|granted| granted == scope)
+    }
+
+    // the actor that writes made by the principal are audited as, e.g. `api_key:storefront` or
+    // `jwt:alice`
+    pub fn actor(&self) -> String {
+        format!("{}:{}", self.method.as_str(), self.subject)
+    }
+}
@@ -1,24 +1,99 @@
+use crate::core::entities::ids::{ProductId, ProductTypeId};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use utoipa::ToSchema;
+use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct Product {
-    id: Option<u32>,
+    id: Option<ProductId>,
     name: String,
     cost: f64,
     active: bool,
+    #[serde(default)]
+    version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    external_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    public_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    slug: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    product_type_id: Option<ProductTypeId>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    attributes: Map<String, Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stock_quantity: Option<u32>,
 }
 
 impl Product {
-    pub fn new(name: String, cost: f64, active: bool, id: Option<u32>) -> Product {
+    pub fn new(name: String, cost: f64, active: bool, id: Option<ProductId>) -> Product {
         Product {
             name,
             cost,
             active,
             id,
+            version: 0,
+            external_key: None,
+            description: None,
+            public_id: None,
+            slug: None,
+            product_type_id: None,
+            attributes: Map::new(),
+            stock_quantity: None,
         }
     }
 
-    pub fn id(&self) -> Option<u32> {
+    // sets the version of a stored product. Products that have not been stored yet have version 0
+    pub fn with_version(mut self, version: u32) -> Product {
+        self.version = version;
+        self
+    }
+
+    // sets the key a product is known by in the systems it is imported from
+    pub fn with_external_key(mut self, external_key: Option<String>) -> Product {
+        self.external_key = external_key;
+        self
+    }
+
+    // sets the description of a product, in the default locale like its name
+    pub fn with_description(mut self, description: Option<String>) -> Product {
+        self.description = description;
+        self
+    }
+
+    // replaces the name and description with their text in another locale, for products that are
+    // only read from then on
+    pub fn localized(mut self, name: String, description: Option<String>) -> Product {
+        self.name = name;
+        self.description = description;
+        self
+    }
+
+    // sets the identifiers a stored product is referred to by outside of the store. Both are
+    // assigned by the datastore when the product is created
+    pub fn with_public_identifiers(mut self, public_id: Uuid, slug: String) -> Product {
+        self.public_id = Some(public_id);
+        self.slug = Some(slug);
+        self
+    }
+
+    // sets the type of a product along with its values for the attributes that type defines
+    pub fn with_attributes(mut self, product_type_id: Option<ProductTypeId>, attributes: Map<String, Value>) -> Product {
+        self.product_type_id = product_type_id;
+        self.attributes = attributes;
+        self
+    }
+
+    // sets the number of units on hand, `None` for products whose stock is not tracked
+    pub fn with_stock_quantity(mut self, stock_quantity: Option<u32>) -> Product {
+        self.stock_quantity = stock_quantity;
+        self
+    }
+
+    pub fn id(&self) -> Option<ProductId> {
         self.id
     }
 
@@ -33,4 +108,36 @@ impl Product {
     pub fn active(&self) -> bool {
         self.active
     }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn external_key(&self) -> Option<&str> {
+        self.external_key.as_deref()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn public_id(&self) -> Option<Uuid> {
+        self.public_id
+    }
+
+    pub fn slug(&self) -> Option<&str> {
+        self.slug.as_deref()
+    }
+
+    pub fn product_type_id(&self) -> Option<ProductTypeId> {
+        self.product_type_id
+    }
+
+    pub fn attributes(&self) -> &Map<String, Value> {
+        &self.attributes
+    }
+
+    pub fn stock_quantity(&self) -> Option<u32> {
+        self.stock_quantity
+    }
 }
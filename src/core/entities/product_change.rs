@@ -0,0 +1,218 @@
+use crate::core::entities::ids::ProductId;
+use crate::core::entities::product::Product;
+use crate::core::entities::product_revision::VariantSnapshot;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use utoipa::openapi::schema::{ObjectBuilder, Schema, Type};
+use utoipa::openapi::RefOr;
+use utoipa::{PartialSchema, ToSchema};
+use uuid::Uuid;
+
+// a value that is not a change token, e.g. a timestamp
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidChangeToken {
+    pub value: String,
+}
+
+impl Display for InvalidChangeToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} is not a change token, tokens are taken from the changes feed as they are",
+            self.value
+        )
+    }
+}
+
+impl Error for InvalidChangeToken {}
+
+// where a change is in the changes feed, and where a consumer that has seen it picks up again.
+// Changes are ordered by the transaction that made them and then by the change sequence, e.g.
+// `48213-1907`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ChangeToken {
+    transaction_id: i64,
+    sequence: i64,
+}
+
+impl ChangeToken {
+    pub fn new(transaction_id: i64, sequence: i64) -> ChangeToken {
+        ChangeToken {
+            transaction_id,
+            sequence,
+        }
+    }
+
+    pub fn transaction_id(&self) -> i64 {
+        self.transaction_id
+    }
+
+    pub fn sequence(&self) -> i64 {
+        self.sequence
+    }
+}
+
+impl FromStr for ChangeToken {
+    type Err = InvalidChangeToken;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidChangeToken {
+            value: value.to_string(),
+        };
+        let (transaction_id, sequence) = value.split_once('-').ok_or_else(invalid)?;
+
+        Ok(ChangeToken {
+            transaction_id: transaction_id.parse().map_err(|_| invalid())?,
+            sequence: sequence.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl TryFrom<String> for ChangeToken {
+    type Error = InvalidChangeToken;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<ChangeToken> for String {
+    fn from(token: ChangeToken) -> String {
+        token.to_string()
+    }
+}
+
+// tokens are handed out and taken back as strings, so that is how they are documented
+impl PartialSchema for ChangeToken {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .schema_type(Type::String)
+            .pattern(Some("^[0-9]+-[0-9]+$"))
+            .examples(["48213-1907"])
+            .into()
+    }
+}
+
+impl ToSchema for ChangeToken {}
+
+impl Display for ChangeToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.transaction_id, self.sequence)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    // the product was created or changed, and is in the change as it is now
+    Upserted,
+    // the product was deactivated, consumers that only keep active products drop it
+    Archived,
+    // the product is gone
+    Deleted,
+}
+
+// the latest change to a product. Archived and deleted products are tombstones that only carry
+// their IDs
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProductChange {
+    token: ChangeToken,
+    product_id: ProductId,
+    public_id: Uuid,
+    change: ChangeKind,
+    changed_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    product: Option<Product>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    variants: Option<Vec<VariantSnapshot>>,
+}
+
+impl ProductChange {
+    // a change to a product that is still there and active
+    pub fn upserted(
+        token: ChangeToken,
+        product_id: ProductId,
+        public_id: Uuid,
+        changed_at: DateTime<Utc>,
+        product: Product,
+        variants: Vec<VariantSnapshot>,
+    ) -> ProductChange {
+        ProductChange {
+            token,
+            product_id,
+            public_id,
+            change: ChangeKind::Upserted,
+            changed_at,
+            product: Some(product),
+            variants: Some(variants),
+        }
+    }
+
+    pub fn tombstone(
+        token: ChangeToken,
+        product_id: ProductId,
+        public_id: Uuid,
+        change: ChangeKind,
+        changed_at: DateTime<Utc>,
+    ) -> ProductChange {
+        ProductChange {
+            token,
+            product_id,
+            public_id,
+            change,
+            changed_at,
+            product: None,
+            variants: None,
+        }
+    }
+
+    pub fn token(&self) -> ChangeToken {
+        self.token
+    }
+
+    pub fn product_id(&self) -> ProductId {
+        self.product_id
+    }
+
+    pub fn public_id(&self) -> Uuid {
+        self.public_id
+    }
+
+    pub fn change(&self) -> ChangeKind {
+        self.change
+    }
+
+    pub fn changed_at(&self) -> DateTime<Utc> {
+        self.changed_at
+    }
+
+    pub fn product(&self) -> Option<&Product> {
+        self.product.as_ref()
+    }
+
+    pub fn variants(&self) -> Option<&[VariantSnapshot]> {
+        self.variants.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod product_change_tests {
+    use crate::core::entities::product_change::ChangeToken;
+
+    #[test]
+    fn test_change_tokens_round_trip_and_order_by_transaction_first() {
+        let token = "48213-1907".parse::<ChangeToken>().expect("Error parsing token");
+
+        assert_eq!(ChangeToken::new(48213, 1907), token);
+        assert_eq!("48213-1907", token.to_string());
+        assert!(ChangeToken::new(48213, 1907) < ChangeToken::new(48214, 12));
+        assert!(ChangeToken::new(48213, 1907) < ChangeToken::new(48213, 1908));
+
+        assert!("2026-10-19T08:00:00Z".parse::<ChangeToken>().is_err());
+        assert!("48213".parse::<ChangeToken>().is_err());
+    }
+}
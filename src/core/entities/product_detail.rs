@@ -0,0 +1,135 @@
+use crate::core::entities::bundle::Bundle;
+use crate::core::entities::locale::Locale;
+use crate::core::entities::product::Product;
+use crate::core::entities::product_relation::RelatedProduct;
+use crate::core::entities::specification::Specification;
+use crate::core::markdown::render_markdown;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+// a product as it is shown to a reader: its text in the best locale available to them, along with
+// its variants and their values in display form. `locale` is the locale the name is in and
+// `description_html` is the Markdown description rendered for display
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProductDetail {
+    #[serde(flatten)]
+    product: Product,
+    locale: Locale,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description_html: Option<String>,
+    specifications: Vec<Specification>,
+    variants: Vec<LocalizedVariant>,
+    related_products: Vec<RelatedProduct>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bundle: Option<Bundle>,
+}
+
+impl ProductDetail {
+    pub fn new(
+        product: Product,
+        locale: Locale,
+        specifications: Vec<Specification>,
+        variants: Vec<LocalizedVariant>,
+    ) -> ProductDetail {
+        let description_html = product.description().map(render_markdown);
+
+        ProductDetail {
+            product,
+            locale,
+            description_html,
+            specifications,
+            variants,
+            related_products: Vec::new(),
+            bundle: None,
+        }
+    }
+
+    // sets the active products the product links to
+    pub fn with_related_products(mut self, related_products: Vec<RelatedProduct>) -> ProductDetail {
+        self.related_products = related_products;
+        self
+    }
+
+    // sets the bundle the product is, for products that are made of other products
+    pub fn with_bundle(mut self, bundle: Option<Bundle>) -> ProductDetail {
+        self.bundle = bundle;
+        self
+    }
+
+    pub fn product(&self) -> &Product {
+        &self.product
+    }
+
+    pub fn locale(&self) -> &Locale {
+        &self.locale
+    }
+
+    pub fn description_html(&self) -> Option<&str> {
+        self.description_html.as_deref()
+    }
+
+    pub fn specifications(&self) -> &[Specification] {
+        &self.specifications
+    }
+
+    pub fn variants(&self) -> &[LocalizedVariant] {
+        &self.variants
+    }
+
+    pub fn related_products(&self) -> &[RelatedProduct] {
+        &self.related_products
+    }
+
+    pub fn bundle(&self) -> Option<&Bundle> {
+        self.bundle.as_ref()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LocalizedVariant {
+    name: String,
+    display_name: String,
+    values: Vec<LocalizedVariantValue>,
+}
+
+impl LocalizedVariant {
+    pub fn new(name: String, display_name: String, values: Vec<LocalizedVariantValue>) -> LocalizedVariant {
+        LocalizedVariant {
+            name,
+            display_name,
+            values,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn display_name(&self) -> &str {
+        self.display_name.as_str()
+    }
+
+    pub fn values(&self) -> &[LocalizedVariantValue] {
+        &self.values
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LocalizedVariantValue {
+    value: Option<String>,
+    display_value: Option<String>,
+}
+
+impl LocalizedVariantValue {
+    pub fn new(value: Option<String>, display_value: Option<String>) -> LocalizedVariantValue {
+        LocalizedVariantValue { value, display_value }
+    }
+
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    pub fn display_value(&self) -> Option<&str> {
+        self.display_value.as_deref()
+    }
+}
@@ -0,0 +1,77 @@
+use crate::core::entities::product::Product;
+use crate::core::entities::product_revision::VariantSnapshot;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+// a product as it is written to a catalog export, with everything a feed needs about it
+#[derive(Debug)]
+pub struct ExportedProduct {
+    product: Product,
+    variants: Vec<VariantSnapshot>,
+    image_urls: Vec<String>,
+    updated_at: DateTime<Utc>,
+    available_quantity: Option<u32>,
+}
+
+impl ExportedProduct {
+    pub fn new(
+        product: Product,
+        variants: Vec<VariantSnapshot>,
+        image_urls: Vec<String>,
+        updated_at: DateTime<Utc>,
+    ) -> ExportedProduct {
+        ExportedProduct {
+            product,
+            variants,
+            image_urls,
+            updated_at,
+            available_quantity: None,
+        }
+    }
+
+    // sets how many units can be sold, `None` when the stock is not tracked
+    pub fn with_available_quantity(mut self, available_quantity: Option<u32>) -> ExportedProduct {
+        self.available_quantity = available_quantity;
+        self
+    }
+
+    pub fn product(&self) -> &Product {
+        &self.product
+    }
+
+    pub fn variants(&self) -> &[VariantSnapshot] {
+        &self.variants
+    }
+
+    pub fn image_urls(&self) -> &[String] {
+        &self.image_urls
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    pub fn available_quantity(&self) -> Option<u32> {
+        self.available_quantity
+    }
+
+    // whether the product can be bought, which products whose stock is not tracked always can
+    // while they are active
+    pub fn in_stock(&self) -> bool {
+        self.product.active() && self.available_quantity != Some(0)
+    }
+
+    // the values of the product's variants grouped by variant name, leaving out variants attached
+    // without a value
+    pub fn variant_values(&self) -> BTreeMap<&str, Vec<&str>> {
+        let mut variant_values = BTreeMap::<&str, Vec<&str>>::new();
+
+        for variant in &self.variants {
+            if let Some(value) = variant.value() {
+                variant_values.entry(variant.name()).or_default().push(value.as_str());
+            }
+        }
+
+        variant_values
+    }
+}
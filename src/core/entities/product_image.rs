@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// a picture of a product, shown in the order the product's images were given in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ProductImage {
+    url: String,
+}
+
+impl ProductImage {
+    pub fn new(url: String) -> ProductImage {
+        ProductImage { url }
+    }
+
+    pub fn url(&self) -> &str {
+        self.url.as_str()
+    }
+}
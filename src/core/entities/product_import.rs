@@ -0,0 +1,32 @@
+use crate::core::entities::ids::ProductId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportAction {
+    Created,
+    Updated,
+    // the stored product already matched the imported one, so nothing was written
+    Unchanged,
+}
+
+// the outcome of importing a single complete product
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportedProduct {
+    product_id: ProductId,
+    action: ImportAction,
+}
+
+impl ImportedProduct {
+    pub fn new(product_id: ProductId, action: ImportAction) -> ImportedProduct {
+        ImportedProduct { product_id, action }
+    }
+
+    pub fn product_id(&self) -> ProductId {
+        self.product_id
+    }
+
+    pub fn action(&self) -> ImportAction {
+        self.action
+    }
+}
@@ -0,0 +1,166 @@
+use crate::core::entities::ids::ProductId;
+use serde::Deserialize;
+use std::convert::Infallible;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use utoipa::openapi::schema::{ObjectBuilder, Schema, Type};
+use utoipa::openapi::RefOr;
+use utoipa::{PartialSchema, ToSchema};
+use uuid::Uuid;
+
+// slugs are cut at this length so that URLs stay readable for products with very long names
+const MAX_SLUG_LENGTH: usize = 80;
+
+// used for names that have nothing a slug can be made from, e.g. names written in a non-latin script
+const FALLBACK_SLUG: &str = "product";
+
+// the ways a product can be referred to from outside. Lookups accept any of them, but only the
+// public ID and slug are handed out in URLs
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(from = "String")]
+pub enum ProductReference {
+    Id(ProductId),
+    PublicId(Uuid),
+    Slug(String),
+}
+
+impl FromStr for ProductReference {
+    type Err = Infallible;
+
+    // a reference that parses as a number is taken as an ID, then as a UUID, and anything else is
+    // a slug. `slugify` makes sure that no slug parses as one of the other two
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Ok(id) = value.parse::<ProductId>() {
+            return Ok(ProductReference::Id(id));
+        }
+
+        Ok(match Uuid::parse_str(value) {
+            Ok(public_id) => ProductReference::PublicId(public_id),
+            Err(_) => ProductReference::Slug(value.to_string()),
+        })
+    }
+}
+
+impl From<String> for ProductReference {
+    fn from(value: String) -> Self {
+        match value.parse() {
+            Ok(reference) => reference,
+            Err(infallible) => match infallible {},
+        }
+    }
+}
+
+impl Display for ProductReference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProductReference::Id(id) => write!(f, "{}", id),
+            ProductReference::PublicId(public_id) => write!(f, "{}", public_id),
+            ProductReference::Slug(slug) => write!(f, "{}", slug),
+        }
+    }
+}
+
+// references are written as one string whichever kind they are
+impl PartialSchema for ProductReference {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .schema_type(Type::String)
+            .description(Some("A product ID, public ID or slug"))
+            .examples(["leather-wallet"])
+            .into()
+    }
+}
+
+impl ToSchema for ProductReference {}
+
+// the product a reference points at. `redirected` is set when the reference was a slug the
+// product had before it was renamed, in which case `slug` is the one it goes by now
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedProduct {
+    id: ProductId,
+    slug: String,
+    redirected: bool,
+}
+
+impl ResolvedProduct {
+    pub fn new(id: ProductId, slug: String, redirected: bool) -> ResolvedProduct {
+        ResolvedProduct { id, slug, redirected }
+    }
+
+    pub fn id(&self) -> ProductId {
+        self.id
+    }
+
+    pub fn slug(&self) -> &str {
+        self.slug.as_str()
+    }
+
+    pub fn redirected(&self) -> bool {
+        self.redirected
+    }
+}
+
+// makes the URL-safe base of a slug from a product name: lowercase ASCII letters and digits, with
+// every other run of characters collapsed into a single `-`. Callers add a suffix when the base is
+// already taken by another product
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::with_capacity(name.len());
+
+    for character in name.chars().flat_map(char::to_lowercase) {
+        if character.is_ascii_alphanumeric() {
+            slug.push(character);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    slug.truncate(MAX_SLUG_LENGTH);
+    let slug = slug.trim_end_matches('-');
+
+    if slug.is_empty() {
+        return FALLBACK_SLUG.to_string();
+    }
+
+    // a slug that reads as an ID or a UUID would be looked up as one, so it gets a prefix
+    if slug.bytes().all(|byte| byte.is_ascii_digit()) || Uuid::parse_str(slug).is_ok() {
+        format!("{}-{}", FALLBACK_SLUG, slug)
+    } else {
+        slug.to_string()
+    }
+}
+
+#[cfg(test)]
+mod product_reference_tests {
+    use crate::core::entities::ids::ProductId;
+    use crate::core::entities::product_reference::{slugify, ProductReference};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!("running-shoes", slugify("Running Shoes"));
+        assert_eq!("boots-size-42", slugify("  Boots -- size 42!  "));
+        assert_eq!("caf-au-lait", slugify("Café au lait"));
+        assert_eq!("product", slugify("靴"));
+        assert_eq!("product-1990", slugify("1990"));
+        assert_eq!(
+            "product-0190a8b2-5e4c-7d3a-9b1e-3f2a1c0d4e5f",
+            slugify("0190A8B2-5E4C-7D3A-9B1E-3F2A1C0D4E5F")
+        );
+        assert_eq!(80, slugify(&"a".repeat(100)).len());
+    }
+
+    #[test]
+    fn test_parse_product_reference() {
+        let public_id = Uuid::now_v7();
+
+        assert_eq!(
+            ProductReference::Id(ProductId::try_from(42).unwrap()),
+            "42".parse().unwrap()
+        );
+        assert_eq!(ProductReference::PublicId(public_id), public_id.to_string().parse().unwrap());
+        assert_eq!(
+            ProductReference::Slug("running-shoes-2".to_string()),
+            "running-shoes-2".parse().unwrap()
+        );
+    }
+}
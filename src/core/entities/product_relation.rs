@@ -0,0 +1,171 @@
+use crate::core::entities::ids::ProductId;
+use crate::core::entities::product::Product;
+use anyhow::{anyhow, Error as AnyError};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+// how a product relates to another: similar products, products bought along with it, better
+// products to upgrade to, accessories for it and products that replace it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationType {
+    Related,
+    CrossSell,
+    UpSell,
+    Accessory,
+    Replacement,
+}
+
+impl RelationType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelationType::Related => "related",
+            RelationType::CrossSell => "cross_sell",
+            RelationType::UpSell => "up_sell",
+            RelationType::Accessory => "accessory",
+            RelationType::Replacement => "replacement",
+        }
+    }
+}
+
+impl FromStr for RelationType {
+    type Err = AnyError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "related" => Ok(RelationType::Related),
+            "cross_sell" => Ok(RelationType::CrossSell),
+            "up_sell" => Ok(RelationType::UpSell),
+            "accessory" => Ok(RelationType::Accessory),
+            "replacement" => Ok(RelationType::Replacement),
+            other => Err(anyhow!("Unknown relation type: {}", other)),
+        }
+    }
+}
+
+// a link from a product to another, as it is written. Links of the same type are shown in order
+// of their `position`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ProductRelation {
+    relation_type: RelationType,
+    product_id: ProductId,
+    position: i32,
+}
+
+impl ProductRelation {
+    pub fn new(relation_type: RelationType, product_id: ProductId, position: i32) -> ProductRelation {
+        ProductRelation {
+            relation_type,
+            product_id,
+            position,
+        }
+    }
+
+    pub fn relation_type(&self) -> RelationType {
+        self.relation_type
+    }
+
+    pub fn product_id(&self) -> ProductId {
+        self.product_id
+    }
+
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+}
+
+// checks the links of the product `id`: a product is not linked to itself and is linked to
+// another at most once per type. Returns every problem found
+pub fn validate_relations(id: ProductId, relations: &[ProductRelation]) -> Result<(), Vec<String>> {
+    let mut problems = Vec::new();
+
+    for (index, relation) in relations.iter().enumerate() {
+        if relation.product_id == id {
+            problems.push(format!("Product {} cannot be related to itself", id));
+        }
+        if relations[..index].iter().any(|earlier| {
+            earlier.relation_type == relation.relation_type && earlier.product_id == relation.product_id
+        }) {
+            problems.push(format!(
+                "Product {} is given more than once as {}",
+                relation.product_id,
+                relation.relation_type.as_str()
+            ));
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems)
+    }
+}
+
+// a product linked to the one being read, as it is shown to a reader
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RelatedProduct {
+    relation_type: RelationType,
+    position: i32,
+    product: Product,
+}
+
+impl RelatedProduct {
+    pub fn new(relation_type: RelationType, position: i32, product: Product) -> RelatedProduct {
+        RelatedProduct {
+            relation_type,
+            position,
+            product,
+        }
+    }
+
+    pub fn relation_type(&self) -> RelationType {
+        self.relation_type
+    }
+
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    pub fn product(&self) -> &Product {
+        &self.product
+    }
+}
+
+#[cfg(test)]
+mod product_relation_tests {
+    use crate::core::entities::ids::ProductId;
+    use crate::core::entities::product_relation::{validate_relations, ProductRelation, RelationType};
+
+    fn id(id: i32) -> ProductId {
+        ProductId::try_from(id).unwrap()
+    }
+
+    #[test]
+    fn test_validate_relations() {
+        assert_eq!(
+            Ok(()),
+            validate_relations(
+                id(1),
+                &[
+                    ProductRelation::new(RelationType::CrossSell, id(2), 0),
+                    ProductRelation::new(RelationType::UpSell, id(2), 0),
+                ]
+            )
+        );
+        assert_eq!(
+            Err(vec![
+                "Product 1 cannot be related to itself".to_string(),
+                "Product 2 is given more than once as accessory".to_string(),
+            ]),
+            validate_relations(
+                id(1),
+                &[
+                    ProductRelation::new(RelationType::Related, id(1), 0),
+                    ProductRelation::new(RelationType::Accessory, id(2), 0),
+                    ProductRelation::new(RelationType::Accessory, id(2), 1),
+                ]
+            )
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use crate::core::entities::audit_record::AuditRecord;
+use crate::core::entities::ids::VariantId;
+use crate::core::entities::product::Product;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+// selects a revision of a product either by its number or by the point in time it was current at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevisionSelector {
+    Revision(u32),
+    At(DateTime<Utc>),
+}
+
+// a variant value attached to a product at the time a revision was taken
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct VariantSnapshot {
+    variant_id: VariantId,
+    name: String,
+    value: Option<String>,
+}
+
+impl VariantSnapshot {
+    pub fn new(variant_id: VariantId, name: String, value: Option<String>) -> VariantSnapshot {
+        VariantSnapshot {
+            variant_id,
+            name,
+            value,
+        }
+    }
+
+    pub fn variant_id(&self) -> VariantId {
+        self.variant_id
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn value(&self) -> &Option<String> {
+        &self.value
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductRevision {
+    revision: u32,
+    product: Product,
+    variants: Vec<VariantSnapshot>,
+    actor: String,
+    created_at: DateTime<Utc>,
+}
+
+impl ProductRevision {
+    pub fn new(
+        revision: u32,
+        product: Product,
+        variants: Vec<VariantSnapshot>,
+        actor: String,
+        created_at: DateTime<Utc>,
+    ) -> ProductRevision {
+        ProductRevision {
+            revision,
+            product,
+            variants,
+            actor,
+            created_at,
+        }
+    }
+
+    pub fn revision(&self) -> u32 {
+        self.revision
+    }
+
+    pub fn product(&self) -> &Product {
+        &self.product
+    }
+
+    pub fn variants(&self) -> &[VariantSnapshot] {
+        &self.variants
+    }
+
+    pub fn actor(&self) -> &str {
+        self.actor.as_str()
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    // compares this revision against a later one, listing the product fields that changed and
+    // the variant values that were added or removed in between
+    pub fn diff(&self, other: &ProductRevision) -> ProductRevisionDiff {
+        let before = serde_json::to_value(&self.product).unwrap_or(Value::Null);
+        let after = serde_json::to_value(&other.product).unwrap_or(Value::Null);
+
+        ProductRevisionDiff {
+            from_revision: self.revision,
+            to_revision: other.revision,
+            changes: AuditRecord::diff(Some(&before), Some(&after)),
+            added_variants: other
+                .variants
+                .iter()
+                .filter(|variant| !self.variants.contains(variant))
+                .cloned()
+                .collect(),
+            removed_variants: self
+                .variants
+                .iter()
+                .filter(|variant| !other.variants.contains(variant))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductRevisionDiff {
+    from_revision: u32,
+    to_revision: u32,
+    changes: Value,
+    added_variants: Vec<VariantSnapshot>,
+    removed_variants: Vec<VariantSnapshot>,
+}
+
+impl ProductRevisionDiff {
+    pub fn from_revision(&self) -> u32 {
+        self.from_revision
+    }
+
+    pub fn to_revision(&self) -> u32 {
+        self.to_revision
+    }
+
+    pub fn changes(&self) -> &Value {
+        &self.changes
+    }
+
+    pub fn added_variants(&self) -> &[VariantSnapshot] {
+        &self.added_variants
+    }
+
+    pub fn removed_variants(&self) -> &[VariantSnapshot] {
+        &self.removed_variants
+    }
+}
+
+#[cfg(test)]
+mod product_revision_tests {
+    use crate::core::entities::ids::{ProductId, VariantId};
+    use crate::core::entities::product::Product;
+    use crate::core::entities::product_revision::{ProductRevision, VariantSnapshot};
+    use chrono::Utc;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_between_revisions() {
+        let product_id = ProductId::try_from(1).unwrap();
+        let size = VariantId::try_from(1).unwrap();
+        let size_12 = VariantSnapshot::new(size, "size".to_string(), Some("12".to_string()));
+        let size_14 = VariantSnapshot::new(size, "size".to_string(), Some("14".to_string()));
+        let size_16 = VariantSnapshot::new(size, "size".to_string(), Some("16".to_string()));
+
+        let first = ProductRevision::new(
+            1,
+            Product::new("boots".to_string(), 13.23, true, Some(product_id)),
+            vec![size_12.clone(), size_14.clone()],
+            "system".to_string(),
+            Utc::now(),
+        );
+        let second = ProductRevision::new(
+            2,
+            Product::new("boots".to_string(), 15.0, true, Some(product_id)),
+            vec![size_14, size_16.clone()],
+            "system".to_string(),
+            Utc::now(),
+        );
+
+        let actual = first.diff(&second);
+
+        assert_eq!(1, actual.from_revision());
+        assert_eq!(2, actual.to_revision());
+        assert_eq!(&json!({"cost": {"before": 13.23, "after": 15.0}}), actual.changes());
+        assert_eq!(&[size_16], actual.added_variants());
+        assert_eq!(&[size_12], actual.removed_variants());
+    }
+}
@@ -0,0 +1,248 @@
+use crate::core::entities::ids::ProductTypeId;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+}
+
+impl AttributeType {
+    fn accepts(&self, value: &Value) -> bool {
+        match self {
+            AttributeType::String => value.is_string(),
+            AttributeType::Integer => value.is_i64() || value.is_u64(),
+            AttributeType::Number => value.is_number(),
+            AttributeType::Boolean => value.is_boolean(),
+        }
+    }
+}
+
+impl Display for AttributeType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttributeType::String => write!(f, "a string"),
+            AttributeType::Integer => write!(f, "an integer"),
+            AttributeType::Number => write!(f, "a number"),
+            AttributeType::Boolean => write!(f, "a boolean"),
+        }
+    }
+}
+
+// an attribute that products of a type carry, e.g. `material`. When `allowed_values` is set the
+// attribute can only take one of them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AttributeDefinition {
+    name: String,
+    data_type: AttributeType,
+    #[serde(default)]
+    required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    allowed_values: Option<Vec<Value>>,
+}
+
+impl AttributeDefinition {
+    pub fn new(
+        name: String,
+        data_type: AttributeType,
+        required: bool,
+        allowed_values: Option<Vec<Value>>,
+    ) -> AttributeDefinition {
+        AttributeDefinition {
+            name,
+            data_type,
+            required,
+            allowed_values,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn data_type(&self) -> AttributeType {
+        self.data_type
+    }
+
+    pub fn required(&self) -> bool {
+        self.required
+    }
+
+    pub fn allowed_values(&self) -> Option<&[Value]> {
+        self.allowed_values.as_deref()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ProductType {
+    id: Option<ProductTypeId>,
+    name: String,
+    attributes: Vec<AttributeDefinition>,
+}
+
+impl ProductType {
+    pub fn new(name: String, attributes: Vec<AttributeDefinition>, id: Option<ProductTypeId>) -> ProductType {
+        ProductType { id, name, attributes }
+    }
+
+    pub fn id(&self) -> Option<ProductTypeId> {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn attributes(&self) -> &[AttributeDefinition] {
+        &self.attributes
+    }
+
+    // checks that the attribute schema itself makes sense: names are present and distinct and
+    // allowed values are of the attribute's type. Returns every problem found
+    pub fn validate_schema(&self) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+        let mut names = HashSet::new();
+
+        for attribute in &self.attributes {
+            if attribute.name.trim().is_empty() {
+                problems.push("Attribute names must not be empty".to_string());
+            } else if !names.insert(attribute.name.as_str()) {
+                problems.push(format!("Attribute {} is defined more than once", attribute.name));
+            }
+
+            match &attribute.allowed_values {
+                Some(allowed_values) if allowed_values.is_empty() => {
+                    problems.push(format!("Attribute {} allows no values", attribute.name))
+                }
+                Some(allowed_values) if !allowed_values.iter().all(|value| attribute.data_type.accepts(value)) => {
+                    problems.push(format!(
+                        "Allowed values of attribute {} must each be {}",
+                        attribute.name, attribute.data_type
+                    ))
+                }
+                _ => {}
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    // checks a product's attributes against the schema: every required attribute is present, no
+    // attribute is unknown and every value is of its attribute's type and allowed. Returns every
+    // problem found
+    pub fn validate_attributes(&self, attributes: &Map<String, Value>) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+
+        for (name, value) in attributes {
+            let Some(attribute) = self.attributes.iter().find(|attribute| &attribute.name == name) else {
+                problems.push(format!("Attribute {} is not defined for product type {}", name, self.name));
+                continue;
+            };
+
+            if !attribute.data_type.accepts(value) {
+                problems.push(format!("Attribute {} must be {}", name, attribute.data_type));
+            } else if attribute
+                .allowed_values
+                .as_ref()
+                .is_some_and(|allowed_values| !allowed_values.contains(value))
+            {
+                problems.push(format!("Attribute {} cannot be {}", name, value));
+            }
+        }
+
+        for attribute in self.attributes.iter().filter(|attribute| attribute.required) {
+            if !attributes.contains_key(&attribute.name) {
+                problems.push(format!("Attribute {} is required", attribute.name));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+}
+
+#[cfg(test)]
+mod product_type_tests {
+    use crate::core::entities::product_type::{AttributeDefinition, AttributeType, ProductType};
+    use serde_json::{json, Map, Value};
+
+    fn shoes() -> ProductType {
+        ProductType::new(
+            "Shoes".to_string(),
+            vec![
+                AttributeDefinition::new(
+                    "material".to_string(),
+                    AttributeType::String,
+                    true,
+                    Some(vec![json!("leather"), json!("canvas")]),
+                ),
+                AttributeDefinition::new("waterproof".to_string(), AttributeType::Boolean, false, None),
+                AttributeDefinition::new("weight_grams".to_string(), AttributeType::Integer, false, None),
+            ],
+            None,
+        )
+    }
+
+    fn attributes(value: Value) -> Map<String, Value> {
+        value.as_object().cloned().unwrap()
+    }
+
+    #[test]
+    fn test_validate_attributes() {
+        assert_eq!(
+            Ok(()),
+            shoes().validate_attributes(&attributes(json!({"material": "leather", "weight_grams": 450})))
+        );
+        assert_eq!(
+            Err(vec![
+                "Attribute colour is not defined for product type Shoes".to_string(),
+                "Attribute waterproof must be a boolean".to_string(),
+                "Attribute weight_grams must be an integer".to_string(),
+                "Attribute material is required".to_string(),
+            ]),
+            shoes().validate_attributes(&attributes(
+                json!({"colour": "red", "waterproof": "yes", "weight_grams": 450.5})
+            ))
+        );
+        assert_eq!(
+            Err(vec!["Attribute material cannot be \"suede\"".to_string()]),
+            shoes().validate_attributes(&attributes(json!({"material": "suede"})))
+        );
+    }
+
+    #[test]
+    fn test_validate_schema() {
+        assert_eq!(Ok(()), shoes().validate_schema());
+
+        let invalid = ProductType::new(
+            "Lamps".to_string(),
+            vec![
+                AttributeDefinition::new("wattage".to_string(), AttributeType::Number, true, Some(vec![json!("60")])),
+                AttributeDefinition::new("wattage".to_string(), AttributeType::Number, false, None),
+            ],
+            None,
+        );
+
+        assert_eq!(
+            Err(vec![
+                "Allowed values of attribute wattage must each be a number".to_string(),
+                "Attribute wattage is defined more than once".to_string(),
+            ]),
+            invalid.validate_schema()
+        );
+    }
+}
@@ -1,11 +1,13 @@
+use crate::core::entities::ids::{ProductId, VariantId};
+
 pub struct ProductVariant {
-    product_id: i32,
-    variant_id: i32,
+    product_id: ProductId,
+    variant_id: VariantId,
     value: Option<String>,
 }
 
 impl ProductVariant {
-    pub fn new(product_id: i32, variant_id: i32, value: Option<String>) -> Self {
+    pub fn new(product_id: ProductId, variant_id: VariantId, value: Option<String>) -> Self {
         ProductVariant {
             product_id,
             variant_id,
@@ -13,11 +15,11 @@ impl ProductVariant {
         }
     }
 
-    pub fn product_id(&self) -> i32 {
+    pub fn product_id(&self) -> ProductId {
         self.product_id
     }
 
-    pub fn variant_id(&self) -> i32 {
+    pub fn variant_id(&self) -> VariantId {
         self.variant_id
     }
 
@@ -0,0 +1,209 @@
+use chrono::{DateTime, Duration, Utc};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// a value that is not a rate limit, e.g. `100 per minute`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidRateLimit {
+    pub value: String,
+}
+
+impl Display for InvalidRateLimit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} is not a valid rate limit, rate limits are a number of requests per number of seconds, e.g. `100/60`",
+            self.value
+        )
+    }
+}
+
+impl Error for InvalidRateLimit {}
+
+// how many requests a client may make in a period, e.g. `100/60` for 100 requests a minute. The
+// requests do not have to be spread out, a client may make all of them at once and then has to
+// wait for its bucket to refill
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    requests: u32,
+    period: Duration,
+}
+
+impl RateLimit {
+    pub fn new(requests: u32, period: Duration) -> RateLimit {
+        RateLimit { requests, period }
+    }
+
+    pub fn requests(&self) -> u32 {
+        self.requests
+    }
+
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    // the tokens a bucket gains per second
+    fn refill_rate(&self) -> f64 {
+        self.requests as f64 * 1000.0 / self.period.num_milliseconds() as f64
+    }
+}
+
+impl FromStr for RateLimit {
+    type Err = InvalidRateLimit;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidRateLimit {
+            value: value.to_string(),
+        };
+        let (requests, seconds) = value.trim().split_once('/').ok_or_else(invalid)?;
+        let requests = requests.trim().parse::<u32>().map_err(|_| invalid())?;
+        let seconds = seconds.trim().parse::<i64>().map_err(|_| invalid())?;
+
+        if requests == 0 || seconds <= 0 {
+            return Err(invalid());
+        }
+
+        Ok(RateLimit::new(requests, Duration::seconds(seconds)))
+    }
+}
+
+impl Display for RateLimit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.requests, self.period.num_seconds())
+    }
+}
+
+// whether a request was let through, and what the client has left afterwards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    limit: RateLimit,
+    allowed: bool,
+    remaining: u32,
+    retry_after: Duration,
+    reset_after: Duration,
+}
+
+impl RateLimitDecision {
+    pub fn limit(&self) -> RateLimit {
+        self.limit
+    }
+
+    pub fn allowed(&self) -> bool {
+        self.allowed
+    }
+
+    // the requests the client can still make right away
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+
+    // how long a client that was turned away has to wait before its next request is let through,
+    // zero when the request was let through
+    pub fn retry_after(&self) -> Duration {
+        self.retry_after
+    }
+
+    // how long until the client's bucket is full again
+    pub fn reset_after(&self) -> Duration {
+        self.reset_after
+    }
+}
+
+// the tokens a client has left. Every request takes a token and the bucket refills evenly, a full
+// period refilling it from empty
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenBucket {
+    tokens: f64,
+    updated_at: DateTime<Utc>,
+}
+
+impl TokenBucket {
+    pub fn full(limit: &RateLimit, now: DateTime<Utc>) -> TokenBucket {
+        TokenBucket {
+            tokens: limit.requests() as f64,
+            updated_at: now,
+        }
+    }
+
+    fn refill(&mut self, limit: &RateLimit, now: DateTime<Utc>) {
+        if now > self.updated_at {
+            let elapsed_seconds = (now - self.updated_at).num_milliseconds() as f64 / 1000.0;
+
+            self.tokens = (self.tokens + elapsed_seconds * limit.refill_rate()).min(limit.requests() as f64);
+            self.updated_at = now;
+        }
+    }
+
+    // whether the bucket has refilled completely by `now`, so that forgetting it changes nothing
+    pub fn is_full_at(&self, limit: &RateLimit, now: DateTime<Utc>) -> bool {
+        let mut bucket = *self;
+        bucket.refill(limit, now);
+
+        bucket.tokens >= limit.requests() as f64
+    }
+
+    // takes a token for a request made at `now` if one is left
+    pub fn take(&mut self, limit: &RateLimit, now: DateTime<Utc>) -> RateLimitDecision {
+        self.refill(limit, now);
+
+        let allowed = self.tokens >= 1.0;
+        if allowed {
+            self.tokens -= 1.0;
+        }
+        let time_to_refill = |tokens: f64| Duration::milliseconds((tokens.max(0.0) / limit.refill_rate() * 1000.0).ceil() as i64);
+
+        RateLimitDecision {
+            limit: *limit,
+            allowed,
+            remaining: self.tokens.floor() as u32,
+            retry_after: if allowed {
+                Duration::zero()
+            } else {
+                time_to_refill(1.0 - self.tokens)
+            },
+            reset_after: time_to_refill(limit.requests() as f64 - self.tokens),
+        }
+    }
+}
+
+#[cfg(test)]
+mod rate_limit_tests {
+    use crate::core::entities::rate_limit::{RateLimit, TokenBucket};
+    use chrono::{Duration, Utc};
+
+    #[test]
+    fn test_parse_rate_limits() {
+        assert_eq!(Ok(RateLimit::new(100, Duration::seconds(60))), "100/60".parse());
+        assert_eq!("100/60", RateLimit::new(100, Duration::seconds(60)).to_string());
+
+        for invalid in ["100", "0/60", "100/0", "-1/60", "100 per minute"] {
+            assert!(invalid.parse::<RateLimit>().is_err(), "{} should not parse", invalid);
+        }
+    }
+
+    #[test]
+    fn test_bucket_turns_requests_away_until_it_refills() {
+        let limit = RateLimit::new(2, Duration::seconds(10));
+        let now = Utc::now();
+        let mut bucket = TokenBucket::full(&limit, now);
+
+        let first = bucket.take(&limit, now);
+        let second = bucket.take(&limit, now);
+        let third = bucket.take(&limit, now);
+
+        assert!(first.allowed() && second.allowed());
+        assert_eq!((1, 0), (first.remaining(), second.remaining()));
+        assert!(!third.allowed());
+        assert_eq!(Duration::seconds(5), third.retry_after());
+        assert_eq!(Duration::seconds(10), third.reset_after());
+
+        // half the period refills one of the two tokens
+        let later = bucket.take(&limit, now + Duration::seconds(5));
+
+        assert!(later.allowed());
+        assert_eq!(0, later.remaining());
+        assert!(!bucket.is_full_at(&limit, now + Duration::seconds(9)));
+        assert!(bucket.is_full_at(&limit, now + Duration::seconds(15)));
+    }
+}
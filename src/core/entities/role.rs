@@ -0,0 +1,75 @@
+use anyhow::{anyhow, Error as AnyError};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+// what a client may do with the catalog. `Admin` allows everything, including managing roles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+pub enum Permission {
+    #[serde(rename = "catalog:read")]
+    CatalogRead,
+    #[serde(rename = "catalog:write")]
+    CatalogWrite,
+    #[serde(rename = "pricing:write")]
+    PricingWrite,
+    #[serde(rename = "inventory:adjust")]
+    InventoryAdjust,
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+impl Permission {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::CatalogRead => "catalog:read",
+            Permission::CatalogWrite => "catalog:write",
+            Permission::PricingWrite => "pricing:write",
+            Permission::InventoryAdjust => "inventory:adjust",
+            Permission::Admin => "admin",
+        }
+    }
+}
+
+impl Display for Permission {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Permission {
+    type Err = AnyError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "catalog:read" => Ok(Permission::CatalogRead),
+            "catalog:write" => Ok(Permission::CatalogWrite),
+            "pricing:write" => Ok(Permission::PricingWrite),
+            "inventory:adjust" => Ok(Permission::InventoryAdjust),
+            "admin" => Ok(Permission::Admin),
+            other => Err(anyhow!("Unknown permission: {}", other)),
+        }
+    }
+}
+
+// a named set of permissions that clients are granted by naming the role in their scopes, e.g.
+// `merchandiser`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Role {
+    name: String,
+    permissions: Vec<Permission>,
+}
+
+impl Role {
+    pub fn new(name: String, permissions: Vec<Permission>) -> Role {
+        Role { name, permissions }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn permissions(&self) -> &[Permission] {
+        &self.permissions
+    }
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// a named fact about a product shown in its specification table, e.g. `Weight` and `1.2 kg`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Specification {
+    name: String,
+    value: String,
+}
+
+impl Specification {
+    pub fn new(name: String, value: String) -> Specification {
+        Specification { name, value }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn value(&self) -> &str {
+        self.value.as_str()
+    }
+}
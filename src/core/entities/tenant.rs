@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// the tenant that connections work for unless they are told otherwise, and that the catalog
+// belonged to before it was split by tenant
+pub const DEFAULT_TENANT: &str = "default";
+
+// a value that is not a tenant ID, e.g. `Acme Inc.`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidTenant {
+    pub value: String,
+}
+
+impl Display for InvalidTenant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} is not a valid tenant, tenants are 1 to 63 lowercase letters, digits, `-` and `_`",
+            self.value
+        )
+    }
+}
+
+impl Error for InvalidTenant {}
+
+// a brand whose catalog is kept apart from the catalogs of the other brands on the same
+// deployment, e.g. `acme`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Tenant(String);
+
+impl Tenant {
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl Default for Tenant {
+    fn default() -> Tenant {
+        Tenant(DEFAULT_TENANT.to_string())
+    }
+}
+
+impl FromStr for Tenant {
+    type Err = InvalidTenant;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let valid = (1..=63).contains(&value.len())
+            && value
+                .bytes()
+                .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'-' || byte == b'_');
+
+        if valid {
+            Ok(Tenant(value.to_string()))
+        } else {
+            Err(InvalidTenant {
+                value: value.to_string(),
+            })
+        }
+    }
+}
+
+impl TryFrom<String> for Tenant {
+    type Error = InvalidTenant;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Tenant> for String {
+    fn from(tenant: Tenant) -> String {
+        tenant.0
+    }
+}
+
+impl Display for Tenant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
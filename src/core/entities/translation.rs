@@ -0,0 +1,67 @@
+use crate::core::entities::locale::Locale;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+// a product's name and description in a locale other than the default one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ProductTranslation {
+    locale: Locale,
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+impl ProductTranslation {
+    pub fn new(locale: Locale, name: String, description: Option<String>) -> ProductTranslation {
+        ProductTranslation {
+            locale,
+            name,
+            description,
+        }
+    }
+
+    pub fn locale(&self) -> &Locale {
+        &self.locale
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+// how a variant and its values are displayed in a locale other than the default one. `values` maps
+// the stored values to their display values, values without an entry are displayed as stored
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct VariantTranslation {
+    locale: Locale,
+    display_name: String,
+    #[serde(default)]
+    values: BTreeMap<String, String>,
+}
+
+impl VariantTranslation {
+    pub fn new(locale: Locale, display_name: String, values: BTreeMap<String, String>) -> VariantTranslation {
+        VariantTranslation {
+            locale,
+            display_name,
+            values,
+        }
+    }
+
+    pub fn locale(&self) -> &Locale {
+        &self.locale
+    }
+
+    pub fn display_name(&self) -> &str {
+        self.display_name.as_str()
+    }
+
+    pub fn values(&self) -> &BTreeMap<String, String> {
+        &self.values
+    }
+}
@@ -1,15 +1,17 @@
-#[derive(Clone)]
+use crate::core::entities::ids::VariantId;
+
+#[derive(Debug, Clone)]
 pub struct Variant {
-    id: Option<u32>,
+    id: Option<VariantId>,
     name: String,
 }
 
 impl Variant {
-    pub fn new(name: String, id: Option<u32>) -> Variant {
+    pub fn new(name: String, id: Option<VariantId>) -> Variant {
         Variant { name, id }
     }
 
-    pub fn id(&self) -> Option<u32> {
+    pub fn id(&self) -> Option<VariantId> {
         self.id
     }
 
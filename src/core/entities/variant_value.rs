@@ -1,6 +1,6 @@
 use crate::core::entities::variant::Variant;
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct VariantValue {
     variant: Variant,
     values: Vec<Option<String>>,
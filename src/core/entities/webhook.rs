@@ -0,0 +1,343 @@
+use crate::core::entities::ids::WebhookSubscriptionId;
+use anyhow::{anyhow, Error as AnyError};
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use rand::distributions::{Alphanumeric, DistString};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use std::str::FromStr;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+const WEBHOOK_SECRET_PREFIX: &str = "whsec_";
+const WEBHOOK_SECRET_RANDOM_LENGTH: usize = 32;
+
+// an endpoint that catalog events are posted to. The secret is never shown again after the
+// subscription is created
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct WebhookSubscription {
+    id: WebhookSubscriptionId,
+    url: String,
+    event_types: Vec<String>,
+    #[serde(skip)]
+    secret: String,
+    active: bool,
+    created_at: DateTime<Utc>,
+}
+
+impl WebhookSubscription {
+    pub fn new(
+        id: WebhookSubscriptionId,
+        url: String,
+        event_types: Vec<String>,
+        secret: String,
+        active: bool,
+        created_at: DateTime<Utc>,
+    ) -> WebhookSubscription {
+        WebhookSubscription {
+            id,
+            url,
+            event_types,
+            secret,
+            active,
+            created_at,
+        }
+    }
+
+    pub fn id(&self) -> WebhookSubscriptionId {
+        self.id
+    }
+
+    pub fn url(&self) -> &str {
+        self.url.as_str()
+    }
+
+    // the types of events the subscription gets, every type when this is empty
+    pub fn event_types(&self) -> &[String] {
+        &self.event_types
+    }
+
+    pub fn secret(&self) -> &str {
+        self.secret.as_str()
+    }
+
+    pub fn active(&self) -> bool {
+        self.active
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn wants(&self, event_type: &str) -> bool {
+        self.active && (self.event_types.is_empty() || self.event_types.iter().any(|wanted| wanted == event_type))
+    }
+}
+
+// generates a secret to sign the payloads of a subscription with, e.g. `whsec_Xb3...`
+pub fn generate_webhook_secret() -> String {
+    format!(
+        "{}{}",
+        WEBHOOK_SECRET_PREFIX,
+        Alphanumeric.sample_string(&mut rand::thread_rng(), WEBHOOK_SECRET_RANDOM_LENGTH)
+    )
+}
+
+// the signature sent with a payload in the `X-Webhook-Signature` header, `t=<timestamp>,v1=<hex>`.
+// The HMAC-SHA256 covers the Unix timestamp and the body joined by a `.`, so a receiver that
+// checks the timestamp is recent can turn away replays of old payloads
+pub fn sign_webhook_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+
+    format!("t={},v1={}", timestamp, hex::encode(mac.finalize().into_bytes()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    // waiting for its first attempt or for the next one after a failure
+    Pending,
+    Delivered,
+    // failed as often as it may and is not attempted anymore, until it is retried by hand
+    Dead,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Dead => "dead",
+        }
+    }
+}
+
+impl FromStr for DeliveryStatus {
+    type Err = AnyError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "pending" => Ok(DeliveryStatus::Pending),
+            "delivered" => Ok(DeliveryStatus::Delivered),
+            "dead" => Ok(DeliveryStatus::Dead),
+            other => Err(anyhow!("Unknown delivery status: {}", other)),
+        }
+    }
+}
+
+// an event on its way to a subscription, along with how its attempts went so far
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct WebhookDelivery {
+    id: i64,
+    subscription_id: WebhookSubscriptionId,
+    event_id: Uuid,
+    event_type: String,
+    #[serde(skip)]
+    payload: Value,
+    status: DeliveryStatus,
+    attempts: u32,
+    next_attempt_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_attempt_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_response_status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delivered_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl WebhookDelivery {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: i64,
+        subscription_id: WebhookSubscriptionId,
+        event_id: Uuid,
+        event_type: String,
+        payload: Value,
+        status: DeliveryStatus,
+        attempts: u32,
+        next_attempt_at: DateTime<Utc>,
+        last_attempt_at: Option<DateTime<Utc>>,
+        last_response_status: Option<u16>,
+        last_error: Option<String>,
+        delivered_at: Option<DateTime<Utc>>,
+        created_at: DateTime<Utc>,
+    ) -> WebhookDelivery {
+        WebhookDelivery {
+            id,
+            subscription_id,
+            event_id,
+            event_type,
+            payload,
+            status,
+            attempts,
+            next_attempt_at,
+            last_attempt_at,
+            last_response_status,
+            last_error,
+            delivered_at,
+            created_at,
+        }
+    }
+
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    pub fn subscription_id(&self) -> WebhookSubscriptionId {
+        self.subscription_id
+    }
+
+    pub fn event_id(&self) -> Uuid {
+        self.event_id
+    }
+
+    pub fn event_type(&self) -> &str {
+        self.event_type.as_str()
+    }
+
+    // the event as it is posted
+    pub fn payload(&self) -> &Value {
+        &self.payload
+    }
+
+    pub fn status(&self) -> DeliveryStatus {
+        self.status
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn next_attempt_at(&self) -> DateTime<Utc> {
+        self.next_attempt_at
+    }
+
+    pub fn last_response_status(&self) -> Option<u16> {
+        self.last_response_status
+    }
+
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    pub fn delivered_at(&self) -> Option<DateTime<Utc>> {
+        self.delivered_at
+    }
+}
+
+// how an attempt to deliver went. Only a 2xx response counts as delivered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeliveryAttempt {
+    Delivered { response_status: u16 },
+    Failed { response_status: Option<u16>, error: String },
+}
+
+// how often and how far apart failed deliveries are attempted again. The wait doubles after every
+// failure, starting at `base_delay` and never longer than `max_delay`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32, base_delay: Duration, max_delay: Duration) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay,
+            max_delay,
+        }
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    // how long to wait after the failure of attempt number `attempts`, or none when that was the
+    // last attempt and the delivery is dead
+    pub fn delay_after(&self, attempts: u32) -> Option<Duration> {
+        if attempts >= self.max_attempts {
+            return None;
+        }
+
+        let factor = 2_i32.checked_pow(attempts.saturating_sub(1)).unwrap_or(i32::MAX);
+
+        Some(
+            self.base_delay
+                .checked_mul(factor)
+                .map_or(self.max_delay, |delay| delay.min(self.max_delay)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod webhook_tests {
+    use crate::core::entities::ids::WebhookSubscriptionId;
+    use crate::core::entities::webhook::{
+        generate_webhook_secret, sign_webhook_payload, RetryPolicy, WebhookSubscription,
+    };
+    use chrono::{Duration, Utc};
+
+    #[test]
+    fn test_payloads_are_signed_with_hmac_sha256() {
+        // the HMAC-SHA256 of `1700000000.{}` under the key `secret`
+        assert_eq!(
+            "t=1700000000,v1=b8569b78799ff9e3cbff0fc2d63a33a2b57f3282abd07c37ae5e8e7d79a5f163",
+            sign_webhook_payload("secret", 1_700_000_000, b"{}")
+        );
+        assert_ne!(
+            sign_webhook_payload("secret", 1_700_000_000, b"{}"),
+            sign_webhook_payload("other", 1_700_000_000, b"{}")
+        );
+
+        let secret = generate_webhook_secret();
+
+        assert!(secret.starts_with("whsec_"));
+        assert_ne!(secret, generate_webhook_secret());
+    }
+
+    #[test]
+    fn test_retries_back_off_exponentially_until_dead() {
+        let policy = RetryPolicy::new(5, Duration::seconds(30), Duration::minutes(2));
+
+        let delays = (1..=5).map(|attempts| policy.delay_after(attempts)).collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                Some(Duration::seconds(30)),
+                Some(Duration::seconds(60)),
+                Some(Duration::seconds(120)),
+                Some(Duration::seconds(120)),
+                None,
+            ],
+            delays
+        );
+    }
+
+    #[test]
+    fn test_subscriptions_filter_events_by_type() {
+        let subscription = |event_types: Vec<&str>, active| {
+            WebhookSubscription::new(
+                WebhookSubscriptionId::try_from(1).unwrap(),
+                "http://localhost:9000/hooks".to_string(),
+                event_types.into_iter().map(String::from).collect(),
+                "whsec_test".to_string(),
+                active,
+                Utc::now(),
+            )
+        };
+
+        assert!(subscription(vec![], true).wants("PriceChanged"));
+        assert!(subscription(vec!["PriceChanged"], true).wants("PriceChanged"));
+        assert!(!subscription(vec!["PriceChanged"], true).wants("ProductCreated"));
+        assert!(!subscription(vec![], false).wants("PriceChanged"));
+    }
+}
@@ -0,0 +1,40 @@
+use pulldown_cmark::{html, Options, Parser};
+
+// renders a description written in Markdown to HTML that is safe to embed in a storefront page.
+// Tables and strikethrough are supported on top of CommonMark. Raw HTML in the source is kept, but
+// the result is sanitized: scripts, styles, event handler attributes and `javascript:` links are
+// removed and links get `rel="noopener noreferrer"`
+pub fn render_markdown(source: &str) -> String {
+    let parser = Parser::new_ext(source, Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH);
+
+    let mut unsafe_html = String::with_capacity(source.len() * 3 / 2);
+    html::push_html(&mut unsafe_html, parser);
+
+    ammonia::clean(&unsafe_html)
+}
+
+#[cfg(test)]
+mod markdown_tests {
+    use crate::core::markdown::render_markdown;
+
+    #[test]
+    fn test_render_markdown() {
+        assert_eq!(
+            "<p><strong>Warm</strong> boots</p>\n<ul>\n<li>Waterproof</li>\n</ul>\n",
+            render_markdown("**Warm** boots\n\n- Waterproof")
+        );
+        assert!(render_markdown("| Size | Fit |\n|---|---|\n| 42 | Wide |").contains("<td>Wide</td>"));
+    }
+
+    #[test]
+    fn test_render_markdown_strips_unsafe_html() {
+        let html = render_markdown(
+            "<script>alert(1)</script>\n\n<img src=\"boots.png\" onerror=\"alert(1)\">\n\n[Care](javascript:alert(1))",
+        );
+
+        assert!(!html.contains("script"));
+        assert!(!html.contains("onerror"));
+        assert!(!html.contains("javascript"));
+        assert!(html.contains("<img src=\"boots.png\">"));
+    }
+}
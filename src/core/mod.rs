@@ -1,2 +1,4 @@
+pub mod authorization;
 pub mod entities;
+pub mod markdown;
 pub mod ports;
@@ -0,0 +1,23 @@
+use crate::core::entities::api_key::ApiKey;
+use anyhow::Result as AnyResult;
+use chrono::{DateTime, Utc};
+
+pub trait ApiKeyDatastore {
+    // stores a new API key by the hash of the key. Names are unique
+    fn create_api_key(
+        &mut self,
+        name: &str,
+        key_hash: &str,
+        scopes: &[String],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> AnyResult<ApiKey>;
+
+    // finds the API key with a given hash, whether or not it can still be used
+    fn find_api_key(&mut self, key_hash: &str) -> AnyResult<Option<ApiKey>>;
+
+    // lists all API keys, revoked and expired ones included, ordered by name
+    fn list_api_keys(&mut self) -> AnyResult<Vec<ApiKey>>;
+
+    // revokes the API key with a given name, so that it cannot be used anymore
+    fn revoke_api_key(&mut self, name: &str) -> AnyResult<ApiKey>;
+}
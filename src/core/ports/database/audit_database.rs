@@ -0,0 +1,11 @@
+use crate::core::entities::audit_record::{AuditEntityType, AuditRecord};
+use anyhow::Result as AnyResult;
+
+pub trait AuditDatastore {
+    // gets the audit history of an entity, oldest record first
+    fn get_entity_history(
+        &mut self,
+        entity_type: AuditEntityType,
+        entity_id: i32,
+    ) -> AnyResult<Vec<AuditRecord>>;
+}
@@ -0,0 +1,15 @@
+use crate::core::entities::bundle::{Bundle, BundleDefinition};
+use crate::core::entities::ids::ProductId;
+use anyhow::Result as AnyResult;
+
+pub trait BundleDatastore {
+    // get the bundle that the product with a given ID is, with its price and availability
+    fn get_bundle(&mut self, id: ProductId) -> AnyResult<Bundle>;
+
+    // makes a product a bundle of other products, or replaces what it is made of. Components must
+    // be existing products that are not bundles themselves. The product moves to its next version
+    fn set_bundle(&mut self, id: ProductId, definition: BundleDefinition) -> AnyResult<Bundle>;
+
+    // turns a bundle back into a plain product, keeping the product itself
+    fn delete_bundle(&mut self, id: ProductId) -> AnyResult<()>;
+}
@@ -0,0 +1,10 @@
+use crate::core::entities::product_change::{ChangeToken, ProductChange};
+use anyhow::Result as AnyResult;
+
+pub trait ChangeFeedDatastore {
+    // lists up to `limit` product changes after `since` in feed order, or from the start of the feed
+    // when `since` is not set. A product is listed once, with its latest change. Changes of
+    // transactions that may still be followed by the commit of an earlier one are held back, so a
+    // consumer that carries on from the token of the last change it got never misses one
+    fn list_product_changes(&mut self, since: Option<ChangeToken>, limit: i64) -> AnyResult<Vec<ProductChange>>;
+}
@@ -0,0 +1,93 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+// errors raised by datastores that callers are expected to handle. They are returned wrapped in an
+// `anyhow::Error` and can be recovered with `downcast_ref`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DatastoreError {
+    NotFound {
+        entity: &'static str,
+        id: i32,
+    },
+    // no product goes by the public ID or slug a caller referred to it with
+    UnknownReference {
+        entity: &'static str,
+        reference: String,
+    },
+    // another entity already goes by a name that has to be unique
+    AlreadyExists {
+        entity: &'static str,
+        reference: String,
+    },
+    VersionConflict {
+        entity: &'static str,
+        id: i32,
+        expected_version: u32,
+        current_version: u32,
+    },
+    // the attributes of a product do not fit the schema of its type, or a schema is not valid
+    InvalidAttributes {
+        entity: &'static str,
+        problems: Vec<String>,
+    },
+    // a bundle cannot be made of the components it was given
+    InvalidBundle {
+        id: i32,
+        problems: Vec<String>,
+    },
+    // a product cannot be linked to the products it was given
+    InvalidRelations {
+        id: i32,
+        problems: Vec<String>,
+    },
+    // an entity cannot be removed while others still depend on it, e.g. a bundle on its components
+    InUse {
+        entity: &'static str,
+        id: i32,
+        used_by: String,
+    },
+    // a client tried to do something its permissions do not allow
+    PermissionDenied {
+        subject: String,
+        permission: &'static str,
+    },
+}
+
+impl Display for DatastoreError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            DatastoreError::NotFound { entity, id } => write!(f, "{} {} does not exist", entity, id),
+            DatastoreError::UnknownReference { entity, reference } => {
+                write!(f, "{} {} does not exist", entity, reference)
+            }
+            DatastoreError::AlreadyExists { entity, reference } => write!(f, "{} {} already exists", entity, reference),
+            DatastoreError::VersionConflict {
+                entity,
+                id,
+                expected_version,
+                current_version,
+            } => write!(
+                f,
+                "{} {} is at version {}, not the expected version {}",
+                entity, id, current_version, expected_version
+            ),
+            DatastoreError::InvalidAttributes { entity, problems } => {
+                write!(f, "{} attributes are invalid: {}", entity, problems.join("; "))
+            }
+            DatastoreError::InvalidBundle { id, problems } => {
+                write!(f, "Bundle {} is invalid: {}", id, problems.join("; "))
+            }
+            DatastoreError::InvalidRelations { id, problems } => {
+                write!(f, "Relations of product {} are invalid: {}", id, problems.join("; "))
+            }
+            DatastoreError::InUse { entity, id, used_by } => {
+                write!(f, "{} {} is still used by {}", entity, id, used_by)
+            }
+            DatastoreError::PermissionDenied { subject, permission } => {
+                write!(f, "{} is not allowed to do this, it needs the {} permission", subject, permission)
+            }
+        }
+    }
+}
+
+impl Error for DatastoreError {}
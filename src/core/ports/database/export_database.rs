@@ -0,0 +1,18 @@
+use crate::core::entities::product_export::ExportedProduct;
+use anyhow::Result as AnyResult;
+use chrono::{DateTime, Utc};
+
+pub trait ProductExportDatastore {
+    // lists the names of every variant, sorted by name
+    fn list_variant_names(&mut self) -> AnyResult<Vec<String>>;
+
+    // streams every active product, in ID order, to `visit` as it is read from the database instead
+    // of loading the whole catalog first. When `changed_since` is set only products changed after
+    // it are streamed, inactive ones included so that feeds learn they are no longer for sale.
+    // Returns the number of products streamed
+    fn stream_exported_products(
+        &mut self,
+        changed_since: Option<DateTime<Utc>>,
+        visit: &mut dyn FnMut(ExportedProduct) -> AnyResult<()>,
+    ) -> AnyResult<usize>;
+}
@@ -0,0 +1,19 @@
+use crate::core::entities::idempotency_record::{IdempotencyRecord, IdempotentResponse};
+use anyhow::Result as AnyResult;
+use chrono::{DateTime, Utc};
+
+pub trait IdempotencyDatastore {
+    // reserves an idempotency key of `principal` for a request until `expires_at`. Returns the
+    // existing record instead when the principal has already reserved the key and it has not expired
+    // yet. Expired keys are deleted
+    fn reserve_idempotency_key(
+        &mut self,
+        principal: &str,
+        key: &str,
+        fingerprint: &str,
+        expires_at: DateTime<Utc>,
+    ) -> AnyResult<Option<IdempotencyRecord>>;
+
+    // stores the response of the request that reserved a key
+    fn complete_idempotency_key(&mut self, principal: &str, key: &str, response: &IdempotentResponse) -> AnyResult<()>;
+}
@@ -0,0 +1,12 @@
+use crate::core::entities::ids::ProductId;
+use crate::core::entities::product_image::ProductImage;
+use anyhow::Result as AnyResult;
+
+pub trait ImageDatastore {
+    // lists the images of a product in the order they were given in
+    fn list_product_images(&mut self, id: ProductId) -> AnyResult<Vec<ProductImage>>;
+
+    // replaces all images of a product. The product moves to its next version, so that exports
+    // since an earlier point pick up the new images
+    fn set_product_images(&mut self, id: ProductId, images: Vec<ProductImage>) -> AnyResult<Vec<ProductImage>>;
+}
@@ -1,2 +1,19 @@
+pub mod api_key_database;
+pub mod audit_database;
+pub mod bundle_database;
+pub mod change_database;
+pub mod errors;
+pub mod export_database;
+pub mod idempotency_database;
+pub mod image_database;
+pub mod outbox_database;
 pub mod product_database;
+pub mod product_type_database;
+pub mod relation_database;
+pub mod revision_database;
+pub mod role_database;
+pub mod specification_database;
+pub mod translation_database;
 pub mod utils;
+pub mod variant_database;
+pub mod webhook_database;
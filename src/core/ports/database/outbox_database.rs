@@ -0,0 +1,19 @@
+use crate::core::entities::domain_event::OutboxEvent;
+use crate::core::entities::webhook::RetryPolicy;
+use anyhow::Result as AnyResult;
+use chrono::Duration;
+
+pub trait OutboxDatastore {
+    // hands up to `limit` due events of every tenant to `deliver`, oldest first, marking each one
+    // delivered once `deliver` succeeds. An event that fails is kept with its error and attempted
+    // again as `retry_policy` says, without holding back the events after it, and is dead once it
+    // runs out of attempts. Events are held for `lease` while they are delivered, and events held
+    // by another relay are skipped. Returns the number of events delivered
+    fn relay_pending_events(
+        &mut self,
+        limit: i64,
+        lease: Duration,
+        retry_policy: &RetryPolicy,
+        deliver: &mut dyn FnMut(&OutboxEvent) -> AnyResult<()>,
+    ) -> AnyResult<usize>;
+}
@@ -1,29 +1,67 @@
 use crate::core::entities::complete_product::CompleteProduct;
+use crate::core::entities::ids::ProductId;
 use crate::core::entities::product::Product;
+use crate::core::entities::product_import::ImportedProduct;
+use crate::core::entities::product_reference::{ProductReference, ResolvedProduct};
 use crate::core::entities::product_variant::ProductVariant;
 use crate::core::entities::variant::Variant;
 use crate::core::ports::database::utils::ListQueryParams;
 use anyhow::Result as AnyResult;
 
+// a product along with the values of its variants
+pub type ProductWithVariants = (Product, Vec<(ProductVariant, Variant)>);
+
 pub trait ProductDatastore {
     // creates a product
-    fn create_product(&self, product: Product) -> AnyResult<Product>;
+    fn create_product(&mut self, product: Product) -> AnyResult<Product>;
 
     // creates a complete product
-    fn create_complete_product(&self, complete_product: CompleteProduct) -> AnyResult<i32>;
+    fn create_complete_product(&mut self, complete_product: CompleteProduct) -> AnyResult<ProductId>;
+
+    // creates or updates a batch of complete products in a single transaction. A product whose
+    // external key is already stored updates that product and replaces its variant values. Each
+    // product is applied in its own savepoint so that one failing product does not abort the rest
+    // of the batch, and nothing is committed when `dry_run` is set
+    fn upsert_complete_products(
+        &mut self,
+        complete_products: Vec<CompleteProduct>,
+        dry_run: bool,
+    ) -> AnyResult<Vec<AnyResult<ImportedProduct>>>;
+
+    // updates the fields of a product. Fails with a version conflict when the product is no longer
+    // at `expected_version`
+    fn update_product(&mut self, id: ProductId, product: Product, expected_version: u32) -> AnyResult<Product>;
+
+    // deletes a product along with its variant values. Fails with a version conflict when the
+    // product is no longer at `expected_version`
+    fn delete_product(&mut self, id: ProductId, expected_version: u32) -> AnyResult<()>;
+
+    // finds the product an ID, public ID or slug refers to. Slugs a product had before it was
+    // renamed still resolve to it, marked as redirected
+    fn resolve_product(&mut self, reference: &ProductReference) -> AnyResult<ResolvedProduct>;
+
+    // finds the product known by an external key, e.g. the SKU it is imported with
+    fn find_product_by_external_key(&mut self, external_key: &str) -> AnyResult<ProductId>;
 
     // get product by a given ID
-    fn get_product(&self, id: u32) -> AnyResult<Product>;
+    fn get_product(&mut self, id: ProductId) -> AnyResult<Product>;
 
     // get product with a given ID with its variants
-    fn get_product_with_variants(&self, id: u32) -> AnyResult<(Product, Vec<ProductVariant, Variant>)>;
+    fn get_product_with_variants(&mut self, id: ProductId) -> AnyResult<(Product, Vec<(ProductVariant, Variant)>)>;
 
     // lists products
-    fn list_products(&self, params: ListQueryParams) -> Vec<Product>;
+    fn list_products(&mut self, params: ListQueryParams) -> AnyResult<Vec<Product>>;
+
+    // lists the variant values of several products at once, in the order they were attached, so
+    // that callers that need the variants of many products do not query them one product at a time
+    fn list_variants_of_products(&mut self, ids: &[ProductId]) -> AnyResult<Vec<(ProductVariant, Variant)>>;
 
-    // lists products with their variants
+    // lists up to `limit` products with their variants in ID order, starting after the product
+    // `after`. Pages are found by where the last one ended rather than by offset, so the catalog can
+    // be read through a page at a time without keeping a transaction open across pages
     fn list_products_with_variants(
-        &self,
-        params: ListQueryParams,
-    ) -> AnyResult<Vec<(Product, Vec<ProductVariant, Variant>)>>;
+        &mut self,
+        after: Option<ProductId>,
+        limit: i64,
+    ) -> AnyResult<Vec<ProductWithVariants>>;
 }
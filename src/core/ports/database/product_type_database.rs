@@ -0,0 +1,21 @@
+use crate::core::entities::ids::ProductTypeId;
+use crate::core::entities::product_type::ProductType;
+use anyhow::Result as AnyResult;
+
+pub trait ProductTypeDatastore {
+    // lists all product types, ordered by name
+    fn list_product_types(&mut self) -> AnyResult<Vec<ProductType>>;
+
+    // finds the product types with the given IDs, leaving out IDs that are not a product type
+    fn find_product_types(&mut self, ids: &[ProductTypeId]) -> AnyResult<Vec<ProductType>>;
+
+    // get product type with a given ID
+    fn get_product_type(&mut self, id: ProductTypeId) -> AnyResult<ProductType>;
+
+    // creates a product type after checking that its attribute schema is valid
+    fn create_product_type(&mut self, product_type: ProductType) -> AnyResult<ProductType>;
+
+    // replaces the name and attribute schema of a product type. Products of the type keep their
+    // attributes and are checked against the new schema the next time they are written
+    fn update_product_type(&mut self, id: ProductTypeId, product_type: ProductType) -> AnyResult<ProductType>;
+}
@@ -0,0 +1,13 @@
+use crate::core::entities::ids::ProductId;
+use crate::core::entities::product_relation::ProductRelation;
+use anyhow::Result as AnyResult;
+
+pub trait RelationDatastore {
+    // lists the links from a product to others, inactive ones included, ordered by type and position
+    fn list_product_relations(&mut self, id: ProductId) -> AnyResult<Vec<ProductRelation>>;
+
+    // replaces all links from a product to others. Linked products must exist. The product moves
+    // to its next version
+    fn set_product_relations(&mut self, id: ProductId, relations: Vec<ProductRelation>)
+        -> AnyResult<Vec<ProductRelation>>;
+}
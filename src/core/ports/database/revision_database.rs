@@ -0,0 +1,30 @@
+use crate::core::entities::ids::ProductId;
+use crate::core::entities::product_revision::{
+    ProductRevision, ProductRevisionDiff, RevisionSelector,
+};
+use anyhow::Result as AnyResult;
+
+pub trait ProductRevisionDatastore {
+    // gets a product and its variants as they were at a given revision or point in time
+    fn get_product_at(&mut self, id: ProductId, selector: RevisionSelector) -> AnyResult<ProductRevision>;
+
+    // lists every revision of a product, oldest first
+    fn list_product_revisions(&mut self, id: ProductId) -> AnyResult<Vec<ProductRevision>>;
+
+    // diffs two revisions of a product
+    fn diff_product_revisions(
+        &mut self,
+        id: ProductId,
+        from_revision: u32,
+        to_revision: u32,
+    ) -> AnyResult<ProductRevisionDiff>;
+
+    // reverts a product to an earlier revision. The revert is itself recorded as a new revision and
+    // fails with a version conflict when the product is no longer at `expected_version`
+    fn revert_product(
+        &mut self,
+        id: ProductId,
+        revision: u32,
+        expected_version: u32,
+    ) -> AnyResult<ProductRevision>;
+}
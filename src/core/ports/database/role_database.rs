@@ -0,0 +1,17 @@
+use crate::core::entities::role::Role;
+use anyhow::Result as AnyResult;
+
+pub trait RoleDatastore {
+    // lists all roles, ordered by name
+    fn list_roles(&mut self) -> AnyResult<Vec<Role>>;
+
+    // finds the roles with the given names. Names that no role goes by are skipped
+    fn find_roles(&mut self, names: &[String]) -> AnyResult<Vec<Role>>;
+
+    fn get_role(&mut self, name: &str) -> AnyResult<Role>;
+
+    // creates the role, or replaces the permissions of the role that already goes by its name
+    fn save_role(&mut self, role: Role) -> AnyResult<Role>;
+
+    fn delete_role(&mut self, name: &str) -> AnyResult<()>;
+}
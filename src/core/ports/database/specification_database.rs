@@ -0,0 +1,16 @@
+use crate::core::entities::ids::ProductId;
+use crate::core::entities::specification::Specification;
+use anyhow::Result as AnyResult;
+
+pub trait SpecificationDatastore {
+    // lists the specifications of a product in the order they were given in
+    fn list_product_specifications(&mut self, id: ProductId) -> AnyResult<Vec<Specification>>;
+
+    // replaces all specifications of a product. The product moves to its next version, so that
+    // readers holding the previous specifications see them change
+    fn set_product_specifications(
+        &mut self,
+        id: ProductId,
+        specifications: Vec<Specification>,
+    ) -> AnyResult<Vec<Specification>>;
+}
@@ -0,0 +1,45 @@
+use crate::core::entities::ids::{ProductId, VariantId};
+use crate::core::entities::locale::{Locale, LocaleFallback};
+use crate::core::entities::product::Product;
+use crate::core::entities::product_detail::ProductDetail;
+use crate::core::entities::translation::{ProductTranslation, VariantTranslation};
+use crate::core::ports::database::utils::{ListQueryParams, ProductFilter};
+use anyhow::Result as AnyResult;
+
+pub trait TranslationDatastore {
+    // lists the translations of a product, ordered by locale
+    fn list_product_translations(&mut self, id: ProductId) -> AnyResult<Vec<ProductTranslation>>;
+
+    // creates or replaces the translation of a product into a locale. The product moves to its next
+    // version, so that readers holding the previous translation see it change
+    fn set_product_translation(
+        &mut self,
+        id: ProductId,
+        translation: ProductTranslation,
+    ) -> AnyResult<ProductTranslation>;
+
+    // removes the translation of a product into a locale, after which that locale falls back
+    fn delete_product_translation(&mut self, id: ProductId, locale: &Locale) -> AnyResult<()>;
+
+    // creates or replaces the translation of a variant's display name and values into a locale.
+    // Value translations of the locale that are not in `translation` are removed
+    fn set_variant_translation(
+        &mut self,
+        id: VariantId,
+        translation: VariantTranslation,
+    ) -> AnyResult<VariantTranslation>;
+
+    // get product with a given ID with its specifications, variants, active related products and,
+    // for a bundle, its components, each text in the first locale along `fallback` that it is
+    // translated to
+    fn get_product_detail(&mut self, id: ProductId, fallback: &LocaleFallback) -> AnyResult<ProductDetail>;
+
+    // lists products that match `filter` with their name and description in the first locale
+    // along `fallback` that they are translated to
+    fn list_localized_products(
+        &mut self,
+        params: ListQueryParams,
+        filter: &ProductFilter,
+        fallback: &LocaleFallback,
+    ) -> AnyResult<Vec<Product>>;
+}
@@ -1,4 +1,15 @@
+use crate::core::entities::ids::ProductTypeId;
+use serde_json::{Map, Value};
+
 pub struct ListQueryParams {
     pub offset: i64,
     pub limit: i64,
 }
+
+// narrows a listing down to products of a type and to products whose attributes contain the
+// given ones, e.g. `{"material": "leather"}`
+#[derive(Debug, Default)]
+pub struct ProductFilter {
+    pub product_type_id: Option<ProductTypeId>,
+    pub attributes: Option<Map<String, Value>>,
+}
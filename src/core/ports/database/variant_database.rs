@@ -0,0 +1,14 @@
+use crate::core::entities::ids::{ProductId, VariantId};
+use crate::core::entities::variant::Variant;
+use anyhow::Result as AnyResult;
+
+pub trait VariantDatastore {
+    // lists every variant along with the number of product values attached to it
+    fn list_variants(&mut self) -> AnyResult<Vec<(Variant, u32)>>;
+
+    // merges a variant into another one. The values of `source_id` are moved over to `target_id`,
+    // dropping the ones a product already has under `target_id`, and `source_id` is deleted. Every
+    // product that had values moved gets a new version and revision. Returns the IDs of those
+    // products
+    fn merge_variants(&mut self, source_id: VariantId, target_id: VariantId) -> AnyResult<Vec<ProductId>>;
+}
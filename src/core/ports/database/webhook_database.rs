@@ -0,0 +1,67 @@
+use crate::core::entities::domain_event::OutboxEvent;
+use crate::core::entities::ids::WebhookSubscriptionId;
+use crate::core::entities::webhook::{
+    DeliveryAttempt, DeliveryStatus, RetryPolicy, WebhookDelivery, WebhookSubscription,
+};
+use crate::core::ports::database::utils::ListQueryParams;
+use anyhow::Result as AnyResult;
+use chrono::Duration;
+
+pub trait WebhookDatastore {
+    // lists the subscriptions of the tenant, oldest first
+    fn list_webhook_subscriptions(&mut self) -> AnyResult<Vec<WebhookSubscription>>;
+
+    fn get_webhook_subscription(&mut self, id: WebhookSubscriptionId) -> AnyResult<WebhookSubscription>;
+
+    fn create_webhook_subscription(
+        &mut self,
+        url: &str,
+        event_types: &[String],
+        secret: &str,
+    ) -> AnyResult<WebhookSubscription>;
+
+    // changes where a subscription's events go and which ones it gets. Its secret stays the same
+    fn update_webhook_subscription(
+        &mut self,
+        id: WebhookSubscriptionId,
+        url: &str,
+        event_types: &[String],
+        active: bool,
+    ) -> AnyResult<WebhookSubscription>;
+
+    // deletes a subscription along with its deliveries
+    fn delete_webhook_subscription(&mut self, id: WebhookSubscriptionId) -> AnyResult<()>;
+
+    // lists the deliveries of a subscription, newest first, optionally only those with `status`
+    fn list_webhook_deliveries(
+        &mut self,
+        subscription_id: WebhookSubscriptionId,
+        status: Option<DeliveryStatus>,
+        params: ListQueryParams,
+    ) -> AnyResult<Vec<WebhookDelivery>>;
+
+    // makes a delivery pending again with its attempts starting over, typically a dead one after
+    // its receiver was fixed
+    fn retry_webhook_delivery(
+        &mut self,
+        subscription_id: WebhookSubscriptionId,
+        delivery_id: i64,
+    ) -> AnyResult<WebhookDelivery>;
+
+    // adds a pending delivery of an event for every active subscription of the tenant that wants
+    // it. Enqueueing the same event again adds nothing. Returns the number of deliveries added
+    fn enqueue_webhook_deliveries(&mut self, event: &OutboxEvent) -> AnyResult<usize>;
+
+    // hands up to `limit` pending deliveries of every tenant that are due to `deliver` along with
+    // their subscription, and records how each attempt went. A failed delivery is attempted again
+    // as `retry_policy` says, and is dead once it runs out of attempts. Deliveries are held for
+    // `lease` while they are posted, and deliveries held by another worker are skipped. Returns the
+    // number of deliveries attempted
+    fn dispatch_due_webhook_deliveries(
+        &mut self,
+        limit: i64,
+        lease: Duration,
+        retry_policy: &RetryPolicy,
+        deliver: &mut dyn FnMut(&WebhookSubscription, &WebhookDelivery) -> DeliveryAttempt,
+    ) -> AnyResult<usize>;
+}
@@ -1 +1,2 @@
 pub mod database;
+pub mod rate_limit_store;
@@ -0,0 +1,15 @@
+use crate::core::entities::rate_limit::{RateLimit, RateLimitDecision};
+use anyhow::Result as AnyResult;
+use chrono::{DateTime, Utc};
+
+// where the token buckets of clients are kept. Stores are shared by all workers of the server, and
+// one shared by several servers makes a limit apply to all of them together
+pub trait RateLimitStore: Send + Sync {
+    // takes a token from the bucket kept under `key` for a request made at `now`. Keys that were
+    // not seen before, or not for as long as it takes their bucket to refill, start with a full one
+    fn take_token(&self, key: &str, limit: &RateLimit, now: DateTime<Utc>) -> AnyResult<RateLimitDecision>;
+
+    // the decision a request made at `now` would get from the bucket kept under `key`, without
+    // taking a token from it
+    fn peek_token(&self, key: &str, limit: &RateLimit, now: DateTime<Utc>) -> AnyResult<RateLimitDecision>;
+}
@@ -0,0 +1,136 @@
+use crate::core::entities::rate_limit::{RateLimit, RateLimitDecision, TokenBucket};
+use crate::core::ports::rate_limit_store::RateLimitStore;
+use anyhow::{anyhow, Result as AnyResult};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// the number of buckets after which full ones are dropped, so that clients that come once do not
+// add up
+const PRUNE_THRESHOLD: usize = 10_000;
+
+// how often full buckets are dropped at most. Dropping them goes through all buckets while every
+// request waits for the lock, so it is not done for each new client
+const PRUNE_INTERVAL_SECONDS: i64 = 60;
+
+#[derive(Debug, Default)]
+struct Buckets {
+    buckets: HashMap<String, (RateLimit, TokenBucket)>,
+    pruned_at: Option<DateTime<Utc>>,
+}
+
+impl Buckets {
+    fn prune(&mut self, now: DateTime<Utc>) {
+        let prune_due = self
+            .pruned_at
+            .is_none_or(|pruned_at| now - pruned_at >= Duration::seconds(PRUNE_INTERVAL_SECONDS));
+
+        if self.buckets.len() >= PRUNE_THRESHOLD && prune_due {
+            self.buckets.retain(|_, (limit, bucket)| !bucket.is_full_at(limit, now));
+            self.pruned_at = Some(now);
+        }
+    }
+}
+
+// keeps buckets in the memory of the server process, so limits apply to each server on its own and
+// start over when it restarts
+#[derive(Debug, Default)]
+pub struct InMemoryRateLimitStore {
+    buckets: Mutex<Buckets>,
+}
+
+impl InMemoryRateLimitStore {
+    pub fn new() -> InMemoryRateLimitStore {
+        InMemoryRateLimitStore::default()
+    }
+}
+
+impl RateLimitStore for InMemoryRateLimitStore {
+    fn take_token(&self, key: &str, limit: &RateLimit, now: DateTime<Utc>) -> AnyResult<RateLimitDecision> {
+        let mut buckets = self
+            .buckets
+            .lock()
+            .map_err(|_| anyhow!("The rate limit buckets were poisoned"))?;
+
+        if !buckets.buckets.contains_key(key) {
+            buckets.prune(now);
+        }
+
+        let (_, bucket) = buckets
+            .buckets
+            .entry(key.to_string())
+            .or_insert_with(|| (*limit, TokenBucket::full(limit, now)));
+
+        Ok(bucket.take(limit, now))
+    }
+
+    fn peek_token(&self, key: &str, limit: &RateLimit, now: DateTime<Utc>) -> AnyResult<RateLimitDecision> {
+        let buckets = self
+            .buckets
+            .lock()
+            .map_err(|_| anyhow!("The rate limit buckets were poisoned"))?;
+
+        let mut bucket = buckets
+            .buckets
+            .get(key)
+            .map(|(_, bucket)| *bucket)
+            .unwrap_or_else(|| TokenBucket::full(limit, now));
+
+        Ok(bucket.take(limit, now))
+    }
+}
+
+#[cfg(test)]
+mod in_memory_rate_limit_store_tests {
+    use crate::core::entities::rate_limit::RateLimit;
+    use crate::core::ports::rate_limit_store::RateLimitStore;
+    use crate::datastore::in_memory_rate_limit_store::{InMemoryRateLimitStore, PRUNE_THRESHOLD};
+    use chrono::{Duration, Utc};
+
+    #[test]
+    fn test_buckets_are_kept_per_key() {
+        let store = InMemoryRateLimitStore::new();
+        let limit = RateLimit::new(1, Duration::seconds(60));
+        let now = Utc::now();
+
+        let partner = store.take_token("reads api_key:partner", &limit, now).unwrap();
+        let partner_again = store.take_token("reads api_key:partner", &limit, now).unwrap();
+        let storefront = store.take_token("reads api_key:storefront", &limit, now).unwrap();
+
+        assert!(partner.allowed());
+        assert!(!partner_again.allowed());
+        assert!(storefront.allowed());
+    }
+
+    #[test]
+    fn test_full_buckets_are_dropped_at_most_once_an_interval() {
+        let store = InMemoryRateLimitStore::new();
+        let limit = RateLimit::new(1, Duration::seconds(1));
+        let now = Utc::now();
+
+        for client in 0..PRUNE_THRESHOLD {
+            store.take_token(&format!("reads ip:{}", client), &limit, now).unwrap();
+        }
+        let later = now + Duration::seconds(2);
+        store.take_token("reads ip:first", &limit, later).unwrap();
+        assert_eq!(1, store.buckets.lock().unwrap().buckets.len());
+
+        for client in 0..PRUNE_THRESHOLD {
+            store.take_token(&format!("reads ip:{}", client), &limit, later).unwrap();
+        }
+        let soon_after = later + Duration::seconds(2);
+        store.take_token("reads ip:second", &limit, soon_after).unwrap();
+        assert_eq!(PRUNE_THRESHOLD + 2, store.buckets.lock().unwrap().buckets.len());
+    }
+
+    #[test]
+    fn test_peeking_takes_no_token() {
+        let store = InMemoryRateLimitStore::new();
+        let limit = RateLimit::new(1, Duration::seconds(60));
+        let now = Utc::now();
+
+        assert!(store.peek_token("failed_authentications ip:10.0.0.7", &limit, now).unwrap().allowed());
+        assert!(store.take_token("failed_authentications ip:10.0.0.7", &limit, now).unwrap().allowed());
+        assert!(!store.peek_token("failed_authentications ip:10.0.0.7", &limit, now).unwrap().allowed());
+    }
+}
@@ -0,0 +1,145 @@
+use anyhow::{anyhow, Result as AnyResult};
+use diesel::migration::MigrationSource;
+use diesel::pg::Pg;
+use diesel::PgConnection;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+// the migrations in `migrations/`, compiled into the binary so that it can migrate a database
+// without the Diesel CLI
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
+// the database schema is not at the version this binary was built for. `pending` are migrations
+// the binary has that the database has not applied, `unknown` are migrations the database has
+// applied that the binary does not have, which happens when an older binary runs against a
+// database migrated by a newer one
+#[derive(Debug, PartialEq)]
+pub struct SchemaVersionError {
+    pub database_version: Option<String>,
+    pub expected_version: Option<String>,
+    pub pending: Vec<String>,
+    pub unknown: Vec<String>,
+}
+
+impl Display for SchemaVersionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Database schema is at version {} but this binary expects version {}",
+            self.database_version.as_deref().unwrap_or("none"),
+            self.expected_version.as_deref().unwrap_or("none"),
+        )?;
+
+        if !self.pending.is_empty() {
+            write!(f, "; {} pending migration(s): {}", self.pending.len(), self.pending.join(", "))?;
+        }
+
+        if !self.unknown.is_empty() {
+            write!(
+                f,
+                "; {} applied migration(s) unknown to this binary: {}",
+                self.unknown.len(),
+                self.unknown.join(", ")
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for SchemaVersionError {}
+
+// compares the migrations the binary expects with the ones applied to the database
+fn schema_version_mismatch(expected: &[String], applied: &[String]) -> Option<SchemaVersionError> {
+    let pending = expected
+        .iter()
+        .filter(|version| !applied.contains(version))
+        .cloned()
+        .collect::<Vec<_>>();
+    let unknown = applied
+        .iter()
+        .filter(|version| !expected.contains(version))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if pending.is_empty() && unknown.is_empty() {
+        return None;
+    }
+
+    Some(SchemaVersionError {
+        database_version: applied.iter().max().cloned(),
+        expected_version: expected.iter().max().cloned(),
+        pending,
+        unknown,
+    })
+}
+
+// runs every migration that has not been applied yet, returning the versions it applied
+pub fn run_pending_migrations(connection: &mut PgConnection) -> AnyResult<Vec<String>> {
+    let applied_versions = connection
+        .run_pending_migrations(MIGRATIONS)
+        .map_err(|error| anyhow!("Error running migrations: {}", error))?;
+
+    Ok(applied_versions.iter().map(|version| version.to_string()).collect())
+}
+
+// makes sure the database schema is at the version this binary was built for before anything
+// uses it. With `run_pending` set, pending migrations are applied first and their versions are
+// returned, otherwise they fail the check. Migrations the binary does not know about always fail
+// it, since they cannot be undone from here
+pub fn ensure_schema_version(connection: &mut PgConnection, run_pending: bool) -> AnyResult<Vec<String>> {
+    let expected = MigrationSource::<Pg>::migrations(&MIGRATIONS)
+        .map_err(|error| anyhow!("Error loading embedded migrations: {}", error))?
+        .iter()
+        .map(|migration| migration.name().version().to_string())
+        .collect::<Vec<_>>();
+    let applied = connection
+        .applied_migrations()
+        .map_err(|error| anyhow!("Error loading applied migrations: {}", error))?
+        .iter()
+        .map(|version| version.to_string())
+        .collect::<Vec<_>>();
+
+    match schema_version_mismatch(&expected, &applied) {
+        None => Ok(Vec::new()),
+        Some(mismatch) if run_pending && mismatch.unknown.is_empty() => run_pending_migrations(connection),
+        Some(mismatch) => Err(mismatch.into()),
+    }
+}
+
+#[cfg(test)]
+mod migrations_tests {
+    use crate::datastore::migrations::{schema_version_mismatch, SchemaVersionError};
+
+    fn versions(versions: &[&str]) -> Vec<String> {
+        versions.iter().map(|version| version.to_string()).collect()
+    }
+
+    #[test]
+    fn test_schema_version_mismatch() {
+        let expected = versions(&["20241121061319", "20261019080000", "20261019090000"]);
+
+        assert_eq!(None, schema_version_mismatch(&expected, &expected));
+
+        assert_eq!(
+            Some(SchemaVersionError {
+                database_version: Some("20261019080000".to_string()),
+                expected_version: Some("20261019090000".to_string()),
+                pending: versions(&["20261019090000"]),
+                unknown: Vec::new(),
+            }),
+            schema_version_mismatch(&expected, &expected[..2])
+        );
+
+        let ahead = versions(&["20241121061319", "20261019080000", "20261019090000", "20261101000000"]);
+        let mismatch = schema_version_mismatch(&expected, &ahead).unwrap();
+
+        assert_eq!(versions(&["20261101000000"]), mismatch.unknown);
+        assert_eq!(
+            "Database schema is at version 20261101000000 but this binary expects version 20261019090000; \
+             1 applied migration(s) unknown to this binary: 20261101000000",
+            mismatch.to_string()
+        );
+    }
+}
@@ -1,2 +1,5 @@
+pub mod in_memory_rate_limit_store;
+pub mod migrations;
 pub mod models;
 pub mod repositories;
+pub mod tenancy;
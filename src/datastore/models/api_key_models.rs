@@ -0,0 +1,27 @@
+use crate::datastore::models::schema::api_keys as ApiKeysTable;
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use serde::Serialize;
+
+// the key hash is never read back, which also keeps it out of the audit log
+#[derive(Debug, Selectable, Queryable, Serialize)]
+#[diesel(table_name = ApiKeysTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ApiKeyModel {
+    pub id: i32,
+    pub name: String,
+    pub tenant_id: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = ApiKeysTable)]
+pub struct NewApiKeyModel<'a> {
+    pub name: &'a str,
+    pub key_hash: &'a str,
+    pub scopes: &'a [String],
+    pub expires_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,27 @@
+use crate::datastore::models::schema::audit_logs as AuditLogsTable;
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use serde_json::Value;
+
+#[derive(Debug, Selectable, Queryable)]
+#[diesel(table_name = AuditLogsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AuditLogModel {
+    pub id: i32,
+    pub actor: String,
+    pub occurred_at: DateTime<Utc>,
+    pub entity_type: String,
+    pub entity_id: i32,
+    pub action: String,
+    pub changes: Value,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = AuditLogsTable)]
+pub struct NewAuditLogModel<'a> {
+    pub actor: &'a str,
+    pub entity_type: &'a str,
+    pub entity_id: i32,
+    pub action: &'a str,
+    pub changes: Value,
+}
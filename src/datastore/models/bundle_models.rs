@@ -0,0 +1,24 @@
+use crate::datastore::models::schema::{
+    bundle_components as BundleComponentsTable, product_bundles as ProductBundlesTable,
+};
+use diesel::{Insertable, Queryable, Selectable};
+use serde::Serialize;
+
+#[derive(Debug, Selectable, Queryable, Insertable, Serialize)]
+#[diesel(table_name = ProductBundlesTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ProductBundleModel {
+    pub product_id: i32,
+    pub pricing: String,
+    pub discount_percent: Option<f64>,
+    pub on_component_deactivated: String,
+}
+
+#[derive(Debug, Selectable, Queryable, Insertable, Serialize)]
+#[diesel(table_name = BundleComponentsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct BundleComponentModel {
+    pub bundle_id: i32,
+    pub component_id: i32,
+    pub quantity: i32,
+}
@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use diesel::sql_types::{Bool, Float8, Int4, Int8, Jsonb, Nullable, Timestamptz, Uuid as SqlUuid, Varchar};
+use diesel::QueryableByName;
+use serde_json::Value;
+use uuid::Uuid;
+
+// a row of the changes feed query, with the product as it is now. The product's fields are null
+// when it was deleted
+#[derive(Debug, QueryableByName)]
+pub struct ProductChangeModel {
+    #[diesel(sql_type = Int8)]
+    pub transaction_id: i64,
+    #[diesel(sql_type = Int8)]
+    pub sequence: i64,
+    #[diesel(sql_type = Int4)]
+    pub product_id: i32,
+    #[diesel(sql_type = SqlUuid)]
+    pub public_id: Uuid,
+    #[diesel(sql_type = Timestamptz)]
+    pub changed_at: DateTime<Utc>,
+    #[diesel(sql_type = Nullable<Varchar>)]
+    pub name: Option<String>,
+    #[diesel(sql_type = Nullable<Float8>)]
+    pub cost: Option<f64>,
+    #[diesel(sql_type = Nullable<Bool>)]
+    pub active: Option<bool>,
+    #[diesel(sql_type = Nullable<Int4>)]
+    pub version: Option<i32>,
+    #[diesel(sql_type = Nullable<Varchar>)]
+    pub external_key: Option<String>,
+    #[diesel(sql_type = Nullable<Varchar>)]
+    pub slug: Option<String>,
+    #[diesel(sql_type = Jsonb)]
+    pub variants: Value,
+}
@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use diesel::sql_types::{Array, Bool, Float8, Int4, Jsonb, Nullable, Text, Timestamptz, Uuid as SqlUuid, Varchar};
+use diesel::QueryableByName;
+use serde_json::Value;
+use uuid::Uuid;
+
+// a product row of the export query, with its variants and images aggregated into the row so that
+// the whole export can be streamed from a single query
+#[derive(Debug, QueryableByName)]
+pub struct ExportedProductModel {
+    #[diesel(sql_type = Int4)]
+    pub id: i32,
+    #[diesel(sql_type = Varchar)]
+    pub name: String,
+    #[diesel(sql_type = Float8)]
+    pub cost: f64,
+    #[diesel(sql_type = Bool)]
+    pub active: bool,
+    #[diesel(sql_type = Int4)]
+    pub version: i32,
+    #[diesel(sql_type = Nullable<Varchar>)]
+    pub external_key: Option<String>,
+    #[diesel(sql_type = SqlUuid)]
+    pub public_id: Uuid,
+    #[diesel(sql_type = Varchar)]
+    pub slug: String,
+    #[diesel(sql_type = Timestamptz)]
+    pub updated_at: DateTime<Utc>,
+    #[diesel(sql_type = Jsonb)]
+    pub variants: Value,
+    #[diesel(sql_type = Array<Text>)]
+    pub image_urls: Vec<String>,
+    #[diesel(sql_type = Nullable<Int4>)]
+    pub available_quantity: Option<i32>,
+}
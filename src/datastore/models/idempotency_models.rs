@@ -0,0 +1,25 @@
+use crate::datastore::models::schema::idempotency_keys as IdempotencyKeysTable;
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use serde_json::Value;
+
+#[derive(Debug, Selectable, Queryable)]
+#[diesel(table_name = IdempotencyKeysTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct IdempotencyKeyModel {
+    pub key: String,
+    pub fingerprint: String,
+    pub response_status: Option<i32>,
+    pub response_headers: Option<Value>,
+    pub response_body: Option<Value>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = IdempotencyKeysTable)]
+pub struct NewIdempotencyKeyModel<'a> {
+    pub principal: &'a str,
+    pub key: &'a str,
+    pub fingerprint: &'a str,
+    pub expires_at: DateTime<Utc>,
+}
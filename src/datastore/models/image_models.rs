@@ -0,0 +1,12 @@
+use crate::datastore::models::schema::product_images as ProductImagesTable;
+use diesel::{Insertable, Queryable, Selectable};
+use serde::Serialize;
+
+#[derive(Debug, Selectable, Queryable, Insertable, Serialize)]
+#[diesel(table_name = ProductImagesTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ProductImageModel {
+    pub product_id: i32,
+    pub url: String,
+    pub position: i32,
+}
@@ -1,3 +1,18 @@
+pub(crate) mod api_key_models;
+pub(crate) mod audit_models;
+pub(crate) mod bundle_models;
+pub(crate) mod change_models;
+pub(crate) mod export_models;
+pub(crate) mod idempotency_models;
+pub(crate) mod image_models;
+pub(crate) mod outbox_models;
 pub(crate) mod product_models;
+pub(crate) mod product_type_models;
+pub(crate) mod relation_models;
+pub(crate) mod revision_models;
+pub(crate) mod role_models;
 pub mod schema;
+pub(crate) mod specification_models;
+pub(crate) mod translation_models;
 pub mod variant_models;
+pub(crate) mod webhook_models;
@@ -0,0 +1,27 @@
+use crate::datastore::models::schema::outbox_events as OutboxEventsTable;
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use serde_json::Value;
+use uuid::Uuid;
+
+#[derive(Debug, Selectable, Queryable)]
+#[diesel(table_name = OutboxEventsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct OutboxEventModel {
+    pub id: i64,
+    pub event_id: Uuid,
+    pub tenant_id: String,
+    pub payload: Value,
+    pub occurred_at: DateTime<Utc>,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = OutboxEventsTable)]
+pub struct NewOutboxEventModel<'a> {
+    pub event_id: Uuid,
+    pub event_type: &'a str,
+    pub product_id: i32,
+    pub payload: Value,
+}
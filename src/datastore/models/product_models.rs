@@ -1,8 +1,10 @@
 use crate::datastore::models::schema::products as ProductsTable;
-use diesel::{Insertable, Queryable, Selectable};
+use diesel::{Identifiable, Insertable, Queryable, Selectable};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
 
-#[derive(Debug, Selectable, Queryable, Serialize, Deserialize)]
+#[derive(Debug, Selectable, Queryable, Identifiable, Serialize, Deserialize)]
 #[diesel(table_name = ProductsTable)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct ProductModel {
@@ -10,6 +12,14 @@ pub struct ProductModel {
     pub name: String,
     pub cost: f64,
     pub active: bool,
+    pub version: i32,
+    pub external_key: Option<String>,
+    pub public_id: Uuid,
+    pub slug: String,
+    pub description: Option<String>,
+    pub product_type_id: Option<i32>,
+    pub attributes: Value,
+    pub stock_quantity: Option<i32>,
 }
 
 #[derive(Insertable, Debug)]
@@ -18,4 +28,11 @@ pub struct NewProductModel<'a> {
     pub name: &'a String,
     pub cost: &'a f64,
     pub active: &'a bool,
+    pub external_key: Option<&'a String>,
+    pub public_id: Uuid,
+    pub slug: &'a str,
+    pub description: Option<&'a str>,
+    pub product_type_id: Option<i32>,
+    pub attributes: &'a Value,
+    pub stock_quantity: Option<i32>,
 }
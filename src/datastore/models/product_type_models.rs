@@ -0,0 +1,22 @@
+use crate::datastore::models::schema::product_types as ProductTypesTable;
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Debug, Selectable, Queryable, Serialize)]
+#[diesel(table_name = ProductTypesTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ProductTypeModel {
+    pub id: i32,
+    pub name: String,
+    pub attribute_schema: Value,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = ProductTypesTable)]
+pub struct NewProductTypeModel<'a> {
+    pub name: &'a str,
+    pub attribute_schema: Value,
+}
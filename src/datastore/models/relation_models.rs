@@ -0,0 +1,13 @@
+use crate::datastore::models::schema::product_relations as ProductRelationsTable;
+use diesel::{Insertable, Queryable, Selectable};
+use serde::Serialize;
+
+#[derive(Debug, Selectable, Queryable, Insertable, Serialize)]
+#[diesel(table_name = ProductRelationsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ProductRelationModel {
+    pub product_id: i32,
+    pub relation_type: String,
+    pub related_product_id: i32,
+    pub position: i32,
+}
@@ -0,0 +1,39 @@
+use crate::datastore::models::schema::product_revisions as ProductRevisionsTable;
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Selectable, Queryable)]
+#[diesel(table_name = ProductRevisionsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ProductRevisionModel {
+    pub product_id: i32,
+    pub revision: i32,
+    pub name: String,
+    pub cost: f64,
+    pub active: bool,
+    pub variants: Value,
+    pub actor: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = ProductRevisionsTable)]
+pub struct NewProductRevisionModel<'a> {
+    pub product_id: i32,
+    pub revision: i32,
+    pub name: &'a str,
+    pub cost: f64,
+    pub active: bool,
+    pub variants: Value,
+    pub actor: &'a str,
+}
+
+// the shape of each entry in the `variants` column of a product revision
+#[derive(Debug, Queryable, Serialize, Deserialize)]
+pub struct VariantSnapshotModel {
+    pub variant_id: i32,
+    pub name: String,
+    pub value: Option<String>,
+}
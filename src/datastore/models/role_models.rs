@@ -0,0 +1,21 @@
+use crate::datastore::models::schema::roles as RolesTable;
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use serde::Serialize;
+
+#[derive(Debug, Selectable, Queryable, Serialize)]
+#[diesel(table_name = RolesTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct RoleModel {
+    pub id: i32,
+    pub name: String,
+    pub permissions: Vec<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = RolesTable)]
+pub struct NewRoleModel<'a> {
+    pub name: &'a str,
+    pub permissions: &'a [String],
+}
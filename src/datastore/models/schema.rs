@@ -1,11 +1,173 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    api_keys (id) {
+        id -> Int4,
+        name -> Varchar,
+        key_hash -> Varchar,
+        scopes -> Array<Text>,
+        expires_at -> Nullable<Timestamptz>,
+        revoked_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        tenant_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    audit_logs (id) {
+        id -> Int4,
+        actor -> Varchar,
+        occurred_at -> Timestamptz,
+        entity_type -> Varchar,
+        entity_id -> Int4,
+        action -> Varchar,
+        changes -> Jsonb,
+        tenant_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    bundle_components (bundle_id, component_id) {
+        bundle_id -> Int4,
+        component_id -> Int4,
+        quantity -> Int4,
+        tenant_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    idempotency_keys (tenant_id, principal, key) {
+        key -> Varchar,
+        fingerprint -> Varchar,
+        response_status -> Nullable<Int4>,
+        response_headers -> Nullable<Jsonb>,
+        response_body -> Nullable<Jsonb>,
+        created_at -> Timestamptz,
+        expires_at -> Timestamptz,
+        tenant_id -> Varchar,
+        principal -> Varchar,
+    }
+}
+
+diesel::table! {
+    outbox_events (id) {
+        id -> Int8,
+        event_id -> Uuid,
+        tenant_id -> Varchar,
+        event_type -> Varchar,
+        product_id -> Int4,
+        payload -> Jsonb,
+        occurred_at -> Timestamptz,
+        attempts -> Int4,
+        last_error -> Nullable<Text>,
+        delivered_at -> Nullable<Timestamptz>,
+        next_attempt_at -> Timestamptz,
+        dead_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    product_bundles (product_id) {
+        product_id -> Int4,
+        pricing -> Varchar,
+        discount_percent -> Nullable<Float8>,
+        on_component_deactivated -> Varchar,
+        tenant_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    product_changes (product_id) {
+        product_id -> Int4,
+        public_id -> Uuid,
+        sequence -> Int8,
+        transaction_id -> Int8,
+        changed_at -> Timestamptz,
+        tenant_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    product_images (id) {
+        id -> Int4,
+        product_id -> Int4,
+        url -> Varchar,
+        position -> Int4,
+        tenant_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    product_relations (product_id, relation_type, related_product_id) {
+        product_id -> Int4,
+        relation_type -> Varchar,
+        related_product_id -> Int4,
+        position -> Int4,
+        tenant_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    product_revisions (id) {
+        id -> Int4,
+        product_id -> Int4,
+        revision -> Int4,
+        name -> Varchar,
+        cost -> Float8,
+        active -> Bool,
+        variants -> Jsonb,
+        actor -> Varchar,
+        created_at -> Timestamptz,
+        tenant_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    product_slug_redirects (tenant_id, slug) {
+        slug -> Varchar,
+        product_id -> Int4,
+        created_at -> Timestamptz,
+        tenant_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    product_specifications (product_id, position) {
+        product_id -> Int4,
+        position -> Int4,
+        name -> Varchar,
+        value -> Varchar,
+        tenant_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    product_translations (product_id, locale) {
+        product_id -> Int4,
+        locale -> Varchar,
+        name -> Varchar,
+        description -> Nullable<Text>,
+        tenant_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    product_types (id) {
+        id -> Int4,
+        name -> Varchar,
+        attribute_schema -> Jsonb,
+        updated_at -> Timestamptz,
+        tenant_id -> Varchar,
+    }
+}
+
 diesel::table! {
     product_variants (id) {
         id -> Int4,
         variant_id -> Int4,
         product_id -> Int4,
         value -> Nullable<Varchar>,
+        tenant_id -> Varchar,
     }
 }
 
@@ -15,6 +177,45 @@ diesel::table! {
         name -> Varchar,
         cost -> Float8,
         active -> Bool,
+        version -> Int4,
+        external_key -> Nullable<Varchar>,
+        updated_at -> Timestamptz,
+        public_id -> Uuid,
+        slug -> Varchar,
+        description -> Nullable<Text>,
+        product_type_id -> Nullable<Int4>,
+        attributes -> Jsonb,
+        stock_quantity -> Nullable<Int4>,
+        tenant_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    roles (id) {
+        id -> Int4,
+        name -> Varchar,
+        permissions -> Array<Text>,
+        updated_at -> Timestamptz,
+        tenant_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    variant_translations (variant_id, locale) {
+        variant_id -> Int4,
+        locale -> Varchar,
+        display_name -> Varchar,
+        tenant_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    variant_value_translations (variant_id, value, locale) {
+        variant_id -> Int4,
+        value -> Varchar,
+        locale -> Varchar,
+        display_value -> Varchar,
+        tenant_id -> Varchar,
     }
 }
 
@@ -22,10 +223,77 @@ diesel::table! {
     variants (id) {
         id -> Int4,
         name -> Varchar,
+        tenant_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    webhook_deliveries (id) {
+        id -> Int8,
+        tenant_id -> Varchar,
+        subscription_id -> Int4,
+        event_id -> Uuid,
+        event_type -> Varchar,
+        payload -> Jsonb,
+        status -> Varchar,
+        attempts -> Int4,
+        next_attempt_at -> Timestamptz,
+        last_attempt_at -> Nullable<Timestamptz>,
+        last_response_status -> Nullable<Int4>,
+        last_error -> Nullable<Text>,
+        delivered_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    webhook_subscriptions (id) {
+        id -> Int4,
+        url -> Varchar,
+        event_types -> Array<Text>,
+        secret -> Varchar,
+        active -> Bool,
+        created_at -> Timestamptz,
+        tenant_id -> Varchar,
     }
 }
 
+diesel::joinable!(bundle_components -> product_bundles (bundle_id));
+diesel::joinable!(bundle_components -> products (component_id));
+diesel::joinable!(product_bundles -> products (product_id));
+diesel::joinable!(product_images -> products (product_id));
+diesel::joinable!(product_relations -> products (related_product_id));
+diesel::joinable!(product_slug_redirects -> products (product_id));
+diesel::joinable!(product_specifications -> products (product_id));
+diesel::joinable!(product_translations -> products (product_id));
 diesel::joinable!(product_variants -> products (product_id));
 diesel::joinable!(product_variants -> variants (variant_id));
+diesel::joinable!(products -> product_types (product_type_id));
+diesel::joinable!(variant_translations -> variants (variant_id));
+diesel::joinable!(variant_value_translations -> variants (variant_id));
+diesel::joinable!(webhook_deliveries -> webhook_subscriptions (subscription_id));
 
-diesel::allow_tables_to_appear_in_same_query!(product_variants, products, variants,);
+diesel::allow_tables_to_appear_in_same_query!(
+    api_keys,
+    audit_logs,
+    bundle_components,
+    idempotency_keys,
+    outbox_events,
+    product_bundles,
+    product_changes,
+    product_images,
+    product_relations,
+    product_revisions,
+    product_slug_redirects,
+    product_specifications,
+    product_translations,
+    product_types,
+    product_variants,
+    products,
+    roles,
+    variant_translations,
+    variant_value_translations,
+    variants,
+    webhook_deliveries,
+    webhook_subscriptions,
+);
@@ -0,0 +1,13 @@
+use crate::datastore::models::schema::product_specifications as ProductSpecificationsTable;
+use diesel::{Insertable, Queryable, Selectable};
+use serde::Serialize;
+
+#[derive(Debug, Selectable, Queryable, Insertable, Serialize)]
+#[diesel(table_name = ProductSpecificationsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ProductSpecificationModel {
+    pub product_id: i32,
+    pub position: i32,
+    pub name: String,
+    pub value: String,
+}
@@ -0,0 +1,35 @@
+use crate::datastore::models::schema::{
+    product_translations as ProductTranslationsTable, variant_translations as VariantTranslationsTable,
+    variant_value_translations as VariantValueTranslationsTable,
+};
+use diesel::{Insertable, Queryable, Selectable};
+use serde::Serialize;
+
+#[derive(Debug, Selectable, Queryable, Insertable, Serialize)]
+#[diesel(table_name = ProductTranslationsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ProductTranslationModel {
+    pub product_id: i32,
+    pub locale: String,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Selectable, Queryable, Insertable, Serialize)]
+#[diesel(table_name = VariantTranslationsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct VariantTranslationModel {
+    pub variant_id: i32,
+    pub locale: String,
+    pub display_name: String,
+}
+
+#[derive(Debug, Selectable, Queryable, Insertable, Serialize)]
+#[diesel(table_name = VariantValueTranslationsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct VariantValueTranslationModel {
+    pub variant_id: i32,
+    pub value: String,
+    pub locale: String,
+    pub display_value: String,
+}
@@ -1,5 +1,6 @@
+use crate::datastore::models::product_models::ProductModel;
 use crate::datastore::models::schema::{
-    product_variants as ProductVariantsTable, products, variants as VariantsTable,
+    product_variants as ProductVariantsTable, variants as VariantsTable,
 };
 use diesel::{Associations, Identifiable, Insertable, Queryable, Selectable};
 use serde::{Deserialize, Serialize};
@@ -19,12 +20,22 @@ pub struct NewVariantModel {
     pub name: String,
 }
 
-#[derive(Debug, Insertable, Associations)]
-#[diesel(belongs_to(products))]
+#[derive(Debug, Selectable, Queryable, Identifiable, Associations, Serialize, Deserialize)]
+#[diesel(belongs_to(ProductModel, foreign_key = product_id))]
 #[diesel(table_name = ProductVariantsTable)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct ProductVariantModel {
+    pub id: i32,
+    pub variant_id: i32,
     pub product_id: i32,
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = ProductVariantsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewProductVariantModel {
     pub variant_id: i32,
+    pub product_id: i32,
     pub value: Option<String>,
 }
@@ -0,0 +1,59 @@
+use crate::datastore::models::schema::{
+    webhook_deliveries as WebhookDeliveriesTable, webhook_subscriptions as WebhookSubscriptionsTable,
+};
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use serde::Serialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+#[derive(Debug, Selectable, Queryable, Serialize)]
+#[diesel(table_name = WebhookSubscriptionsTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WebhookSubscriptionModel {
+    pub id: i32,
+    pub url: String,
+    pub event_types: Vec<String>,
+    // kept out of the audit log
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = WebhookSubscriptionsTable)]
+pub struct NewWebhookSubscriptionModel<'a> {
+    pub url: &'a str,
+    pub event_types: &'a [String],
+    pub secret: &'a str,
+}
+
+#[derive(Debug, Selectable, Queryable)]
+#[diesel(table_name = WebhookDeliveriesTable)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WebhookDeliveryModel {
+    pub id: i64,
+    pub tenant_id: String,
+    pub subscription_id: i32,
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub payload: Value,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub last_response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = WebhookDeliveriesTable)]
+pub struct NewWebhookDeliveryModel<'a> {
+    pub subscription_id: i32,
+    pub event_id: Uuid,
+    pub event_type: &'a str,
+    pub payload: &'a Value,
+}
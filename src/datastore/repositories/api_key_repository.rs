@@ -0,0 +1,190 @@
+use crate::core::entities::api_key::ApiKey;
+use crate::core::entities::audit_record::{AuditAction, AuditEntityType, SYSTEM_ACTOR};
+use crate::core::ports::database::api_key_database::ApiKeyDatastore;
+use crate::core::ports::database::errors::DatastoreError;
+use crate::datastore::models::api_key_models::{ApiKeyModel, NewApiKeyModel};
+use crate::datastore::models::schema::api_keys;
+use crate::datastore::repositories::audit_repository::append_audit_record;
+use crate::datastore::repositories::mappers::map_api_key_model_to_api_key;
+use anyhow::Result as AnyResult;
+use chrono::{DateTime, Utc};
+use diesel::dsl::now;
+use diesel::{Connection, ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper};
+
+pub struct ApiKeyRepository<'a> {
+    connection: &'a mut PgConnection,
+    actor: String,
+}
+
+impl<'a> ApiKeyRepository<'a> {
+    pub fn new(connection: &'a mut PgConnection) -> ApiKeyRepository<'a> {
+        ApiKeyRepository {
+            connection,
+            actor: SYSTEM_ACTOR.to_string(),
+        }
+    }
+
+    // sets the actor that is recorded in the audit log for writes made through this repository
+    pub fn with_actor(mut self, actor: impl Into<String>) -> ApiKeyRepository<'a> {
+        self.actor = actor.into();
+        self
+    }
+}
+
+impl ApiKeyDatastore for ApiKeyRepository<'_> {
+    fn create_api_key(
+        &mut self,
+        name: &str,
+        key_hash: &str,
+        scopes: &[String],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> AnyResult<ApiKey> {
+        let actor = self.actor.as_str();
+
+        let created_api_key = self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            let taken = api_keys::table
+                .filter(api_keys::name.eq(name))
+                .select(api_keys::id)
+                .first::<i32>(connection)
+                .optional()?;
+            if taken.is_some() {
+                return Err(DatastoreError::AlreadyExists {
+                    entity: "API key",
+                    reference: name.to_string(),
+                }
+                .into());
+            }
+
+            let created_api_key = diesel::insert_into(api_keys::table)
+                .values(NewApiKeyModel {
+                    name,
+                    key_hash,
+                    scopes,
+                    expires_at,
+                })
+                .returning(ApiKeyModel::as_returning())
+                .get_result(connection)?;
+
+            append_audit_record(
+                connection,
+                actor,
+                AuditEntityType::ApiKey,
+                created_api_key.id,
+                AuditAction::Created,
+                None,
+                Some(&serde_json::to_value(&created_api_key)?),
+            )?;
+
+            Ok(created_api_key)
+        })?;
+
+        map_api_key_model_to_api_key(created_api_key)
+    }
+
+    fn find_api_key(&mut self, key_hash: &str) -> AnyResult<Option<ApiKey>> {
+        api_keys::table
+            .filter(api_keys::key_hash.eq(key_hash))
+            .select(ApiKeyModel::as_select())
+            .first::<ApiKeyModel>(self.connection)
+            .optional()?
+            .map(map_api_key_model_to_api_key)
+            .transpose()
+    }
+
+    fn list_api_keys(&mut self) -> AnyResult<Vec<ApiKey>> {
+        api_keys::table
+            .order(api_keys::name.asc())
+            .select(ApiKeyModel::as_select())
+            .load::<ApiKeyModel>(self.connection)?
+            .into_iter()
+            .map(map_api_key_model_to_api_key)
+            .collect()
+    }
+
+    fn revoke_api_key(&mut self, name: &str) -> AnyResult<ApiKey> {
+        let actor = self.actor.as_str();
+
+        let revoked_api_key = self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            let existing_api_key = api_keys::table
+                .filter(api_keys::name.eq(name))
+                .select(ApiKeyModel::as_select())
+                .for_update()
+                .first::<ApiKeyModel>(connection)
+                .optional()?
+                .ok_or_else(|| DatastoreError::UnknownReference {
+                    entity: "API key",
+                    reference: name.to_string(),
+                })?;
+            if existing_api_key.revoked_at.is_some() {
+                return Ok(existing_api_key);
+            }
+
+            let revoked_api_key = diesel::update(api_keys::table.find(existing_api_key.id))
+                .set(api_keys::revoked_at.eq(now))
+                .returning(ApiKeyModel::as_returning())
+                .get_result(connection)?;
+
+            append_audit_record(
+                connection,
+                actor,
+                AuditEntityType::ApiKey,
+                revoked_api_key.id,
+                AuditAction::Updated,
+                Some(&serde_json::to_value(&existing_api_key)?),
+                Some(&serde_json::to_value(&revoked_api_key)?),
+            )?;
+
+            Ok(revoked_api_key)
+        })?;
+
+        map_api_key_model_to_api_key(revoked_api_key)
+    }
+}
+
+#[cfg(test)]
+mod api_key_repository_tests {
+    use crate::core::entities::api_key::{generate_api_key, hash_api_key};
+    use crate::core::ports::database::api_key_database::ApiKeyDatastore;
+    use crate::core::ports::database::errors::DatastoreError;
+    use crate::datastore::repositories::api_key_repository::ApiKeyRepository;
+    use crate::establish_connection_test;
+    use chrono::Utc;
+    use diesel::Connection;
+
+    #[test]
+    fn test_api_keys_are_found_by_hash_until_revoked() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut repository = ApiKeyRepository::new(conn);
+            let key = generate_api_key();
+            let scopes = vec!["catalog:write".to_string()];
+
+            let created_api_key = repository
+                .create_api_key("storefront", &hash_api_key(&key), &scopes, None)
+                .expect("Error creating API key");
+            let error = repository
+                .create_api_key("storefront", &hash_api_key(&generate_api_key()), &[], None)
+                .expect_err("Two API keys were given the same name");
+            assert!(matches!(
+                error.downcast_ref::<DatastoreError>(),
+                Some(DatastoreError::AlreadyExists { .. })
+            ));
+
+            let found_api_key = repository
+                .find_api_key(&hash_api_key(&key))
+                .expect("Error finding API key")
+                .expect("API key was not found by its hash");
+            assert_eq!(created_api_key, found_api_key);
+            assert_eq!(scopes, found_api_key.scopes());
+            assert!(found_api_key.is_usable_at(Utc::now()));
+            assert_eq!(None, repository.find_api_key(&hash_api_key(&generate_api_key())).unwrap());
+
+            repository.revoke_api_key("storefront").expect("Error revoking API key");
+            let revoked_api_key = repository.find_api_key(&hash_api_key(&key)).unwrap().unwrap();
+            assert!(!revoked_api_key.is_usable_at(Utc::now()));
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,71 @@
+use crate::core::entities::audit_record::{AuditAction, AuditEntityType, AuditRecord};
+use crate::core::ports::database::audit_database::AuditDatastore;
+use crate::datastore::models::audit_models::{AuditLogModel, NewAuditLogModel};
+use crate::datastore::models::schema::audit_logs::dsl::{
+    audit_logs, entity_id as audit_log_entity_id, entity_type as audit_log_entity_type,
+    id as audit_log_id, tenant_id as audit_log_tenant_id,
+};
+use crate::datastore::repositories::mappers::map_audit_log_model_to_audit_record;
+use crate::datastore::tenancy::current_tenant_id;
+use anyhow::Result as AnyResult;
+use diesel::result::Error as DieselError;
+use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper};
+use serde_json::Value;
+
+pub struct AuditRepository<'a> {
+    connection: &'a mut PgConnection,
+}
+
+impl<'a> AuditRepository<'a> {
+    pub fn new(connection: &'a mut PgConnection) -> AuditRepository<'a> {
+        AuditRepository { connection }
+    }
+}
+
+// appends an audit record for a change to an entity. This is expected to be called with the same
+// connection as the write being audited, inside its transaction, so that both commit or roll back
+// together
+pub(crate) fn append_audit_record(
+    connection: &mut PgConnection,
+    actor: &str,
+    entity_type: AuditEntityType,
+    entity_id: i32,
+    action: AuditAction,
+    before: Option<&Value>,
+    after: Option<&Value>,
+) -> Result<(), DieselError> {
+    let new_audit_log = NewAuditLogModel {
+        actor,
+        entity_type: entity_type.as_str(),
+        entity_id,
+        action: action.as_str(),
+        changes: AuditRecord::diff(before, after),
+    };
+
+    diesel::insert_into(audit_logs)
+        .values(new_audit_log)
+        .execute(connection)?;
+
+    Ok(())
+}
+
+impl AuditDatastore for AuditRepository<'_> {
+    fn get_entity_history(
+        &mut self,
+        entity_type: AuditEntityType,
+        entity_id: i32,
+    ) -> AnyResult<Vec<AuditRecord>> {
+        let records = audit_logs
+            .filter(audit_log_tenant_id.eq(current_tenant_id()))
+            .filter(audit_log_entity_type.eq(entity_type.as_str()))
+            .filter(audit_log_entity_id.eq(entity_id))
+            .order(audit_log_id.asc())
+            .select(AuditLogModel::as_select())
+            .load::<AuditLogModel>(self.connection)?;
+
+        records
+            .into_iter()
+            .map(map_audit_log_model_to_audit_record)
+            .collect()
+    }
+}
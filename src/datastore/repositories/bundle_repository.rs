@@ -0,0 +1,436 @@
+use crate::core::entities::audit_record::{AuditAction, AuditEntityType, SYSTEM_ACTOR};
+use crate::core::entities::bundle::{Bundle, BundleComponent, BundleDefinition, BundlePricing, ComponentDeactivation};
+use crate::core::entities::ids::ProductId;
+use crate::core::entities::product::Product;
+use crate::core::ports::database::bundle_database::BundleDatastore;
+use crate::core::ports::database::errors::DatastoreError;
+use crate::datastore::models::bundle_models::{BundleComponentModel, ProductBundleModel};
+use crate::datastore::models::product_models::ProductModel;
+use crate::datastore::models::schema::{bundle_components, product_bundles, products};
+use crate::datastore::repositories::audit_repository::append_audit_record;
+use crate::datastore::repositories::change_repository::record_product_change;
+use crate::datastore::repositories::mappers::{
+    map_product_bundle_model_to_bundle_settings, map_product_model_to_product,
+};
+use crate::datastore::repositories::outbox_repository::append_product_events;
+use crate::datastore::repositories::revision_repository::record_product_revision;
+use crate::datastore::repositories::translation_repository::touch_product;
+use crate::datastore::tenancy::current_tenant_id;
+use anyhow::Result as AnyResult;
+use diesel::dsl::now;
+use diesel::{Connection, ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper};
+use std::collections::HashMap;
+
+pub struct BundleRepository<'a> {
+    connection: &'a mut PgConnection,
+    actor: String,
+}
+
+impl<'a> BundleRepository<'a> {
+    pub fn new(connection: &'a mut PgConnection) -> BundleRepository<'a> {
+        BundleRepository {
+            connection,
+            actor: SYSTEM_ACTOR.to_string(),
+        }
+    }
+
+    // sets the actor that is recorded in the audit log for writes made through this repository
+    pub fn with_actor(mut self, actor: impl Into<String>) -> BundleRepository<'a> {
+        self.actor = actor.into();
+        self
+    }
+}
+
+fn fetch_product(connection: &mut PgConnection, id: i32) -> AnyResult<Product> {
+    let existing_product = products::table
+        .find(id)
+        .filter(products::tenant_id.eq(current_tenant_id()))
+        .select(ProductModel::as_select())
+        .first::<ProductModel>(connection)
+        .optional()?
+        .ok_or(DatastoreError::NotFound { entity: "Product", id })?;
+
+    map_product_model_to_product(existing_product)
+}
+
+// how a bundle is priced along with its components, as they are stored
+type BundleDefinitionModels = (ProductBundleModel, Vec<BundleComponentModel>);
+
+fn fetch_definition(connection: &mut PgConnection, id: i32) -> AnyResult<Option<BundleDefinitionModels>> {
+    let Some(product_bundle) = product_bundles::table
+        .find(id)
+        .select(ProductBundleModel::as_select())
+        .first::<ProductBundleModel>(connection)
+        .optional()?
+    else {
+        return Ok(None);
+    };
+
+    let components = bundle_components::table
+        .filter(bundle_components::bundle_id.eq(id))
+        .order(bundle_components::component_id.asc())
+        .select(BundleComponentModel::as_select())
+        .load::<BundleComponentModel>(connection)?;
+
+    Ok(Some((product_bundle, components)))
+}
+
+// the bundle a product is, with its components as they are stored now. `None` when the product
+// is not a bundle
+pub(crate) fn fetch_bundle(
+    connection: &mut PgConnection,
+    id: ProductId,
+    bundle_product: &Product,
+) -> AnyResult<Option<Bundle>> {
+    let Some((product_bundle, component_models)) = fetch_definition(connection, i32::from(id))? else {
+        return Ok(None);
+    };
+    let (pricing, on_component_deactivated) = map_product_bundle_model_to_bundle_settings(&product_bundle)?;
+
+    let component_products = products::table
+        .filter(products::id.eq_any(component_models.iter().map(|component| component.component_id)))
+        .select(ProductModel::as_select())
+        .load::<ProductModel>(connection)?
+        .into_iter()
+        .map(|product| Ok((product.id, map_product_model_to_product(product)?)))
+        .collect::<AnyResult<HashMap<i32, Product>>>()?;
+
+    let components = component_models
+        .into_iter()
+        .map(|component| {
+            let component_product = component_products
+                .get(&component.component_id)
+                .ok_or(DatastoreError::NotFound { entity: "Product", id: component.component_id })?;
+
+            Ok(BundleComponent::new(
+                component_product,
+                ProductId::try_from(component.component_id)?,
+                u32::try_from(component.quantity)?,
+            ))
+        })
+        .collect::<AnyResult<Vec<_>>>()?;
+
+    Ok(Some(Bundle::new(bundle_product, id, pricing, on_component_deactivated, components)))
+}
+
+// the bundles that use a product as a component, by ID
+pub(crate) fn fetch_bundles_using(connection: &mut PgConnection, component_id: i32) -> AnyResult<Vec<i32>> {
+    Ok(bundle_components::table
+        .filter(bundle_components::component_id.eq(component_id))
+        .order(bundle_components::bundle_id.asc())
+        .select(bundle_components::bundle_id)
+        .load::<i32>(connection)?)
+}
+
+// deactivates the active bundles that use a component which was just deactivated, for bundles
+// that are set to follow their components. Each one moves to its next version and revision
+pub(crate) fn deactivate_bundles_using(connection: &mut PgConnection, actor: &str, component_id: i32) -> AnyResult<()> {
+    let bundle_ids = bundle_components::table
+        .inner_join(product_bundles::table)
+        .filter(bundle_components::component_id.eq(component_id))
+        .filter(product_bundles::on_component_deactivated.eq(ComponentDeactivation::Deactivate.as_str()))
+        .select(bundle_components::bundle_id)
+        .load::<i32>(connection)?;
+
+    for bundle_id in bundle_ids {
+        let Some(existing_bundle) = products::table
+            .find(bundle_id)
+            .filter(products::active.eq(true))
+            .for_update()
+            .select(ProductModel::as_select())
+            .first::<ProductModel>(connection)
+            .optional()?
+        else {
+            continue;
+        };
+
+        let deactivated_bundle = diesel::update(products::table.find(bundle_id))
+            .set((
+                products::active.eq(false),
+                products::version.eq(products::version + 1),
+                products::updated_at.eq(now),
+            ))
+            .returning(ProductModel::as_returning())
+            .get_result::<ProductModel>(connection)?;
+
+        append_audit_record(
+            connection,
+            actor,
+            AuditEntityType::Product,
+            bundle_id,
+            AuditAction::Updated,
+            Some(&serde_json::to_value(&existing_bundle)?),
+            Some(&serde_json::to_value(&deactivated_bundle)?),
+        )?;
+        append_product_events(connection, Some(&existing_bundle), Some(&deactivated_bundle))?;
+        record_product_revision(connection, bundle_id, actor)?;
+        record_product_change(connection, bundle_id)?;
+    }
+
+    Ok(())
+}
+
+// checks what a bundle is made of against what is stored: every component exists, is not the
+// bundle itself and is not a bundle, and the bundle is not a component of another bundle
+fn validate_components(connection: &mut PgConnection, id: i32, definition: &BundleDefinition) -> AnyResult<()> {
+    let mut problems = definition.validate().err().unwrap_or_default();
+    let component_ids = definition
+        .components()
+        .iter()
+        .map(|component| i32::from(component.product_id()))
+        .collect::<Vec<_>>();
+
+    let existing_ids = products::table
+        .filter(products::tenant_id.eq(current_tenant_id()))
+        .filter(products::id.eq_any(&component_ids))
+        .select(products::id)
+        .load::<i32>(connection)?;
+    let bundle_ids = product_bundles::table
+        .filter(product_bundles::product_id.eq_any(&component_ids))
+        .select(product_bundles::product_id)
+        .load::<i32>(connection)?;
+
+    for component_id in component_ids {
+        if component_id == id {
+            problems.push("A bundle cannot be a component of itself".to_string());
+        } else if !existing_ids.contains(&component_id) {
+            problems.push(format!("Component {} does not exist", component_id));
+        } else if bundle_ids.contains(&component_id) {
+            problems.push(format!("Component {} is a bundle itself", component_id));
+        }
+    }
+
+    if !fetch_bundles_using(connection, id)?.is_empty() {
+        problems.push(format!("Product {} is a component of another bundle", id));
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(DatastoreError::InvalidBundle { id, problems }.into())
+    }
+}
+
+fn audited_definition(definition: Option<&BundleDefinitionModels>) -> serde_json::Value {
+    match definition {
+        Some((product_bundle, components)) => serde_json::json!({
+            "pricing": product_bundle.pricing,
+            "discount_percent": product_bundle.discount_percent,
+            "on_component_deactivated": product_bundle.on_component_deactivated,
+            "components": components,
+        }),
+        None => serde_json::Value::Null,
+    }
+}
+
+impl BundleDatastore for BundleRepository<'_> {
+    fn get_bundle(&mut self, id: ProductId) -> AnyResult<Bundle> {
+        let bundle_product = fetch_product(self.connection, i32::from(id))?;
+
+        fetch_bundle(self.connection, id, &bundle_product)?
+            .ok_or_else(|| DatastoreError::NotFound { entity: "Bundle", id: id.get() }.into())
+    }
+
+    fn set_bundle(&mut self, id: ProductId, definition: BundleDefinition) -> AnyResult<Bundle> {
+        let actor = self.actor.as_str();
+        let bundle_id = i32::from(id);
+
+        self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            touch_product(connection, id)?;
+            validate_components(connection, bundle_id, &definition)?;
+
+            let existing_definition = fetch_definition(connection, bundle_id)?;
+
+            let (pricing, discount_percent) = match definition.pricing() {
+                BundlePricing::Fixed => ("fixed", None),
+                BundlePricing::Derived { discount_percent } => ("derived", Some(discount_percent)),
+            };
+            diesel::insert_into(product_bundles::table)
+                .values(ProductBundleModel {
+                    product_id: bundle_id,
+                    pricing: pricing.to_string(),
+                    discount_percent,
+                    on_component_deactivated: definition.on_component_deactivated().as_str().to_string(),
+                })
+                .on_conflict(product_bundles::product_id)
+                .do_update()
+                .set((
+                    product_bundles::pricing.eq(pricing),
+                    product_bundles::discount_percent.eq(discount_percent),
+                    product_bundles::on_component_deactivated.eq(definition.on_component_deactivated().as_str()),
+                ))
+                .execute(connection)?;
+
+            diesel::delete(bundle_components::table.filter(bundle_components::bundle_id.eq(bundle_id)))
+                .execute(connection)?;
+            let new_components = definition
+                .components()
+                .iter()
+                .map(|component| {
+                    Ok(BundleComponentModel {
+                        bundle_id,
+                        component_id: i32::from(component.product_id()),
+                        quantity: i32::try_from(component.quantity())?,
+                    })
+                })
+                .collect::<AnyResult<Vec<_>>>()?;
+            diesel::insert_into(bundle_components::table)
+                .values(new_components)
+                .execute(connection)?;
+
+            let saved_definition = fetch_definition(connection, bundle_id)?;
+
+            append_audit_record(
+                connection,
+                actor,
+                AuditEntityType::Bundle,
+                bundle_id,
+                if existing_definition.is_some() { AuditAction::Updated } else { AuditAction::Created },
+                existing_definition.as_ref().map(|definition| audited_definition(Some(definition))).as_ref(),
+                Some(&audited_definition(saved_definition.as_ref())),
+            )?;
+
+            Ok(())
+        })?;
+
+        self.get_bundle(id)
+    }
+
+    fn delete_bundle(&mut self, id: ProductId) -> AnyResult<()> {
+        let actor = self.actor.as_str();
+        let bundle_id = i32::from(id);
+
+        self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            let existing_definition = fetch_definition(connection, bundle_id)?
+                .ok_or(DatastoreError::NotFound { entity: "Bundle", id: bundle_id })?;
+
+            diesel::delete(product_bundles::table.find(bundle_id)).execute(connection)?;
+            touch_product(connection, id)?;
+
+            append_audit_record(
+                connection,
+                actor,
+                AuditEntityType::Bundle,
+                bundle_id,
+                AuditAction::Deleted,
+                Some(&audited_definition(Some(&existing_definition))),
+                None,
+            )?;
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod bundle_repository_tests {
+    use crate::core::entities::bundle::{BundleDefinition, BundlePricing, ComponentDeactivation, ComponentQuantity};
+    use crate::core::entities::ids::ProductId;
+    use crate::core::entities::product::Product;
+    use crate::core::ports::database::bundle_database::BundleDatastore;
+    use crate::core::ports::database::errors::DatastoreError;
+    use crate::core::ports::database::product_database::ProductDatastore;
+    use crate::datastore::repositories::bundle_repository::BundleRepository;
+    use crate::datastore::repositories::product_repository::ProductRepository;
+    use crate::establish_connection_test;
+    use diesel::{Connection, PgConnection};
+
+    fn create_product(conn: &mut PgConnection, name: &str, cost: f64, stock_quantity: Option<u32>) -> ProductId {
+        ProductRepository::new(conn)
+            .create_product(Product::new(name.to_string(), cost, true, None).with_stock_quantity(stock_quantity))
+            .expect("Error creating product")
+            .id()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_deactivating_component_deactivates_bundle() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let kit_id = create_product(conn, "Starter kit", 0.0, None);
+            let tent_id = create_product(conn, "Tent", 120.0, Some(7));
+            let stove_id = create_product(conn, "Stove", 40.0, Some(10));
+
+            let bundle = BundleRepository::new(conn)
+                .set_bundle(
+                    kit_id,
+                    BundleDefinition::new(
+                        BundlePricing::Derived { discount_percent: 20.0 },
+                        ComponentDeactivation::Deactivate,
+                        vec![ComponentQuantity::new(tent_id, 1), ComponentQuantity::new(stove_id, 2)],
+                    ),
+                )
+                .expect("Error creating bundle");
+
+            assert_eq!(160.0, bundle.price());
+            assert_eq!(Some(5), bundle.available_quantity());
+
+            let mut product_repository = ProductRepository::new(conn);
+            let error = product_repository
+                .delete_product(stove_id, 1)
+                .expect_err("A component of a bundle was deleted");
+            assert!(matches!(error.downcast_ref::<DatastoreError>(), Some(DatastoreError::InUse { .. })));
+
+            product_repository
+                .update_product(stove_id, Product::new("Stove".to_string(), 40.0, false, Some(stove_id)), 1)
+                .expect("Error deactivating component");
+
+            let kit = product_repository.get_product(kit_id).expect("Error loading bundle product");
+            assert!(!kit.active());
+            assert_eq!(3, kit.version());
+
+            let bundle = BundleRepository::new(conn).get_bundle(kit_id).expect("Error loading bundle");
+            assert_eq!(Some(0), bundle.available_quantity());
+            assert_eq!(1, bundle.warnings().len());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_bundle_cannot_contain_bundles() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let kit_id = create_product(conn, "Starter kit", 50.0, None);
+            let tent_id = create_product(conn, "Tent", 120.0, None);
+            let mut bundle_repository = BundleRepository::new(conn);
+
+            bundle_repository
+                .set_bundle(
+                    kit_id,
+                    BundleDefinition::new(
+                        BundlePricing::Fixed,
+                        ComponentDeactivation::Warn,
+                        vec![ComponentQuantity::new(tent_id, 1)],
+                    ),
+                )
+                .expect("Error creating bundle");
+
+            let error = bundle_repository
+                .set_bundle(
+                    tent_id,
+                    BundleDefinition::new(
+                        BundlePricing::Fixed,
+                        ComponentDeactivation::Warn,
+                        vec![ComponentQuantity::new(kit_id, 1)],
+                    ),
+                )
+                .expect_err("A bundle was made a component of another bundle");
+
+            assert_eq!(
+                Some(&DatastoreError::InvalidBundle {
+                    id: i32::from(tent_id),
+                    problems: vec![
+                        format!("Component {} is a bundle itself", kit_id),
+                        format!("Product {} is a component of another bundle", tent_id),
+                    ],
+                }),
+                error.downcast_ref::<DatastoreError>()
+            );
+
+            Ok(())
+        })
+    }
+}
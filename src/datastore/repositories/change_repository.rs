@@ -0,0 +1,221 @@
+use crate::core::entities::product_change::{ChangeToken, ProductChange};
+use crate::core::ports::database::change_database::ChangeFeedDatastore;
+use crate::datastore::models::change_models::ProductChangeModel;
+use crate::datastore::repositories::mappers::map_product_change_model_to_product_change;
+use anyhow::Result as AnyResult;
+use diesel::sql_types::{Int4, Int8, Nullable};
+use diesel::{PgConnection, RunQueryDsl};
+
+// moves a product to the end of the changes feed, or adds it there when it was just created
+const RECORD_PRODUCT_CHANGE_QUERY: &str = "
+    INSERT INTO product_changes (product_id, public_id)
+    SELECT products.id, products.public_id FROM products WHERE products.id = $1
+    ON CONFLICT (product_id) DO UPDATE SET
+        sequence = DEFAULT,
+        transaction_id = DEFAULT,
+        changed_at = DEFAULT";
+
+// the changes of the current tenant after a token, with each product as it is now. Transaction IDs
+// are handed out before changes are committed, so a transaction may still commit changes that come
+// before ones that are already visible. Only changes of transactions older than every one still
+// running are listed, along with those of the transaction reading the feed
+const PRODUCT_CHANGES_QUERY: &str = "
+    SELECT
+        product_changes.transaction_id,
+        product_changes.sequence,
+        product_changes.product_id,
+        product_changes.public_id,
+        product_changes.changed_at,
+        products.name,
+        products.cost,
+        products.active,
+        products.version,
+        products.external_key,
+        products.slug,
+        COALESCE(
+            (
+                SELECT jsonb_agg(
+                    jsonb_build_object('variant_id', variants.id, 'name', variants.name, 'value', product_variants.value)
+                    ORDER BY product_variants.id
+                )
+                FROM product_variants
+                INNER JOIN variants ON variants.id = product_variants.variant_id
+                WHERE product_variants.product_id = products.id
+            ),
+            '[]'::jsonb
+        ) AS variants
+    FROM product_changes
+    LEFT JOIN products ON products.id = product_changes.product_id
+    WHERE product_changes.tenant_id = current_tenant_id()
+        AND ($1 IS NULL OR (product_changes.transaction_id, product_changes.sequence) > ($1, $2))
+        AND (
+            product_changes.transaction_id < pg_snapshot_xmin(pg_current_snapshot())::TEXT::BIGINT
+            OR product_changes.transaction_id = pg_current_xact_id_if_assigned()::TEXT::BIGINT
+        )
+    ORDER BY product_changes.transaction_id, product_changes.sequence
+    LIMIT $3";
+
+pub struct ChangeRepository<'a> {
+    connection: &'a mut PgConnection,
+}
+
+impl<'a> ChangeRepository<'a> {
+    pub fn new(connection: &'a mut PgConnection) -> ChangeRepository<'a> {
+        ChangeRepository { connection }
+    }
+}
+
+// records that a product was written, for the changes feed. Like revisions, this is expected to be
+// called inside the transaction of the write, after it has been applied. A product that is about
+// to be deleted is recorded before it is, and its change becomes a tombstone once it is gone
+pub(crate) fn record_product_change(connection: &mut PgConnection, product_id: i32) -> AnyResult<()> {
+    diesel::sql_query(RECORD_PRODUCT_CHANGE_QUERY)
+        .bind::<Int4, _>(product_id)
+        .execute(connection)?;
+
+    Ok(())
+}
+
+impl ChangeFeedDatastore for ChangeRepository<'_> {
+    fn list_product_changes(&mut self, since: Option<ChangeToken>, limit: i64) -> AnyResult<Vec<ProductChange>> {
+        diesel::sql_query(PRODUCT_CHANGES_QUERY)
+            .bind::<Nullable<Int8>, _>(since.map(|since| since.transaction_id()))
+            .bind::<Nullable<Int8>, _>(since.map(|since| since.sequence()))
+            .bind::<Int8, _>(limit)
+            .load::<ProductChangeModel>(self.connection)?
+            .into_iter()
+            .map(map_product_change_model_to_product_change)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod change_repository_tests {
+    use crate::core::entities::product::Product;
+    use crate::core::entities::locale::Locale;
+    use crate::core::entities::product_change::{ChangeKind, ChangeToken};
+    use crate::core::entities::translation::ProductTranslation;
+    use crate::core::ports::database::change_database::ChangeFeedDatastore;
+    use crate::core::ports::database::product_database::ProductDatastore;
+    use crate::core::ports::database::translation_database::TranslationDatastore;
+    use crate::datastore::repositories::change_repository::ChangeRepository;
+    use crate::datastore::repositories::product_repository::ProductRepository;
+    use crate::datastore::repositories::translation_repository::TranslationRepository;
+    use crate::establish_connection_test;
+    use diesel::{Connection, PgConnection};
+
+    // the token of the last change in the feed
+    fn end_of_feed(conn: &mut PgConnection) -> Option<ChangeToken> {
+        let mut change_repository = ChangeRepository::new(conn);
+        let mut since = None;
+        loop {
+            let changes = change_repository
+                .list_product_changes(since, 1000)
+                .expect("Error listing changes");
+            match changes.last() {
+                Some(last_change) => since = Some(last_change.token()),
+                None => return since,
+            }
+        }
+    }
+
+    #[test]
+    fn test_changes_resume_from_a_token_and_end_in_tombstones() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            // the feed is read up to its end first, so that only the changes of this test are left
+            let since = end_of_feed(conn);
+
+            let mut product_repository = ProductRepository::new(conn);
+            let boots = product_repository
+                .create_product(Product::new("boots".to_string(), 13.23, true, None))
+                .expect("Error creating product");
+            let sandals = product_repository
+                .create_product(Product::new("sandals".to_string(), 9.99, true, None))
+                .expect("Error creating product");
+            let clogs = product_repository
+                .create_product(Product::new("clogs".to_string(), 20.0, true, None))
+                .expect("Error creating product");
+
+            let first_change = ChangeRepository::new(conn)
+                .list_product_changes(since, 1)
+                .expect("Error listing changes");
+            assert_eq!(1, first_change.len());
+            assert_eq!(boots.id(), Some(first_change[0].product_id()));
+            assert_eq!(ChangeKind::Upserted, first_change[0].change());
+            assert_eq!(Some(&[][..]), first_change[0].variants());
+            let resume_at = Some(first_change[0].token());
+
+            let mut product_repository = ProductRepository::new(conn);
+            let boots_id = boots.id().unwrap();
+            product_repository
+                .update_product(boots_id, Product::new("boots".to_string(), 11.0, true, Some(boots_id)), boots.version())
+                .expect("Error updating product");
+            let sandals_id = sandals.id().unwrap();
+            product_repository
+                .update_product(
+                    sandals_id,
+                    Product::new("sandals".to_string(), 9.99, false, Some(sandals_id)),
+                    sandals.version(),
+                )
+                .expect("Error archiving product");
+            product_repository
+                .delete_product(clogs.id().unwrap(), clogs.version())
+                .expect("Error deleting product");
+
+            let changes = ChangeRepository::new(conn)
+                .list_product_changes(resume_at, 10)
+                .expect("Error listing changes");
+
+            // every product is listed once, where its latest change put it
+            assert_eq!(
+                vec![
+                    (boots_id, ChangeKind::Upserted),
+                    (sandals_id, ChangeKind::Archived),
+                    (clogs.id().unwrap(), ChangeKind::Deleted),
+                ],
+                changes
+                    .iter()
+                    .map(|change| (change.product_id(), change.change()))
+                    .collect::<Vec<_>>()
+            );
+            assert!(changes.windows(2).all(|pair| pair[0].token() < pair[1].token()));
+            assert_eq!(Some(11.0), changes[0].product().map(Product::cost));
+            assert!(changes[1].product().is_none());
+            assert!(changes[2].product().is_none());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_writes_that_only_move_a_product_to_its_next_version_are_in_the_feed() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let boots = ProductRepository::new(conn)
+                .create_product(Product::new("boots".to_string(), 13.23, true, None))
+                .expect("Error creating product");
+            let boots_id = boots.id().unwrap();
+            let since = end_of_feed(conn);
+
+            TranslationRepository::new(conn)
+                .set_product_translation(
+                    boots_id,
+                    ProductTranslation::new("de".parse::<Locale>().unwrap(), "Stiefel".to_string(), None),
+                )
+                .expect("Error translating product");
+
+            let changes = ChangeRepository::new(conn)
+                .list_product_changes(since, 10)
+                .expect("Error listing changes");
+
+            assert_eq!(1, changes.len());
+            assert_eq!(boots_id, changes[0].product_id());
+            assert_eq!(Some(2), changes[0].product().map(Product::version));
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,215 @@
+use crate::core::entities::product_export::ExportedProduct;
+use crate::core::ports::database::export_database::ProductExportDatastore;
+use crate::datastore::models::export_models::ExportedProductModel;
+use crate::datastore::models::schema::variants::dsl::{name as variant_name, tenant_id as variant_tenant_id, variants};
+use crate::datastore::repositories::mappers::map_exported_product_model_to_exported_product;
+use crate::datastore::tenancy::current_tenant_id;
+use anyhow::Result as AnyResult;
+use chrono::{DateTime, Utc};
+use diesel::pg::PgRowByRowLoadingMode;
+use diesel::sql_types::{Nullable, Timestamptz};
+use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+
+// every active product of the current tenant with its variants and images aggregated into a single
+// row, or every product changed since `$1` when it is set. The variants use the same shape as the
+// variant snapshots of product revisions. The available quantity is worked out like `Bundle` does
+// for bundles, from the stock of their components, and is the product's own stock otherwise
+const EXPORTED_PRODUCTS_QUERY: &str = "
+    SELECT
+        products.id,
+        products.name,
+        products.cost,
+        products.active,
+        products.version,
+        products.external_key,
+        products.public_id,
+        products.slug,
+        products.updated_at,
+        COALESCE(
+            (
+                SELECT jsonb_agg(
+                    jsonb_build_object('variant_id', variants.id, 'name', variants.name, 'value', product_variants.value)
+                    ORDER BY product_variants.id
+                )
+                FROM product_variants
+                INNER JOIN variants ON variants.id = product_variants.variant_id
+                WHERE product_variants.product_id = products.id
+            ),
+            '[]'::jsonb
+        ) AS variants,
+        ARRAY(
+            SELECT product_images.url
+            FROM product_images
+            WHERE product_images.product_id = products.id
+            ORDER BY product_images.position, product_images.id
+        ) AS image_urls,
+        CASE
+            WHEN NOT products.active THEN 0
+            WHEN EXISTS (SELECT 1 FROM product_bundles WHERE product_bundles.product_id = products.id) THEN (
+                SELECT CASE
+                    WHEN COALESCE(bool_and(components.active), TRUE)
+                        THEN MIN(components.stock_quantity / GREATEST(bundle_components.quantity, 1))
+                    ELSE 0
+                END
+                FROM bundle_components
+                INNER JOIN products AS components ON components.id = bundle_components.component_id
+                WHERE bundle_components.bundle_id = products.id
+            )
+            ELSE products.stock_quantity
+        END AS available_quantity
+    FROM products
+    WHERE products.tenant_id = current_tenant_id()
+        AND ($1 IS NULL OR products.updated_at > $1)
+        AND (products.active OR $1 IS NOT NULL)
+    ORDER BY products.id";
+
+pub struct ExportRepository<'a> {
+    connection: &'a mut PgConnection,
+}
+
+impl<'a> ExportRepository<'a> {
+    pub fn new(connection: &'a mut PgConnection) -> ExportRepository<'a> {
+        ExportRepository { connection }
+    }
+}
+
+impl ProductExportDatastore for ExportRepository<'_> {
+    fn list_variant_names(&mut self) -> AnyResult<Vec<String>> {
+        Ok(variants
+            .filter(variant_tenant_id.eq(current_tenant_id()))
+            .select(variant_name)
+            .distinct()
+            .order(variant_name.asc())
+            .load::<String>(self.connection)?)
+    }
+
+    // rows are loaded one at a time, so memory use stays flat however large the catalog is. The
+    // connection is busy until the last row is read, so `visit` must not use it
+    fn stream_exported_products(
+        &mut self,
+        changed_since: Option<DateTime<Utc>>,
+        visit: &mut dyn FnMut(ExportedProduct) -> AnyResult<()>,
+    ) -> AnyResult<usize> {
+        let rows = diesel::sql_query(EXPORTED_PRODUCTS_QUERY)
+            .bind::<Nullable<Timestamptz>, _>(changed_since)
+            .load_iter::<ExportedProductModel, PgRowByRowLoadingMode>(self.connection)?;
+
+        let mut streamed = 0;
+        for row in rows {
+            visit(map_exported_product_model_to_exported_product(row?)?)?;
+            streamed += 1;
+        }
+
+        Ok(streamed)
+    }
+}
+
+#[cfg(test)]
+mod export_repository_tests {
+    use crate::core::entities::bundle::{BundleDefinition, BundlePricing, ComponentDeactivation, ComponentQuantity};
+    use crate::core::entities::product::Product;
+    use crate::core::ports::database::bundle_database::BundleDatastore;
+    use crate::core::ports::database::export_database::ProductExportDatastore;
+    use crate::core::ports::database::product_database::ProductDatastore;
+    use crate::datastore::repositories::bundle_repository::BundleRepository;
+    use crate::datastore::repositories::export_repository::ExportRepository;
+    use crate::datastore::repositories::product_repository::ProductRepository;
+    use crate::establish_connection_test;
+    use diesel::Connection;
+
+    #[test]
+    fn test_stream_exported_products_changed_since() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut product_repository = ProductRepository::new(conn);
+            product_repository
+                .create_product(Product::new("boots".to_string(), 13.23, true, None))
+                .expect("Error creating product");
+            product_repository
+                .create_product(Product::new("clogs".to_string(), 20.0, true, None).with_stock_quantity(Some(0)))
+                .expect("Error creating product");
+            product_repository
+                .create_product(Product::new("sandals".to_string(), 9.99, false, None))
+                .expect("Error creating product");
+
+            let mut export_repository = ExportRepository::new(conn);
+            let mut stream = |changed_since| {
+                let mut exported_products = Vec::new();
+                export_repository
+                    .stream_exported_products(changed_since, &mut |exported_product| {
+                        exported_products
+                            .push((exported_product.product().name().to_string(), exported_product.in_stock()));
+                        Ok(())
+                    })
+                    .expect("Error streaming products");
+                exported_products
+            };
+
+            assert_eq!(
+                vec![("boots".to_string(), true), ("clogs".to_string(), false)],
+                stream(None)
+            );
+
+            // an incremental export also has the products that were taken off sale
+            assert_eq!(
+                vec![
+                    ("boots".to_string(), true),
+                    ("clogs".to_string(), false),
+                    ("sandals".to_string(), false),
+                ],
+                stream(Some(chrono::Utc::now() - chrono::Duration::hours(1)))
+            );
+            assert!(stream(Some(chrono::Utc::now() + chrono::Duration::hours(1))).is_empty());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_bundles_are_exported_with_the_quantity_their_components_make_up() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut product_repository = ProductRepository::new(conn);
+            let mut create = |name: &str, stock_quantity| {
+                product_repository
+                    .create_product(
+                        Product::new(name.to_string(), 40.0, true, None).with_stock_quantity(stock_quantity),
+                    )
+                    .expect("Error creating product")
+                    .id()
+                    .unwrap()
+            };
+            let kit_id = create("Starter kit", None);
+            let tent_id = create("Tent", Some(7));
+            let stove_id = create("Stove", Some(10));
+            let bundle = BundleRepository::new(conn)
+                .set_bundle(
+                    kit_id,
+                    BundleDefinition::new(
+                        BundlePricing::Fixed,
+                        ComponentDeactivation::Warn,
+                        vec![ComponentQuantity::new(tent_id, 1), ComponentQuantity::new(stove_id, 2)],
+                    ),
+                )
+                .expect("Error creating bundle");
+
+            let mut available_quantities = Vec::new();
+            ExportRepository::new(conn)
+                .stream_exported_products(None, &mut |exported_product| {
+                    available_quantities.push((exported_product.product().id(), exported_product.available_quantity()));
+                    Ok(())
+                })
+                .expect("Error streaming products");
+
+            assert_eq!(
+                vec![(Some(kit_id), bundle.available_quantity()), (Some(tent_id), Some(7)), (Some(stove_id), Some(10))],
+                available_quantities
+            );
+            assert_eq!(Some(5), bundle.available_quantity());
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,158 @@
+use crate::core::entities::idempotency_record::{IdempotencyRecord, IdempotentResponse};
+use crate::core::ports::database::idempotency_database::IdempotencyDatastore;
+use crate::datastore::models::idempotency_models::{IdempotencyKeyModel, NewIdempotencyKeyModel};
+use crate::datastore::models::schema::idempotency_keys::dsl::{
+    expires_at as idempotency_key_expires_at, idempotency_keys, key as idempotency_key_key,
+    principal as idempotency_key_principal, response_body as idempotency_key_response_body,
+    response_headers as idempotency_key_response_headers,
+    response_status as idempotency_key_response_status, tenant_id as idempotency_key_tenant_id,
+};
+use crate::datastore::repositories::mappers::map_idempotency_key_model_to_idempotency_record;
+use crate::datastore::tenancy::current_tenant_id;
+use anyhow::Result as AnyResult;
+use chrono::{DateTime, Utc};
+use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper};
+
+pub struct IdempotencyRepository<'a> {
+    connection: &'a mut PgConnection,
+}
+
+impl<'a> IdempotencyRepository<'a> {
+    pub fn new(connection: &'a mut PgConnection) -> IdempotencyRepository<'a> {
+        IdempotencyRepository { connection }
+    }
+}
+
+impl IdempotencyDatastore for IdempotencyRepository<'_> {
+    // a concurrent request holding the same key keeps its row locked until it commits, so the
+    // insert below waits for it and then sees its stored response
+    fn reserve_idempotency_key(
+        &mut self,
+        principal: &str,
+        key: &str,
+        fingerprint: &str,
+        expires_at: DateTime<Utc>,
+    ) -> AnyResult<Option<IdempotencyRecord>> {
+        diesel::delete(idempotency_keys.filter(idempotency_key_expires_at.le(Utc::now())))
+            .execute(self.connection)?;
+
+        let reserved = diesel::insert_into(idempotency_keys)
+            .values(NewIdempotencyKeyModel {
+                principal,
+                key,
+                fingerprint,
+                expires_at,
+            })
+            .on_conflict_do_nothing()
+            .execute(self.connection)?;
+
+        if reserved == 1 {
+            return Ok(None);
+        }
+
+        let existing_key = idempotency_keys
+            .filter(idempotency_key_tenant_id.eq(current_tenant_id()))
+            .filter(idempotency_key_principal.eq(principal))
+            .filter(idempotency_key_key.eq(key))
+            .select(IdempotencyKeyModel::as_select())
+            .first::<IdempotencyKeyModel>(self.connection)?;
+
+        Ok(Some(map_idempotency_key_model_to_idempotency_record(existing_key)?))
+    }
+
+    fn complete_idempotency_key(&mut self, principal: &str, key: &str, response: &IdempotentResponse) -> AnyResult<()> {
+        diesel::update(
+            idempotency_keys
+                .filter(idempotency_key_tenant_id.eq(current_tenant_id()))
+                .filter(idempotency_key_principal.eq(principal))
+                .filter(idempotency_key_key.eq(key)),
+        )
+            .set((
+                idempotency_key_response_status.eq(i32::from(response.status())),
+                idempotency_key_response_headers.eq(serde_json::to_value(response.headers())?),
+                idempotency_key_response_body.eq(response.body()),
+            ))
+            .execute(self.connection)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod idempotency_repository_tests {
+    use crate::core::entities::idempotency_record::IdempotentResponse;
+    use crate::core::ports::database::idempotency_database::IdempotencyDatastore;
+    use crate::datastore::repositories::idempotency_repository::IdempotencyRepository;
+    use crate::establish_connection_test;
+    use chrono::{Duration, Utc};
+    use diesel::Connection;
+    use serde_json::json;
+
+    #[test]
+    fn test_reserved_keys_return_the_stored_response() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut idempotency_repository = IdempotencyRepository::new(conn);
+            let expires_at = Utc::now() + Duration::hours(1);
+
+            let reserved = idempotency_repository
+                .reserve_idempotency_key("api_key:storefront", "create-1", "fingerprint", expires_at)
+                .expect("Error reserving key");
+            assert!(reserved.is_none());
+
+            let running = idempotency_repository
+                .reserve_idempotency_key("api_key:storefront", "create-1", "other fingerprint", expires_at)
+                .expect("Error reserving key")
+                .expect("Reserved key was not found");
+            assert_eq!("fingerprint", running.fingerprint());
+            assert_eq!(None, running.response());
+
+            let response = IdempotentResponse::new(201, vec![], json!({"id": 1}));
+            idempotency_repository
+                .complete_idempotency_key("api_key:storefront", "create-1", &response)
+                .expect("Error completing key");
+
+            let finished = idempotency_repository
+                .reserve_idempotency_key("api_key:storefront", "create-1", "fingerprint", expires_at)
+                .expect("Error reserving key")
+                .expect("Reserved key was not found");
+            assert_eq!(Some(&response), finished.response());
+
+            let other_principal = idempotency_repository
+                .reserve_idempotency_key("api_key:warehouse", "create-1", "fingerprint", expires_at)
+                .expect("Error reserving key");
+            assert!(other_principal.is_none());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_expired_keys_are_deleted_and_can_be_reserved_again() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut idempotency_repository = IdempotencyRepository::new(conn);
+            let expired_at = Utc::now() - Duration::minutes(1);
+            let expires_at = Utc::now() + Duration::hours(1);
+
+            idempotency_repository
+                .reserve_idempotency_key("api_key:storefront", "create-2", "fingerprint", expired_at)
+                .expect("Error reserving key");
+
+            let reserved = idempotency_repository
+                .reserve_idempotency_key("api_key:storefront", "create-2", "other fingerprint", expires_at)
+                .expect("Error reserving key");
+            assert!(reserved.is_none());
+
+            let running = idempotency_repository
+                .reserve_idempotency_key("api_key:storefront", "create-2", "fingerprint", expires_at)
+                .expect("Error reserving key")
+                .expect("Reserved key was not found");
+            assert_eq!("other fingerprint", running.fingerprint());
+
+            Ok(())
+        })
+    }
+}
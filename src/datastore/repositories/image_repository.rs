@@ -0,0 +1,156 @@
+use crate::core::entities::audit_record::{AuditAction, AuditEntityType, SYSTEM_ACTOR};
+use crate::core::entities::ids::ProductId;
+use crate::core::entities::product_image::ProductImage;
+use crate::core::ports::database::image_database::ImageDatastore;
+use crate::datastore::models::image_models::ProductImageModel;
+use crate::datastore::models::schema::product_images;
+use crate::datastore::repositories::audit_repository::append_audit_record;
+use crate::datastore::repositories::mappers::map_product_image_model_to_product_image;
+use crate::datastore::repositories::translation_repository::touch_product;
+use crate::datastore::tenancy::current_tenant_id;
+use anyhow::Result as AnyResult;
+use diesel::{Connection, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper};
+
+pub struct ImageRepository<'a> {
+    connection: &'a mut PgConnection,
+    actor: String,
+}
+
+impl<'a> ImageRepository<'a> {
+    pub fn new(connection: &'a mut PgConnection) -> ImageRepository<'a> {
+        ImageRepository {
+            connection,
+            actor: SYSTEM_ACTOR.to_string(),
+        }
+    }
+
+    // sets the actor that is recorded in the audit log for writes made through this repository
+    pub fn with_actor(mut self, actor: impl Into<String>) -> ImageRepository<'a> {
+        self.actor = actor.into();
+        self
+    }
+}
+
+fn fetch_product_images(connection: &mut PgConnection, id: ProductId) -> AnyResult<Vec<ProductImage>> {
+    product_images::table
+        .filter(product_images::tenant_id.eq(current_tenant_id()))
+        .filter(product_images::product_id.eq(i32::from(id)))
+        .order((product_images::position.asc(), product_images::id.asc()))
+        .select(ProductImageModel::as_select())
+        .load::<ProductImageModel>(connection)?
+        .into_iter()
+        .map(map_product_image_model_to_product_image)
+        .collect()
+}
+
+impl ImageDatastore for ImageRepository<'_> {
+    fn list_product_images(&mut self, id: ProductId) -> AnyResult<Vec<ProductImage>> {
+        fetch_product_images(self.connection, id)
+    }
+
+    fn set_product_images(&mut self, id: ProductId, images: Vec<ProductImage>) -> AnyResult<Vec<ProductImage>> {
+        let actor = self.actor.as_str();
+
+        self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            touch_product(connection, id)?;
+
+            let existing_images = fetch_product_images(connection, id)?;
+            diesel::delete(
+                product_images::table
+                    .filter(product_images::tenant_id.eq(current_tenant_id()))
+                    .filter(product_images::product_id.eq(i32::from(id))),
+            )
+            .execute(connection)?;
+
+            let new_images = images
+                .iter()
+                .enumerate()
+                .map(|(position, image)| {
+                    Ok(ProductImageModel {
+                        product_id: i32::from(id),
+                        url: image.url().to_string(),
+                        position: i32::try_from(position)?,
+                    })
+                })
+                .collect::<AnyResult<Vec<_>>>()?;
+            if !new_images.is_empty() {
+                diesel::insert_into(product_images::table).values(new_images).execute(connection)?;
+            }
+
+            append_audit_record(
+                connection,
+                actor,
+                AuditEntityType::ProductImages,
+                i32::from(id),
+                AuditAction::Updated,
+                Some(&serde_json::to_value(&existing_images)?),
+                Some(&serde_json::to_value(&images)?),
+            )?;
+
+            Ok(())
+        })?;
+
+        Ok(images)
+    }
+}
+
+#[cfg(test)]
+mod image_repository_tests {
+    use crate::core::entities::product::Product;
+    use crate::core::entities::product_image::ProductImage;
+    use crate::core::ports::database::export_database::ProductExportDatastore;
+    use crate::core::ports::database::image_database::ImageDatastore;
+    use crate::core::ports::database::product_database::ProductDatastore;
+    use crate::datastore::repositories::export_repository::ExportRepository;
+    use crate::datastore::repositories::image_repository::ImageRepository;
+    use crate::datastore::repositories::product_repository::ProductRepository;
+    use crate::establish_connection_test;
+    use diesel::Connection;
+
+    const BOOTS_SIDE: &str = "https://cdn.example.com/boots-side.jpg";
+    const BOOTS_SOLE: &str = "https://cdn.example.com/boots-sole.jpg";
+
+    fn image(url: &str) -> ProductImage {
+        ProductImage::new(url.to_string())
+    }
+
+    #[test]
+    fn test_set_product_images_replaces_them_and_exports_them() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let product_id = ProductRepository::new(conn)
+                .create_product(Product::new("Trail boots".to_string(), 140.0, true, None))
+                .expect("Error creating product")
+                .id()
+                .unwrap();
+
+            let mut image_repository = ImageRepository::new(conn);
+            image_repository
+                .set_product_images(product_id, vec![image("https://cdn.example.com/boots-front.jpg")])
+                .expect("Error setting images");
+            image_repository
+                .set_product_images(product_id, vec![image(BOOTS_SIDE), image(BOOTS_SOLE)])
+                .expect("Error setting images");
+
+            assert_eq!(
+                vec![image(BOOTS_SIDE), image(BOOTS_SOLE)],
+                image_repository.list_product_images(product_id).expect("Error listing images")
+            );
+            let product = ProductRepository::new(conn).get_product(product_id).expect("Error loading product");
+            assert_eq!(3, product.version());
+
+            let mut image_urls = Vec::new();
+            ExportRepository::new(conn)
+                .stream_exported_products(None, &mut |exported_product| {
+                    image_urls.extend(exported_product.image_urls().iter().cloned());
+                    Ok(())
+                })
+                .expect("Error streaming products");
+
+            assert_eq!(vec![BOOTS_SIDE, BOOTS_SOLE], image_urls);
+
+            Ok(())
+        })
+    }
+}
@@ -1,33 +1,312 @@
+use crate::core::entities::api_key::ApiKey;
+use crate::core::entities::audit_record::AuditRecord;
+use crate::core::entities::bundle::{BundlePricing, ComponentDeactivation};
+use crate::core::entities::domain_event::OutboxEvent;
+use crate::core::entities::idempotency_record::{IdempotencyRecord, IdempotentResponse};
+use crate::core::entities::ids::{ApiKeyId, ProductId, ProductTypeId, VariantId, WebhookSubscriptionId};
 use crate::core::entities::product::Product;
+use crate::core::entities::product_change::{ChangeKind, ChangeToken, ProductChange};
+use crate::core::entities::product_export::ExportedProduct;
+use crate::core::entities::product_image::ProductImage;
+use crate::core::entities::product_relation::ProductRelation;
+use crate::core::entities::product_type::ProductType;
+use crate::core::entities::product_revision::{ProductRevision, VariantSnapshot};
+use crate::core::entities::translation::ProductTranslation;
 use crate::core::entities::product_variant::ProductVariant;
+use crate::core::entities::role::Role;
+use crate::core::entities::specification::Specification;
 use crate::core::entities::variant::Variant;
+use crate::core::entities::webhook::{WebhookDelivery, WebhookSubscription};
+use crate::datastore::models::api_key_models::ApiKeyModel;
+use crate::datastore::models::audit_models::AuditLogModel;
+use crate::datastore::models::bundle_models::ProductBundleModel;
+use crate::datastore::models::change_models::ProductChangeModel;
+use crate::datastore::models::export_models::ExportedProductModel;
+use crate::datastore::models::idempotency_models::IdempotencyKeyModel;
+use crate::datastore::models::image_models::ProductImageModel;
+use crate::datastore::models::outbox_models::OutboxEventModel;
 use crate::datastore::models::product_models::ProductModel;
+use crate::datastore::models::product_type_models::ProductTypeModel;
+use crate::datastore::models::relation_models::ProductRelationModel;
+use crate::datastore::models::revision_models::{ProductRevisionModel, VariantSnapshotModel};
+use crate::datastore::models::role_models::RoleModel;
+use crate::datastore::models::specification_models::ProductSpecificationModel;
+use crate::datastore::models::translation_models::ProductTranslationModel;
 use crate::datastore::models::variant_models::{ProductVariantModel, VariantModel};
+use crate::datastore::models::webhook_models::{WebhookDeliveryModel, WebhookSubscriptionModel};
+use anyhow::{anyhow, Result as AnyResult};
 
-pub fn map_product_model_to_product(product_model: ProductModel) -> Product {
-    Product::new(
+pub fn map_product_model_to_product(product_model: ProductModel) -> AnyResult<Product> {
+    Ok(Product::new(
         product_model.name,
         product_model.cost,
         product_model.active,
-        Some(product_model.id as u32)
+        Some(ProductId::try_from(product_model.id)?)
     )
+    .with_version(u32::try_from(product_model.version)?)
+    .with_external_key(product_model.external_key)
+    .with_description(product_model.description)
+    .with_public_identifiers(product_model.public_id, product_model.slug)
+    .with_attributes(
+        product_model.product_type_id.map(ProductTypeId::try_from).transpose()?,
+        serde_json::from_value(product_model.attributes)?,
+    )
+    .with_stock_quantity(product_model.stock_quantity.map(u32::try_from).transpose()?))
 }
 
-pub fn map_product_variant_model_to_product_variant(product_variant_model: ProductVariantModel) -> ProductVariant {
-    ProductVariant::new(
-        product_variant_model.product_id,
-        product_variant_model.variant_id,
+pub fn map_product_variant_model_to_product_variant(product_variant_model: ProductVariantModel) -> AnyResult<ProductVariant> {
+    Ok(ProductVariant::new(
+        ProductId::try_from(product_variant_model.product_id)?,
+        VariantId::try_from(product_variant_model.variant_id)?,
         product_variant_model.value,
-    )
+    ))
 }
 
-pub fn map_variant_model_to_variant(variant_model: VariantModel) -> Variant {
-    Variant::new(
+pub fn map_variant_model_to_variant(variant_model: VariantModel) -> AnyResult<Variant> {
+    Ok(Variant::new(
         variant_model.name,
-        Some(variant_model.id as u32),
+        Some(VariantId::try_from(variant_model.id)?),
+    ))
+}
+
+pub fn map_product_and_variant_model_to_variant(model: (ProductVariantModel, VariantModel)) -> AnyResult<(ProductVariant, Variant)> {
+    Ok((map_product_variant_model_to_product_variant(model.0)?, map_variant_model_to_variant(model.1)?))
+}
+
+pub fn map_audit_log_model_to_audit_record(audit_log_model: AuditLogModel) -> AnyResult<AuditRecord> {
+    Ok(AuditRecord::new(
+        audit_log_model.id,
+        audit_log_model.actor,
+        audit_log_model.occurred_at,
+        audit_log_model.entity_type.parse()?,
+        audit_log_model.entity_id,
+        audit_log_model.action.parse()?,
+        audit_log_model.changes,
+    ))
+}
+
+pub fn map_variant_snapshot_model_to_variant_snapshot(variant_snapshot_model: VariantSnapshotModel) -> AnyResult<VariantSnapshot> {
+    Ok(VariantSnapshot::new(
+        VariantId::try_from(variant_snapshot_model.variant_id)?,
+        variant_snapshot_model.name,
+        variant_snapshot_model.value,
+    ))
+}
+
+pub fn map_product_revision_model_to_product_revision(product_revision_model: ProductRevisionModel) -> AnyResult<ProductRevision> {
+    let variant_snapshots = serde_json::from_value::<Vec<VariantSnapshotModel>>(product_revision_model.variants)?;
+
+    Ok(ProductRevision::new(
+        u32::try_from(product_revision_model.revision)?,
+        Product::new(
+            product_revision_model.name,
+            product_revision_model.cost,
+            product_revision_model.active,
+            Some(ProductId::try_from(product_revision_model.product_id)?),
+        ),
+        variant_snapshots
+            .into_iter()
+            .map(map_variant_snapshot_model_to_variant_snapshot)
+            .collect::<AnyResult<_>>()?,
+        product_revision_model.actor,
+        product_revision_model.created_at,
+    ))
+}
+
+pub fn map_idempotency_key_model_to_idempotency_record(idempotency_key_model: IdempotencyKeyModel) -> AnyResult<IdempotencyRecord> {
+    let response = match idempotency_key_model.response_status {
+        Some(status) => Some(IdempotentResponse::new(
+            u16::try_from(status)?,
+            serde_json::from_value(idempotency_key_model.response_headers.unwrap_or_default())?,
+            idempotency_key_model.response_body.unwrap_or_default(),
+        )),
+        None => None,
+    };
+
+    Ok(IdempotencyRecord::new(
+        idempotency_key_model.key,
+        idempotency_key_model.fingerprint,
+        response,
+        idempotency_key_model.expires_at,
+    ))
+}
+
+pub fn map_exported_product_model_to_exported_product(exported_product_model: ExportedProductModel) -> AnyResult<ExportedProduct> {
+    let variant_snapshots = serde_json::from_value::<Vec<VariantSnapshotModel>>(exported_product_model.variants)?;
+
+    Ok(ExportedProduct::new(
+        Product::new(
+            exported_product_model.name,
+            exported_product_model.cost,
+            exported_product_model.active,
+            Some(ProductId::try_from(exported_product_model.id)?),
+        )
+        .with_version(u32::try_from(exported_product_model.version)?)
+        .with_external_key(exported_product_model.external_key)
+        .with_public_identifiers(exported_product_model.public_id, exported_product_model.slug),
+        variant_snapshots
+            .into_iter()
+            .map(map_variant_snapshot_model_to_variant_snapshot)
+            .collect::<AnyResult<_>>()?,
+        exported_product_model.image_urls,
+        exported_product_model.updated_at,
     )
+    .with_available_quantity(exported_product_model.available_quantity.map(u32::try_from).transpose()?))
 }
 
-pub fn map_product_and_variant_model_to_variant(model: (ProductVariantModel, VariantModel)) -> (ProductVariant, Variant) {
-    (map_product_variant_model_to_product_variant(model.0), map_variant_model_to_variant(model.1))
+pub fn map_product_translation_model_to_product_translation(product_translation_model: ProductTranslationModel) -> AnyResult<ProductTranslation> {
+    Ok(ProductTranslation::new(
+        product_translation_model.locale.parse()?,
+        product_translation_model.name,
+        product_translation_model.description,
+    ))
+}
+
+pub fn map_product_specification_model_to_specification(product_specification_model: ProductSpecificationModel) -> AnyResult<Specification> {
+    Ok(Specification::new(product_specification_model.name, product_specification_model.value))
+}
+
+pub fn map_product_image_model_to_product_image(product_image_model: ProductImageModel) -> AnyResult<ProductImage> {
+    Ok(ProductImage::new(product_image_model.url))
+}
+
+pub fn map_product_type_model_to_product_type(product_type_model: ProductTypeModel) -> AnyResult<ProductType> {
+    Ok(ProductType::new(
+        product_type_model.name,
+        serde_json::from_value(product_type_model.attribute_schema)?,
+        Some(ProductTypeId::try_from(product_type_model.id)?),
+    ))
+}
+
+pub fn map_product_bundle_model_to_bundle_settings(product_bundle_model: &ProductBundleModel) -> AnyResult<(BundlePricing, ComponentDeactivation)> {
+    let pricing = match (product_bundle_model.pricing.as_str(), product_bundle_model.discount_percent) {
+        ("fixed", _) => BundlePricing::Fixed,
+        ("derived", discount_percent) => BundlePricing::Derived { discount_percent: discount_percent.unwrap_or_default() },
+        (other, _) => return Err(anyhow!("Unknown bundle pricing: {}", other)),
+    };
+
+    Ok((pricing, product_bundle_model.on_component_deactivated.parse()?))
+}
+
+pub fn map_product_relation_model_to_product_relation(product_relation_model: ProductRelationModel) -> AnyResult<ProductRelation> {
+    Ok(ProductRelation::new(
+        product_relation_model.relation_type.parse()?,
+        ProductId::try_from(product_relation_model.related_product_id)?,
+        product_relation_model.position,
+    ))
+}
+
+pub fn map_api_key_model_to_api_key(api_key_model: ApiKeyModel) -> AnyResult<ApiKey> {
+    Ok(ApiKey::new(
+        ApiKeyId::try_from(api_key_model.id)?,
+        api_key_model.name,
+        api_key_model.tenant_id.parse()?,
+        api_key_model.scopes,
+        api_key_model.expires_at,
+        api_key_model.revoked_at,
+        api_key_model.created_at,
+    ))
+}
+
+pub fn map_role_model_to_role(role_model: RoleModel) -> AnyResult<Role> {
+    Ok(Role::new(
+        role_model.name,
+        role_model
+            .permissions
+            .iter()
+            .map(|permission| permission.parse())
+            .collect::<AnyResult<Vec<_>>>()?,
+    ))
+}
+
+pub fn map_outbox_event_model_to_outbox_event(outbox_event_model: OutboxEventModel) -> AnyResult<OutboxEvent> {
+    Ok(OutboxEvent::new(
+        outbox_event_model.id,
+        outbox_event_model.event_id,
+        outbox_event_model.tenant_id.parse()?,
+        outbox_event_model.occurred_at,
+        u32::try_from(outbox_event_model.attempts)?,
+        serde_json::from_value(outbox_event_model.payload)?,
+    ))
+}
+
+pub fn map_webhook_subscription_model_to_webhook_subscription(
+    webhook_subscription_model: WebhookSubscriptionModel,
+) -> AnyResult<WebhookSubscription> {
+    Ok(WebhookSubscription::new(
+        WebhookSubscriptionId::try_from(webhook_subscription_model.id)?,
+        webhook_subscription_model.url,
+        webhook_subscription_model.event_types,
+        webhook_subscription_model.secret,
+        webhook_subscription_model.active,
+        webhook_subscription_model.created_at,
+    ))
+}
+
+pub fn map_webhook_delivery_model_to_webhook_delivery(
+    webhook_delivery_model: WebhookDeliveryModel,
+) -> AnyResult<WebhookDelivery> {
+    Ok(WebhookDelivery::new(
+        webhook_delivery_model.id,
+        WebhookSubscriptionId::try_from(webhook_delivery_model.subscription_id)?,
+        webhook_delivery_model.event_id,
+        webhook_delivery_model.event_type,
+        webhook_delivery_model.payload,
+        webhook_delivery_model.status.parse()?,
+        u32::try_from(webhook_delivery_model.attempts)?,
+        webhook_delivery_model.next_attempt_at,
+        webhook_delivery_model.last_attempt_at,
+        webhook_delivery_model.last_response_status.map(u16::try_from).transpose()?,
+        webhook_delivery_model.last_error,
+        webhook_delivery_model.delivered_at,
+        webhook_delivery_model.created_at,
+    ))
+}
+
+// a change whose product is gone is a deletion, and one whose product is inactive an archival
+pub fn map_product_change_model_to_product_change(product_change_model: ProductChangeModel) -> AnyResult<ProductChange> {
+    let token = ChangeToken::new(product_change_model.transaction_id, product_change_model.sequence);
+    let product_id = ProductId::try_from(product_change_model.product_id)?;
+
+    let (Some(name), Some(cost), Some(active), Some(version), Some(slug)) = (
+        product_change_model.name,
+        product_change_model.cost,
+        product_change_model.active,
+        product_change_model.version,
+        product_change_model.slug,
+    ) else {
+        return Ok(ProductChange::tombstone(
+            token,
+            product_id,
+            product_change_model.public_id,
+            ChangeKind::Deleted,
+            product_change_model.changed_at,
+        ));
+    };
+    if !active {
+        return Ok(ProductChange::tombstone(
+            token,
+            product_id,
+            product_change_model.public_id,
+            ChangeKind::Archived,
+            product_change_model.changed_at,
+        ));
+    }
+
+    let variant_snapshots = serde_json::from_value::<Vec<VariantSnapshotModel>>(product_change_model.variants)?;
+
+    Ok(ProductChange::upserted(
+        token,
+        product_id,
+        product_change_model.public_id,
+        product_change_model.changed_at,
+        Product::new(name, cost, active, Some(product_id))
+            .with_version(u32::try_from(version)?)
+            .with_external_key(product_change_model.external_key)
+            .with_public_identifiers(product_change_model.public_id, slug),
+        variant_snapshots
+            .into_iter()
+            .map(map_variant_snapshot_model_to_variant_snapshot)
+            .collect::<AnyResult<_>>()?,
+    ))
 }
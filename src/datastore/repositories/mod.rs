@@ -1,2 +1,18 @@
-mod product_repository;
+pub mod api_key_repository;
+pub mod audit_repository;
+pub mod bundle_repository;
+pub mod change_repository;
+pub mod export_repository;
+pub mod idempotency_repository;
+pub mod image_repository;
+pub mod outbox_repository;
+pub mod product_repository;
+pub mod product_type_repository;
 mod mappers;
+pub mod relation_repository;
+mod revision_repository;
+pub mod role_repository;
+pub mod specification_repository;
+pub mod translation_repository;
+pub mod variant_repository;
+pub mod webhook_repository;
@@ -0,0 +1,264 @@
+use crate::core::entities::domain_event::{DomainEvent, OutboxEvent};
+use crate::core::entities::ids::ProductId;
+use crate::core::entities::webhook::RetryPolicy;
+use crate::core::ports::database::outbox_database::OutboxDatastore;
+use crate::datastore::models::outbox_models::{NewOutboxEventModel, OutboxEventModel};
+use crate::datastore::models::product_models::ProductModel;
+use crate::datastore::models::schema::outbox_events::dsl::{
+    attempts as outbox_event_attempts, dead_at as outbox_event_dead_at, delivered_at as outbox_event_delivered_at,
+    id as outbox_event_id, last_error as outbox_event_last_error, next_attempt_at as outbox_event_next_attempt_at,
+    outbox_events,
+};
+use crate::datastore::repositories::mappers::map_outbox_event_model_to_outbox_event;
+use anyhow::Result as AnyResult;
+use chrono::{Duration, Utc};
+use diesel::dsl::now;
+use diesel::{Connection, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper};
+use uuid::Uuid;
+
+pub struct OutboxRepository<'a> {
+    connection: &'a mut PgConnection,
+}
+
+impl<'a> OutboxRepository<'a> {
+    pub fn new(connection: &'a mut PgConnection) -> OutboxRepository<'a> {
+        OutboxRepository { connection }
+    }
+}
+
+// writes an event to the outbox for the tenant the connection works for. Like audit records, this
+// is expected to be called inside the transaction of the change the event describes, so that the
+// event is only relayed if the change is committed
+pub(crate) fn append_outbox_event(connection: &mut PgConnection, event: &DomainEvent) -> AnyResult<()> {
+    diesel::insert_into(outbox_events)
+        .values(NewOutboxEventModel {
+            event_id: Uuid::now_v7(),
+            event_type: event.event_type(),
+            product_id: event.product_id().get(),
+            payload: serde_json::to_value(event)?,
+        })
+        .execute(connection)?;
+
+    Ok(())
+}
+
+// writes the events of a change to a product, given the product before and after it like audit
+// records are. An update writes `PriceChanged` next to `ProductUpdated` when the cost changed
+pub(crate) fn append_product_events(
+    connection: &mut PgConnection,
+    before: Option<&ProductModel>,
+    after: Option<&ProductModel>,
+) -> AnyResult<()> {
+    let events = match (before, after) {
+        (None, Some(created)) => vec![DomainEvent::ProductCreated {
+            product_id: ProductId::try_from(created.id)?,
+            public_id: created.public_id,
+            name: created.name.clone(),
+            slug: created.slug.clone(),
+            cost: created.cost,
+            active: created.active,
+        }],
+        (Some(existing), Some(updated)) => {
+            let mut events = vec![DomainEvent::ProductUpdated {
+                product_id: ProductId::try_from(updated.id)?,
+                public_id: updated.public_id,
+                name: updated.name.clone(),
+                slug: updated.slug.clone(),
+                cost: updated.cost,
+                active: updated.active,
+                version: u32::try_from(updated.version)?,
+            }];
+            if existing.cost != updated.cost {
+                events.push(DomainEvent::PriceChanged {
+                    product_id: ProductId::try_from(updated.id)?,
+                    public_id: updated.public_id,
+                    old_cost: existing.cost,
+                    new_cost: updated.cost,
+                });
+            }
+            events
+        }
+        (Some(deleted), None) => vec![DomainEvent::ProductDeleted {
+            product_id: ProductId::try_from(deleted.id)?,
+            public_id: deleted.public_id,
+        }],
+        (None, None) => Vec::new(),
+    };
+
+    for event in &events {
+        append_outbox_event(connection, event)?;
+    }
+
+    Ok(())
+}
+
+impl OutboxDatastore for OutboxRepository<'_> {
+    // the events are claimed in a short transaction of their own and delivered with none open, so a
+    // slow sink holds no locks. A relay that stops halfway through leaves the rest of its events to
+    // the next one once the lease is over, and events are delivered at least once
+    fn relay_pending_events(
+        &mut self,
+        limit: i64,
+        lease: Duration,
+        retry_policy: &RetryPolicy,
+        deliver: &mut dyn FnMut(&OutboxEvent) -> AnyResult<()>,
+    ) -> AnyResult<usize> {
+        let claimed_events = claim_due_events(self.connection, limit, lease)?;
+
+        let mut delivered = 0;
+        for claimed_event in claimed_events {
+            let event_row_id = claimed_event.id;
+            let claimed_until = claimed_event.next_attempt_at;
+            let event = map_outbox_event_model_to_outbox_event(claimed_event)?;
+            // the outcome only counts while the claim holds, an event taken over by another relay in
+            // the meantime is theirs to record
+            let claimed = outbox_events
+                .find(event_row_id)
+                .filter(outbox_event_next_attempt_at.eq(claimed_until));
+
+            match deliver(&event) {
+                Ok(()) => {
+                    diesel::update(claimed)
+                        .set(outbox_event_delivered_at.eq(now))
+                        .execute(self.connection)?;
+                    delivered += 1;
+                }
+                Err(error) => {
+                    let attempts = event.attempts() + 1;
+                    let attempted_at = Utc::now();
+                    let (next_attempt_at, dead_at) = match retry_policy.delay_after(attempts) {
+                        Some(delay) => (attempted_at + delay, None),
+                        None => (attempted_at, Some(attempted_at)),
+                    };
+
+                    diesel::update(claimed)
+                        .set((
+                            outbox_event_attempts.eq(i32::try_from(attempts)?),
+                            outbox_event_last_error.eq(format!("{:#}", error)),
+                            outbox_event_next_attempt_at.eq(next_attempt_at),
+                            outbox_event_dead_at.eq(dead_at),
+                        ))
+                        .execute(self.connection)?;
+                }
+            }
+        }
+
+        Ok(delivered)
+    }
+}
+
+// claims up to `limit` due events of every tenant, oldest first, by moving their next attempt to
+// the end of `lease` so that other relays leave them alone while they are delivered
+fn claim_due_events(connection: &mut PgConnection, limit: i64, lease: Duration) -> AnyResult<Vec<OutboxEventModel>> {
+    connection.transaction::<_, anyhow::Error, _>(|connection| {
+        let due_event_ids = outbox_events
+            .filter(outbox_event_delivered_at.is_null())
+            .filter(outbox_event_dead_at.is_null())
+            // attempts are scheduled by the clock of the relay, so they are due by that clock too
+            .filter(outbox_event_next_attempt_at.le(Utc::now()))
+            .order(outbox_event_id.asc())
+            .limit(limit)
+            .for_update()
+            .skip_locked()
+            .select(outbox_event_id)
+            .load::<i64>(connection)?;
+
+        let mut claimed_events = diesel::update(outbox_events.filter(outbox_event_id.eq_any(&due_event_ids)))
+            .set(outbox_event_next_attempt_at.eq(Utc::now() + lease))
+            .returning(OutboxEventModel::as_returning())
+            .get_results::<OutboxEventModel>(connection)?;
+        claimed_events.sort_by_key(|claimed_event| claimed_event.id);
+
+        Ok(claimed_events)
+    })
+}
+
+#[cfg(test)]
+mod outbox_repository_tests {
+    use crate::core::entities::domain_event::DomainEvent;
+    use crate::core::entities::product::Product;
+    use crate::core::entities::webhook::RetryPolicy;
+    use crate::core::ports::database::outbox_database::OutboxDatastore;
+    use crate::core::ports::database::product_database::ProductDatastore;
+    use crate::datastore::repositories::outbox_repository::OutboxRepository;
+    use crate::datastore::repositories::product_repository::ProductRepository;
+    use crate::establish_connection_test;
+    use anyhow::anyhow;
+    use chrono::Duration;
+    use diesel::Connection;
+
+    #[test]
+    fn test_product_writes_are_relayed_past_failing_events_until_those_are_dead() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let lease = Duration::minutes(5);
+            let retry_policy = RetryPolicy::new(2, Duration::zero(), Duration::zero());
+
+            // events written before this test are delivered first, so that only its own are left
+            OutboxRepository::new(conn)
+                .relay_pending_events(i64::MAX, lease, &retry_policy, &mut |_| Ok(()))
+                .expect("Error relaying events");
+
+            let mut product_repository = ProductRepository::new(conn);
+            let created_product = product_repository
+                .create_product(Product::new("boots".to_string(), 13.23, true, None))
+                .expect("Error creating product");
+            let product_id = created_product.id().unwrap();
+            product_repository
+                .update_product(
+                    product_id,
+                    Product::new("boots".to_string(), 11.0, true, Some(product_id)),
+                    created_product.version(),
+                )
+                .expect("Error updating product");
+
+            // the sink rejects the first event, which does not hold back the ones after it
+            let mut outbox_repository = OutboxRepository::new(conn);
+            let mut relayed = Vec::new();
+            let delivered = outbox_repository
+                .relay_pending_events(10, lease, &retry_policy, &mut |event| match event.event() {
+                    DomainEvent::ProductCreated { .. } => Err(anyhow!("the sink rejects it")),
+                    _ => {
+                        relayed.push(event.event().clone());
+                        Ok(())
+                    }
+                })
+                .expect("Error relaying events");
+
+            assert_eq!(2, delivered);
+            assert!(matches!(relayed[0], DomainEvent::ProductUpdated { version: 2, .. }));
+            assert_eq!(
+                DomainEvent::PriceChanged {
+                    product_id,
+                    public_id: created_product.public_id().unwrap(),
+                    old_cost: 13.23,
+                    new_cost: 11.0,
+                },
+                relayed[1]
+            );
+
+            // the failed event is attempted again until it runs out of attempts, and is left alone then
+            let mut retried = Vec::new();
+            let delivered = outbox_repository
+                .relay_pending_events(10, lease, &retry_policy, &mut |event| {
+                    retried.push((event.attempts(), event.event().clone()));
+                    Err(anyhow!("the sink rejects it"))
+                })
+                .expect("Error relaying events");
+
+            assert_eq!(0, delivered);
+            assert_eq!(1, retried.len());
+            assert_eq!(1, retried[0].0);
+            assert!(matches!(retried[0].1, DomainEvent::ProductCreated { cost, .. } if cost == 13.23));
+
+            let redelivered = outbox_repository
+                .relay_pending_events(10, lease, &retry_policy, &mut |_| Ok(()))
+                .expect("Error relaying events");
+
+            assert_eq!(0, redelivered);
+
+            Ok(())
+        })
+    }
+}
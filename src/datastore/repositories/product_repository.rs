@@ -1,206 +1,922 @@
+use crate::core::entities::audit_record::{AuditAction, AuditEntityType, SYSTEM_ACTOR};
 use crate::core::entities::complete_product::CompleteProduct;
+use crate::core::entities::domain_event::DomainEvent;
+use crate::core::entities::ids::{ProductId, VariantId};
 use crate::core::entities::product::Product;
+use crate::core::entities::product_import::{ImportAction, ImportedProduct};
+use crate::core::entities::product_reference::{slugify, ProductReference, ResolvedProduct};
+use crate::core::entities::product_revision::{
+    ProductRevision, ProductRevisionDiff, RevisionSelector,
+};
 use crate::core::entities::product_variant::ProductVariant;
 use crate::core::entities::variant::Variant;
-use crate::core::ports::database::product_database::ProductDatastore;
+use crate::core::entities::variant_value::VariantValue;
+use crate::core::ports::database::errors::DatastoreError;
+use crate::core::ports::database::product_database::{ProductDatastore, ProductWithVariants};
+use crate::core::ports::database::revision_database::ProductRevisionDatastore;
 use crate::core::ports::database::utils::ListQueryParams;
 use crate::datastore::models::product_models::{NewProductModel, ProductModel};
+use crate::datastore::models::revision_models::VariantSnapshotModel;
+use crate::datastore::models::schema::product_slug_redirects;
 use crate::datastore::models::schema::product_variants::dsl::{
-    product_id as product_variant_product_id, product_variants, value as product_variant_value,
-    variant_id as product_variant_variant_id,
+    id as product_variant_id, product_id as product_variant_product_id, product_variants,
+    value as product_variant_value,
+};
+use crate::datastore::models::schema::products::dsl::{
+    active as product_active, attributes as product_attributes, cost as product_cost,
+    description as product_description, external_key as product_external_key,
+    id as product_row_id, name as product_name, product_type_id as product_product_type_id, products,
+    public_id as product_public_id, slug as product_slug, stock_quantity as product_stock_quantity,
+    tenant_id as product_tenant_id, updated_at as product_updated_at, version as product_version,
+};
+use crate::datastore::models::schema::variants::dsl::{
+    name as variant_name, tenant_id as variant_tenant_id, variants,
 };
-use crate::datastore::models::schema::products::dsl::products;
-use crate::datastore::models::schema::variants::dsl::{name as variant_name, variants};
-use crate::datastore::models::variant_models::{ProductVariantModel, VariantModel};
-use anyhow::Result as AnyResult;
-use diesel::row::NamedRow;
+use crate::datastore::models::variant_models::{
+    NewProductVariantModel, ProductVariantModel, VariantModel,
+};
+use crate::datastore::repositories::audit_repository::append_audit_record;
+use crate::datastore::repositories::bundle_repository::{deactivate_bundles_using, fetch_bundles_using};
+use crate::datastore::repositories::change_repository::record_product_change;
+use crate::datastore::repositories::outbox_repository::{append_outbox_event, append_product_events};
+use crate::datastore::repositories::product_type_repository::validate_product_attributes;
+use crate::datastore::repositories::revision_repository::{
+    fetch_product_revision, fetch_product_revisions, record_product_revision,
+};
+use crate::datastore::tenancy::current_tenant_id;
+use anyhow::{anyhow, Result as AnyResult};
+use diesel::dsl::now;
 use diesel::result::Error as DieselError;
 use diesel::{
-    BelongingToDsl, Connection, GroupedBy, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper,
+    BelongingToDsl, BoolExpressionMethods, Connection, ExpressionMethods, GroupedBy, OptionalExtension,
+    PgConnection, QueryDsl, RunQueryDsl, SelectableHelper, TextExpressionMethods,
+};
+use serde_json::Value;
+use uuid::Uuid;
+use crate::datastore::repositories::mappers::{
+    map_product_and_variant_model_to_variant, map_product_model_to_product,
+    map_product_revision_model_to_product_revision,
 };
-use crate::datastore::repositories::mappers::{map_product_and_variant_model_to_variant, map_product_model_to_product};
 
-struct ProductRepository<'a> {
+pub struct ProductRepository<'a> {
     connection: &'a mut PgConnection,
+    actor: String,
 }
 
 impl<'a> ProductRepository<'a> {
-    fn new(connection: &'a mut PgConnection) -> ProductRepository<'a> {
-        ProductRepository { connection }
+    pub fn new(connection: &'a mut PgConnection) -> ProductRepository<'a> {
+        ProductRepository {
+            connection,
+            actor: SYSTEM_ACTOR.to_string(),
+        }
+    }
+
+    // sets the actor that is recorded in the audit log for writes made through this repository
+    pub fn with_actor(mut self, actor: impl Into<String>) -> ProductRepository<'a> {
+        self.actor = actor.into();
+        self
     }
 }
 
 impl<'a> ProductRepository<'a> {
 
-    fn insert(&self, product: NewProductModel) -> Result<ProductModel, DieselError> {
-       diesel::insert_into(products)
-            .values(product)
-            .returning(ProductModel::as_returning())
-            .get_result(self.connection)
-    }
-
-    fn fetch_products(&self, params: ListQueryParams) -> Vec<ProductModel> {
+    // products are listed in the order they were created, so that pages follow on from each other
+    fn fetch_products(&mut self, params: ListQueryParams) -> Result<Vec<ProductModel>, DieselError> {
         products
+            .filter(product_tenant_id.eq(current_tenant_id()))
+            .order(product_row_id.asc())
             .limit(params.limit)
             .offset(params.offset)
+            .select(ProductModel::as_select())
             .load::<ProductModel>(self.connection)
-            .unwrap_or_else(|error| {
-                panic!("Error loading products: {:?}", error);
-            })
     }
 
-    fn fetch_product_by_id(&self, id: i32) -> Result<ProductModel, DieselError> {
+    fn fetch_product_by_id(&mut self, id: i32) -> Result<ProductModel, DieselError> {
         products
             .find(id)
+            .filter(product_tenant_id.eq(current_tenant_id()))
+            .select(ProductModel::as_select())
             .first(self.connection)
     }
 
-    fn fetch_product_with_variants(&self, id: i32) -> AnyResult<(ProductModel, Vec<ProductVariantModel, VariantModel>)> {
-        let existing_product = products.find(id).get_result::<ProductModel>(self.connection)?;
+    fn fetch_product_with_variants(&mut self, id: i32) -> AnyResult<(ProductModel, Vec<(ProductVariantModel, VariantModel)>)> {
+        let existing_product = products
+            .find(id)
+            .filter(product_tenant_id.eq(current_tenant_id()))
+            .select(ProductModel::as_select())
+            .get_result::<ProductModel>(self.connection)
+            .optional()?
+            .ok_or(DatastoreError::NotFound { entity: "Product", id })?;
 
         let variants_result = ProductVariantModel::belonging_to(&existing_product)
             .inner_join(variants)
-            .load::<(ProductVariant, Variant)>(self.connection)?;
+            .order(product_variant_id.asc())
+            .select((ProductVariantModel::as_select(), VariantModel::as_select()))
+            .load::<(ProductVariantModel, VariantModel)>(self.connection)?;
 
         Ok((existing_product, variants_result))
     }
 }
 
-impl ProductDatastore for ProductRepository<'_> {
-    fn create_product(&self, product: Product) -> AnyResult<Product> {
-        let new_product = NewProductModel {
-            name: (&product.name()).parse().unwrap(),
-            cost: &product.cost(),
-            active: &product.active(),
-        };
+// loads a product and locks it for the rest of the transaction, making sure it is still at the
+// version the caller last read
+fn lock_product_at_version(
+    connection: &mut PgConnection,
+    id: ProductId,
+    expected_version: u32,
+) -> AnyResult<ProductModel> {
+    let existing_product = products
+        .find(i32::from(id))
+        .filter(product_tenant_id.eq(current_tenant_id()))
+        .for_update()
+        .select(ProductModel::as_select())
+        .first::<ProductModel>(connection)
+        .optional()?
+        .ok_or(DatastoreError::NotFound { entity: "Product", id: id.get() })?;
+
+    let current_version = u32::try_from(existing_product.version)?;
+
+    if current_version != expected_version {
+        return Err(DatastoreError::VersionConflict {
+            entity: "Product",
+            id: id.get(),
+            expected_version,
+            current_version,
+        }
+        .into());
+    }
+
+    Ok(existing_product)
+}
+
+// picks the slug for a product named `name`: the slugified name when no other product goes by it
+// or redirects from it, otherwise the first free one with a `-2`, `-3`, ... suffix. `product_id` is
+// the product the slug is for, whose own slugs do not count as taken
+fn unique_product_slug(connection: &mut PgConnection, name: &str, product_id: Option<i32>) -> AnyResult<String> {
+    let base = slugify(name);
+    let pattern = format!("{}-%", base);
+    let owner_id = product_id.unwrap_or_default();
+
+    let mut taken_slugs = products
+        .filter(product_tenant_id.eq(current_tenant_id()))
+        .filter(product_slug.eq(&base).or(product_slug.like(&pattern)))
+        .filter(product_row_id.ne(owner_id))
+        .select(product_slug)
+        .load::<String>(connection)?;
+    taken_slugs.extend(
+        product_slug_redirects::table
+            .filter(product_slug_redirects::tenant_id.eq(current_tenant_id()))
+            .filter(product_slug_redirects::slug.eq(&base).or(product_slug_redirects::slug.like(&pattern)))
+            .filter(product_slug_redirects::product_id.ne(owner_id))
+            .select(product_slug_redirects::slug)
+            .load::<String>(connection)?,
+    );
+
+    let mut candidate = base.clone();
+    let mut suffix = 1;
+    while taken_slugs.contains(&candidate) {
+        suffix += 1;
+        candidate = format!("{}-{}", base, suffix);
+    }
+
+    Ok(candidate)
+}
+
+// the fields of a product that writes set, as opposed to the identifiers and version that the
+// datastore keeps
+struct ProductFields<'b> {
+    name: &'b str,
+    description: Option<&'b str>,
+    cost: f64,
+    active: bool,
+    product_type_id: Option<i32>,
+    attributes: Value,
+    stock_quantity: Option<i32>,
+}
+
+impl<'b> ProductFields<'b> {
+    fn of(product: &'b Product) -> AnyResult<ProductFields<'b>> {
+        Ok(ProductFields {
+            name: product.name(),
+            description: product.description(),
+            cost: product.cost(),
+            active: product.active(),
+            product_type_id: product.product_type_id().map(i32::from),
+            attributes: Value::Object(product.attributes().clone()),
+            stock_quantity: product.stock_quantity().map(i32::try_from).transpose()?,
+        })
+    }
+
+    // keeps the description, type, attributes and stock a product already has, for writes whose
+    // source does not carry them
+    fn keeping_details_of(mut self, existing_product: &'b ProductModel) -> ProductFields<'b> {
+        self.description = existing_product.description.as_deref();
+        self.product_type_id = existing_product.product_type_id;
+        self.attributes = existing_product.attributes.clone();
+        self.stock_quantity = existing_product.stock_quantity;
+        self
+    }
+
+    fn validate(&self, connection: &mut PgConnection) -> AnyResult<()> {
+        let attributes = self.attributes.as_object().ok_or_else(|| DatastoreError::InvalidAttributes {
+            entity: "Product",
+            problems: vec!["Attributes must be an object".to_string()],
+        })?;
+
+        validate_product_attributes(connection, self.product_type_id, attributes)
+    }
+}
+
+fn insert_product(connection: &mut PgConnection, actor: &str, product: &Product) -> AnyResult<ProductModel> {
+    let fields = ProductFields::of(product)?;
+    fields.validate(connection)?;
+
+    let name = product.name().to_string();
+    let external_key = product.external_key().map(String::from);
+    let slug = unique_product_slug(connection, &name, None)?;
+    let new_product = NewProductModel {
+        name: &name,
+        cost: &product.cost(),
+        active: &product.active(),
+        external_key: external_key.as_ref(),
+        public_id: Uuid::now_v7(),
+        slug: &slug,
+        description: product.description(),
+        product_type_id: fields.product_type_id,
+        attributes: &fields.attributes,
+        stock_quantity: fields.stock_quantity,
+    };
+
+    let created_product = diesel::insert_into(products)
+        .values(new_product)
+        .returning(ProductModel::as_returning())
+        .get_result(connection)?;
 
-        let result = diesel::insert_into(products)
-            .values(new_product)
-            .returning(ProductModel::as_returning())
-            .get_result(self.connection)?;
+    append_audit_record(
+        connection,
+        actor,
+        AuditEntityType::Product,
+        created_product.id,
+        AuditAction::Created,
+        None,
+        Some(&serde_json::to_value(&created_product)?),
+    )?;
+    append_product_events(connection, None, Some(&created_product))?;
 
-        Ok(Product::new(
-            result.name,
-            result.cost,
-            result.active,
-            Some(result.id as u32),
+    Ok(created_product)
+}
+
+// updates the fields of a product that has already been locked, moving it to its next version. A
+// rename that changes the slugified name gives the product a new slug, and the old one redirects to
+// it from then on. Deactivating a product deactivates the bundles that follow their components
+fn update_product_fields(
+    connection: &mut PgConnection,
+    actor: &str,
+    existing_product: &ProductModel,
+    fields: ProductFields,
+) -> AnyResult<ProductModel> {
+    fields.validate(connection)?;
+
+    let slug = if slugify(fields.name) == slugify(&existing_product.name) {
+        existing_product.slug.clone()
+    } else {
+        let renamed_slug = unique_product_slug(connection, fields.name, Some(existing_product.id))?;
+
+        diesel::delete(
+            product_slug_redirects::table
+                .filter(product_slug_redirects::tenant_id.eq(current_tenant_id()))
+                .filter(product_slug_redirects::slug.eq(&renamed_slug)),
+        )
+        .execute(connection)?;
+        diesel::insert_into(product_slug_redirects::table)
+            .values((
+                product_slug_redirects::slug.eq(&existing_product.slug),
+                product_slug_redirects::product_id.eq(existing_product.id),
+            ))
+            .execute(connection)?;
+
+        renamed_slug
+    };
+
+    let updated_product = diesel::update(products.find(existing_product.id))
+        .set((
+            product_name.eq(fields.name),
+            product_description.eq(fields.description),
+            product_cost.eq(fields.cost),
+            product_active.eq(fields.active),
+            product_product_type_id.eq(fields.product_type_id),
+            product_attributes.eq(&fields.attributes),
+            product_stock_quantity.eq(fields.stock_quantity),
+            product_version.eq(product_version + 1),
+            product_updated_at.eq(now),
+            product_slug.eq(&slug),
         ))
+        .returning(ProductModel::as_returning())
+        .get_result(connection)?;
+
+    append_audit_record(
+        connection,
+        actor,
+        AuditEntityType::Product,
+        updated_product.id,
+        AuditAction::Updated,
+        Some(&serde_json::to_value(existing_product)?),
+        Some(&serde_json::to_value(&updated_product)?),
+    )?;
+    append_product_events(connection, Some(existing_product), Some(&updated_product))?;
+
+    if existing_product.active && !updated_product.active {
+        deactivate_bundles_using(connection, actor, updated_product.id)?;
+    }
+
+    Ok(updated_product)
+}
+
+fn attach_product_variant(
+    connection: &mut PgConnection,
+    actor: &str,
+    product_id: i32,
+    variant_id: i32,
+    value: Option<String>,
+) -> AnyResult<()> {
+    let created_product_variant = diesel::insert_into(product_variants)
+        .values(NewProductVariantModel {
+            variant_id,
+            product_id,
+            value,
+        })
+        .returning(ProductVariantModel::as_returning())
+        .get_result(connection)?;
+
+    append_audit_record(
+        connection,
+        actor,
+        AuditEntityType::ProductVariant,
+        created_product_variant.id,
+        AuditAction::Created,
+        None,
+        Some(&serde_json::to_value(&created_product_variant)?),
+    )?;
+    let attached_variant_name = variants.find(variant_id).select(variant_name).first::<String>(connection)?;
+    append_outbox_event(
+        connection,
+        &DomainEvent::VariantAdded {
+            product_id: ProductId::try_from(product_id)?,
+            variant_id: VariantId::try_from(variant_id)?,
+            variant: attached_variant_name,
+            value: created_product_variant.value,
+        },
+    )?;
+
+    Ok(())
+}
+
+// attaches variant values to a product. If variants exists already, they are reused and if not, a
+// new one is created and that is attached to the product
+fn attach_variant_values(
+    connection: &mut PgConnection,
+    actor: &str,
+    product_id: i32,
+    variant_values: &[VariantValue],
+) -> AnyResult<()> {
+    for new_variant in variant_values {
+        let existing_variant = variants
+            .filter(variant_tenant_id.eq(current_tenant_id()))
+            .filter(variant_name.eq(new_variant.variant().name()))
+            .select(VariantModel::as_select())
+            .first::<VariantModel>(connection)
+            .optional()?;
+
+        let last_variant = match existing_variant {
+            Some(variant) => variant,
+            None => {
+                let created_variant = diesel::insert_into(variants)
+                    .values(variant_name.eq(new_variant.variant().name()))
+                    .returning(VariantModel::as_returning())
+                    .get_result(connection)?;
+
+                append_audit_record(
+                    connection,
+                    actor,
+                    AuditEntityType::Variant,
+                    created_variant.id,
+                    AuditAction::Created,
+                    None,
+                    Some(&serde_json::to_value(&created_variant)?),
+                )?;
+
+                created_variant
+            }
+        };
+
+        for new_value in new_variant.values() {
+            attach_product_variant(connection, actor, product_id, last_variant.id, new_value.clone())?;
+        }
+    }
+
+    Ok(())
+}
+
+// removes every variant value from a product, auditing each removed value
+fn detach_variant_values(connection: &mut PgConnection, actor: &str, product_id: i32) -> AnyResult<()> {
+    let removed_product_variants = diesel::delete(product_variants.filter(product_variant_product_id.eq(product_id)))
+        .returning(ProductVariantModel::as_returning())
+        .get_results(connection)?;
+
+    for removed_product_variant in removed_product_variants {
+        append_audit_record(
+            connection,
+            actor,
+            AuditEntityType::ProductVariant,
+            removed_product_variant.id,
+            AuditAction::Deleted,
+            Some(&serde_json::to_value(&removed_product_variant)?),
+            None,
+        )?;
+        append_outbox_event(
+            connection,
+            &DomainEvent::VariantRemoved {
+                product_id: ProductId::try_from(product_id)?,
+                variant_id: VariantId::try_from(removed_product_variant.variant_id)?,
+                value: removed_product_variant.value,
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+// whether a stored product already has the fields and variant values of a complete product
+fn matches_complete_product(
+    connection: &mut PgConnection,
+    existing_product: &ProductModel,
+    complete_product: &CompleteProduct,
+) -> AnyResult<bool> {
+    let product = complete_product.product();
+
+    if existing_product.name != product.name()
+        || existing_product.cost != product.cost()
+        || existing_product.active != product.active()
+    {
+        return Ok(false);
+    }
+
+    let mut existing_values = product_variants
+        .inner_join(variants)
+        .filter(product_variant_product_id.eq(existing_product.id))
+        .select((variant_name, product_variant_value))
+        .load::<(String, Option<String>)>(connection)?;
+    let mut new_values = complete_product
+        .variants()
+        .iter()
+        .flat_map(|variant_value| {
+            variant_value
+                .values()
+                .iter()
+                .map(|value| (variant_value.variant().name().to_string(), value.clone()))
+        })
+        .collect::<Vec<_>>();
+
+    existing_values.sort();
+    new_values.sort();
+
+    Ok(existing_values == new_values)
+}
+
+fn upsert_complete_product(
+    connection: &mut PgConnection,
+    actor: &str,
+    complete_product: &CompleteProduct,
+) -> AnyResult<ImportedProduct> {
+    let product = complete_product.product();
+
+    let existing_product = match product.external_key() {
+        Some(key) => products
+            .filter(product_tenant_id.eq(current_tenant_id()))
+            .filter(product_external_key.eq(key))
+            .for_update()
+            .select(ProductModel::as_select())
+            .first::<ProductModel>(connection)
+            .optional()?,
+        None => None,
+    };
+
+    match existing_product {
+        Some(existing_product) if matches_complete_product(connection, &existing_product, complete_product)? => {
+            Ok(ImportedProduct::new(ProductId::try_from(existing_product.id)?, ImportAction::Unchanged))
+        }
+        Some(existing_product) => {
+            // import rows do not carry descriptions, attributes or stock, so the stored ones are kept
+            let updated_product = update_product_fields(
+                connection,
+                actor,
+                &existing_product,
+                ProductFields::of(product)?.keeping_details_of(&existing_product),
+            )?;
+            detach_variant_values(connection, actor, updated_product.id)?;
+            attach_variant_values(connection, actor, updated_product.id, complete_product.variants())?;
+            record_product_revision(connection, updated_product.id, actor)?;
+            record_product_change(connection, updated_product.id)?;
+
+            Ok(ImportedProduct::new(ProductId::try_from(updated_product.id)?, ImportAction::Updated))
+        }
+        None => {
+            let created_product = insert_product(connection, actor, product)?;
+            attach_variant_values(connection, actor, created_product.id, complete_product.variants())?;
+            record_product_revision(connection, created_product.id, actor)?;
+            record_product_change(connection, created_product.id)?;
+
+            Ok(ImportedProduct::new(ProductId::try_from(created_product.id)?, ImportAction::Created))
+        }
+    }
+}
+
+impl ProductDatastore for ProductRepository<'_> {
+    fn create_product(&mut self, product: Product) -> AnyResult<Product> {
+        let actor = self.actor.as_str();
+
+        let result = self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            let created_product = insert_product(connection, actor, &product)?;
+
+            record_product_revision(connection, created_product.id, actor)?;
+            record_product_change(connection, created_product.id)?;
+
+            Ok(created_product)
+        })?;
+
+        map_product_model_to_product(result)
     }
 
     // creates a new product along with its variants. If variants exists already, they are skipped
     // and if not, a new one is created and that is attached to the product
-    fn create_complete_product(&self, complete_product: CompleteProduct) -> AnyResult<i32> {
-        self.connection.transaction(|| {
-            let created_product = diesel::insert_into(products)
-                .values(complete_product.product())
-                .returning(ProductModel::as_returning())
-                .get_result(self.connection)?;
-
-            for new_variant in complete_product.variants() {
-                let variant_results = variants
-                    .filter(variant_name.eq(&new_variant.variant().name()))
-                    .limit(1)
-                    .load::<VariantModel>(self.connection)?;
-
-                let last_variant = match variant_results.first() {
-                    Some(variant) => variant,
-                    None => {
-                        diesel::insert_into(variants)
-                            .values(variant_name.eq(&new_variant.variant().name()))
-                            .returning(VariantModel::as_returning())
-                            .get_result(self.connection)?;
-                    }
-                };
-
-                for new_value in new_variant.values() {
-                    diesel::insert_into(product_variants)
-                        .values((
-                            product_variant_product_id.eq(created_product.id),
-                            product_variant_variant_id.eq(last_variant.id),
-                            product_variant_value.eq(new_value),
-                        ))
-                        .execute(self.connection)?
+    fn create_complete_product(&mut self, complete_product: CompleteProduct) -> AnyResult<ProductId> {
+        let actor = self.actor.as_str();
+
+        self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            let created_product = insert_product(connection, actor, complete_product.product())?;
+
+            attach_variant_values(connection, actor, created_product.id, complete_product.variants())?;
+
+            record_product_revision(connection, created_product.id, actor)?;
+            record_product_change(connection, created_product.id)?;
+
+            Ok(ProductId::try_from(created_product.id)?)
+        })
+    }
+
+    fn upsert_complete_products(
+        &mut self,
+        complete_products: Vec<CompleteProduct>,
+        dry_run: bool,
+    ) -> AnyResult<Vec<AnyResult<ImportedProduct>>> {
+        let actor = self.actor.as_str();
+        let mut results = Vec::with_capacity(complete_products.len());
+
+        let outcome = self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            for complete_product in &complete_products {
+                results.push(connection.transaction::<_, anyhow::Error, _>(|connection| {
+                    upsert_complete_product(connection, actor, complete_product)
+                }));
+            }
+
+            // a dry run goes through every write so that the results are accurate, then rolls the
+            // whole batch back
+            if dry_run {
+                return Err(DieselError::RollbackTransaction.into());
+            }
+
+            Ok(())
+        });
+
+        match outcome {
+            Ok(()) => Ok(results),
+            Err(error) if dry_run && matches!(error.downcast_ref::<DieselError>(), Some(DieselError::RollbackTransaction)) => {
+                Ok(results)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    fn update_product(&mut self, id: ProductId, product: Product, expected_version: u32) -> AnyResult<Product> {
+        let actor = self.actor.as_str();
+
+        let result = self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            let existing_product = lock_product_at_version(connection, id, expected_version)?;
+
+            let updated_product =
+                update_product_fields(connection, actor, &existing_product, ProductFields::of(&product)?)?;
+
+            record_product_revision(connection, updated_product.id, actor)?;
+            record_product_change(connection, updated_product.id)?;
+
+            Ok(updated_product)
+        })?;
+
+        map_product_model_to_product(result)
+    }
+
+    fn delete_product(&mut self, id: ProductId, expected_version: u32) -> AnyResult<()> {
+        let actor = self.actor.as_str();
+
+        self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            let existing_product = lock_product_at_version(connection, id, expected_version)?;
+
+            let bundle_ids = fetch_bundles_using(connection, existing_product.id)?;
+            if !bundle_ids.is_empty() {
+                return Err(DatastoreError::InUse {
+                    entity: "Product",
+                    id: existing_product.id,
+                    used_by: format!(
+                        "bundles {}",
+                        bundle_ids.iter().map(i32::to_string).collect::<Vec<_>>().join(", ")
+                    ),
                 }
+                .into());
             }
 
-            created_product.id
+            detach_variant_values(connection, actor, existing_product.id)?;
+            record_product_change(connection, existing_product.id)?;
+
+            diesel::delete(products.find(existing_product.id)).execute(connection)?;
+
+            append_audit_record(
+                connection,
+                actor,
+                AuditEntityType::Product,
+                existing_product.id,
+                AuditAction::Deleted,
+                Some(&serde_json::to_value(&existing_product)?),
+                None,
+            )?;
+            append_product_events(connection, Some(&existing_product), None)?;
+
+            Ok(())
         })
     }
 
-    fn get_product(&self, id: u32) -> AnyResult<Product> {
-        let existing_product = self.fetch_product_by_id(id as i32).unwrap_or_else(|error| {
-            panic!("Error loading product: {:?}", error);
-        });
+    fn resolve_product(&mut self, reference: &ProductReference) -> AnyResult<ResolvedProduct> {
+        let unknown_reference = || DatastoreError::UnknownReference {
+            entity: "Product",
+            reference: reference.to_string(),
+        };
 
-        Ok(map_product_model_to_product(existing_product))
+        let current_product = match reference {
+            ProductReference::Id(id) => products
+                .find(i32::from(*id))
+                .filter(product_tenant_id.eq(current_tenant_id()))
+                .select((product_row_id, product_slug))
+                .first::<(i32, String)>(self.connection)
+                .optional()?
+                .ok_or(DatastoreError::NotFound { entity: "Product", id: id.get() })?,
+            ProductReference::PublicId(public_id) => products
+                .filter(product_tenant_id.eq(current_tenant_id()))
+                .filter(product_public_id.eq(public_id))
+                .select((product_row_id, product_slug))
+                .first::<(i32, String)>(self.connection)
+                .optional()?
+                .ok_or_else(unknown_reference)?,
+            ProductReference::Slug(slug) => {
+                let current_product = products
+                    .filter(product_tenant_id.eq(current_tenant_id()))
+                    .filter(product_slug.eq(slug))
+                    .select((product_row_id, product_slug))
+                    .first::<(i32, String)>(self.connection)
+                    .optional()?;
+
+                if let Some((id, slug)) = current_product {
+                    return Ok(ResolvedProduct::new(ProductId::try_from(id)?, slug, false));
+                }
+
+                let (id, slug) = product_slug_redirects::table
+                    .inner_join(products)
+                    .filter(product_slug_redirects::tenant_id.eq(current_tenant_id()))
+                    .filter(product_slug_redirects::slug.eq(slug))
+                    .select((product_row_id, product_slug))
+                    .first::<(i32, String)>(self.connection)
+                    .optional()?
+                    .ok_or_else(unknown_reference)?;
+
+                return Ok(ResolvedProduct::new(ProductId::try_from(id)?, slug, true));
+            }
+        };
+
+        let (id, slug) = current_product;
+
+        Ok(ResolvedProduct::new(ProductId::try_from(id)?, slug, false))
     }
 
-    fn get_product_with_variants(&self, id: u32) -> AnyResult<(Product, Vec<ProductVariant, Variant>)> {
-        let existing_product_with_variants = self.fetch_product_with_variants(id as i32).unwrap_or_else(|error| {
-            panic!("Error loading product with variants: {:?}", error);
-        });
+    fn find_product_by_external_key(&mut self, external_key: &str) -> AnyResult<ProductId> {
+        let id = products
+            .filter(product_tenant_id.eq(current_tenant_id()))
+            .filter(product_external_key.eq(external_key))
+            .select(product_row_id)
+            .first::<i32>(self.connection)
+            .optional()?
+            .ok_or_else(|| DatastoreError::UnknownReference {
+                entity: "Product",
+                reference: external_key.to_string(),
+            })?;
+
+        Ok(ProductId::try_from(id)?)
+    }
+
+    fn get_product(&mut self, id: ProductId) -> AnyResult<Product> {
+        let existing_product = self
+            .fetch_product_by_id(i32::from(id))
+            .optional()?
+            .ok_or(DatastoreError::NotFound { entity: "Product", id: id.get() })?;
+
+        map_product_model_to_product(existing_product)
+    }
+
+    fn get_product_with_variants(&mut self, id: ProductId) -> AnyResult<(Product, Vec<(ProductVariant, Variant)>)> {
+        let existing_product_with_variants = self.fetch_product_with_variants(i32::from(id))?;
 
         let (existing_product, existing_product_variants) = existing_product_with_variants;
 
-        let product = map_product_model_to_product(existing_product);
+        let product = map_product_model_to_product(existing_product)?;
 
         let mut variants_result: Vec<(ProductVariant, Variant)> = vec![];
 
         for (p, v) in existing_product_variants {
-            variants_result.push(map_product_and_variant_model_to_variant((p, v)));
+            variants_result.push(map_product_and_variant_model_to_variant((p, v))?);
         }
 
-        Ok(product, variants_result)
+        Ok((product, variants_result))
     }
 
-    fn list_products(&self, params: ListQueryParams) -> Vec<Product> {
-        let records = self.fetch_products(params);
-
-        let mut product_records: Vec<Product> = vec![];
-
-        for record in records {
-            let product = map_product_model_to_product(record);
+    fn list_products(&mut self, params: ListQueryParams) -> AnyResult<Vec<Product>> {
+        self.fetch_products(params)?
+            .into_iter()
+            .map(map_product_model_to_product)
+            .collect()
+    }
 
-            product_records.push(product);
-        }
+    fn list_variants_of_products(&mut self, ids: &[ProductId]) -> AnyResult<Vec<(ProductVariant, Variant)>> {
+        let product_ids = ids.iter().map(|id| id.get()).collect::<Vec<_>>();
 
-        product_records
+        product_variants
+            .inner_join(variants)
+            .filter(product_variant_product_id.eq_any(&product_ids))
+            .filter(variant_tenant_id.eq(current_tenant_id()))
+            .order(product_variant_id.asc())
+            .select((ProductVariantModel::as_select(), VariantModel::as_select()))
+            .load::<(ProductVariantModel, VariantModel)>(self.connection)?
+            .into_iter()
+            .map(map_product_and_variant_model_to_variant)
+            .collect()
     }
 
     fn list_products_with_variants(
-        &self,
-        params: ListQueryParams,
-    ) -> AnyResult<Vec<(Product, Vec<ProductVariant, Variant>)>> {
-        let product_records = self.fetch_products(params);
+        &mut self,
+        after: Option<ProductId>,
+        limit: i64,
+    ) -> AnyResult<Vec<ProductWithVariants>> {
+        let product_records = products
+            .filter(product_tenant_id.eq(current_tenant_id()))
+            .filter(product_row_id.gt(after.map(i32::from).unwrap_or(0)))
+            .order(product_row_id.asc())
+            .limit(limit)
+            .select(ProductModel::as_select())
+            .load::<ProductModel>(self.connection)?;
         let variants_result = ProductVariantModel::belonging_to(&product_records)
             .inner_join(variants)
+            .order(product_variant_id.asc())
+            .select((ProductVariantModel::as_select(), VariantModel::as_select()))
             .load::<(ProductVariantModel, VariantModel)>(self.connection)?
             .grouped_by(&product_records);
 
         let data = product_records
             .into_iter()
             .zip(variants_result)
-            .collect::<Vec<_>>();
+            .map(|(product, product_variants_result)| {
+                Ok((
+                    map_product_model_to_product(product)?,
+                    product_variants_result
+                        .into_iter()
+                        .map(map_product_and_variant_model_to_variant)
+                        .collect::<AnyResult<Vec<_>>>()?,
+                ))
+            })
+            .collect::<AnyResult<Vec<_>>>()?;
 
         Ok(data)
     }
 }
 
+impl ProductRevisionDatastore for ProductRepository<'_> {
+    fn get_product_at(&mut self, id: ProductId, selector: RevisionSelector) -> AnyResult<ProductRevision> {
+        let existing_revision = fetch_product_revision(self.connection, i32::from(id), selector)?
+            .ok_or_else(|| anyhow!("No revision of product {} matches {:?}", id, selector))?;
+
+        map_product_revision_model_to_product_revision(existing_revision)
+    }
+
+    fn list_product_revisions(&mut self, id: ProductId) -> AnyResult<Vec<ProductRevision>> {
+        fetch_product_revisions(self.connection, i32::from(id))?
+            .into_iter()
+            .map(map_product_revision_model_to_product_revision)
+            .collect()
+    }
+
+    fn diff_product_revisions(
+        &mut self,
+        id: ProductId,
+        from_revision: u32,
+        to_revision: u32,
+    ) -> AnyResult<ProductRevisionDiff> {
+        let from = self.get_product_at(id, RevisionSelector::Revision(from_revision))?;
+        let to = self.get_product_at(id, RevisionSelector::Revision(to_revision))?;
+
+        Ok(from.diff(&to))
+    }
+
+    // restores the product fields and variant set of an earlier revision. Variant values are
+    // replaced wholesale, so each removed and re-attached value is audited on its own
+    fn revert_product(
+        &mut self,
+        id: ProductId,
+        revision: u32,
+        expected_version: u32,
+    ) -> AnyResult<ProductRevision> {
+        let actor = self.actor.as_str();
+
+        let reverted_revision = self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            let target_revision =
+                fetch_product_revision(connection, i32::from(id), RevisionSelector::Revision(revision))?
+                    .ok_or_else(|| anyhow!("Revision {} of product {} does not exist", revision, id))?;
+            let target_variants =
+                serde_json::from_value::<Vec<VariantSnapshotModel>>(target_revision.variants)?;
+
+            let existing_product = lock_product_at_version(connection, id, expected_version)?;
+
+            // revisions do not cover descriptions, attributes or stock, so reverting keeps the current ones
+            let reverted_product = update_product_fields(
+                connection,
+                actor,
+                &existing_product,
+                ProductFields {
+                    name: &target_revision.name,
+                    description: None,
+                    cost: target_revision.cost,
+                    active: target_revision.active,
+                    product_type_id: None,
+                    attributes: Value::Null,
+                    stock_quantity: None,
+                }
+                .keeping_details_of(&existing_product),
+            )?;
+
+            detach_variant_values(connection, actor, reverted_product.id)?;
+
+            for target_variant in target_variants {
+                attach_product_variant(
+                    connection,
+                    actor,
+                    reverted_product.id,
+                    target_variant.variant_id,
+                    target_variant.value,
+                )?;
+            }
+
+            let reverted_revision = record_product_revision(connection, reverted_product.id, actor)?;
+            record_product_change(connection, reverted_product.id)?;
+
+            Ok(reverted_revision)
+        })?;
+
+        map_product_revision_model_to_product_revision(reverted_revision)
+    }
+}
+
 #[cfg(test)]
 mod product_repository_tests {
+    use crate::core::entities::audit_record::{AuditAction, AuditEntityType};
+    use crate::core::entities::complete_product::CompleteProduct;
     use crate::core::entities::product::Product;
-    use crate::core::ports::database::product_database::ProductDatastore;
+    use crate::core::entities::product_import::{ImportAction, ImportedProduct};
+    use crate::core::entities::product_reference::{ProductReference, ResolvedProduct};
+    use crate::core::entities::product_revision::RevisionSelector;
+    use crate::core::entities::tenant::Tenant;
+    use crate::core::entities::variant::Variant;
+    use crate::core::entities::variant_value::VariantValue;
+    use crate::core::ports::database::audit_database::AuditDatastore;
+    use crate::core::ports::database::errors::DatastoreError;
+    use crate::core::ports::database::product_database::{ProductDatastore, ProductWithVariants};
+    use crate::core::ports::database::revision_database::ProductRevisionDatastore;
     use crate::core::ports::database::utils::ListQueryParams;
+    use crate::datastore::repositories::audit_repository::AuditRepository;
     use crate::datastore::repositories::product_repository::ProductRepository;
+    use crate::datastore::tenancy::set_current_tenant;
+    use crate::establish_connection_test;
     use diesel::Connection;
-    use product_store::establish_connection_test;
 
     #[test]
     fn test_create_product() {
         let mut conn = establish_connection_test();
-        let product_repository = ProductRepository::new(&mut conn);
 
-        conn.test_transaction::<_, diesel::result::Error, _>(|_| {
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut product_repository = ProductRepository::new(conn);
             let product_name = String::from("boots");
             let product_cost = 1323.12;
             let is_product_active = true;
@@ -213,9 +929,422 @@ mod product_repository_tests {
             ));
 
             let actual_product = actual.unwrap();
-            let expected_id: u32 = 1;
+            let product_id = actual_product.id().expect("Created product has no id");
+
+            let stored_product = product_repository.get_product(product_id).expect("Error getting product");
+            assert_eq!(Some(product_id), stored_product.id());
+            assert_eq!("boots", stored_product.name());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_create_product_appends_audit_record() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let created_product = ProductRepository::new(conn)
+                .with_actor("merchandiser@example.com")
+                .create_product(Product::new("boots".to_string(), 13.23, true, None))
+                .expect("Error creating product");
+            let product_id = created_product.id().unwrap();
+
+            let history = AuditRepository::new(conn)
+                .get_entity_history(AuditEntityType::Product, product_id.get())
+                .expect("Error loading audit history");
+
+            assert_eq!(1, history.len());
+            assert_eq!("merchandiser@example.com", history[0].actor());
+            assert_eq!(AuditAction::Created, history[0].action());
+            assert_eq!(
+                serde_json::json!({"before": null, "after": 13.23}),
+                history[0].changes()["cost"]
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_update_product_rejects_stale_version() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut product_repository = ProductRepository::new(conn);
+            let created_product = product_repository
+                .create_product(Product::new("boots".to_string(), 13.23, true, None))
+                .expect("Error creating product");
+            let product_id = created_product.id().unwrap();
+
+            let updated_product = product_repository
+                .update_product(
+                    product_id,
+                    Product::new("boots".to_string(), 15.0, true, Some(product_id)),
+                    created_product.version(),
+                )
+                .expect("Error updating product");
+
+            assert_eq!(created_product.version() + 1, updated_product.version());
+            assert_eq!(15.0, updated_product.cost());
+
+            let stale_update = product_repository
+                .update_product(
+                    product_id,
+                    Product::new("boots".to_string(), 20.0, true, Some(product_id)),
+                    created_product.version(),
+                )
+                .unwrap_err();
+
+            assert_eq!(
+                Some(&DatastoreError::VersionConflict {
+                    entity: "Product",
+                    id: product_id.get(),
+                    expected_version: created_product.version(),
+                    current_version: updated_product.version(),
+                }),
+                stale_update.downcast_ref::<DatastoreError>()
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_renamed_product_redirects_from_old_slug() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut product_repository = ProductRepository::new(conn);
+            let first_product = product_repository
+                .create_product(Product::new("Trail Runner".to_string(), 80.0, true, None))
+                .expect("Error creating product");
+            let second_product = product_repository
+                .create_product(Product::new("trail runner!".to_string(), 85.0, true, None))
+                .expect("Error creating product");
+
+            assert_eq!(Some("trail-runner"), first_product.slug());
+            assert_eq!(Some("trail-runner-2"), second_product.slug());
+
+            let first_product_id = first_product.id().unwrap();
+            let renamed_product = product_repository
+                .update_product(
+                    first_product_id,
+                    Product::new("Trail Runner GTX".to_string(), 80.0, true, Some(first_product_id)),
+                    first_product.version(),
+                )
+                .expect("Error renaming product");
+
+            assert_eq!(Some("trail-runner-gtx"), renamed_product.slug());
+            assert_eq!(first_product.public_id(), renamed_product.public_id());
+
+            let resolved_product = product_repository
+                .resolve_product(&ProductReference::Slug("trail-runner".to_string()))
+                .expect("Error resolving old slug");
+
+            assert_eq!(
+                ResolvedProduct::new(first_product_id, "trail-runner-gtx".to_string(), true),
+                resolved_product
+            );
+
+            // the old slug keeps redirecting, so a new product does not get it
+            let third_product = product_repository
+                .create_product(Product::new("Trail Runner".to_string(), 90.0, true, None))
+                .expect("Error creating product");
+
+            assert_eq!(Some("trail-runner-3"), third_product.slug());
+
+            let resolved_by_public_id = product_repository
+                .resolve_product(&ProductReference::PublicId(third_product.public_id().unwrap()))
+                .expect("Error resolving public id");
+
+            assert_eq!(third_product.id().unwrap(), resolved_by_public_id.id());
+            assert!(!resolved_by_public_id.redirected());
+
+            let unknown_slug = product_repository
+                .resolve_product(&ProductReference::Slug("trail-runner-4".to_string()))
+                .unwrap_err();
+
+            assert_eq!(
+                Some(&DatastoreError::UnknownReference {
+                    entity: "Product",
+                    reference: "trail-runner-4".to_string(),
+                }),
+                unknown_slug.downcast_ref::<DatastoreError>()
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_revert_product_records_new_revision() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut product_repository = ProductRepository::new(conn);
+            let created_product = product_repository
+                .create_product(Product::new("boots".to_string(), 13.23, true, None))
+                .expect("Error creating product");
+            let product_id = created_product.id().unwrap();
+
+            let reverted = product_repository
+                .revert_product(product_id, 1, created_product.version())
+                .expect("Error reverting product");
 
-            assert_eq!(Some(expected_id), actual_product.id());
+            assert_eq!(2, reverted.revision());
+            assert_eq!("boots", reverted.product().name());
+
+            let revisions = product_repository
+                .list_product_revisions(product_id)
+                .expect("Error listing revisions");
+            let revision_numbers = revisions
+                .iter()
+                .map(|revision| revision.revision())
+                .collect::<Vec<_>>();
+
+            assert_eq!(vec![1, 2], revision_numbers);
+
+            let diff = product_repository
+                .diff_product_revisions(product_id, 1, 2)
+                .expect("Error diffing revisions");
+
+            assert_eq!(&serde_json::json!({}), diff.changes());
+
+            let current = product_repository
+                .get_product_at(product_id, RevisionSelector::At(chrono::Utc::now()))
+                .expect("Error loading product at the current time");
+
+            assert_eq!(2, current.revision());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_deleted_product_keeps_its_revisions() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut product_repository = ProductRepository::new(conn);
+            let created_product = product_repository
+                .create_product(Product::new("sandals".to_string(), 21.5, true, None))
+                .expect("Error creating product");
+            let product_id = created_product.id().unwrap();
+
+            product_repository
+                .delete_product(product_id, created_product.version())
+                .expect("Error deleting product");
+
+            let revisions = product_repository
+                .list_product_revisions(product_id)
+                .expect("Error listing revisions");
+
+            assert_eq!(1, revisions.len());
+            assert_eq!("sandals", revisions[0].product().name());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_upsert_complete_products_by_external_key() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut product_repository = ProductRepository::new(conn);
+            let sneakers = |cost: f64| {
+                CompleteProduct::new(
+                    Product::new("sneakers".to_string(), cost, true, None)
+                        .with_external_key(Some("sku-1".to_string())),
+                    vec![VariantValue::new(
+                        Variant::new("size".to_string(), None),
+                        vec![Some("40".to_string()), Some("41".to_string())],
+                    )],
+                )
+            };
+
+            let dry_run = product_repository
+                .upsert_complete_products(vec![sneakers(59.9)], true)
+                .expect("Error importing products");
+
+            assert_eq!(ImportAction::Created, dry_run[0].as_ref().unwrap().action());
+            assert!(product_repository
+                .list_products(ListQueryParams { limit: 10, offset: 0 })
+                .expect("Error listing products")
+                .is_empty());
+
+            let actions = |results: Vec<anyhow::Result<ImportedProduct>>| {
+                results
+                    .into_iter()
+                    .map(|result| result.unwrap().action())
+                    .collect::<Vec<_>>()
+            };
+
+            let created = product_repository
+                .upsert_complete_products(vec![sneakers(59.9)], false)
+                .expect("Error importing products");
+            let unchanged = product_repository
+                .upsert_complete_products(vec![sneakers(59.9)], false)
+                .expect("Error importing products");
+            let updated = product_repository
+                .upsert_complete_products(vec![sneakers(49.9)], false)
+                .expect("Error importing products");
+
+            assert_eq!(vec![ImportAction::Created], actions(created));
+            assert_eq!(vec![ImportAction::Unchanged], actions(unchanged));
+            assert_eq!(vec![ImportAction::Updated], actions(updated));
+
+            let stored_products = product_repository
+                .list_products(ListQueryParams { limit: 10, offset: 0 })
+                .expect("Error listing products");
+
+            assert_eq!(1, stored_products.len());
+            assert_eq!(49.9, stored_products[0].cost());
+            assert_eq!(Some("sku-1"), stored_products[0].external_key());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_tenants_only_see_their_own_products() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let acme = "acme".parse::<Tenant>().unwrap();
+            let globex = "globex".parse::<Tenant>().unwrap();
+            let trail_runner = || {
+                Product::new("Trail Runner".to_string(), 80.0, true, None).with_external_key(Some("sku-1".to_string()))
+            };
+
+            set_current_tenant(conn, &acme).expect("Error setting tenant");
+            let acme_product = ProductRepository::new(conn)
+                .create_product(trail_runner())
+                .expect("Error creating product");
+
+            // slugs and external keys are only unique within a tenant
+            set_current_tenant(conn, &globex).expect("Error setting tenant");
+            let mut product_repository = ProductRepository::new(conn);
+            let globex_product = product_repository
+                .create_product(trail_runner())
+                .expect("Error creating product");
+
+            assert_eq!(Some("trail-runner"), globex_product.slug());
+
+            let listed_ids = product_repository
+                .list_products(ListQueryParams { limit: 10, offset: 0 })
+                .expect("Error listing products")
+                .iter()
+                .map(|product| product.id())
+                .collect::<Vec<_>>();
+
+            assert_eq!(vec![globex_product.id()], listed_ids);
+
+            let other_tenants_product = product_repository
+                .get_product(acme_product.id().unwrap())
+                .unwrap_err();
+
+            assert!(matches!(
+                other_tenants_product.downcast_ref::<DatastoreError>(),
+                Some(DatastoreError::NotFound { .. })
+            ));
+
+            let resolved_product = product_repository
+                .resolve_product(&ProductReference::Slug("trail-runner".to_string()))
+                .expect("Error resolving slug");
+
+            assert_eq!(globex_product.id().unwrap(), resolved_product.id());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_list_variants_of_several_products_at_once() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut product_repository = ProductRepository::new(conn);
+            let mut create = |name: &str, sizes: Vec<&str>| {
+                product_repository
+                    .create_complete_product(CompleteProduct::new(
+                        Product::new(name.to_string(), 59.9, true, None),
+                        vec![VariantValue::new(
+                            Variant::new("size".to_string(), None),
+                            sizes.into_iter().map(|size| Some(size.to_string())).collect(),
+                        )],
+                    ))
+                    .expect("Error creating product")
+            };
+            let sneakers = create("sneakers", vec!["40", "41"]);
+            let boots = create("boots", vec!["42"]);
+            let sandals = create("sandals", vec!["38"]);
+
+            let listed_values = product_repository
+                .list_variants_of_products(&[sneakers, boots])
+                .expect("Error listing variants")
+                .iter()
+                .map(|(product_variant, variant)| {
+                    (
+                        product_variant.product_id(),
+                        variant.name().to_string(),
+                        product_variant.value().clone().unwrap(),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                vec![
+                    (sneakers, "size".to_string(), "40".to_string()),
+                    (sneakers, "size".to_string(), "41".to_string()),
+                    (boots, "size".to_string(), "42".to_string()),
+                ],
+                listed_values
+            );
+            assert!(!listed_values.iter().any(|(product_id, _, _)| *product_id == sandals));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_list_products_with_variants_picks_up_after_the_last_product() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut product_repository = ProductRepository::new(conn);
+            let mut create = |name: &str, size: &str| {
+                product_repository
+                    .create_complete_product(CompleteProduct::new(
+                        Product::new(name.to_string(), 59.9, true, None),
+                        vec![VariantValue::new(
+                            Variant::new("size".to_string(), None),
+                            vec![Some(size.to_string())],
+                        )],
+                    ))
+                    .expect("Error creating product")
+            };
+            let sneakers = create("sneakers", "40");
+            let boots = create("boots", "42");
+            let sandals = create("sandals", "38");
+
+            let listed = |page: Vec<ProductWithVariants>| {
+                page.into_iter()
+                    .map(|(product, product_variants)| {
+                        (product.id().unwrap(), product_variants[0].0.value().clone().unwrap())
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let first_page = product_repository
+                .list_products_with_variants(Some(sneakers), 1)
+                .expect("Error listing products");
+            assert_eq!(vec![(boots, "42".to_string())], listed(first_page));
+
+            let second_page = product_repository
+                .list_products_with_variants(Some(boots), 2)
+                .expect("Error listing products");
+            assert_eq!(vec![(sandals, "38".to_string())], listed(second_page));
 
             Ok(())
         })
@@ -286,7 +1415,7 @@ mod product_repository_tests {
     //         );
     //
     //         let actual_product = actual.unwrap();
-    //         let expected_id: u32 = 1;
+    //         let expected_id = ProductId::try_from(1).unwrap();
     //
     //         assert_eq!(Some(expected_id), actual_product);
     //
@@ -299,36 +1428,38 @@ mod product_repository_tests {
         use diesel::result::Error;
 
         let mut database_connection = establish_connection_test();
-        let product_repository = ProductRepository::new(&mut database_connection);
         let product_one = Product::new("boots".to_string(), 13.23, true, None);
 
         let product_two = Product::new("running shoes".to_string(), 10.99, true, None);
         let product_three = Product::new("running shoes".to_string(), 10.99, true, None);
 
-        database_connection.test_transaction::<_, Error, _>(|| {
-            product_repository
-                .create_product(product_one)
-                .expect("Error creating product");
-            product_repository
-                .create_product(product_two)
-                .expect("Should be able to created");
-            product_repository
-                .create_product(product_three)
-                .expect("Failed to insert product ");
+        database_connection.test_transaction::<_, Error, _>(|conn| {
+            // a tenant of its own, so that only the products created here are listed
+            set_current_tenant(conn, &"listing".parse::<Tenant>().unwrap()).expect("Error setting tenant");
+
+            let mut product_repository = ProductRepository::new(conn);
+            let created_products = vec![
+                product_repository
+                    .create_product(product_one)
+                    .expect("Error creating product"),
+                product_repository
+                    .create_product(product_two)
+                    .expect("Should be able to created"),
+                product_repository
+                    .create_product(product_three)
+                    .expect("Failed to insert product "),
+            ];
 
-            let actual_products = product_repository.list_products(ListQueryParams {
-                limit: 10,
-                offset: 10,
-            });
+            let actual_products = product_repository
+                .list_products(ListQueryParams {
+                    limit: 10,
+                    offset: 0,
+                })
+                .expect("Error listing products");
 
             assert_eq!(
                 serde_json::to_string(&actual_products).unwrap(),
-                serde_json::to_string(&vec![
-                    Product::new("boots".to_string(), 13.23, true, Some(1),),
-                    Product::new("high heels".to_string(), 20.99, true, Some(2),),
-                    Product::new("running shoes".to_string(), 10.99, true, Some(3),)
-                ])
-                .unwrap()
+                serde_json::to_string(&created_products).unwrap()
             );
 
             Ok(())
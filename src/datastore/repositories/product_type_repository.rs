@@ -0,0 +1,275 @@
+use crate::core::entities::audit_record::{AuditAction, AuditEntityType, SYSTEM_ACTOR};
+use crate::core::entities::ids::ProductTypeId;
+use crate::core::entities::product_type::ProductType;
+use crate::core::ports::database::errors::DatastoreError;
+use crate::core::ports::database::product_type_database::ProductTypeDatastore;
+use crate::datastore::models::product_type_models::{NewProductTypeModel, ProductTypeModel};
+use crate::datastore::models::schema::product_types;
+use crate::datastore::repositories::audit_repository::append_audit_record;
+use crate::datastore::repositories::mappers::map_product_type_model_to_product_type;
+use crate::datastore::tenancy::current_tenant_id;
+use anyhow::Result as AnyResult;
+use diesel::dsl::now;
+use diesel::{Connection, ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper};
+use serde_json::{Map, Value};
+
+pub struct ProductTypeRepository<'a> {
+    connection: &'a mut PgConnection,
+    actor: String,
+}
+
+impl<'a> ProductTypeRepository<'a> {
+    pub fn new(connection: &'a mut PgConnection) -> ProductTypeRepository<'a> {
+        ProductTypeRepository {
+            connection,
+            actor: SYSTEM_ACTOR.to_string(),
+        }
+    }
+
+    // sets the actor that is recorded in the audit log for writes made through this repository
+    pub fn with_actor(mut self, actor: impl Into<String>) -> ProductTypeRepository<'a> {
+        self.actor = actor.into();
+        self
+    }
+}
+
+fn fetch_product_type(connection: &mut PgConnection, id: i32) -> AnyResult<ProductTypeModel> {
+    Ok(product_types::table
+        .find(id)
+        .filter(product_types::tenant_id.eq(current_tenant_id()))
+        .select(ProductTypeModel::as_select())
+        .first::<ProductTypeModel>(connection)
+        .optional()?
+        .ok_or(DatastoreError::NotFound { entity: "Product type", id })?)
+}
+
+// makes sure no other product type goes by `name`, since names are unique
+fn ensure_unique_name(connection: &mut PgConnection, name: &str, product_type_id: Option<i32>) -> AnyResult<()> {
+    let taken = product_types::table
+        .filter(product_types::tenant_id.eq(current_tenant_id()))
+        .filter(product_types::name.eq(name))
+        .filter(product_types::id.ne(product_type_id.unwrap_or_default()))
+        .select(product_types::id)
+        .first::<i32>(connection)
+        .optional()?;
+
+    match taken {
+        Some(_) => Err(DatastoreError::AlreadyExists {
+            entity: "Product type",
+            reference: name.to_string(),
+        }
+        .into()),
+        None => Ok(()),
+    }
+}
+
+fn validated_schema(product_type: &ProductType) -> AnyResult<Value> {
+    product_type
+        .validate_schema()
+        .map_err(|problems| DatastoreError::InvalidAttributes { entity: "Product type", problems })?;
+
+    Ok(serde_json::to_value(product_type.attributes())?)
+}
+
+// checks the attributes of a product against the schema of its type. A product without a type
+// carries no attributes
+pub(crate) fn validate_product_attributes(
+    connection: &mut PgConnection,
+    product_type_id: Option<i32>,
+    attributes: &Map<String, Value>,
+) -> AnyResult<()> {
+    let problems = match product_type_id {
+        Some(product_type_id) => {
+            let product_type = map_product_type_model_to_product_type(fetch_product_type(connection, product_type_id)?)?;
+
+            product_type.validate_attributes(attributes).err()
+        }
+        None if !attributes.is_empty() => Some(vec!["Only products with a product type have attributes".to_string()]),
+        None => None,
+    };
+
+    match problems {
+        Some(problems) => Err(DatastoreError::InvalidAttributes { entity: "Product", problems }.into()),
+        None => Ok(()),
+    }
+}
+
+impl ProductTypeDatastore for ProductTypeRepository<'_> {
+    fn list_product_types(&mut self) -> AnyResult<Vec<ProductType>> {
+        product_types::table
+            .filter(product_types::tenant_id.eq(current_tenant_id()))
+            .order(product_types::name.asc())
+            .select(ProductTypeModel::as_select())
+            .load::<ProductTypeModel>(self.connection)?
+            .into_iter()
+            .map(map_product_type_model_to_product_type)
+            .collect()
+    }
+
+    fn find_product_types(&mut self, ids: &[ProductTypeId]) -> AnyResult<Vec<ProductType>> {
+        let product_type_ids = ids.iter().map(|id| id.get()).collect::<Vec<_>>();
+
+        product_types::table
+            .filter(product_types::tenant_id.eq(current_tenant_id()))
+            .filter(product_types::id.eq_any(&product_type_ids))
+            .order(product_types::name.asc())
+            .select(ProductTypeModel::as_select())
+            .load::<ProductTypeModel>(self.connection)?
+            .into_iter()
+            .map(map_product_type_model_to_product_type)
+            .collect()
+    }
+
+    fn get_product_type(&mut self, id: ProductTypeId) -> AnyResult<ProductType> {
+        map_product_type_model_to_product_type(fetch_product_type(self.connection, i32::from(id))?)
+    }
+
+    fn create_product_type(&mut self, product_type: ProductType) -> AnyResult<ProductType> {
+        let actor = self.actor.as_str();
+        let attribute_schema = validated_schema(&product_type)?;
+
+        let created_product_type = self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            ensure_unique_name(connection, product_type.name(), None)?;
+
+            let created_product_type = diesel::insert_into(product_types::table)
+                .values(NewProductTypeModel {
+                    name: product_type.name(),
+                    attribute_schema,
+                })
+                .returning(ProductTypeModel::as_returning())
+                .get_result(connection)?;
+
+            append_audit_record(
+                connection,
+                actor,
+                AuditEntityType::ProductType,
+                created_product_type.id,
+                AuditAction::Created,
+                None,
+                Some(&serde_json::to_value(&created_product_type)?),
+            )?;
+
+            Ok(created_product_type)
+        })?;
+
+        map_product_type_model_to_product_type(created_product_type)
+    }
+
+    fn update_product_type(&mut self, id: ProductTypeId, product_type: ProductType) -> AnyResult<ProductType> {
+        let actor = self.actor.as_str();
+        let attribute_schema = validated_schema(&product_type)?;
+
+        let updated_product_type = self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            let existing_product_type = fetch_product_type(connection, i32::from(id))?;
+            ensure_unique_name(connection, product_type.name(), Some(existing_product_type.id))?;
+
+            let updated_product_type = diesel::update(product_types::table.find(existing_product_type.id))
+                .set((
+                    product_types::name.eq(product_type.name()),
+                    product_types::attribute_schema.eq(attribute_schema),
+                    product_types::updated_at.eq(now),
+                ))
+                .returning(ProductTypeModel::as_returning())
+                .get_result(connection)?;
+
+            append_audit_record(
+                connection,
+                actor,
+                AuditEntityType::ProductType,
+                updated_product_type.id,
+                AuditAction::Updated,
+                Some(&serde_json::to_value(&existing_product_type)?),
+                Some(&serde_json::to_value(&updated_product_type)?),
+            )?;
+
+            Ok(updated_product_type)
+        })?;
+
+        map_product_type_model_to_product_type(updated_product_type)
+    }
+}
+
+#[cfg(test)]
+mod product_type_repository_tests {
+    use crate::core::entities::locale::LocaleFallback;
+    use crate::core::entities::product::Product;
+    use crate::core::entities::product_type::{AttributeDefinition, AttributeType, ProductType};
+    use crate::core::ports::database::errors::DatastoreError;
+    use crate::core::ports::database::product_database::ProductDatastore;
+    use crate::core::ports::database::product_type_database::ProductTypeDatastore;
+    use crate::core::ports::database::translation_database::TranslationDatastore;
+    use crate::core::ports::database::utils::{ListQueryParams, ProductFilter};
+    use crate::datastore::repositories::product_repository::ProductRepository;
+    use crate::datastore::repositories::product_type_repository::ProductTypeRepository;
+    use crate::datastore::repositories::translation_repository::TranslationRepository;
+    use crate::establish_connection_test;
+    use diesel::Connection;
+    use serde_json::{json, Map, Value};
+
+    fn attributes(value: Value) -> Map<String, Value> {
+        value.as_object().cloned().unwrap()
+    }
+
+    #[test]
+    fn test_product_attributes_are_validated_and_filterable() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let product_type_id = ProductTypeRepository::new(conn)
+                .create_product_type(ProductType::new(
+                    "Typed shoes".to_string(),
+                    vec![AttributeDefinition::new(
+                        "material".to_string(),
+                        AttributeType::String,
+                        true,
+                        Some(vec![json!("leather"), json!("canvas")]),
+                    )],
+                    None,
+                ))
+                .expect("Error creating product type")
+                .id();
+
+            let mut product_repository = ProductRepository::new(conn);
+            let error = product_repository
+                .create_product(
+                    Product::new("Suede loafers".to_string(), 90.0, true, None)
+                        .with_attributes(product_type_id, attributes(json!({"material": "suede"}))),
+                )
+                .expect_err("Product with a disallowed attribute value was created");
+
+            assert!(matches!(
+                error.downcast_ref::<DatastoreError>(),
+                Some(DatastoreError::InvalidAttributes { .. })
+            ));
+
+            let leather_id = product_repository
+                .create_product(
+                    Product::new("Leather loafers".to_string(), 95.0, true, None)
+                        .with_attributes(product_type_id, attributes(json!({"material": "leather"}))),
+                )
+                .expect("Error creating product")
+                .id();
+            product_repository
+                .create_product(
+                    Product::new("Canvas loafers".to_string(), 45.0, true, None)
+                        .with_attributes(product_type_id, attributes(json!({"material": "canvas"}))),
+                )
+                .expect("Error creating product");
+
+            let listed_products = TranslationRepository::new(conn)
+                .list_localized_products(
+                    ListQueryParams { limit: 10, offset: 0 },
+                    &ProductFilter {
+                        product_type_id,
+                        attributes: Some(attributes(json!({"material": "leather"}))),
+                    },
+                    &LocaleFallback::new(&[], "en".parse().unwrap()),
+                )
+                .expect("Error listing products");
+
+            assert_eq!(vec![leather_id], listed_products.iter().map(Product::id).collect::<Vec<_>>());
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,226 @@
+use crate::core::entities::audit_record::{AuditAction, AuditEntityType, SYSTEM_ACTOR};
+use crate::core::entities::ids::ProductId;
+use crate::core::entities::product_relation::{validate_relations, ProductRelation};
+use crate::core::ports::database::errors::DatastoreError;
+use crate::core::ports::database::relation_database::RelationDatastore;
+use crate::datastore::models::product_models::ProductModel;
+use crate::datastore::models::relation_models::ProductRelationModel;
+use crate::datastore::models::schema::{product_relations, products};
+use crate::datastore::repositories::audit_repository::append_audit_record;
+use crate::datastore::repositories::mappers::map_product_relation_model_to_product_relation;
+use crate::datastore::repositories::translation_repository::touch_product;
+use crate::datastore::tenancy::current_tenant_id;
+use anyhow::Result as AnyResult;
+use diesel::{Connection, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper};
+
+pub struct RelationRepository<'a> {
+    connection: &'a mut PgConnection,
+    actor: String,
+}
+
+impl<'a> RelationRepository<'a> {
+    pub fn new(connection: &'a mut PgConnection) -> RelationRepository<'a> {
+        RelationRepository {
+            connection,
+            actor: SYSTEM_ACTOR.to_string(),
+        }
+    }
+
+    // sets the actor that is recorded in the audit log for writes made through this repository
+    pub fn with_actor(mut self, actor: impl Into<String>) -> RelationRepository<'a> {
+        self.actor = actor.into();
+        self
+    }
+}
+
+fn fetch_product_relations(connection: &mut PgConnection, id: ProductId) -> AnyResult<Vec<ProductRelation>> {
+    product_relations::table
+        .filter(product_relations::product_id.eq(i32::from(id)))
+        .order((
+            product_relations::relation_type.asc(),
+            product_relations::position.asc(),
+            product_relations::related_product_id.asc(),
+        ))
+        .select(ProductRelationModel::as_select())
+        .load::<ProductRelationModel>(connection)?
+        .into_iter()
+        .map(map_product_relation_model_to_product_relation)
+        .collect()
+}
+
+// the active products linked from a product along with how they are linked, ordered by type and
+// position. Links to inactive products are left out
+pub(crate) fn fetch_related_product_models(
+    connection: &mut PgConnection,
+    id: ProductId,
+) -> AnyResult<Vec<(ProductRelationModel, ProductModel)>> {
+    Ok(product_relations::table
+        .inner_join(products::table)
+        .filter(products::tenant_id.eq(current_tenant_id()))
+        .filter(product_relations::product_id.eq(i32::from(id)))
+        .filter(products::active.eq(true))
+        .order((
+            product_relations::relation_type.asc(),
+            product_relations::position.asc(),
+            product_relations::related_product_id.asc(),
+        ))
+        .select((ProductRelationModel::as_select(), ProductModel::as_select()))
+        .load::<(ProductRelationModel, ProductModel)>(connection)?)
+}
+
+// checks the links on their own and that every linked product exists
+fn validate_product_relations(
+    connection: &mut PgConnection,
+    id: ProductId,
+    relations: &[ProductRelation],
+) -> AnyResult<()> {
+    let mut problems = validate_relations(id, relations).err().unwrap_or_default();
+
+    let related_product_ids = relations
+        .iter()
+        .map(|relation| i32::from(relation.product_id()))
+        .collect::<Vec<_>>();
+    let existing_product_ids = products::table
+        .filter(products::tenant_id.eq(current_tenant_id()))
+        .filter(products::id.eq_any(&related_product_ids))
+        .select(products::id)
+        .load::<i32>(connection)?;
+    for relation in relations {
+        if !existing_product_ids.contains(&i32::from(relation.product_id())) {
+            problems.push(format!("Product {} does not exist", relation.product_id()));
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(DatastoreError::InvalidRelations { id: id.get(), problems }.into())
+    }
+}
+
+impl RelationDatastore for RelationRepository<'_> {
+    fn list_product_relations(&mut self, id: ProductId) -> AnyResult<Vec<ProductRelation>> {
+        fetch_product_relations(self.connection, id)
+    }
+
+    fn set_product_relations(
+        &mut self,
+        id: ProductId,
+        relations: Vec<ProductRelation>,
+    ) -> AnyResult<Vec<ProductRelation>> {
+        let actor = self.actor.as_str();
+
+        self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            touch_product(connection, id)?;
+            validate_product_relations(connection, id, &relations)?;
+
+            let existing_relations = fetch_product_relations(connection, id)?;
+            diesel::delete(product_relations::table.filter(product_relations::product_id.eq(i32::from(id))))
+                .execute(connection)?;
+
+            let new_relations = relations
+                .iter()
+                .map(|relation| ProductRelationModel {
+                    product_id: i32::from(id),
+                    relation_type: relation.relation_type().as_str().to_string(),
+                    related_product_id: i32::from(relation.product_id()),
+                    position: relation.position(),
+                })
+                .collect::<Vec<_>>();
+            if !new_relations.is_empty() {
+                diesel::insert_into(product_relations::table)
+                    .values(new_relations)
+                    .execute(connection)?;
+            }
+
+            let saved_relations = fetch_product_relations(connection, id)?;
+            append_audit_record(
+                connection,
+                actor,
+                AuditEntityType::ProductRelations,
+                i32::from(id),
+                AuditAction::Updated,
+                Some(&serde_json::to_value(&existing_relations)?),
+                Some(&serde_json::to_value(&saved_relations)?),
+            )?;
+
+            Ok(saved_relations)
+        })
+    }
+}
+
+#[cfg(test)]
+mod relation_repository_tests {
+    use crate::core::entities::locale::LocaleFallback;
+    use crate::core::entities::product::Product;
+    use crate::core::entities::product_relation::{ProductRelation, RelationType};
+    use crate::core::ports::database::errors::DatastoreError;
+    use crate::core::ports::database::product_database::ProductDatastore;
+    use crate::core::ports::database::relation_database::RelationDatastore;
+    use crate::core::ports::database::translation_database::TranslationDatastore;
+    use crate::datastore::repositories::product_repository::ProductRepository;
+    use crate::datastore::repositories::relation_repository::RelationRepository;
+    use crate::datastore::repositories::translation_repository::TranslationRepository;
+    use crate::establish_connection_test;
+    use diesel::Connection;
+
+    #[test]
+    fn test_product_detail_leaves_out_inactive_related_products() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut product_repository = ProductRepository::new(conn);
+            let mut create_product = |name: &str, active: bool| {
+                product_repository
+                    .create_product(Product::new(name.to_string(), 50.0, active, None))
+                    .expect("Error creating product")
+                    .id()
+                    .unwrap()
+            };
+            let boots_id = create_product("Hiking boots", true);
+            let socks_id = create_product("Wool socks", true);
+            let laces_id = create_product("Spare laces", true);
+            let old_boots_id = create_product("Hiking boots, last season", false);
+
+            let mut relation_repository = RelationRepository::new(conn);
+            let saved_relations = relation_repository
+                .set_product_relations(
+                    boots_id,
+                    vec![
+                        ProductRelation::new(RelationType::CrossSell, socks_id, 2),
+                        ProductRelation::new(RelationType::CrossSell, laces_id, 1),
+                        ProductRelation::new(RelationType::Related, old_boots_id, 0),
+                    ],
+                )
+                .expect("Error setting relations");
+
+            assert_eq!(
+                vec![laces_id, socks_id, old_boots_id],
+                saved_relations.iter().map(ProductRelation::product_id).collect::<Vec<_>>()
+            );
+
+            let error = relation_repository
+                .set_product_relations(boots_id, vec![ProductRelation::new(RelationType::UpSell, boots_id, 0)])
+                .expect_err("A product was related to itself");
+            assert!(matches!(
+                error.downcast_ref::<DatastoreError>(),
+                Some(DatastoreError::InvalidRelations { .. })
+            ));
+
+            let detail = TranslationRepository::new(conn)
+                .get_product_detail(boots_id, &LocaleFallback::new(&[], "en".parse().unwrap()))
+                .expect("Error loading product detail");
+
+            assert_eq!(
+                vec![Some(laces_id), Some(socks_id)],
+                detail
+                    .related_products()
+                    .iter()
+                    .map(|related_product| related_product.product().id())
+                    .collect::<Vec<_>>()
+            );
+
+            Ok(())
+        })
+    }
+}
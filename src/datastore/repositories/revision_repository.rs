@@ -0,0 +1,92 @@
+use crate::core::entities::product_revision::RevisionSelector;
+use crate::datastore::models::product_models::ProductModel;
+use crate::datastore::models::revision_models::{
+    NewProductRevisionModel, ProductRevisionModel, VariantSnapshotModel,
+};
+use crate::datastore::models::schema::{product_revisions, product_variants, products, variants};
+use diesel::result::Error as DieselError;
+use diesel::{
+    ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper,
+};
+
+// snapshots the current state of a product and its variants as the product's next revision. This
+// is expected to be called inside the transaction of the write that changed the product, after the
+// write has been applied
+pub(crate) fn record_product_revision(
+    connection: &mut PgConnection,
+    product_id: i32,
+    actor: &str,
+) -> Result<ProductRevisionModel, DieselError> {
+    let product = products::table
+        .find(product_id)
+        .for_update()
+        .select(ProductModel::as_select())
+        .first::<ProductModel>(connection)?;
+
+    let variant_snapshots = product_variants::table
+        .inner_join(variants::table)
+        .filter(product_variants::product_id.eq(product_id))
+        .order(product_variants::id.asc())
+        .select((variants::id, variants::name, product_variants::value))
+        .load::<VariantSnapshotModel>(connection)?;
+
+    let latest_revision = product_revisions::table
+        .filter(product_revisions::product_id.eq(product_id))
+        .order(product_revisions::revision.desc())
+        .select(product_revisions::revision)
+        .first::<i32>(connection)
+        .optional()?;
+
+    let new_revision = NewProductRevisionModel {
+        product_id,
+        revision: latest_revision.unwrap_or(0) + 1,
+        name: &product.name,
+        cost: product.cost,
+        active: product.active,
+        variants: serde_json::to_value(&variant_snapshots)
+            .map_err(|error| DieselError::SerializationError(Box::new(error)))?,
+        actor,
+    };
+
+    diesel::insert_into(product_revisions::table)
+        .values(new_revision)
+        .returning(ProductRevisionModel::as_returning())
+        .get_result(connection)
+}
+
+// fetches the revision of a product matching the selector. Selecting by time returns the revision
+// that was current at that moment
+pub(crate) fn fetch_product_revision(
+    connection: &mut PgConnection,
+    product_id: i32,
+    selector: RevisionSelector,
+) -> Result<Option<ProductRevisionModel>, DieselError> {
+    let query = product_revisions::table
+        .filter(product_revisions::product_id.eq(product_id))
+        .select(ProductRevisionModel::as_select())
+        .into_boxed();
+
+    let query = match selector {
+        // revisions are stored as positive 32 bit integers, so a larger one cannot exist
+        RevisionSelector::Revision(revision) => match i32::try_from(revision) {
+            Ok(revision) => query.filter(product_revisions::revision.eq(revision)),
+            Err(_) => return Ok(None),
+        },
+        RevisionSelector::At(timestamp) => query
+            .filter(product_revisions::created_at.le(timestamp))
+            .order(product_revisions::revision.desc()),
+    };
+
+    query.first::<ProductRevisionModel>(connection).optional()
+}
+
+pub(crate) fn fetch_product_revisions(
+    connection: &mut PgConnection,
+    product_id: i32,
+) -> Result<Vec<ProductRevisionModel>, DieselError> {
+    product_revisions::table
+        .filter(product_revisions::product_id.eq(product_id))
+        .order(product_revisions::revision.asc())
+        .select(ProductRevisionModel::as_select())
+        .load::<ProductRevisionModel>(connection)
+}
@@ -0,0 +1,222 @@
+use crate::core::entities::audit_record::{AuditAction, AuditEntityType, SYSTEM_ACTOR};
+use crate::core::entities::role::Role;
+use crate::core::ports::database::errors::DatastoreError;
+use crate::core::ports::database::role_database::RoleDatastore;
+use crate::datastore::models::role_models::{NewRoleModel, RoleModel};
+use crate::datastore::models::schema::roles;
+use crate::datastore::repositories::audit_repository::append_audit_record;
+use crate::datastore::repositories::mappers::map_role_model_to_role;
+use crate::datastore::tenancy::current_tenant_id;
+use anyhow::Result as AnyResult;
+use diesel::dsl::now;
+use diesel::{Connection, ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper};
+
+pub struct RoleRepository<'a> {
+    connection: &'a mut PgConnection,
+    actor: String,
+}
+
+impl<'a> RoleRepository<'a> {
+    pub fn new(connection: &'a mut PgConnection) -> RoleRepository<'a> {
+        RoleRepository {
+            connection,
+            actor: SYSTEM_ACTOR.to_string(),
+        }
+    }
+
+    // sets the actor that is recorded in the audit log for writes made through this repository
+    pub fn with_actor(mut self, actor: impl Into<String>) -> RoleRepository<'a> {
+        self.actor = actor.into();
+        self
+    }
+}
+
+fn fetch_role_model(connection: &mut PgConnection, name: &str) -> AnyResult<RoleModel> {
+    Ok(roles::table
+        .filter(roles::tenant_id.eq(current_tenant_id()))
+        .filter(roles::name.eq(name))
+        .select(RoleModel::as_select())
+        .for_update()
+        .first::<RoleModel>(connection)
+        .optional()?
+        .ok_or_else(|| DatastoreError::UnknownReference {
+            entity: "Role",
+            reference: name.to_string(),
+        })?)
+}
+
+impl RoleDatastore for RoleRepository<'_> {
+    fn list_roles(&mut self) -> AnyResult<Vec<Role>> {
+        roles::table
+            .filter(roles::tenant_id.eq(current_tenant_id()))
+            .order(roles::name.asc())
+            .select(RoleModel::as_select())
+            .load::<RoleModel>(self.connection)?
+            .into_iter()
+            .map(map_role_model_to_role)
+            .collect()
+    }
+
+    fn find_roles(&mut self, names: &[String]) -> AnyResult<Vec<Role>> {
+        roles::table
+            .filter(roles::tenant_id.eq(current_tenant_id()))
+            .filter(roles::name.eq_any(names))
+            .order(roles::name.asc())
+            .select(RoleModel::as_select())
+            .load::<RoleModel>(self.connection)?
+            .into_iter()
+            .map(map_role_model_to_role)
+            .collect()
+    }
+
+    fn get_role(&mut self, name: &str) -> AnyResult<Role> {
+        map_role_model_to_role(fetch_role_model(self.connection, name)?)
+    }
+
+    fn save_role(&mut self, role: Role) -> AnyResult<Role> {
+        let actor = self.actor.as_str();
+        let permissions = role
+            .permissions()
+            .iter()
+            .map(|permission| permission.as_str().to_string())
+            .collect::<Vec<_>>();
+
+        let saved_role = self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            let existing_role = fetch_role_model(connection, role.name()).ok();
+
+            let saved_role = diesel::insert_into(roles::table)
+                .values(NewRoleModel {
+                    name: role.name(),
+                    permissions: &permissions,
+                })
+                .on_conflict((roles::tenant_id, roles::name))
+                .do_update()
+                .set((roles::permissions.eq(&permissions), roles::updated_at.eq(now)))
+                .returning(RoleModel::as_returning())
+                .get_result(connection)?;
+
+            append_audit_record(
+                connection,
+                actor,
+                AuditEntityType::Role,
+                saved_role.id,
+                if existing_role.is_some() {
+                    AuditAction::Updated
+                } else {
+                    AuditAction::Created
+                },
+                existing_role.map(|existing_role| serde_json::to_value(&existing_role)).transpose()?.as_ref(),
+                Some(&serde_json::to_value(&saved_role)?),
+            )?;
+
+            Ok(saved_role)
+        })?;
+
+        map_role_model_to_role(saved_role)
+    }
+
+    fn delete_role(&mut self, name: &str) -> AnyResult<()> {
+        let actor = self.actor.as_str();
+
+        self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            let existing_role = fetch_role_model(connection, name)?;
+            diesel::delete(roles::table.find(existing_role.id)).execute(connection)?;
+
+            append_audit_record(
+                connection,
+                actor,
+                AuditEntityType::Role,
+                existing_role.id,
+                AuditAction::Deleted,
+                Some(&serde_json::to_value(&existing_role)?),
+                None,
+            )?;
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod role_repository_tests {
+    use crate::core::entities::role::{Permission, Role};
+    use crate::core::ports::database::errors::DatastoreError;
+    use crate::core::ports::database::role_database::RoleDatastore;
+    use crate::core::entities::tenant::Tenant;
+    use crate::datastore::repositories::role_repository::RoleRepository;
+    use crate::datastore::tenancy::set_current_tenant;
+    use crate::establish_connection_test;
+    use diesel::Connection;
+
+    #[test]
+    fn test_roles_are_saved_found_and_deleted() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut repository = RoleRepository::new(conn);
+
+            repository
+                .save_role(Role::new("auditor".to_string(), vec![Permission::CatalogRead]))
+                .expect("Error creating role");
+            let saved_role = repository
+                .save_role(Role::new(
+                    "auditor".to_string(),
+                    vec![Permission::CatalogRead, Permission::InventoryAdjust],
+                ))
+                .expect("Error updating role");
+            assert_eq!(saved_role, repository.get_role("auditor").expect("Error reading role"));
+
+            let found_roles = repository
+                .find_roles(&["auditor".to_string(), "partner".to_string(), "nobody".to_string()])
+                .expect("Error finding roles");
+            assert_eq!(
+                vec!["auditor", "partner"],
+                found_roles.iter().map(Role::name).collect::<Vec<_>>()
+            );
+
+            repository.delete_role("auditor").expect("Error deleting role");
+            let error = repository.get_role("auditor").expect_err("A deleted role was found");
+            assert!(matches!(
+                error.downcast_ref::<DatastoreError>(),
+                Some(DatastoreError::UnknownReference { .. })
+            ));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_editing_a_role_only_changes_the_permissions_of_its_tenant() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let acme = "acme".parse::<Tenant>().unwrap();
+            let globex = "globex".parse::<Tenant>().unwrap();
+
+            set_current_tenant(conn, &globex).expect("Error setting tenant");
+            RoleRepository::new(conn)
+                .save_role(Role::new("auditor".to_string(), vec![Permission::CatalogRead]))
+                .expect("Error creating role");
+
+            // a role of the same name is another role in another tenant
+            set_current_tenant(conn, &acme).expect("Error setting tenant");
+            let mut repository = RoleRepository::new(conn);
+            repository
+                .save_role(Role::new("auditor".to_string(), vec![Permission::CatalogRead]))
+                .expect("Error creating role");
+            repository
+                .save_role(Role::new("auditor".to_string(), vec![Permission::Admin]))
+                .expect("Error updating role");
+            assert_eq!(&[Permission::Admin], repository.get_role("auditor").unwrap().permissions());
+
+            set_current_tenant(conn, &globex).expect("Error setting tenant");
+            let globex_roles = RoleRepository::new(conn)
+                .find_roles(&["auditor".to_string()])
+                .expect("Error finding roles");
+            assert_eq!(1, globex_roles.len());
+            assert_eq!(&[Permission::CatalogRead], globex_roles[0].permissions());
+
+            Ok(())
+        })
+    }
+}
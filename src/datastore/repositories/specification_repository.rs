@@ -0,0 +1,165 @@
+use crate::core::entities::audit_record::{AuditAction, AuditEntityType, SYSTEM_ACTOR};
+use crate::core::entities::ids::ProductId;
+use crate::core::entities::specification::Specification;
+use crate::core::ports::database::specification_database::SpecificationDatastore;
+use crate::datastore::models::schema::product_specifications;
+use crate::datastore::models::specification_models::ProductSpecificationModel;
+use crate::datastore::repositories::audit_repository::append_audit_record;
+use crate::datastore::repositories::mappers::map_product_specification_model_to_specification;
+use crate::datastore::repositories::translation_repository::touch_product;
+use anyhow::Result as AnyResult;
+use diesel::{Connection, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper};
+
+pub struct SpecificationRepository<'a> {
+    connection: &'a mut PgConnection,
+    actor: String,
+}
+
+impl<'a> SpecificationRepository<'a> {
+    pub fn new(connection: &'a mut PgConnection) -> SpecificationRepository<'a> {
+        SpecificationRepository {
+            connection,
+            actor: SYSTEM_ACTOR.to_string(),
+        }
+    }
+
+    // sets the actor that is recorded in the audit log for writes made through this repository
+    pub fn with_actor(mut self, actor: impl Into<String>) -> SpecificationRepository<'a> {
+        self.actor = actor.into();
+        self
+    }
+}
+
+pub(crate) fn fetch_product_specifications(
+    connection: &mut PgConnection,
+    id: ProductId,
+) -> AnyResult<Vec<Specification>> {
+    product_specifications::table
+        .filter(product_specifications::product_id.eq(i32::from(id)))
+        .order(product_specifications::position.asc())
+        .select(ProductSpecificationModel::as_select())
+        .load::<ProductSpecificationModel>(connection)?
+        .into_iter()
+        .map(map_product_specification_model_to_specification)
+        .collect()
+}
+
+impl SpecificationDatastore for SpecificationRepository<'_> {
+    fn list_product_specifications(&mut self, id: ProductId) -> AnyResult<Vec<Specification>> {
+        fetch_product_specifications(self.connection, id)
+    }
+
+    fn set_product_specifications(
+        &mut self,
+        id: ProductId,
+        specifications: Vec<Specification>,
+    ) -> AnyResult<Vec<Specification>> {
+        let actor = self.actor.as_str();
+
+        self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            touch_product(connection, id)?;
+
+            let existing_specifications = fetch_product_specifications(connection, id)?;
+            diesel::delete(product_specifications::table.filter(product_specifications::product_id.eq(i32::from(id))))
+                .execute(connection)?;
+
+            let new_specifications = specifications
+                .iter()
+                .enumerate()
+                .map(|(position, specification)| {
+                    Ok(ProductSpecificationModel {
+                        product_id: i32::from(id),
+                        position: i32::try_from(position)?,
+                        name: specification.name().to_string(),
+                        value: specification.value().to_string(),
+                    })
+                })
+                .collect::<AnyResult<Vec<_>>>()?;
+            if !new_specifications.is_empty() {
+                diesel::insert_into(product_specifications::table)
+                    .values(new_specifications)
+                    .execute(connection)?;
+            }
+
+            append_audit_record(
+                connection,
+                actor,
+                AuditEntityType::ProductSpecifications,
+                i32::from(id),
+                AuditAction::Updated,
+                Some(&serde_json::to_value(&existing_specifications)?),
+                Some(&serde_json::to_value(&specifications)?),
+            )?;
+
+            Ok(())
+        })?;
+
+        Ok(specifications)
+    }
+}
+
+#[cfg(test)]
+mod specification_repository_tests {
+    use crate::core::entities::locale::LocaleFallback;
+    use crate::core::entities::product::Product;
+    use crate::core::entities::specification::Specification;
+    use crate::core::ports::database::product_database::ProductDatastore;
+    use crate::core::ports::database::specification_database::SpecificationDatastore;
+    use crate::core::ports::database::translation_database::TranslationDatastore;
+    use crate::datastore::repositories::product_repository::ProductRepository;
+    use crate::datastore::repositories::specification_repository::SpecificationRepository;
+    use crate::datastore::repositories::translation_repository::TranslationRepository;
+    use crate::establish_connection_test;
+    use diesel::Connection;
+
+    fn specification(name: &str, value: &str) -> Specification {
+        Specification::new(name.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn test_set_product_specifications_replaces_them() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let product_id = ProductRepository::new(conn)
+                .create_product(
+                    Product::new("Trail boots".to_string(), 140.0, true, None)
+                        .with_description(Some("**Grippy** soles".to_string())),
+                )
+                .expect("Error creating product")
+                .id()
+                .unwrap();
+
+            let mut specification_repository = SpecificationRepository::new(conn);
+            specification_repository
+                .set_product_specifications(
+                    product_id,
+                    vec![specification("Weight", "1.2 kg"), specification("Upper", "Leather")],
+                )
+                .expect("Error setting specifications");
+            specification_repository
+                .set_product_specifications(
+                    product_id,
+                    vec![specification("Upper", "Suede"), specification("Sole", "Rubber")],
+                )
+                .expect("Error setting specifications");
+
+            assert_eq!(
+                vec![specification("Upper", "Suede"), specification("Sole", "Rubber")],
+                specification_repository
+                    .list_product_specifications(product_id)
+                    .expect("Error listing specifications")
+            );
+
+            let detail = TranslationRepository::new(conn)
+                .get_product_detail(product_id, &LocaleFallback::new(&[], "en".parse().unwrap()))
+                .expect("Error loading product detail");
+
+            assert_eq!(3, detail.product().version());
+            assert_eq!(Some("<p><strong>Grippy</strong> soles</p>\n"), detail.description_html());
+            assert_eq!("Suede", detail.specifications()[0].value());
+
+            Ok(())
+        })
+    }
+}
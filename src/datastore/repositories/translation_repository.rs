@@ -0,0 +1,658 @@
+use crate::core::entities::audit_record::{AuditAction, AuditEntityType, SYSTEM_ACTOR};
+use crate::core::entities::ids::{ProductId, VariantId};
+use crate::core::entities::locale::{Locale, LocaleFallback};
+use crate::core::entities::product::Product;
+use crate::core::entities::product_detail::{LocalizedVariant, LocalizedVariantValue, ProductDetail};
+use crate::core::entities::product_relation::RelatedProduct;
+use crate::core::entities::translation::{ProductTranslation, VariantTranslation};
+use crate::core::ports::database::errors::DatastoreError;
+use crate::core::ports::database::translation_database::TranslationDatastore;
+use crate::core::ports::database::utils::{ListQueryParams, ProductFilter};
+use crate::datastore::models::product_models::ProductModel;
+use crate::datastore::models::schema::{
+    product_translations, product_variants, products, variant_translations, variant_value_translations, variants,
+};
+use crate::datastore::models::translation_models::{
+    ProductTranslationModel, VariantTranslationModel, VariantValueTranslationModel,
+};
+use crate::datastore::repositories::audit_repository::append_audit_record;
+use crate::datastore::repositories::bundle_repository::fetch_bundle;
+use crate::datastore::repositories::change_repository::record_product_change;
+use crate::datastore::repositories::mappers::{
+    map_product_model_to_product, map_product_translation_model_to_product_translation,
+};
+use crate::datastore::repositories::relation_repository::fetch_related_product_models;
+use crate::datastore::repositories::specification_repository::fetch_product_specifications;
+use crate::datastore::tenancy::current_tenant_id;
+use anyhow::Result as AnyResult;
+use diesel::dsl::now;
+use diesel::upsert::excluded;
+use diesel::{
+    Connection, ExpressionMethods, OptionalExtension, PgConnection, PgJsonbExpressionMethods, QueryDsl, RunQueryDsl,
+    SelectableHelper,
+};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+pub struct TranslationRepository<'a> {
+    connection: &'a mut PgConnection,
+    actor: String,
+}
+
+impl<'a> TranslationRepository<'a> {
+    pub fn new(connection: &'a mut PgConnection) -> TranslationRepository<'a> {
+        TranslationRepository {
+            connection,
+            actor: SYSTEM_ACTOR.to_string(),
+        }
+    }
+
+    // sets the actor that is recorded in the audit log for writes made through this repository
+    pub fn with_actor(mut self, actor: impl Into<String>) -> TranslationRepository<'a> {
+        self.actor = actor.into();
+        self
+    }
+}
+
+fn locale_strings(locales: &[Locale]) -> Vec<&str> {
+    locales.iter().map(Locale::as_str).collect()
+}
+
+// moves a product to its next version after one of its translations changed, and to the end of the
+// changes feed so that consumers pick up the version
+pub(crate) fn touch_product(connection: &mut PgConnection, id: ProductId) -> AnyResult<()> {
+    let touched_rows = diesel::update(
+        products::table
+            .find(i32::from(id))
+            .filter(products::tenant_id.eq(current_tenant_id())),
+    )
+    .set((products::version.eq(products::version + 1), products::updated_at.eq(now)))
+    .execute(connection)?;
+
+    if touched_rows == 0 {
+        return Err(DatastoreError::NotFound { entity: "Product", id: id.get() }.into());
+    }
+    record_product_change(connection, i32::from(id))?;
+
+    Ok(())
+}
+
+fn fetch_product_translations(
+    connection: &mut PgConnection,
+    product_ids: &[i32],
+    locales: &[Locale],
+) -> AnyResult<Vec<ProductTranslationModel>> {
+    Ok(product_translations::table
+        .filter(product_translations::product_id.eq_any(product_ids))
+        .filter(product_translations::locale.eq_any(locale_strings(locales)))
+        .select(ProductTranslationModel::as_select())
+        .load::<ProductTranslationModel>(connection)?)
+}
+
+// the product with its name and description in the first locale along the chain that has them,
+// along with the locale the name is in
+fn localize_product(
+    product: Product,
+    translations: &[ProductTranslationModel],
+    fallback: &LocaleFallback,
+) -> (Product, Locale) {
+    let product_id = product.id().map(i32::from);
+    let translation = |locale: &Locale| {
+        translations
+            .iter()
+            .find(|translation| Some(translation.product_id) == product_id && translation.locale == locale.as_str())
+    };
+
+    let (name_locale, name) = fallback
+        .resolve(Some(product.name()), |locale| translation(locale).map(|translation| translation.name.as_str()))
+        .map(|(locale, name)| (locale.clone(), name.to_string()))
+        .unwrap_or_else(|| (fallback.default_locale().clone(), product.name().to_string()));
+    let description = fallback
+        .resolve(product.description(), |locale| {
+            translation(locale).and_then(|translation| translation.description.as_deref())
+        })
+        .map(|(_, description)| description.to_string());
+
+    (product.localized(name, description), name_locale)
+}
+
+impl TranslationDatastore for TranslationRepository<'_> {
+    fn list_product_translations(&mut self, id: ProductId) -> AnyResult<Vec<ProductTranslation>> {
+        product_translations::table
+            .filter(product_translations::product_id.eq(i32::from(id)))
+            .order(product_translations::locale.asc())
+            .select(ProductTranslationModel::as_select())
+            .load::<ProductTranslationModel>(self.connection)?
+            .into_iter()
+            .map(map_product_translation_model_to_product_translation)
+            .collect()
+    }
+
+    fn set_product_translation(
+        &mut self,
+        id: ProductId,
+        translation: ProductTranslation,
+    ) -> AnyResult<ProductTranslation> {
+        let actor = self.actor.as_str();
+
+        let saved_translation = self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            touch_product(connection, id)?;
+
+            let existing_translation = product_translations::table
+                .find((i32::from(id), translation.locale().as_str()))
+                .select(ProductTranslationModel::as_select())
+                .first::<ProductTranslationModel>(connection)
+                .optional()?;
+
+            let saved_translation = diesel::insert_into(product_translations::table)
+                .values(ProductTranslationModel {
+                    product_id: i32::from(id),
+                    locale: translation.locale().to_string(),
+                    name: translation.name().to_string(),
+                    description: translation.description().map(String::from),
+                })
+                .on_conflict((product_translations::product_id, product_translations::locale))
+                .do_update()
+                .set((
+                    product_translations::name.eq(excluded(product_translations::name)),
+                    product_translations::description.eq(excluded(product_translations::description)),
+                ))
+                .returning(ProductTranslationModel::as_returning())
+                .get_result(connection)?;
+
+            append_audit_record(
+                connection,
+                actor,
+                AuditEntityType::ProductTranslation,
+                i32::from(id),
+                if existing_translation.is_some() { AuditAction::Updated } else { AuditAction::Created },
+                existing_translation.map(serde_json::to_value).transpose()?.as_ref(),
+                Some(&serde_json::to_value(&saved_translation)?),
+            )?;
+
+            Ok(saved_translation)
+        })?;
+
+        map_product_translation_model_to_product_translation(saved_translation)
+    }
+
+    fn delete_product_translation(&mut self, id: ProductId, locale: &Locale) -> AnyResult<()> {
+        let actor = self.actor.as_str();
+
+        self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            let removed_translation = diesel::delete(product_translations::table.find((i32::from(id), locale.as_str())))
+                .returning(ProductTranslationModel::as_returning())
+                .get_result::<ProductTranslationModel>(connection)
+                .optional()?
+                .ok_or_else(|| DatastoreError::UnknownReference {
+                    entity: "Product translation",
+                    reference: format!("{}/{}", id, locale),
+                })?;
+
+            touch_product(connection, id)?;
+
+            append_audit_record(
+                connection,
+                actor,
+                AuditEntityType::ProductTranslation,
+                i32::from(id),
+                AuditAction::Deleted,
+                Some(&serde_json::to_value(&removed_translation)?),
+                None,
+            )?;
+
+            Ok(())
+        })
+    }
+
+    fn set_variant_translation(
+        &mut self,
+        id: VariantId,
+        translation: VariantTranslation,
+    ) -> AnyResult<VariantTranslation> {
+        let actor = self.actor.as_str();
+        let variant_id = i32::from(id);
+        let locale = translation.locale().as_str();
+
+        self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            variants::table
+                .find(variant_id)
+                .filter(variants::tenant_id.eq(current_tenant_id()))
+                .for_update()
+                .select(variants::id)
+                .first::<i32>(connection)
+                .optional()?
+                .ok_or(DatastoreError::NotFound { entity: "Variant", id: variant_id })?;
+
+            let existing_translation = variant_translations::table
+                .find((variant_id, locale))
+                .select(VariantTranslationModel::as_select())
+                .first::<VariantTranslationModel>(connection)
+                .optional()?;
+            let existing_values = diesel::delete(
+                variant_value_translations::table
+                    .filter(variant_value_translations::variant_id.eq(variant_id))
+                    .filter(variant_value_translations::locale.eq(locale)),
+            )
+            .returning(VariantValueTranslationModel::as_returning())
+            .get_results::<VariantValueTranslationModel>(connection)?;
+
+            let saved_translation = diesel::insert_into(variant_translations::table)
+                .values(VariantTranslationModel {
+                    variant_id,
+                    locale: locale.to_string(),
+                    display_name: translation.display_name().to_string(),
+                })
+                .on_conflict((variant_translations::variant_id, variant_translations::locale))
+                .do_update()
+                .set(variant_translations::display_name.eq(excluded(variant_translations::display_name)))
+                .returning(VariantTranslationModel::as_returning())
+                .get_result(connection)?;
+            let new_values = translation
+                .values()
+                .iter()
+                .map(|(value, display_value)| VariantValueTranslationModel {
+                    variant_id,
+                    value: value.clone(),
+                    locale: locale.to_string(),
+                    display_value: display_value.clone(),
+                })
+                .collect::<Vec<_>>();
+            let saved_values = if new_values.is_empty() {
+                Vec::new()
+            } else {
+                diesel::insert_into(variant_value_translations::table)
+                    .values(new_values)
+                    .returning(VariantValueTranslationModel::as_returning())
+                    .get_results::<VariantValueTranslationModel>(connection)?
+            };
+
+            let audited = |translation: &VariantTranslationModel, values: &[VariantValueTranslationModel]| {
+                serde_json::json!({
+                    "locale": translation.locale,
+                    "display_name": translation.display_name,
+                    "values": values
+                        .iter()
+                        .map(|value| (value.value.clone(), value.display_value.clone()))
+                        .collect::<BTreeMap<_, _>>(),
+                })
+            };
+
+            append_audit_record(
+                connection,
+                actor,
+                AuditEntityType::VariantTranslation,
+                variant_id,
+                if existing_translation.is_some() { AuditAction::Updated } else { AuditAction::Created },
+                existing_translation
+                    .as_ref()
+                    .map(|existing_translation| audited(existing_translation, &existing_values))
+                    .as_ref(),
+                Some(&audited(&saved_translation, &saved_values)),
+            )?;
+
+            // the products that use the variant show its translations, so they move to their next
+            // version too and cached copies of them are not served any longer
+            let product_ids = product_variants::table
+                .filter(product_variants::variant_id.eq(variant_id))
+                .filter(product_variants::tenant_id.eq(current_tenant_id()))
+                .select(product_variants::product_id)
+                .distinct()
+                .order(product_variants::product_id.asc())
+                .load::<i32>(connection)?;
+            for product_id in product_ids {
+                touch_product(connection, ProductId::try_from(product_id)?)?;
+            }
+
+            Ok(())
+        })?;
+
+        Ok(translation)
+    }
+
+    fn get_product_detail(&mut self, id: ProductId, fallback: &LocaleFallback) -> AnyResult<ProductDetail> {
+        let translated_locales = locale_strings(fallback.translated_locales());
+
+        let existing_product = products::table
+            .find(i32::from(id))
+            .filter(products::tenant_id.eq(current_tenant_id()))
+            .select(ProductModel::as_select())
+            .first::<ProductModel>(self.connection)
+            .optional()?
+            .ok_or(DatastoreError::NotFound { entity: "Product", id: id.get() })?;
+        let translations = fetch_product_translations(self.connection, &[i32::from(id)], fallback.translated_locales())?;
+        let (product, locale) = localize_product(map_product_model_to_product(existing_product)?, &translations, fallback);
+        let specifications = fetch_product_specifications(self.connection, id)?;
+        let bundle = fetch_bundle(self.connection, id, &product)?;
+
+        let related_product_models = fetch_related_product_models(self.connection, id)?;
+        let related_product_ids = related_product_models
+            .iter()
+            .map(|(relation, _)| relation.related_product_id)
+            .collect::<Vec<_>>();
+        let related_translations =
+            fetch_product_translations(self.connection, &related_product_ids, fallback.translated_locales())?;
+        let related_products = related_product_models
+            .into_iter()
+            .map(|(relation, related_product)| {
+                let (related_product, _) =
+                    localize_product(map_product_model_to_product(related_product)?, &related_translations, fallback);
+
+                Ok(RelatedProduct::new(relation.relation_type.parse()?, relation.position, related_product))
+            })
+            .collect::<AnyResult<Vec<_>>>()?;
+
+        let product_values = product_variants::table
+            .inner_join(variants::table)
+            .filter(product_variants::product_id.eq(i32::from(id)))
+            .order(product_variants::id.asc())
+            .select((variants::id, variants::name, product_variants::value))
+            .load::<(i32, String, Option<String>)>(self.connection)?;
+        let variant_ids = product_values.iter().map(|(variant_id, _, _)| *variant_id).collect::<Vec<_>>();
+
+        let display_names = variant_translations::table
+            .filter(variant_translations::variant_id.eq_any(&variant_ids))
+            .filter(variant_translations::locale.eq_any(&translated_locales))
+            .select(VariantTranslationModel::as_select())
+            .load::<VariantTranslationModel>(self.connection)?;
+        let display_values = variant_value_translations::table
+            .filter(variant_value_translations::variant_id.eq_any(&variant_ids))
+            .filter(variant_value_translations::locale.eq_any(&translated_locales))
+            .select(VariantValueTranslationModel::as_select())
+            .load::<VariantValueTranslationModel>(self.connection)?;
+
+        // variants are listed in the order their first value was attached to the product
+        let mut localized_variants: Vec<(i32, LocalizedVariant)> = Vec::new();
+        let mut variant_values: BTreeMap<i32, Vec<LocalizedVariantValue>> = BTreeMap::new();
+
+        for (variant_id, variant_name, value) in product_values {
+            if !localized_variants.iter().any(|(id, _)| *id == variant_id) {
+                let display_name = fallback
+                    .resolve(Some(&variant_name), |locale| {
+                        display_names
+                            .iter()
+                            .find(|translation| {
+                                translation.variant_id == variant_id && translation.locale == locale.as_str()
+                            })
+                            .map(|translation| translation.display_name.as_str())
+                    })
+                    .map(|(_, display_name)| display_name.to_string())
+                    .unwrap_or_else(|| variant_name.clone());
+
+                localized_variants.push((variant_id, LocalizedVariant::new(variant_name, display_name, Vec::new())));
+            }
+
+            let display_value = fallback
+                .resolve(value.as_deref(), |locale| {
+                    display_values
+                        .iter()
+                        .find(|translation| {
+                            translation.variant_id == variant_id
+                                && Some(translation.value.as_str()) == value.as_deref()
+                                && translation.locale == locale.as_str()
+                        })
+                        .map(|translation| translation.display_value.as_str())
+                })
+                .map(|(_, display_value)| display_value.to_string());
+
+            variant_values
+                .entry(variant_id)
+                .or_default()
+                .push(LocalizedVariantValue::new(value, display_value));
+        }
+
+        let variants = localized_variants
+            .into_iter()
+            .map(|(variant_id, variant)| {
+                LocalizedVariant::new(
+                    variant.name().to_string(),
+                    variant.display_name().to_string(),
+                    variant_values.remove(&variant_id).unwrap_or_default(),
+                )
+            })
+            .collect();
+
+        Ok(ProductDetail::new(product, locale, specifications, variants)
+            .with_related_products(related_products)
+            .with_bundle(bundle))
+    }
+
+    fn list_localized_products(
+        &mut self,
+        params: ListQueryParams,
+        filter: &ProductFilter,
+        fallback: &LocaleFallback,
+    ) -> AnyResult<Vec<Product>> {
+        let mut query = products::table
+            .filter(products::tenant_id.eq(current_tenant_id()))
+            .into_boxed();
+        if let Some(product_type_id) = filter.product_type_id {
+            query = query.filter(products::product_type_id.eq(i32::from(product_type_id)));
+        }
+        if let Some(attributes) = &filter.attributes {
+            query = query.filter(products::attributes.contains(Value::Object(attributes.clone())));
+        }
+
+        let product_records = query
+            .order(products::id.asc())
+            .limit(params.limit)
+            .offset(params.offset)
+            .select(ProductModel::as_select())
+            .load::<ProductModel>(self.connection)?;
+        let product_ids = product_records.iter().map(|product| product.id).collect::<Vec<_>>();
+        let translations = fetch_product_translations(self.connection, &product_ids, fallback.translated_locales())?;
+
+        product_records
+            .into_iter()
+            .map(|product| {
+                let (product, _) = localize_product(map_product_model_to_product(product)?, &translations, fallback);
+
+                Ok(product)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod translation_repository_tests {
+    use crate::core::entities::complete_product::CompleteProduct;
+    use crate::core::entities::locale::{Locale, LocaleFallback};
+    use crate::core::entities::product::Product;
+    use crate::core::entities::product_type::ProductType;
+    use crate::core::entities::translation::{ProductTranslation, VariantTranslation};
+    use crate::core::entities::variant::Variant;
+    use crate::core::entities::variant_value::VariantValue;
+    use crate::core::ports::database::product_database::ProductDatastore;
+    use crate::core::ports::database::product_type_database::ProductTypeDatastore;
+    use crate::core::ports::database::translation_database::TranslationDatastore;
+    use crate::core::ports::database::utils::{ListQueryParams, ProductFilter};
+    use crate::core::ports::database::variant_database::VariantDatastore;
+    use crate::datastore::repositories::product_repository::ProductRepository;
+    use crate::datastore::repositories::product_type_repository::ProductTypeRepository;
+    use crate::datastore::repositories::translation_repository::TranslationRepository;
+    use crate::datastore::repositories::variant_repository::VariantRepository;
+    use crate::establish_connection_test;
+    use diesel::Connection;
+    use std::collections::BTreeMap;
+
+    fn locale(tag: &str) -> Locale {
+        tag.parse().unwrap()
+    }
+
+    #[test]
+    fn test_product_detail_falls_back_along_chain() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let product_id = ProductRepository::new(conn)
+                .create_complete_product(CompleteProduct::new(
+                    Product::new("Hiking boots".to_string(), 120.0, true, None)
+                        .with_description(Some("Sturdy boots".to_string())),
+                    vec![VariantValue::new(
+                        Variant::new("translated colour".to_string(), None),
+                        vec![Some("red".to_string()), Some("blue".to_string())],
+                    )],
+                ))
+                .expect("Error creating product");
+            let variant_id = VariantRepository::new(conn)
+                .list_variants()
+                .expect("Error listing variants")
+                .into_iter()
+                .find(|(variant, _)| variant.name() == "translated colour")
+                .and_then(|(variant, _)| variant.id())
+                .unwrap();
+
+            let mut translation_repository = TranslationRepository::new(conn);
+            translation_repository
+                .set_product_translation(
+                    product_id,
+                    ProductTranslation::new(locale("de"), "Wanderschuhe".to_string(), None),
+                )
+                .expect("Error translating product");
+            translation_repository
+                .set_variant_translation(
+                    variant_id,
+                    VariantTranslation::new(
+                        locale("de"),
+                        "Farbe".to_string(),
+                        BTreeMap::from([("red".to_string(), "rot".to_string())]),
+                    ),
+                )
+                .expect("Error translating variant");
+
+            let detail = translation_repository
+                .get_product_detail(product_id, &LocaleFallback::new(&[locale("de-AT")], locale("en")))
+                .expect("Error loading product detail");
+
+            assert_eq!("de", detail.locale().as_str());
+            assert_eq!("Wanderschuhe", detail.product().name());
+            assert_eq!(Some("Sturdy boots"), detail.product().description());
+            assert_eq!("Farbe", detail.variants()[0].display_name());
+            assert_eq!(
+                vec![Some("rot"), Some("blue")],
+                detail.variants()[0]
+                    .values()
+                    .iter()
+                    .map(|value| value.display_value())
+                    .collect::<Vec<_>>()
+            );
+
+            let detail = translation_repository
+                .get_product_detail(product_id, &LocaleFallback::new(&[locale("fr")], locale("en")))
+                .expect("Error loading product detail");
+
+            assert_eq!("en", detail.locale().as_str());
+            assert_eq!("Hiking boots", detail.product().name());
+            assert_eq!("translated colour", detail.variants()[0].display_name());
+
+            let product = ProductRepository::new(conn).get_product(product_id).expect("Error loading product");
+
+            // one version for the product translation and one for the variant translation
+            assert_eq!("Hiking boots", product.name());
+            assert_eq!(3, product.version());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_translating_a_variant_moves_the_products_using_it_to_their_next_version() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut product_repository = ProductRepository::new(conn);
+            let size = || {
+                VariantValue::new(Variant::new("translated size".to_string(), None), vec![Some("M".to_string())])
+            };
+            let shirt_id = product_repository
+                .create_complete_product(CompleteProduct::new(
+                    Product::new("Shirt".to_string(), 20.0, true, None),
+                    vec![size()],
+                ))
+                .expect("Error creating product");
+            let jacket_id = product_repository
+                .create_complete_product(CompleteProduct::new(
+                    Product::new("Jacket".to_string(), 90.0, true, None),
+                    vec![size()],
+                ))
+                .expect("Error creating product");
+            let scarf_id = product_repository
+                .create_product(Product::new("Scarf".to_string(), 15.0, true, None))
+                .expect("Error creating product")
+                .id()
+                .unwrap();
+            let variant_id = VariantRepository::new(conn)
+                .list_variants()
+                .expect("Error listing variants")
+                .into_iter()
+                .find(|(variant, _)| variant.name() == "translated size")
+                .and_then(|(variant, _)| variant.id())
+                .unwrap();
+
+            TranslationRepository::new(conn)
+                .set_variant_translation(
+                    variant_id,
+                    VariantTranslation::new(locale("de"), "Größe".to_string(), BTreeMap::new()),
+                )
+                .expect("Error translating variant");
+
+            let mut product_repository = ProductRepository::new(conn);
+            let version = |product_repository: &mut ProductRepository, id| {
+                product_repository.get_product(id).expect("Error loading product").version()
+            };
+            assert_eq!(2, version(&mut product_repository, shirt_id));
+            assert_eq!(2, version(&mut product_repository, jacket_id));
+            assert_eq!(1, version(&mut product_repository, scarf_id));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_list_localized_products_pages_follow_on_from_each_other() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let product_type_id = ProductTypeRepository::new(conn)
+                .create_product_type(ProductType::new("Paged shoes".to_string(), vec![], None))
+                .expect("Error creating product type")
+                .id();
+
+            let mut product_repository = ProductRepository::new(conn);
+            let mut product_ids = ["Clogs", "Mules", "Pumps", "Wedges"]
+                .into_iter()
+                .map(|name| {
+                    product_repository
+                        .create_product(
+                            Product::new(name.to_string(), 50.0, true, None)
+                                .with_attributes(product_type_id, Default::default()),
+                        )
+                        .expect("Error creating product")
+                        .id()
+                })
+                .collect::<Vec<_>>();
+            product_ids.sort();
+
+            let mut translation_repository = TranslationRepository::new(conn);
+            let mut list_page = |offset| {
+                translation_repository
+                    .list_localized_products(
+                        ListQueryParams { limit: 2, offset },
+                        &ProductFilter { product_type_id, attributes: None },
+                        &LocaleFallback::new(&[], locale("en")),
+                    )
+                    .expect("Error listing products")
+                    .iter()
+                    .map(Product::id)
+                    .collect::<Vec<_>>()
+            };
+            let first_page = list_page(0);
+            let second_page = list_page(2);
+
+            assert_eq!(product_ids[..2], first_page[..]);
+            assert_eq!(product_ids[2..], second_page[..]);
+
+            Ok(())
+        })
+    }
+}
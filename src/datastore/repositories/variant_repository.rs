@@ -0,0 +1,265 @@
+use crate::core::entities::audit_record::{AuditAction, AuditEntityType, SYSTEM_ACTOR};
+use crate::core::entities::domain_event::DomainEvent;
+use crate::core::entities::ids::{ProductId, VariantId};
+use crate::core::entities::variant::Variant;
+use crate::core::ports::database::errors::DatastoreError;
+use crate::core::ports::database::variant_database::VariantDatastore;
+use crate::datastore::models::schema::{product_variants, products, variants};
+use crate::datastore::models::variant_models::{ProductVariantModel, VariantModel};
+use crate::datastore::repositories::audit_repository::append_audit_record;
+use crate::datastore::repositories::change_repository::record_product_change;
+use crate::datastore::repositories::mappers::map_variant_model_to_variant;
+use crate::datastore::repositories::outbox_repository::append_outbox_event;
+use crate::datastore::repositories::revision_repository::record_product_revision;
+use crate::datastore::tenancy::current_tenant_id;
+use anyhow::{bail, Result as AnyResult};
+use diesel::dsl::{count, now};
+use diesel::{
+    Connection, ExpressionMethods, NullableExpressionMethods, OptionalExtension, PgConnection,
+    PgExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper,
+};
+
+pub struct VariantRepository<'a> {
+    connection: &'a mut PgConnection,
+    actor: String,
+}
+
+impl<'a> VariantRepository<'a> {
+    pub fn new(connection: &'a mut PgConnection) -> VariantRepository<'a> {
+        VariantRepository {
+            connection,
+            actor: SYSTEM_ACTOR.to_string(),
+        }
+    }
+
+    // sets the actor that is recorded in the audit log for writes made through this repository
+    pub fn with_actor(mut self, actor: impl Into<String>) -> VariantRepository<'a> {
+        self.actor = actor.into();
+        self
+    }
+}
+
+fn lock_variant(connection: &mut PgConnection, id: VariantId) -> AnyResult<VariantModel> {
+    Ok(variants::table
+        .find(i32::from(id))
+        .filter(variants::tenant_id.eq(current_tenant_id()))
+        .for_update()
+        .select(VariantModel::as_select())
+        .first::<VariantModel>(connection)
+        .optional()?
+        .ok_or(DatastoreError::NotFound { entity: "Variant", id: id.get() })?)
+}
+
+impl VariantDatastore for VariantRepository<'_> {
+    fn list_variants(&mut self) -> AnyResult<Vec<(Variant, u32)>> {
+        let variants_with_usage = variants::table
+            .left_join(product_variants::table)
+            .filter(variants::tenant_id.eq(current_tenant_id()))
+            .group_by(variants::id)
+            .order(variants::name.asc())
+            .select((VariantModel::as_select(), count(product_variants::id.nullable())))
+            .load::<(VariantModel, i64)>(self.connection)?;
+
+        variants_with_usage
+            .into_iter()
+            .map(|(variant, usage)| Ok((map_variant_model_to_variant(variant)?, u32::try_from(usage)?)))
+            .collect()
+    }
+
+    fn merge_variants(&mut self, source_id: VariantId, target_id: VariantId) -> AnyResult<Vec<ProductId>> {
+        if source_id == target_id {
+            bail!("Cannot merge variant {} into itself", source_id);
+        }
+
+        let actor = self.actor.as_str();
+
+        self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            let source_variant = lock_variant(connection, source_id)?;
+            let target_variant = lock_variant(connection, target_id)?;
+
+            let mut affected_product_ids = product_variants::table
+                .filter(product_variants::variant_id.eq(source_variant.id))
+                .select(product_variants::product_id)
+                .distinct()
+                .load::<i32>(connection)?;
+            affected_product_ids.sort();
+
+            // products are locked before their values move, the same way every other write to a
+            // product locks it first
+            products::table
+                .filter(products::id.eq_any(&affected_product_ids))
+                .order(products::id.asc())
+                .for_update()
+                .select(products::id)
+                .load::<i32>(connection)?;
+
+            let source_values = product_variants::table
+                .filter(product_variants::variant_id.eq(source_variant.id))
+                .order(product_variants::id.asc())
+                .select(ProductVariantModel::as_select())
+                .load::<ProductVariantModel>(connection)?;
+
+            for source_value in source_values {
+                let product_id = ProductId::try_from(source_value.product_id)?;
+                let already_on_target = product_variants::table
+                    .filter(product_variants::product_id.eq(source_value.product_id))
+                    .filter(product_variants::variant_id.eq(target_variant.id))
+                    .filter(product_variants::value.is_not_distinct_from(&source_value.value))
+                    .select(product_variants::id)
+                    .first::<i32>(connection)
+                    .optional()?
+                    .is_some();
+
+                if already_on_target {
+                    diesel::delete(product_variants::table.find(source_value.id)).execute(connection)?;
+
+                    append_audit_record(
+                        connection,
+                        actor,
+                        AuditEntityType::ProductVariant,
+                        source_value.id,
+                        AuditAction::Deleted,
+                        Some(&serde_json::to_value(&source_value)?),
+                        None,
+                    )?;
+                    append_outbox_event(
+                        connection,
+                        &DomainEvent::VariantRemoved {
+                            product_id,
+                            variant_id: source_id,
+                            value: source_value.value,
+                        },
+                    )?;
+                } else {
+                    let moved_value = diesel::update(product_variants::table.find(source_value.id))
+                        .set(product_variants::variant_id.eq(target_variant.id))
+                        .returning(ProductVariantModel::as_returning())
+                        .get_result(connection)?;
+
+                    append_audit_record(
+                        connection,
+                        actor,
+                        AuditEntityType::ProductVariant,
+                        moved_value.id,
+                        AuditAction::Updated,
+                        Some(&serde_json::to_value(&source_value)?),
+                        Some(&serde_json::to_value(&moved_value)?),
+                    )?;
+                    // to a product a moved value is one that went away and one that came in
+                    append_outbox_event(
+                        connection,
+                        &DomainEvent::VariantRemoved {
+                            product_id,
+                            variant_id: source_id,
+                            value: source_value.value,
+                        },
+                    )?;
+                    append_outbox_event(
+                        connection,
+                        &DomainEvent::VariantAdded {
+                            product_id,
+                            variant_id: target_id,
+                            variant: target_variant.name.clone(),
+                            value: moved_value.value,
+                        },
+                    )?;
+                }
+            }
+
+            diesel::delete(variants::table.find(source_variant.id)).execute(connection)?;
+
+            append_audit_record(
+                connection,
+                actor,
+                AuditEntityType::Variant,
+                source_variant.id,
+                AuditAction::Deleted,
+                Some(&serde_json::to_value(&source_variant)?),
+                None,
+            )?;
+
+            for product_id in &affected_product_ids {
+                diesel::update(products::table.find(product_id))
+                    .set((products::version.eq(products::version + 1), products::updated_at.eq(now)))
+                    .execute(connection)?;
+
+                record_product_revision(connection, *product_id, actor)?;
+                record_product_change(connection, *product_id)?;
+            }
+
+            Ok(affected_product_ids
+                .into_iter()
+                .map(ProductId::try_from)
+                .collect::<Result<Vec<_>, _>>()?)
+        })
+    }
+}
+
+#[cfg(test)]
+mod variant_repository_tests {
+    use crate::core::entities::complete_product::CompleteProduct;
+    use crate::core::entities::product::Product;
+    use crate::core::entities::variant::Variant;
+    use crate::core::entities::variant_value::VariantValue;
+    use crate::core::ports::database::product_database::ProductDatastore;
+    use crate::core::ports::database::variant_database::VariantDatastore;
+    use crate::datastore::repositories::product_repository::ProductRepository;
+    use crate::datastore::repositories::variant_repository::VariantRepository;
+    use crate::establish_connection_test;
+    use diesel::Connection;
+
+    #[test]
+    fn test_merge_variants() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let product_id = ProductRepository::new(conn)
+                .create_complete_product(CompleteProduct::new(
+                    Product::new("boots".to_string(), 13.23, true, None),
+                    vec![
+                        VariantValue::new(
+                            Variant::new("size".to_string(), None),
+                            vec![Some("40".to_string()), Some("41".to_string())],
+                        ),
+                        VariantValue::new(
+                            Variant::new("Size".to_string(), None),
+                            vec![Some("41".to_string()), Some("42".to_string())],
+                        ),
+                    ],
+                ))
+                .expect("Error creating product");
+
+            let mut variant_repository = VariantRepository::new(conn);
+            let listed_variants = variant_repository.list_variants().expect("Error listing variants");
+            let variant_id = |name: &str| {
+                listed_variants
+                    .iter()
+                    .find(|(variant, _)| variant.name() == name)
+                    .and_then(|(variant, _)| variant.id())
+                    .unwrap()
+            };
+
+            let affected_products = variant_repository
+                .merge_variants(variant_id("Size"), variant_id("size"))
+                .expect("Error merging variants");
+
+            assert_eq!(vec![product_id], affected_products);
+
+            let merged_variants = variant_repository.list_variants().expect("Error listing variants");
+            let size_usage = merged_variants
+                .iter()
+                .map(|(variant, usage)| (variant.name().to_string(), *usage))
+                .collect::<Vec<_>>();
+
+            assert_eq!(vec![("size".to_string(), 3)], size_usage);
+
+            let merged_product = ProductRepository::new(conn)
+                .get_product(product_id)
+                .expect("Error loading product");
+
+            assert_eq!(2, merged_product.version());
+
+            Ok(())
+        })
+    }
+}
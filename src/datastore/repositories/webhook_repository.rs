@@ -0,0 +1,490 @@
+use crate::core::entities::audit_record::{AuditAction, AuditEntityType, SYSTEM_ACTOR};
+use crate::core::entities::domain_event::OutboxEvent;
+use crate::core::entities::ids::WebhookSubscriptionId;
+use crate::core::entities::tenant::Tenant;
+use crate::core::entities::webhook::{
+    DeliveryAttempt, DeliveryStatus, RetryPolicy, WebhookDelivery, WebhookSubscription,
+};
+use crate::core::ports::database::errors::DatastoreError;
+use crate::core::ports::database::utils::ListQueryParams;
+use crate::core::ports::database::webhook_database::WebhookDatastore;
+use crate::datastore::models::schema::{webhook_deliveries, webhook_subscriptions};
+use crate::datastore::models::webhook_models::{
+    NewWebhookDeliveryModel, NewWebhookSubscriptionModel, WebhookDeliveryModel, WebhookSubscriptionModel,
+};
+use crate::datastore::repositories::audit_repository::append_audit_record;
+use crate::datastore::repositories::mappers::{
+    map_webhook_delivery_model_to_webhook_delivery, map_webhook_subscription_model_to_webhook_subscription,
+};
+use crate::datastore::tenancy::{current_tenant_id, set_current_tenant};
+use anyhow::Result as AnyResult;
+use chrono::{Duration, Utc};
+use diesel::dsl::now;
+use diesel::{Connection, ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper};
+
+pub struct WebhookRepository<'a> {
+    connection: &'a mut PgConnection,
+    actor: String,
+}
+
+impl<'a> WebhookRepository<'a> {
+    pub fn new(connection: &'a mut PgConnection) -> WebhookRepository<'a> {
+        WebhookRepository {
+            connection,
+            actor: SYSTEM_ACTOR.to_string(),
+        }
+    }
+
+    // sets the actor that is recorded in the audit log for writes made through this repository
+    pub fn with_actor(mut self, actor: impl Into<String>) -> WebhookRepository<'a> {
+        self.actor = actor.into();
+        self
+    }
+}
+
+fn fetch_webhook_subscription_model(
+    connection: &mut PgConnection,
+    id: WebhookSubscriptionId,
+) -> AnyResult<WebhookSubscriptionModel> {
+    Ok(webhook_subscriptions::table
+        .find(id.get())
+        .filter(webhook_subscriptions::tenant_id.eq(current_tenant_id()))
+        .select(WebhookSubscriptionModel::as_select())
+        .first::<WebhookSubscriptionModel>(connection)
+        .optional()?
+        .ok_or(DatastoreError::NotFound {
+            entity: "Webhook subscription",
+            id: id.get(),
+        })?)
+}
+
+impl WebhookDatastore for WebhookRepository<'_> {
+    fn list_webhook_subscriptions(&mut self) -> AnyResult<Vec<WebhookSubscription>> {
+        webhook_subscriptions::table
+            .filter(webhook_subscriptions::tenant_id.eq(current_tenant_id()))
+            .order(webhook_subscriptions::id.asc())
+            .select(WebhookSubscriptionModel::as_select())
+            .load::<WebhookSubscriptionModel>(self.connection)?
+            .into_iter()
+            .map(map_webhook_subscription_model_to_webhook_subscription)
+            .collect()
+    }
+
+    fn get_webhook_subscription(&mut self, id: WebhookSubscriptionId) -> AnyResult<WebhookSubscription> {
+        map_webhook_subscription_model_to_webhook_subscription(fetch_webhook_subscription_model(self.connection, id)?)
+    }
+
+    fn create_webhook_subscription(
+        &mut self,
+        url: &str,
+        event_types: &[String],
+        secret: &str,
+    ) -> AnyResult<WebhookSubscription> {
+        let actor = self.actor.as_str();
+
+        let created_subscription = self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            let created_subscription = diesel::insert_into(webhook_subscriptions::table)
+                .values(NewWebhookSubscriptionModel {
+                    url,
+                    event_types,
+                    secret,
+                })
+                .returning(WebhookSubscriptionModel::as_returning())
+                .get_result(connection)?;
+
+            append_audit_record(
+                connection,
+                actor,
+                AuditEntityType::WebhookSubscription,
+                created_subscription.id,
+                AuditAction::Created,
+                None,
+                Some(&serde_json::to_value(&created_subscription)?),
+            )?;
+
+            Ok(created_subscription)
+        })?;
+
+        map_webhook_subscription_model_to_webhook_subscription(created_subscription)
+    }
+
+    fn update_webhook_subscription(
+        &mut self,
+        id: WebhookSubscriptionId,
+        url: &str,
+        event_types: &[String],
+        active: bool,
+    ) -> AnyResult<WebhookSubscription> {
+        let actor = self.actor.as_str();
+
+        let updated_subscription = self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            let existing_subscription = fetch_webhook_subscription_model(connection, id)?;
+
+            let updated_subscription = diesel::update(webhook_subscriptions::table.find(existing_subscription.id))
+                .set((
+                    webhook_subscriptions::url.eq(url),
+                    webhook_subscriptions::event_types.eq(event_types),
+                    webhook_subscriptions::active.eq(active),
+                ))
+                .returning(WebhookSubscriptionModel::as_returning())
+                .get_result(connection)?;
+
+            append_audit_record(
+                connection,
+                actor,
+                AuditEntityType::WebhookSubscription,
+                updated_subscription.id,
+                AuditAction::Updated,
+                Some(&serde_json::to_value(&existing_subscription)?),
+                Some(&serde_json::to_value(&updated_subscription)?),
+            )?;
+
+            Ok(updated_subscription)
+        })?;
+
+        map_webhook_subscription_model_to_webhook_subscription(updated_subscription)
+    }
+
+    fn delete_webhook_subscription(&mut self, id: WebhookSubscriptionId) -> AnyResult<()> {
+        let actor = self.actor.as_str();
+
+        self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            let existing_subscription = fetch_webhook_subscription_model(connection, id)?;
+            diesel::delete(webhook_subscriptions::table.find(existing_subscription.id)).execute(connection)?;
+
+            append_audit_record(
+                connection,
+                actor,
+                AuditEntityType::WebhookSubscription,
+                existing_subscription.id,
+                AuditAction::Deleted,
+                Some(&serde_json::to_value(&existing_subscription)?),
+                None,
+            )?;
+
+            Ok(())
+        })
+    }
+
+    fn list_webhook_deliveries(
+        &mut self,
+        subscription_id: WebhookSubscriptionId,
+        status: Option<DeliveryStatus>,
+        params: ListQueryParams,
+    ) -> AnyResult<Vec<WebhookDelivery>> {
+        // the subscription is looked up first so that the deliveries of another tenant's
+        // subscription are not found
+        fetch_webhook_subscription_model(self.connection, subscription_id)?;
+
+        let mut query = webhook_deliveries::table
+            .filter(webhook_deliveries::subscription_id.eq(subscription_id.get()))
+            .into_boxed();
+        if let Some(status) = status {
+            query = query.filter(webhook_deliveries::status.eq(status.as_str()));
+        }
+
+        query
+            .order(webhook_deliveries::id.desc())
+            .offset(params.offset)
+            .limit(params.limit)
+            .select(WebhookDeliveryModel::as_select())
+            .load::<WebhookDeliveryModel>(self.connection)?
+            .into_iter()
+            .map(map_webhook_delivery_model_to_webhook_delivery)
+            .collect()
+    }
+
+    fn retry_webhook_delivery(
+        &mut self,
+        subscription_id: WebhookSubscriptionId,
+        delivery_id: i64,
+    ) -> AnyResult<WebhookDelivery> {
+        self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            fetch_webhook_subscription_model(connection, subscription_id)?;
+
+            let retried_delivery = diesel::update(
+                webhook_deliveries::table
+                    .find(delivery_id)
+                    .filter(webhook_deliveries::subscription_id.eq(subscription_id.get())),
+            )
+            .set((
+                webhook_deliveries::status.eq(DeliveryStatus::Pending.as_str()),
+                webhook_deliveries::attempts.eq(0),
+                webhook_deliveries::next_attempt_at.eq(now),
+                webhook_deliveries::delivered_at.eq(None::<chrono::DateTime<Utc>>),
+            ))
+            .returning(WebhookDeliveryModel::as_returning())
+            .get_result(connection)
+            .optional()?
+            .ok_or_else(|| DatastoreError::UnknownReference {
+                entity: "Webhook delivery",
+                reference: delivery_id.to_string(),
+            })?;
+
+            map_webhook_delivery_model_to_webhook_delivery(retried_delivery)
+        })
+    }
+
+    fn enqueue_webhook_deliveries(&mut self, event: &OutboxEvent) -> AnyResult<usize> {
+        let payload = serde_json::to_value(event)?;
+
+        self.connection.transaction::<_, anyhow::Error, _>(|connection| {
+            let subscriptions = webhook_subscriptions::table
+                .filter(webhook_subscriptions::tenant_id.eq(current_tenant_id()))
+                .filter(webhook_subscriptions::active.eq(true))
+                .select(WebhookSubscriptionModel::as_select())
+                .load::<WebhookSubscriptionModel>(connection)?
+                .into_iter()
+                .map(map_webhook_subscription_model_to_webhook_subscription)
+                .collect::<AnyResult<Vec<_>>>()?;
+
+            let new_deliveries = subscriptions
+                .iter()
+                .filter(|subscription| subscription.wants(event.event().event_type()))
+                .map(|subscription| NewWebhookDeliveryModel {
+                    subscription_id: subscription.id().get(),
+                    event_id: event.event_id(),
+                    event_type: event.event().event_type(),
+                    payload: &payload,
+                })
+                .collect::<Vec<_>>();
+
+            Ok(diesel::insert_into(webhook_deliveries::table)
+                .values(&new_deliveries)
+                .on_conflict_do_nothing()
+                .execute(connection)?)
+        })
+    }
+
+    // the deliveries are claimed in a short transaction of their own and posted with none open, so
+    // a slow receiver holds no locks. Each attempt is then recorded on its own as the tenant the
+    // delivery belongs to
+    fn dispatch_due_webhook_deliveries(
+        &mut self,
+        limit: i64,
+        lease: Duration,
+        retry_policy: &RetryPolicy,
+        deliver: &mut dyn FnMut(&WebhookSubscription, &WebhookDelivery) -> DeliveryAttempt,
+    ) -> AnyResult<usize> {
+        let claimed_deliveries = claim_due_webhook_deliveries(self.connection, limit, lease)?;
+
+        let attempted = claimed_deliveries.len();
+        for claimed_delivery in claimed_deliveries {
+            set_current_tenant(self.connection, &claimed_delivery.tenant_id.parse::<Tenant>()?)?;
+            let subscription_id = WebhookSubscriptionId::try_from(claimed_delivery.subscription_id)?;
+            let claimed_until = claimed_delivery.next_attempt_at;
+            let delivery = map_webhook_delivery_model_to_webhook_delivery(claimed_delivery)?;
+
+            // a subscription that was turned off gets none of its pending deliveries
+            let subscription = fetch_webhook_subscription_model(self.connection, subscription_id)
+                .and_then(map_webhook_subscription_model_to_webhook_subscription)?;
+            let attempt = if subscription.active() {
+                deliver(&subscription, &delivery)
+            } else {
+                DeliveryAttempt::Failed {
+                    response_status: None,
+                    error: "The subscription is not active".to_string(),
+                }
+            };
+
+            let attempts = delivery.attempts() + 1;
+            let attempted_at = Utc::now();
+            let (status, next_attempt_at, response_status, error, delivered_at) = match attempt {
+                DeliveryAttempt::Delivered { response_status } => (
+                    DeliveryStatus::Delivered,
+                    attempted_at,
+                    Some(response_status),
+                    None,
+                    Some(attempted_at),
+                ),
+                DeliveryAttempt::Failed { response_status, error } => {
+                    let delay = retry_policy.delay_after(attempts).filter(|_| subscription.active());
+                    match delay {
+                        Some(delay) => (
+                            DeliveryStatus::Pending,
+                            attempted_at + delay,
+                            response_status,
+                            Some(error),
+                            None,
+                        ),
+                        None => (DeliveryStatus::Dead, attempted_at, response_status, Some(error), None),
+                    }
+                }
+            };
+
+            // the attempt only counts while the claim holds. A delivery that was retried by hand or
+            // taken over by another dispatcher in the meantime is theirs to record
+            diesel::update(
+                webhook_deliveries::table
+                    .find(delivery.id())
+                    .filter(webhook_deliveries::status.eq(DeliveryStatus::Pending.as_str()))
+                    .filter(webhook_deliveries::next_attempt_at.eq(claimed_until)),
+            )
+            .set((
+                webhook_deliveries::status.eq(status.as_str()),
+                webhook_deliveries::attempts.eq(i32::try_from(attempts)?),
+                webhook_deliveries::next_attempt_at.eq(next_attempt_at),
+                webhook_deliveries::last_attempt_at.eq(attempted_at),
+                webhook_deliveries::last_response_status.eq(response_status.map(i32::from)),
+                webhook_deliveries::last_error.eq(error),
+                webhook_deliveries::delivered_at.eq(delivered_at),
+            ))
+            .execute(self.connection)?;
+        }
+
+        Ok(attempted)
+    }
+}
+
+// claims up to `limit` due deliveries of every tenant by moving their next attempt to the end of
+// `lease`, so that other dispatchers leave them alone while they are posted. Should the dispatcher
+// stop before recording how they went, they are due again once the lease is over
+fn claim_due_webhook_deliveries(
+    connection: &mut PgConnection,
+    limit: i64,
+    lease: Duration,
+) -> AnyResult<Vec<WebhookDeliveryModel>> {
+    connection.transaction::<_, anyhow::Error, _>(|connection| {
+        let due_delivery_ids = webhook_deliveries::table
+            .filter(webhook_deliveries::status.eq(DeliveryStatus::Pending.as_str()))
+            .filter(webhook_deliveries::next_attempt_at.le(now))
+            .order(webhook_deliveries::next_attempt_at.asc())
+            .limit(limit)
+            .for_update()
+            .skip_locked()
+            .select(webhook_deliveries::id)
+            .load::<i64>(connection)?;
+
+        Ok(diesel::update(webhook_deliveries::table.filter(webhook_deliveries::id.eq_any(&due_delivery_ids)))
+            .set(webhook_deliveries::next_attempt_at.eq(Utc::now() + lease))
+            .returning(WebhookDeliveryModel::as_returning())
+            .get_results::<WebhookDeliveryModel>(connection)?)
+    })
+}
+
+#[cfg(test)]
+mod webhook_repository_tests {
+    use crate::core::entities::domain_event::{DomainEvent, OutboxEvent};
+    use crate::core::entities::ids::ProductId;
+    use crate::core::entities::tenant::Tenant;
+    use crate::core::entities::webhook::{DeliveryAttempt, DeliveryStatus, RetryPolicy};
+    use crate::core::ports::database::utils::ListQueryParams;
+    use crate::core::ports::database::webhook_database::WebhookDatastore;
+    use crate::datastore::models::webhook_models::WebhookDeliveryModel;
+    use crate::datastore::repositories::webhook_repository::{claim_due_webhook_deliveries, WebhookRepository};
+    use crate::establish_connection_test;
+    use chrono::{Duration, Utc};
+    use diesel::Connection;
+    use uuid::Uuid;
+
+    fn event(event: DomainEvent) -> OutboxEvent {
+        OutboxEvent::new(1, Uuid::now_v7(), Tenant::default(), Utc::now(), 0, event)
+    }
+
+    #[test]
+    fn test_failed_deliveries_back_off_until_dead_and_can_be_retried() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut repository = WebhookRepository::new(conn);
+            let subscription = repository
+                .create_webhook_subscription(
+                    "http://localhost:9000/hooks",
+                    &["PriceChanged".to_string()],
+                    "whsec_test",
+                )
+                .expect("Error creating subscription");
+            let product_id = ProductId::try_from(1).unwrap();
+
+            let price_changed = event(DomainEvent::PriceChanged {
+                product_id,
+                public_id: Uuid::now_v7(),
+                old_cost: 13.23,
+                new_cost: 11.0,
+            });
+            let product_deleted = event(DomainEvent::ProductDeleted {
+                product_id,
+                public_id: Uuid::now_v7(),
+            });
+            assert_eq!(1, repository.enqueue_webhook_deliveries(&price_changed).unwrap());
+            assert_eq!(0, repository.enqueue_webhook_deliveries(&price_changed).unwrap());
+            assert_eq!(0, repository.enqueue_webhook_deliveries(&product_deleted).unwrap());
+
+            let retry_policy = RetryPolicy::new(2, Duration::minutes(1), Duration::hours(1));
+            let lease = Duration::minutes(5);
+            let mut fail = |_: &_, _: &_| DeliveryAttempt::Failed {
+                response_status: Some(503),
+                error: "503 Service Unavailable".to_string(),
+            };
+            assert_eq!(1, repository.dispatch_due_webhook_deliveries(10, lease, &retry_policy, &mut fail).unwrap());
+            // the next attempt waits for the back off
+            assert_eq!(0, repository.dispatch_due_webhook_deliveries(10, lease, &retry_policy, &mut fail).unwrap());
+
+            let list = |repository: &mut WebhookRepository, status| {
+                repository
+                    .list_webhook_deliveries(subscription.id(), status, ListQueryParams { offset: 0, limit: 10 })
+                    .expect("Error listing deliveries")
+            };
+            let pending = list(&mut repository, Some(DeliveryStatus::Pending));
+            assert_eq!(1, pending.len());
+            assert_eq!(1, pending[0].attempts());
+            assert_eq!(Some(503), pending[0].last_response_status());
+            assert!(pending[0].next_attempt_at() > Utc::now() + Duration::seconds(30));
+
+            // retried by hand, the delivery fails for good once it runs out of attempts
+            let last_chance = RetryPolicy::new(1, Duration::minutes(1), Duration::hours(1));
+            repository.retry_webhook_delivery(subscription.id(), pending[0].id()).unwrap();
+            repository.dispatch_due_webhook_deliveries(10, lease, &last_chance, &mut fail).unwrap();
+            let dead = list(&mut repository, Some(DeliveryStatus::Dead));
+            assert_eq!(1, dead.len());
+
+            repository.retry_webhook_delivery(subscription.id(), dead[0].id()).unwrap();
+            let mut delivered_payloads = Vec::new();
+            repository
+                .dispatch_due_webhook_deliveries(10, lease, &retry_policy, &mut |delivered_to, delivery| {
+                    assert_eq!("whsec_test", delivered_to.secret());
+                    delivered_payloads.push(delivery.payload().clone());
+                    DeliveryAttempt::Delivered { response_status: 204 }
+                })
+                .unwrap();
+
+            let delivered = list(&mut repository, None);
+            assert_eq!(DeliveryStatus::Delivered, delivered[0].status());
+            assert_eq!(Some(204), delivered[0].last_response_status());
+            assert_eq!(price_changed.event_id(), delivered[0].event_id());
+            assert_eq!(vec![serde_json::to_value(&price_changed).unwrap()], delivered_payloads);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_claimed_deliveries_are_left_alone_until_their_lease_is_over() {
+        let mut conn = establish_connection_test();
+
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut repository = WebhookRepository::new(conn);
+            repository
+                .create_webhook_subscription("http://localhost:9000/hooks", &[], "whsec_test")
+                .expect("Error creating subscription");
+            let product_deleted = event(DomainEvent::ProductDeleted {
+                product_id: ProductId::try_from(1).unwrap(),
+                public_id: Uuid::now_v7(),
+            });
+            repository.enqueue_webhook_deliveries(&product_deleted).unwrap();
+            let claimed_event_ids = |claimed: Vec<WebhookDeliveryModel>| {
+                claimed.into_iter().map(|delivery| delivery.event_id).collect::<Vec<_>>()
+            };
+
+            // a lease that is already over stands for a dispatcher that stopped halfway through
+            let claimed = claim_due_webhook_deliveries(conn, 10, Duration::minutes(-1)).unwrap();
+            assert_eq!(vec![product_deleted.event_id()], claimed_event_ids(claimed));
+
+            let claimed = claim_due_webhook_deliveries(conn, 10, Duration::minutes(5)).unwrap();
+            assert_eq!(vec![product_deleted.event_id()], claimed_event_ids(claimed));
+            assert!(claim_due_webhook_deliveries(conn, 10, Duration::minutes(5)).unwrap().is_empty());
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,20 @@
+use crate::core::entities::tenant::Tenant;
+use anyhow::Result as AnyResult;
+use diesel::sql_types::Text;
+use diesel::{define_sql_function, PgConnection, RunQueryDsl};
+
+define_sql_function! {
+    // the tenant the connection works for, the default tenant unless `set_current_tenant` was
+    // called on it. Rows are written for this tenant and repositories only look at its rows
+    fn current_tenant_id() -> Text;
+}
+
+// makes a connection work for `tenant` until it is told otherwise. Pooled connections are reused
+// across requests, so this is called every time one is taken from the pool
+pub fn set_current_tenant(connection: &mut PgConnection, tenant: &Tenant) -> AnyResult<()> {
+    diesel::sql_query("SELECT set_config('app.tenant_id', $1, false)")
+        .bind::<Text, _>(tenant.as_str())
+        .execute(connection)?;
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use crate::core::entities::product_export::ExportedProduct;
+use crate::export::FeedWriter;
+use anyhow::Result as AnyResult;
+use std::io::Write;
+
+const PRODUCT_COLUMNS: [&str; 7] = ["id", "external_key", "name", "cost", "active", "updated_at", "image_urls"];
+const LIST_SEPARATOR: &str = "|";
+
+// writes one row per product, with a `variant:<name>` column per variant holding the product's
+// values for it separated by `|`, the same way the importer reads them
+pub struct CsvFeedWriter<W: Write> {
+    writer: csv::Writer<W>,
+    variant_names: Vec<String>,
+}
+
+impl<W: Write> CsvFeedWriter<W> {
+    pub fn new(writer: W, variant_names: Vec<String>) -> AnyResult<CsvFeedWriter<W>> {
+        let mut writer = csv::Writer::from_writer(writer);
+
+        let variant_columns = variant_names.iter().map(|name| format!("variant:{}", name));
+        writer.write_record(PRODUCT_COLUMNS.iter().map(|column| column.to_string()).chain(variant_columns))?;
+
+        Ok(CsvFeedWriter {
+            writer,
+            variant_names,
+        })
+    }
+}
+
+impl<W: Write> FeedWriter for CsvFeedWriter<W> {
+    fn write_product(&mut self, exported_product: &ExportedProduct) -> AnyResult<()> {
+        let product = exported_product.product();
+        let variant_values = exported_product.variant_values();
+
+        let mut record = vec![
+            product.id().map(|id| id.to_string()).unwrap_or_default(),
+            product.external_key().unwrap_or_default().to_string(),
+            product.name().to_string(),
+            product.cost().to_string(),
+            product.active().to_string(),
+            exported_product.updated_at().to_rfc3339(),
+            exported_product.image_urls().join(LIST_SEPARATOR),
+        ];
+        record.extend(self.variant_names.iter().map(|name| {
+            variant_values
+                .get(name.as_str())
+                .map(|values| values.join(LIST_SEPARATOR))
+                .unwrap_or_default()
+        }));
+
+        self.writer.write_record(record)?;
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> AnyResult<()> {
+        self.writer.flush()?;
+
+        Ok(())
+    }
+}
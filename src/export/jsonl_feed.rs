@@ -0,0 +1,60 @@
+use crate::core::entities::ids::ProductId;
+use crate::core::entities::product_export::ExportedProduct;
+use crate::export::FeedWriter;
+use anyhow::Result as AnyResult;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+// the JSON object written for each product, its fields match the ones the importer reads
+#[derive(Serialize)]
+struct ProductLine<'a> {
+    id: Option<ProductId>,
+    external_key: Option<&'a str>,
+    name: &'a str,
+    cost: f64,
+    active: bool,
+    updated_at: DateTime<Utc>,
+    variants: BTreeMap<&'a str, Vec<&'a str>>,
+    image_urls: &'a [String],
+}
+
+pub struct JsonLinesFeedWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> JsonLinesFeedWriter<W> {
+    pub fn new(writer: W) -> JsonLinesFeedWriter<W> {
+        JsonLinesFeedWriter { writer }
+    }
+}
+
+impl<W: Write> FeedWriter for JsonLinesFeedWriter<W> {
+    fn write_product(&mut self, exported_product: &ExportedProduct) -> AnyResult<()> {
+        let product = exported_product.product();
+
+        serde_json::to_writer(
+            &mut self.writer,
+            &ProductLine {
+                id: product.id(),
+                external_key: product.external_key(),
+                name: product.name(),
+                cost: product.cost(),
+                active: product.active(),
+                updated_at: exported_product.updated_at(),
+                variants: exported_product.variant_values(),
+                image_urls: exported_product.image_urls(),
+            },
+        )?;
+        self.writer.write_all(b"\n")?;
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> AnyResult<()> {
+        self.writer.flush()?;
+
+        Ok(())
+    }
+}
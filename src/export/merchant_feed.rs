@@ -0,0 +1,208 @@
+use crate::core::entities::product_export::ExportedProduct;
+use crate::export::FeedWriter;
+use anyhow::Result as AnyResult;
+use std::env;
+use std::io::Write;
+
+const GOOGLE_NAMESPACE: &str = "http://base.google.com/ns/1.0";
+// Merchant Center ignores any additional image past the tenth
+const MAX_ADDITIONAL_IMAGES: usize = 10;
+
+#[derive(Debug, Clone)]
+pub struct MerchantFeedConfig {
+    // the name and address of the store, used for the feed's channel and each product's link
+    pub store_name: String,
+    pub store_url: String,
+    // the ISO 4217 code product costs are in
+    pub currency: String,
+}
+
+impl MerchantFeedConfig {
+    pub fn from_env() -> MerchantFeedConfig {
+        MerchantFeedConfig {
+            store_name: env::var("EXPORT_STORE_NAME").unwrap_or_else(|_| String::from("Product Store")),
+            store_url: env::var("EXPORT_STORE_URL")
+                .unwrap_or_else(|_| String::from("http://localhost:8080"))
+                .trim_end_matches('/')
+                .to_string(),
+            currency: env::var("EXPORT_CURRENCY").unwrap_or_else(|_| String::from("USD")),
+        }
+    }
+}
+
+// writes a Google Merchant Center product feed, an RSS 2.0 document with one `<item>` per product
+pub struct MerchantFeedWriter<W: Write> {
+    writer: W,
+    config: MerchantFeedConfig,
+}
+
+impl<W: Write> MerchantFeedWriter<W> {
+    pub fn new(mut writer: W, config: MerchantFeedConfig) -> AnyResult<MerchantFeedWriter<W>> {
+        write!(
+            writer,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <rss version=\"2.0\" xmlns:g=\"{}\">\n\
+             <channel>\n\
+             <title>{}</title>\n\
+             <link>{}</link>\n\
+             <description>{}</description>\n",
+            GOOGLE_NAMESPACE,
+            escape_xml(&config.store_name),
+            escape_xml(&config.store_url),
+            escape_xml(&config.store_name),
+        )?;
+
+        Ok(MerchantFeedWriter { writer, config })
+    }
+
+    fn write_element(&mut self, name: &str, value: &str) -> AnyResult<()> {
+        writeln!(self.writer, "<{}>{}</{}>", name, escape_xml(value), name)?;
+
+        Ok(())
+    }
+}
+
+impl<W: Write> FeedWriter for MerchantFeedWriter<W> {
+    fn write_product(&mut self, exported_product: &ExportedProduct) -> AnyResult<()> {
+        let product = exported_product.product();
+        let product_id = product.id().map(|id| id.to_string()).unwrap_or_default();
+        let item_id = product.external_key().map(String::from).unwrap_or_else(|| product_id.clone());
+
+        writeln!(self.writer, "<item>")?;
+        self.write_element("g:id", &item_id)?;
+        self.write_element("title", product.name())?;
+        self.write_element("description", product.name())?;
+        self.write_element(
+            "link",
+            &format!("{}/products/{}", self.config.store_url, product.slug().unwrap_or(&product_id)),
+        )?;
+
+        let mut image_urls = exported_product.image_urls().iter();
+        if let Some(image_url) = image_urls.next() {
+            self.write_element("g:image_link", image_url)?;
+        }
+        for image_url in image_urls.take(MAX_ADDITIONAL_IMAGES) {
+            self.write_element("g:additional_image_link", image_url)?;
+        }
+
+        self.write_element(
+            "g:availability",
+            if exported_product.in_stock() { "in_stock" } else { "out_of_stock" },
+        )?;
+        self.write_element("g:condition", "new")?;
+        self.write_element("g:price", &format!("{:.2} {}", product.cost(), self.config.currency))?;
+
+        for (name, values) in exported_product.variant_values() {
+            writeln!(self.writer, "<g:product_detail>")?;
+            self.write_element("g:attribute_name", name)?;
+            self.write_element("g:attribute_value", &values.join(", "))?;
+            writeln!(self.writer, "</g:product_detail>")?;
+        }
+
+        writeln!(self.writer, "</item>")?;
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> AnyResult<()> {
+        writeln!(self.writer, "</channel>\n</rss>")?;
+        self.writer.flush()?;
+
+        Ok(())
+    }
+}
+
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for character in value.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod merchant_feed_tests {
+    use crate::core::entities::ids::{ProductId, VariantId};
+    use crate::core::entities::product::Product;
+    use crate::core::entities::product_export::ExportedProduct;
+    use crate::core::entities::product_revision::VariantSnapshot;
+    use crate::export::merchant_feed::{MerchantFeedConfig, MerchantFeedWriter};
+    use crate::export::FeedWriter;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_write_product_item() {
+        let config = MerchantFeedConfig {
+            store_name: String::from("Shoes & Co"),
+            store_url: String::from("https://shop.example.com"),
+            currency: String::from("EUR"),
+        };
+        let exported_product = ExportedProduct::new(
+            Product::new("Sneakers <limited>".to_string(), 59.9, true, ProductId::try_from(7).ok())
+                .with_external_key(Some("sku-1".to_string()))
+                .with_public_identifiers(Uuid::now_v7(), "sneakers-limited".to_string()),
+            vec![
+                VariantSnapshot::new(VariantId::try_from(1).unwrap(), "size".to_string(), Some("40".to_string())),
+                VariantSnapshot::new(VariantId::try_from(1).unwrap(), "size".to_string(), Some("41".to_string())),
+            ],
+            vec!["https://cdn.example.com/1.jpg".to_string()],
+            Utc::now(),
+        );
+
+        let mut output = Vec::new();
+        let mut writer = MerchantFeedWriter::new(&mut output, config).unwrap();
+        writer.write_product(&exported_product).unwrap();
+        writer.finish().unwrap();
+        let feed = String::from_utf8(output).unwrap();
+
+        assert!(feed.contains("<title>Shoes &amp; Co</title>"));
+        assert!(feed.contains("<g:id>sku-1</g:id>"));
+        assert!(feed.contains("<title>Sneakers &lt;limited&gt;</title>"));
+        assert!(feed.contains("<link>https://shop.example.com/products/sneakers-limited</link>"));
+        assert!(feed.contains("<g:image_link>https://cdn.example.com/1.jpg</g:image_link>"));
+        assert!(feed.contains("<g:availability>in_stock</g:availability>"));
+        assert!(feed.contains("<g:price>59.90 EUR</g:price>"));
+        assert!(feed.contains("<g:attribute_value>40, 41</g:attribute_value>"));
+        assert!(feed.ends_with("</channel>\n</rss>\n"));
+    }
+
+    #[test]
+    fn test_products_without_stock_are_out_of_stock() {
+        let config = MerchantFeedConfig {
+            store_name: String::from("Shoes & Co"),
+            store_url: String::from("https://shop.example.com"),
+            currency: String::from("EUR"),
+        };
+        let exported_product = |active, available_quantity| {
+            ExportedProduct::new(
+                Product::new("Clogs".to_string(), 20.0, active, ProductId::try_from(8).ok())
+                    .with_public_identifiers(Uuid::now_v7(), "clogs".to_string()),
+                Vec::new(),
+                Vec::new(),
+                Utc::now(),
+            )
+            .with_available_quantity(available_quantity)
+        };
+
+        let mut output = Vec::new();
+        let mut writer = MerchantFeedWriter::new(&mut output, config).unwrap();
+        writer.write_product(&exported_product(true, Some(3))).unwrap();
+        writer.write_product(&exported_product(true, Some(0))).unwrap();
+        writer.write_product(&exported_product(false, None)).unwrap();
+        writer.finish().unwrap();
+        let feed = String::from_utf8(output).unwrap();
+
+        assert_eq!(1, feed.matches("<g:availability>in_stock</g:availability>").count());
+        assert_eq!(2, feed.matches("<g:availability>out_of_stock</g:availability>").count());
+    }
+}
@@ -0,0 +1,84 @@
+pub mod csv_feed;
+pub mod jsonl_feed;
+pub mod merchant_feed;
+
+use crate::core::entities::product_export::ExportedProduct;
+use crate::core::ports::database::export_database::ProductExportDatastore;
+use crate::export::csv_feed::CsvFeedWriter;
+use crate::export::jsonl_feed::JsonLinesFeedWriter;
+use crate::export::merchant_feed::{MerchantFeedConfig, MerchantFeedWriter};
+use anyhow::{anyhow, Result as AnyResult};
+use chrono::{DateTime, Utc};
+use std::io::Write;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    JsonLines,
+    GoogleMerchant,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::JsonLines => "application/x-ndjson",
+            ExportFormat::GoogleMerchant => "application/rss+xml; charset=utf-8",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> AnyResult<Self> {
+        match value {
+            "csv" => Ok(ExportFormat::Csv),
+            "jsonl" => Ok(ExportFormat::JsonLines),
+            "google-merchant" => Ok(ExportFormat::GoogleMerchant),
+            _ => Err(anyhow!("unknown export format '{}'", value)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    // only export products changed after this point, for incremental exports
+    pub changed_since: Option<DateTime<Utc>>,
+    pub merchant_feed: MerchantFeedConfig,
+}
+
+// writes exported products in one of the feed formats
+pub trait FeedWriter {
+    fn write_product(&mut self, product: &ExportedProduct) -> AnyResult<()>;
+
+    // writes whatever closes the feed and flushes it
+    fn finish(&mut self) -> AnyResult<()>;
+}
+
+// exports every active product in the requested format, or every product changed since a point
+// for incremental exports, writing each product as soon as it is read from the datastore. Returns
+// the number of products exported
+pub fn export_products<W: Write>(
+    writer: W,
+    options: &ExportOptions,
+    datastore: &mut impl ProductExportDatastore,
+) -> AnyResult<usize> {
+    let mut feed_writer: Box<dyn FeedWriter + '_> = match options.format {
+        ExportFormat::Csv => Box::new(CsvFeedWriter::new(writer, datastore.list_variant_names()?)?),
+        ExportFormat::JsonLines => Box::new(JsonLinesFeedWriter::new(writer)),
+        ExportFormat::GoogleMerchant => {
+            Box::new(MerchantFeedWriter::new(writer, options.merchant_feed.clone())?)
+        }
+    };
+
+    let exported = datastore.stream_exported_products(options.changed_since, &mut |product| {
+        feed_writer.write_product(&product)
+    })?;
+
+    feed_writer.finish()?;
+
+    Ok(exported)
+}
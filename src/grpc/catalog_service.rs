@@ -0,0 +1,355 @@
+use crate::DbPool;
+use crate::api::auth::{verify_api_key, verify_bearer_token, AuthConfig, API_KEY_HEADER};
+use crate::api::errors::ApiError;
+use crate::api::tenancy::{tenant_for, TenantConfig, TENANT_HEADER};
+use crate::api::{with_connection, with_product_repository};
+use crate::core::entities::complete_product::CompleteProduct;
+use crate::core::entities::ids::{ProductId, ProductTypeId, VariantId};
+use crate::core::entities::principal::Principal;
+use crate::core::entities::product::Product;
+use crate::core::entities::product_reference::ProductReference;
+use crate::core::entities::product_variant::ProductVariant;
+use crate::core::entities::tenant::Tenant;
+use crate::core::entities::variant::Variant;
+use crate::core::entities::variant_value::VariantValue;
+use crate::core::ports::database::product_database::ProductDatastore;
+use crate::datastore::repositories::product_repository::ProductRepository;
+use crate::datastore::tenancy::set_current_tenant;
+use crate::grpc::proto;
+use crate::grpc::proto::get_product_request::Lookup;
+use crate::grpc::proto::product_catalog_server::ProductCatalog;
+use actix_web::{rt, web};
+use serde_json::{Map, Value};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::metadata::MetadataMap;
+use tonic::{Request, Response, Status};
+
+const DEFAULT_PAGE_SIZE: u32 = 500;
+const MAX_PAGE_SIZE: u32 = 5000;
+
+// how many products may wait for a slow client before reading the catalog pauses
+const LIST_CHANNEL_CAPACITY: usize = 64;
+
+// the product catalog served over gRPC. It goes through the same repositories, authentication and
+// tenant resolution as the HTTP API, so both answer the same way
+pub struct CatalogService {
+    pool: web::Data<DbPool>,
+    auth: AuthConfig,
+    tenancy: TenantConfig,
+}
+
+impl CatalogService {
+    pub fn new(pool: DbPool, auth: AuthConfig, tenancy: TenantConfig) -> CatalogService {
+        CatalogService {
+            pool: web::Data::new(pool),
+            auth,
+            tenancy,
+        }
+    }
+
+    // the principal a call authenticates as with its `x-api-key` or `authorization` metadata, if it
+    // carries either
+    async fn authenticate(&self, metadata: &MetadataMap) -> Result<Option<Principal>, ApiError> {
+        if let Some(value) = metadata.get(API_KEY_HEADER.to_lowercase().as_str()) {
+            let key = value
+                .to_str()
+                .map_err(|_| ApiError::Unauthorized("The API key is not valid".to_string()))?
+                .to_string();
+
+            return verify_api_key(self.pool.clone(), key).await.map(Some);
+        }
+
+        if let Some(value) = metadata.get("authorization") {
+            let token = value
+                .to_str()
+                .ok()
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .ok_or_else(|| {
+                    ApiError::Unauthorized("The bearer token is not valid: only bearer tokens are accepted".to_string())
+                })?;
+
+            return verify_bearer_token(&self.auth, token.trim()).map(Some);
+        }
+
+        Ok(None)
+    }
+
+    // the tenant a call works for and the principal it is made by, resolved like for HTTP requests
+    async fn caller(&self, metadata: &MetadataMap) -> Result<(Tenant, Option<Principal>), ApiError> {
+        let principal = self.authenticate(metadata).await?;
+        let requested_tenant = metadata
+            .get(TENANT_HEADER.to_lowercase().as_str())
+            .map(|value| {
+                value
+                    .to_str()
+                    .map_err(|_| ApiError::BadRequest(format!("{} is not valid", TENANT_HEADER)))?
+                    .parse::<Tenant>()
+                    .map_err(|error| ApiError::BadRequest(error.to_string()))
+            })
+            .transpose()?;
+
+        Ok((tenant_for(principal.as_ref(), requested_tenant, Some(&self.tenancy))?, principal))
+    }
+
+    // the tenant and principal of a call that writes, which has to be authenticated
+    async fn writer(&self, metadata: &MetadataMap) -> Result<(Tenant, Principal), ApiError> {
+        match self.caller(metadata).await? {
+            (tenant, Some(principal)) => Ok((tenant, principal)),
+            (_, None) => Err(ApiError::Unauthorized(format!(
+                "This call needs {} metadata or a bearer token",
+                API_KEY_HEADER.to_lowercase()
+            ))),
+        }
+    }
+}
+
+fn invalid_argument(message: impl Into<String>) -> ApiError {
+    ApiError::BadRequest(message.into())
+}
+
+fn product_id(id: i32) -> Result<ProductId, ApiError> {
+    ProductId::try_from(id).map_err(|error| invalid_argument(error.to_string()))
+}
+
+// the product a write describes. Attributes are sent as a JSON object
+fn product_from_input(input: Option<proto::ProductInput>, id: Option<ProductId>) -> Result<Product, ApiError> {
+    let input = input.ok_or_else(|| invalid_argument("product is required"))?;
+    let product_type_id = input
+        .product_type_id
+        .map(ProductTypeId::try_from)
+        .transpose()
+        .map_err(|error| invalid_argument(error.to_string()))?;
+    let attributes = match input.attributes.trim() {
+        "" => Map::new(),
+        attributes => serde_json::from_str::<Map<String, Value>>(attributes)
+            .map_err(|error| invalid_argument(format!("attributes must be a JSON object: {}", error)))?,
+    };
+
+    Ok(Product::new(input.name, input.cost, input.active, id)
+        .with_description(input.description)
+        .with_attributes(product_type_id, attributes)
+        .with_stock_quantity(input.stock_quantity))
+}
+
+fn product_message(product: &Product) -> proto::Product {
+    proto::Product {
+        id: product.id().map(i32::from).unwrap_or_default(),
+        public_id: product.public_id().map(|public_id| public_id.to_string()).unwrap_or_default(),
+        slug: product.slug().unwrap_or_default().to_string(),
+        name: product.name().to_string(),
+        description: product.description().map(String::from),
+        cost: product.cost(),
+        active: product.active(),
+        version: product.version(),
+        sku: product.external_key().map(String::from),
+        product_type_id: product.product_type_id().map(i32::from),
+        attributes: Value::Object(product.attributes().clone()).to_string(),
+        stock_quantity: product.stock_quantity(),
+    }
+}
+
+// a product with its variant values grouped by variant, in the order the variants were attached
+fn complete_product_message(product: &Product, product_variants: Vec<(ProductVariant, Variant)>) -> proto::CompleteProduct {
+    let mut variants = Vec::<(VariantId, proto::VariantValue)>::new();
+
+    for (product_variant, variant) in product_variants {
+        let value = proto::OptionalValue {
+            value: product_variant.value().clone(),
+        };
+
+        match variants
+            .iter_mut()
+            .find(|(variant_id, _)| *variant_id == product_variant.variant_id())
+        {
+            Some((_, variant_value)) => variant_value.values.push(value),
+            None => variants.push((
+                product_variant.variant_id(),
+                proto::VariantValue {
+                    variant: Some(proto::Variant {
+                        id: product_variant.variant_id().get(),
+                        name: variant.name().to_string(),
+                    }),
+                    values: vec![value],
+                },
+            )),
+        }
+    }
+
+    proto::CompleteProduct {
+        product: Some(product_message(product)),
+        variants: variants.into_iter().map(|(_, variant_value)| variant_value).collect(),
+    }
+}
+
+#[tonic::async_trait]
+impl ProductCatalog for CatalogService {
+    async fn create_product(
+        &self,
+        request: Request<proto::CreateProductRequest>,
+    ) -> Result<Response<proto::Product>, Status> {
+        let (tenant, principal) = self.writer(request.metadata()).await?;
+        let product = product_from_input(request.into_inner().product, None)?;
+
+        let created_product = with_product_repository(self.pool.clone(), tenant, principal, move |repository| {
+            repository.create_product(product)
+        })
+        .await?;
+
+        Ok(Response::new(product_message(&created_product)))
+    }
+
+    async fn create_complete_product(
+        &self,
+        request: Request<proto::CreateCompleteProductRequest>,
+    ) -> Result<Response<proto::CompleteProduct>, Status> {
+        let (tenant, principal) = self.writer(request.metadata()).await?;
+        let request = request.into_inner();
+        let complete_product = CompleteProduct::new(
+            product_from_input(request.product, None)?,
+            request
+                .variants
+                .into_iter()
+                .map(|variant| {
+                    VariantValue::new(
+                        Variant::new(variant.name, None),
+                        variant.values.into_iter().map(|value| value.value).collect(),
+                    )
+                })
+                .collect(),
+        );
+
+        let (created_product, product_variants) =
+            with_product_repository(self.pool.clone(), tenant, principal, move |repository| {
+                let created_product_id = repository.create_complete_product(complete_product)?;
+
+                repository.get_product_with_variants(created_product_id)
+            })
+            .await?;
+
+        Ok(Response::new(complete_product_message(&created_product, product_variants)))
+    }
+
+    async fn get_product(
+        &self,
+        request: Request<proto::GetProductRequest>,
+    ) -> Result<Response<proto::CompleteProduct>, Status> {
+        let (tenant, _) = self.caller(request.metadata()).await?;
+        let lookup = request
+            .into_inner()
+            .lookup
+            .ok_or_else(|| invalid_argument("one of id, reference or sku is required"))?;
+        if let Lookup::Id(id) = lookup {
+            product_id(id)?;
+        }
+
+        let (product, product_variants) = with_connection(self.pool.clone(), tenant, move |connection| {
+            let mut repository = ProductRepository::new(connection);
+            let id = match lookup {
+                Lookup::Id(id) => ProductId::try_from(id)?,
+                Lookup::Reference(reference) => repository.resolve_product(&ProductReference::from(reference))?.id(),
+                Lookup::Sku(sku) => repository.find_product_by_external_key(&sku)?,
+            };
+
+            repository.get_product_with_variants(id)
+        })
+        .await?;
+
+        Ok(Response::new(complete_product_message(&product, product_variants)))
+    }
+
+    async fn update_product(
+        &self,
+        request: Request<proto::UpdateProductRequest>,
+    ) -> Result<Response<proto::Product>, Status> {
+        let (tenant, principal) = self.writer(request.metadata()).await?;
+        let request = request.into_inner();
+        let id = product_id(request.id)?;
+        let product = product_from_input(request.product, Some(id))?;
+
+        let updated_product = with_product_repository(self.pool.clone(), tenant, principal, move |repository| {
+            repository.update_product(id, product, request.expected_version)
+        })
+        .await?;
+
+        Ok(Response::new(product_message(&updated_product)))
+    }
+
+    async fn delete_product(
+        &self,
+        request: Request<proto::DeleteProductRequest>,
+    ) -> Result<Response<proto::DeleteProductResponse>, Status> {
+        let (tenant, principal) = self.writer(request.metadata()).await?;
+        let request = request.into_inner();
+        let id = product_id(request.id)?;
+
+        with_product_repository(self.pool.clone(), tenant, principal, move |repository| {
+            repository.delete_product(id, request.expected_version)
+        })
+        .await?;
+
+        Ok(Response::new(proto::DeleteProductResponse {}))
+    }
+
+    type ListProductsStream = ReceiverStream<Result<proto::CompleteProduct, Status>>;
+
+    // the catalog is read a page at a time on the blocking thread pool, each page in a short
+    // read-only transaction of its own. The connection goes back to the pool before the page is sent,
+    // so a slow client holds on to none. Pages pick up after the last product of the one before, so
+    // products written while the stream runs are listed as they are when their page is read. A
+    // failure halfway through ends the stream with an error after the products that were already sent
+    async fn list_products(
+        &self,
+        request: Request<proto::ListProductsRequest>,
+    ) -> Result<Response<Self::ListProductsStream>, Status> {
+        let (tenant, _) = self.caller(request.metadata()).await?;
+        let page_size = match request.into_inner().page_size {
+            0 => DEFAULT_PAGE_SIZE,
+            page_size => page_size.min(MAX_PAGE_SIZE),
+        };
+        let pool = self.pool.clone();
+        let (sender, receiver) = mpsc::channel(LIST_CHANNEL_CAPACITY);
+
+        rt::task::spawn_blocking(move || {
+            let mut after = None;
+
+            loop {
+                let page = pool.get().map_err(anyhow::Error::from).and_then(|mut connection| {
+                    set_current_tenant(&mut connection, &tenant)?;
+
+                    connection
+                        .build_transaction()
+                        .read_only()
+                        .run::<_, anyhow::Error, _>(|connection| {
+                            ProductRepository::new(connection).list_products_with_variants(after, i64::from(page_size))
+                        })
+                });
+                let page = match page {
+                    Ok(page) => page,
+                    Err(error) => {
+                        let _ = sender.blocking_send(Err(Status::from(ApiError::from(error))));
+                        return;
+                    }
+                };
+                let page_length = page.len();
+
+                for (product, product_variants) in page {
+                    after = product.id();
+                    // the client went away, there is no one left to send the rest to
+                    if sender
+                        .blocking_send(Ok(complete_product_message(&product, product_variants)))
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+
+                if page_length < page_size as usize {
+                    return;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+}
@@ -0,0 +1,96 @@
+use crate::api::errors::ApiError;
+use crate::core::ports::database::errors::DatastoreError;
+use tonic::metadata::MetadataValue;
+use tonic::{Code, Status};
+
+// the metadata entry that a version conflict reports the product's current version in, like the
+// `ETag` of an HTTP response
+pub const CURRENT_VERSION_METADATA: &str = "current-version";
+
+fn status_code(error: &ApiError) -> Code {
+    match error {
+        ApiError::PreconditionRequired => Code::FailedPrecondition,
+        ApiError::Unauthorized(_) => Code::Unauthenticated,
+        ApiError::Forbidden(_) => Code::PermissionDenied,
+        ApiError::BadRequest(_) | ApiError::UnprocessableEntity(_) => Code::InvalidArgument,
+        ApiError::TooManyRequests(_) => Code::ResourceExhausted,
+        ApiError::Conflict(_) => Code::Aborted,
+        ApiError::Datastore(DatastoreError::NotFound { .. })
+        | ApiError::Datastore(DatastoreError::UnknownReference { .. }) => Code::NotFound,
+        ApiError::Datastore(DatastoreError::AlreadyExists { .. }) => Code::AlreadyExists,
+        ApiError::Datastore(DatastoreError::VersionConflict { .. })
+        | ApiError::Datastore(DatastoreError::InUse { .. }) => Code::FailedPrecondition,
+        ApiError::Datastore(DatastoreError::InvalidAttributes { .. })
+        | ApiError::Datastore(DatastoreError::InvalidBundle { .. })
+        | ApiError::Datastore(DatastoreError::InvalidRelations { .. }) => Code::InvalidArgument,
+        ApiError::Datastore(DatastoreError::PermissionDenied { .. }) => Code::PermissionDenied,
+        ApiError::Internal(_) => Code::Internal,
+    }
+}
+
+// errors are answered with the status closest to the one the HTTP API answers with. Internal
+// errors are logged and not described to the caller
+impl From<ApiError> for Status {
+    fn from(error: ApiError) -> Self {
+        let code = status_code(&error);
+
+        match &error {
+            ApiError::Datastore(DatastoreError::VersionConflict { current_version, .. }) => {
+                let mut status = Status::new(code, error.to_string());
+                status
+                    .metadata_mut()
+                    .insert(CURRENT_VERSION_METADATA, MetadataValue::from(*current_version));
+                status
+            }
+            ApiError::Datastore(
+                DatastoreError::InvalidAttributes { problems, .. }
+                | DatastoreError::InvalidBundle { problems, .. }
+                | DatastoreError::InvalidRelations { problems, .. },
+            ) => Status::new(code, format!("{}: {}", error, problems.join("; "))),
+            ApiError::Internal(internal_error) => {
+                log::error!("gRPC call failed: {:?}", internal_error);
+                Status::new(code, error.to_string())
+            }
+            _ => Status::new(code, error.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod errors_tests {
+    use crate::api::errors::ApiError;
+    use crate::core::ports::database::errors::DatastoreError;
+    use crate::grpc::errors::CURRENT_VERSION_METADATA;
+    use anyhow::anyhow;
+    use tonic::{Code, Status};
+
+    #[test]
+    fn test_repository_errors_map_to_grpc_status_codes() {
+        let not_found = Status::from(ApiError::Datastore(DatastoreError::NotFound { entity: "Product", id: 7 }));
+        let conflict = Status::from(ApiError::Datastore(DatastoreError::VersionConflict {
+            entity: "Product",
+            id: 7,
+            expected_version: 1,
+            current_version: 3,
+        }));
+        let invalid = Status::from(ApiError::Datastore(DatastoreError::InvalidAttributes {
+            entity: "Product",
+            problems: vec!["Attribute material is required".to_string()],
+        }));
+        let internal = Status::from(ApiError::Internal(anyhow!("connection refused")));
+
+        assert_eq!(Code::NotFound, not_found.code());
+        assert_eq!(Code::FailedPrecondition, conflict.code());
+        assert_eq!(
+            Some("3"),
+            conflict
+                .metadata()
+                .get(CURRENT_VERSION_METADATA)
+                .and_then(|value| value.to_str().ok())
+        );
+        assert_eq!(Code::InvalidArgument, invalid.code());
+        assert!(invalid.message().contains("Attribute material is required"));
+        assert_eq!(Code::Internal, internal.code());
+        assert_eq!("Internal server error", internal.message());
+    }
+}
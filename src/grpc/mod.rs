@@ -0,0 +1,80 @@
+pub mod catalog_service;
+pub mod errors;
+
+pub mod proto {
+    tonic::include_proto!("productstore.catalog.v1");
+}
+
+use crate::DbPool;
+use crate::api::auth::AuthConfig;
+use crate::api::tenancy::TenantConfig;
+use crate::grpc::catalog_service::CatalogService;
+use crate::grpc::proto::product_catalog_server::ProductCatalogServer;
+use anyhow::{Context, Result as AnyResult};
+use std::env;
+use std::net::TcpListener;
+use std::thread;
+use tonic::transport::server::TcpIncoming;
+use tonic::transport::Server;
+
+#[derive(Debug, Clone)]
+pub struct GrpcConfig {
+    // the address the gRPC server listens on, or none when it is not served
+    pub address: Option<String>,
+}
+
+impl GrpcConfig {
+    // the gRPC server is only started when `GRPC_ADDRESS` is set, so that a deployment does not
+    // listen on a port it did not ask for
+    pub fn from_env() -> GrpcConfig {
+        let address = env::var("GRPC_ADDRESS")
+            .ok()
+            .filter(|address| !address.trim().is_empty());
+
+        GrpcConfig { address }
+    }
+}
+
+// serves the catalog over gRPC on a thread of its own, next to the HTTP server. The address is
+// bound before this returns, so a server that cannot listen keeps the binary from starting like the
+// HTTP server does
+pub fn spawn_grpc_server(
+    pool: DbPool,
+    auth: AuthConfig,
+    tenancy: TenantConfig,
+    config: GrpcConfig,
+) -> AnyResult<Option<thread::JoinHandle<()>>> {
+    let Some(address) = config.address else {
+        return Ok(None);
+    };
+    let listener = TcpListener::bind(&address).with_context(|| format!("Error binding gRPC server to {}", address))?;
+    listener.set_nonblocking(true)?;
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .thread_name("grpc-server")
+        .build()?;
+    let service = ProductCatalogServer::new(CatalogService::new(pool, auth, tenancy));
+
+    let server = thread::Builder::new().name("grpc-server".to_string()).spawn(move || {
+        runtime.block_on(async move {
+            let served = match tokio::net::TcpListener::from_std(listener) {
+                Ok(listener) => {
+                    Server::builder()
+                        .add_service(service)
+                        .serve_with_incoming(TcpIncoming::from(listener))
+                        .await
+                }
+                Err(error) => {
+                    log::error!("Serving gRPC failed: {:#}", error);
+                    return;
+                }
+            };
+
+            if let Err(error) = served {
+                log::error!("Serving gRPC failed: {:#}", error);
+            }
+        })
+    })?;
+
+    Ok(Some(server))
+}
@@ -0,0 +1,186 @@
+pub mod rows;
+
+use crate::core::entities::complete_product::CompleteProduct;
+use crate::core::entities::product_import::ImportAction;
+use crate::core::ports::database::product_database::ProductDatastore;
+use crate::import::rows::{read_csv_rows, read_json_lines_rows, RowResult};
+use anyhow::{anyhow, Result as AnyResult};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::io::Read;
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+pub const DEFAULT_BATCH_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportFormat {
+    Csv,
+    JsonLines,
+}
+
+impl FromStr for ImportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> AnyResult<Self> {
+        match value {
+            "csv" => Ok(ImportFormat::Csv),
+            "jsonl" => Ok(ImportFormat::JsonLines),
+            _ => Err(anyhow!("unknown import format '{}'", value)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    pub format: ImportFormat,
+    pub dry_run: bool,
+    pub batch_size: usize,
+}
+
+impl ImportOptions {
+    pub fn new(format: ImportFormat) -> ImportOptions {
+        ImportOptions {
+            format,
+            dry_run: false,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+}
+
+// a row that was not imported, `line` is the line of the row in the imported file
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RowError {
+    pub line: u64,
+    pub external_key: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct ImportReport {
+    pub rows_read: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub failed: Vec<RowError>,
+    pub dry_run: bool,
+}
+
+// a validated row waiting for its batch to be written
+struct PendingRow {
+    line: u64,
+    external_key: Option<String>,
+    complete_product: CompleteProduct,
+}
+
+// imports every row of a CSV or JSON Lines file. Rows are validated one by one and the valid ones
+// are written in batches, each batch in a single transaction. A row that fails, either validation
+// or its write, is reported and does not stop the rest of the import
+pub fn import_products<R: Read>(
+    reader: R,
+    options: &ImportOptions,
+    datastore: &mut impl ProductDatastore,
+) -> AnyResult<ImportReport> {
+    let rows: Box<dyn Iterator<Item = RowResult>> = match options.format {
+        ImportFormat::Csv => Box::new(read_csv_rows(reader)?),
+        ImportFormat::JsonLines => Box::new(read_json_lines_rows(reader)),
+    };
+
+    let batch_size = options.batch_size.max(1);
+    let mut report = ImportReport {
+        dry_run: options.dry_run,
+        ..ImportReport::default()
+    };
+    let mut seen_external_keys = HashSet::new();
+    let mut batch = Vec::with_capacity(batch_size);
+
+    for row in rows {
+        report.rows_read += 1;
+
+        let row = match row {
+            Ok(row) => row,
+            Err(unreadable_row) => {
+                report.failed.push(RowError {
+                    line: unreadable_row.line,
+                    external_key: None,
+                    message: unreadable_row.message,
+                });
+                continue;
+            }
+        };
+
+        let line = row.line;
+        let complete_product = match row.into_complete_product() {
+            Ok(complete_product) => complete_product,
+            Err(message) => {
+                report.failed.push(RowError {
+                    line,
+                    external_key: None,
+                    message,
+                });
+                continue;
+            }
+        };
+
+        let external_key = complete_product.product().external_key().map(String::from);
+        if let Some(key) = &external_key {
+            if !seen_external_keys.insert(key.clone()) {
+                report.failed.push(RowError {
+                    line,
+                    external_key,
+                    message: String::from("external key appears more than once in the file"),
+                });
+                continue;
+            }
+        }
+
+        batch.push(PendingRow {
+            line,
+            external_key,
+            complete_product,
+        });
+
+        if batch.len() >= batch_size {
+            write_batch(&mut batch, options.dry_run, datastore, &mut report)?;
+        }
+    }
+
+    write_batch(&mut batch, options.dry_run, datastore, &mut report)?;
+
+    Ok(report)
+}
+
+fn write_batch(
+    batch: &mut Vec<PendingRow>,
+    dry_run: bool,
+    datastore: &mut impl ProductDatastore,
+    report: &mut ImportReport,
+) -> AnyResult<()> {
+    if batch.is_empty() {
+        return Ok(());
+    }
+
+    let (rows, complete_products): (Vec<_>, Vec<_>) = batch
+        .drain(..)
+        .map(|pending_row| ((pending_row.line, pending_row.external_key), pending_row.complete_product))
+        .unzip();
+
+    let results = datastore.upsert_complete_products(complete_products, dry_run)?;
+
+    for ((line, external_key), result) in rows.into_iter().zip(results) {
+        match result {
+            Ok(imported_product) => match imported_product.action() {
+                ImportAction::Created => report.created += 1,
+                ImportAction::Updated => report.updated += 1,
+                ImportAction::Unchanged => report.unchanged += 1,
+            },
+            Err(error) => report.failed.push(RowError {
+                line,
+                external_key,
+                message: error.to_string(),
+            }),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,264 @@
+use crate::core::entities::complete_product::CompleteProduct;
+use crate::core::entities::product::Product;
+use crate::core::entities::variant::Variant;
+use crate::core::entities::variant_value::VariantValue;
+use anyhow::{anyhow, bail, Result as AnyResult};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read};
+
+const VARIANT_COLUMN_PREFIX: &str = "variant:";
+const VARIANT_VALUE_SEPARATOR: char = '|';
+
+// a single row of an import file, before it is validated
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ImportRow {
+    #[serde(skip)]
+    pub line: u64,
+    pub external_key: Option<String>,
+    pub name: String,
+    pub cost: f64,
+    pub active: Option<bool>,
+    #[serde(default)]
+    pub variants: BTreeMap<String, Vec<String>>,
+}
+
+// a row that could not be read at all, it is reported along with the rows that fail validation
+#[derive(Debug)]
+pub struct UnreadableRow {
+    pub line: u64,
+    pub message: String,
+}
+
+pub type RowResult = Result<ImportRow, UnreadableRow>;
+
+impl ImportRow {
+    // validates the row and turns it into the complete product it describes
+    pub fn into_complete_product(self) -> Result<CompleteProduct, String> {
+        let name = self.name.trim().to_string();
+        if name.is_empty() {
+            return Err(String::from("name must not be empty"));
+        }
+
+        if !self.cost.is_finite() || self.cost < 0.0 {
+            return Err(format!("cost must be a non-negative number, got {}", self.cost));
+        }
+
+        let external_key = match self.external_key.map(|key| key.trim().to_string()) {
+            Some(key) if key.is_empty() => None,
+            key => key,
+        };
+
+        let mut variant_values = Vec::with_capacity(self.variants.len());
+        for (variant_name, values) in self.variants {
+            let variant_name = variant_name.trim().to_string();
+            if variant_name.is_empty() {
+                return Err(String::from("variant names must not be empty"));
+            }
+
+            let mut variant_values_for_name = Vec::with_capacity(values.len());
+            for value in values {
+                let value = value.trim().to_string();
+                if value.is_empty() {
+                    return Err(format!("variant '{}' has an empty value", variant_name));
+                }
+                variant_values_for_name.push(Some(value));
+            }
+
+            if !variant_values_for_name.is_empty() {
+                variant_values.push(VariantValue::new(Variant::new(variant_name, None), variant_values_for_name));
+            }
+        }
+
+        Ok(CompleteProduct::new(
+            Product::new(name, self.cost, self.active.unwrap_or(true), None).with_external_key(external_key),
+            variant_values,
+        ))
+    }
+}
+
+// reads the rows of a CSV file. The header names the product columns (`external_key`, `name`,
+// `cost`, `active`) and one `variant:<name>` column per variant, whose cells hold the values of that
+// variant separated by `|`
+pub fn read_csv_rows<R: Read>(reader: R) -> AnyResult<impl Iterator<Item = RowResult>> {
+    let mut csv_reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(reader);
+    let columns = csv_reader
+        .headers()?
+        .iter()
+        .map(CsvColumn::parse)
+        .collect::<AnyResult<Vec<_>>>()?;
+
+    if !columns.contains(&CsvColumn::Name) || !columns.contains(&CsvColumn::Cost) {
+        bail!("the CSV header must contain the 'name' and 'cost' columns");
+    }
+
+    Ok(csv_reader.into_records().map(move |record| {
+        let record = record.map_err(|error| UnreadableRow {
+            line: error.position().map(|position| position.line()).unwrap_or_default(),
+            message: error.to_string(),
+        })?;
+        let line = record.position().map(|position| position.line()).unwrap_or_default();
+
+        parse_csv_record(&columns, &record).map_err(|error| UnreadableRow {
+            line,
+            message: error.to_string(),
+        })
+    }))
+}
+
+// reads the rows of a JSON Lines file, one product object per line. Blank lines are skipped
+pub fn read_json_lines_rows<R: Read>(reader: R) -> impl Iterator<Item = RowResult> {
+    BufReader::new(reader)
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let line_number = index as u64 + 1;
+
+            match line {
+                Ok(line) if line.trim().is_empty() => None,
+                Ok(line) => Some(
+                    serde_json::from_str::<ImportRow>(&line)
+                        .map(|row| ImportRow {
+                            line: line_number,
+                            ..row
+                        })
+                        .map_err(|error| UnreadableRow {
+                            line: line_number,
+                            message: error.to_string(),
+                        }),
+                ),
+                Err(error) => Some(Err(UnreadableRow {
+                    line: line_number,
+                    message: error.to_string(),
+                })),
+            }
+        })
+}
+
+#[derive(Debug, PartialEq)]
+enum CsvColumn {
+    ExternalKey,
+    Name,
+    Cost,
+    Active,
+    Variant(String),
+}
+
+impl CsvColumn {
+    fn parse(header: &str) -> AnyResult<CsvColumn> {
+        match header {
+            "external_key" => Ok(CsvColumn::ExternalKey),
+            "name" => Ok(CsvColumn::Name),
+            "cost" => Ok(CsvColumn::Cost),
+            "active" => Ok(CsvColumn::Active),
+            _ => match header.strip_prefix(VARIANT_COLUMN_PREFIX) {
+                Some(variant_name) if !variant_name.trim().is_empty() => {
+                    Ok(CsvColumn::Variant(variant_name.trim().to_string()))
+                }
+                _ => Err(anyhow!("unknown CSV column '{}'", header)),
+            },
+        }
+    }
+}
+
+fn parse_csv_record(columns: &[CsvColumn], record: &csv::StringRecord) -> AnyResult<ImportRow> {
+    let mut row = ImportRow {
+        line: record.position().map(|position| position.line()).unwrap_or_default(),
+        ..ImportRow::default()
+    };
+
+    for (column, cell) in columns.iter().zip(record.iter()) {
+        match column {
+            CsvColumn::ExternalKey => row.external_key = Some(cell.to_string()),
+            CsvColumn::Name => row.name = cell.to_string(),
+            CsvColumn::Cost => {
+                row.cost = cell
+                    .parse()
+                    .map_err(|_| anyhow!("cost '{}' is not a number", cell))?
+            }
+            CsvColumn::Active => row.active = parse_csv_bool(cell)?,
+            CsvColumn::Variant(variant_name) => {
+                // an empty cell means the product does not come in that variant
+                if !cell.is_empty() {
+                    row.variants.insert(
+                        variant_name.clone(),
+                        cell.split(VARIANT_VALUE_SEPARATOR).map(String::from).collect(),
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(row)
+}
+
+fn parse_csv_bool(cell: &str) -> AnyResult<Option<bool>> {
+    match cell.to_ascii_lowercase().as_str() {
+        "" => Ok(None),
+        "true" | "yes" | "1" => Ok(Some(true)),
+        "false" | "no" | "0" => Ok(Some(false)),
+        _ => Err(anyhow!("active '{}' is not a boolean", cell)),
+    }
+}
+
+#[cfg(test)]
+mod rows_tests {
+    use crate::import::rows::{read_csv_rows, read_json_lines_rows, ImportRow};
+
+    #[test]
+    fn test_read_csv_rows() {
+        let file = "external_key,name,cost,active,variant:size,variant:color\n\
+                    sku-1,Sneakers,59.9,true,40|41|42,red\n\
+                    sku-2,Socks,4.5,,,\n";
+
+        let rows = read_csv_rows(file.as_bytes())
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].line, 2);
+        assert_eq!(rows[0].external_key.as_deref(), Some("sku-1"));
+        assert_eq!(rows[0].variants["size"], vec!["40", "41", "42"]);
+        assert_eq!(rows[0].variants["color"], vec!["red"]);
+        assert_eq!(rows[1].active, None);
+        assert!(rows[1].variants.is_empty());
+    }
+
+    #[test]
+    fn test_read_csv_rows_rejects_unknown_columns() {
+        let file = "name,cost,colour\nSneakers,59.9,red\n";
+
+        assert!(read_csv_rows(file.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_read_json_lines_rows() {
+        let file = "{\"external_key\":\"sku-1\",\"name\":\"Sneakers\",\"cost\":59.9,\"variants\":{\"size\":[\"40\"]}}\n\
+                    \n\
+                    {\"name\":\"Socks\"}\n";
+
+        let rows = read_json_lines_rows(file.as_bytes()).collect::<Vec<_>>();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].as_ref().unwrap().variants["size"], vec!["40"]);
+        assert_eq!(rows[1].as_ref().unwrap_err().line, 3);
+    }
+
+    #[test]
+    fn test_into_complete_product_validates_row() {
+        let row = |name: &str, cost: f64| ImportRow {
+            name: name.to_string(),
+            cost,
+            ..ImportRow::default()
+        };
+
+        assert!(row(" ", 10.0).into_complete_product().is_err());
+        assert!(row("Sneakers", -1.0).into_complete_product().is_err());
+        assert!(row("Sneakers", f64::NAN).into_complete_product().is_err());
+
+        let complete_product = row("Sneakers", 10.0).into_complete_product().unwrap();
+        assert!(complete_product.product().active());
+    }
+}
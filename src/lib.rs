@@ -3,8 +3,19 @@ extern crate dotenvy;
 extern crate serde;
 extern crate serde_json;
 
+pub mod api;
+pub mod cli;
+pub mod core;
+pub mod datastore;
+pub mod export;
+pub mod grpc;
+pub mod import;
+pub mod outbox;
+pub mod webhooks;
+
 use diesel::pg::PgConnection;
 use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, CustomizeConnection, Pool};
 use dotenvy::dotenv;
 use std::env;
 
@@ -12,12 +23,46 @@ pub fn establish_connection() -> PgConnection {
     dotenv().ok();
 
     let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-    PgConnection::establish(&database_url).expect(&format!("Error connecting to {}", database_url))
+    PgConnection::establish(&database_url).unwrap_or_else(|error| panic!("Error connecting to {}: {}", database_url, error))
+}
+
+pub type DbPool = Pool<ConnectionManager<PgConnection>>;
+
+pub fn create_connection_pool() -> DbPool {
+    dotenv().ok();
+
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    Pool::builder()
+        .build(ConnectionManager::<PgConnection>::new(&database_url))
+        .unwrap_or_else(|error| panic!("Error creating connection pool for {}: {}", database_url, error))
 }
 
 pub fn establish_connection_test() -> PgConnection {
     dotenv().ok();
 
     let database_url = env::var("TEST_DATABASE_URL").expect("DATABASE_URL must be set");
-    PgConnection::establish(&database_url).expect(&format!("Error connecting to {}", database_url))
+    PgConnection::establish(&database_url).unwrap_or_else(|error| panic!("Error connecting to {}: {}", database_url, error))
+}
+
+// keeps every connection of a test pool in a transaction that is never committed
+#[derive(Debug)]
+struct TestTransaction;
+
+impl CustomizeConnection<PgConnection, diesel::r2d2::Error> for TestTransaction {
+    fn on_acquire(&self, connection: &mut PgConnection) -> Result<(), diesel::r2d2::Error> {
+        connection.begin_test_transaction().map_err(diesel::r2d2::Error::QueryError)
+    }
+}
+
+// a pool for tests of code that takes its connections from a pool. It holds a single connection in
+// a test transaction, so what the code writes is seen by the rest of the test and then rolled back
+pub fn create_connection_pool_test() -> DbPool {
+    dotenv().ok();
+
+    let database_url = env::var("TEST_DATABASE_URL").expect("DATABASE_URL must be set");
+    Pool::builder()
+        .max_size(1)
+        .connection_customizer(Box::new(TestTransaction))
+        .build(ConnectionManager::<PgConnection>::new(&database_url))
+        .unwrap_or_else(|error| panic!("Error creating connection pool for {}: {}", database_url, error))
 }
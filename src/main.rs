@@ -1,6 +1,105 @@
-mod core;
-mod datastore;
+use product_store::api;
+use product_store::api::auth::{authenticate, AuthConfig};
+use product_store::api::idempotency::IdempotencyConfig;
+use product_store::api::language::LocaleConfig;
+use product_store::api::rate_limit::{limit_failed_authentications, limit_rate, RateLimitConfig, RateLimiter};
+use product_store::api::tenancy::{resolve_tenant, TenantConfig};
+use product_store::datastore::migrations::ensure_schema_version;
+use product_store::export::merchant_feed::MerchantFeedConfig;
+use product_store::grpc::{spawn_grpc_server, GrpcConfig};
+use product_store::outbox::{spawn_relay, RelayConfig};
+use product_store::webhooks::receivers::ReceiverPolicy;
+use product_store::webhooks::{spawn_webhook_dispatcher, WebhookConfig};
+use actix_web::middleware::{from_fn, Logger};
+use actix_web::{web, App, HttpServer};
+use std::{env, io};
 
-fn main() {
-    println!("Hello, world!");
+// pending migrations are applied on startup when the server is started with `--migrate` or with
+// `RUN_MIGRATIONS` set, otherwise it refuses to start until the schema is migrated
+fn run_migrations_on_startup() -> bool {
+    env::args().skip(1).any(|argument| argument == "--migrate")
+        || env::var("RUN_MIGRATIONS").is_ok_and(|value| matches!(value.as_str(), "true" | "1"))
+}
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    env_logger::init_from_env(env_logger::Env::default().default_filter_or("info"));
+
+    let pool = product_store::create_connection_pool();
+
+    let mut connection = pool.get().map_err(io::Error::other)?;
+    match ensure_schema_version(&mut connection, run_migrations_on_startup()) {
+        Ok(applied_versions) => {
+            for version in applied_versions {
+                log::info!("applied migration {}", version);
+            }
+        }
+        Err(error) => {
+            log::error!("{:#}", error);
+            std::process::exit(1);
+        }
+    }
+    drop(connection);
+
+    let idempotency = web::Data::new(IdempotencyConfig::from_env());
+    let merchant_feed = web::Data::new(MerchantFeedConfig::from_env());
+    let locale = web::Data::new(LocaleConfig::from_env());
+    let tenancy = web::Data::new(TenantConfig::from_env());
+    let receivers = web::Data::new(ReceiverPolicy::from_env());
+    let auth = match AuthConfig::from_env() {
+        Ok(auth) => web::Data::new(auth),
+        Err(error) => {
+            log::error!("{:#}", error);
+            std::process::exit(1);
+        }
+    };
+    let rate_limiter = match RateLimitConfig::from_env() {
+        Ok(config) => web::Data::new(RateLimiter::in_memory(config)),
+        Err(error) => {
+            log::error!("{:#}", error);
+            std::process::exit(1);
+        }
+    };
+    match RelayConfig::from_env().and_then(|config| spawn_relay(pool.clone(), config)) {
+        Ok(Some(_)) => log::info!("relaying catalog events"),
+        Ok(None) => log::info!("no OUTBOX_SINK is set, catalog events are kept in the outbox"),
+        Err(error) => {
+            log::error!("{:#}", error);
+            std::process::exit(1);
+        }
+    }
+    if let Err(error) = spawn_webhook_dispatcher(pool.clone(), WebhookConfig::from_env()) {
+        log::error!("{:#}", error);
+        std::process::exit(1);
+    }
+    match spawn_grpc_server(pool.clone(), auth.get_ref().clone(), tenancy.get_ref().clone(), GrpcConfig::from_env()) {
+        Ok(Some(_)) => log::info!("serving gRPC"),
+        Ok(None) => log::info!("no GRPC_ADDRESS is set, the catalog is not served over gRPC"),
+        Err(error) => {
+            log::error!("{:#}", error);
+            std::process::exit(1);
+        }
+    }
+    let address = env::var("SERVER_ADDRESS").unwrap_or_else(|_| String::from("127.0.0.1:8080"));
+
+    HttpServer::new(move || {
+        App::new()
+            .wrap(from_fn(limit_rate))
+            .wrap(from_fn(resolve_tenant))
+            .wrap(from_fn(authenticate))
+            .wrap(from_fn(limit_failed_authentications))
+            .wrap(Logger::default())
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(idempotency.clone())
+            .app_data(merchant_feed.clone())
+            .app_data(locale.clone())
+            .app_data(auth.clone())
+            .app_data(tenancy.clone())
+            .app_data(receivers.clone())
+            .app_data(rate_limiter.clone())
+            .configure(api::configure)
+    })
+    .bind(address)?
+    .run()
+    .await
 }
@@ -0,0 +1,26 @@
+use crate::core::entities::domain_event::OutboxEvent;
+use crate::outbox::EventSink;
+use anyhow::Result as AnyResult;
+use std::io::Write;
+
+// writes each event as a line of JSON, to stdout or to a file that other services tail
+pub struct JsonLinesSink<W: Write + Send> {
+    writer: W,
+}
+
+impl<W: Write + Send> JsonLinesSink<W> {
+    pub fn new(writer: W) -> JsonLinesSink<W> {
+        JsonLinesSink { writer }
+    }
+}
+
+impl<W: Write + Send> EventSink for JsonLinesSink<W> {
+    // events are flushed one at a time, an event is only marked delivered once it is written out
+    fn deliver(&mut self, event: &OutboxEvent) -> AnyResult<()> {
+        serde_json::to_writer(&mut self.writer, event)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,189 @@
+pub mod json_lines_sink;
+pub mod subscriptions_sink;
+pub mod webhook_sink;
+
+use crate::DbPool;
+use crate::core::entities::domain_event::OutboxEvent;
+use crate::core::entities::webhook::RetryPolicy;
+use crate::core::ports::database::outbox_database::OutboxDatastore;
+use crate::datastore::repositories::outbox_repository::OutboxRepository;
+use crate::outbox::json_lines_sink::JsonLinesSink;
+use crate::outbox::subscriptions_sink::SubscriptionsSink;
+use crate::outbox::webhook_sink::{WebhookSink, REQUEST_TIMEOUT};
+use anyhow::{anyhow, Context, Result as AnyResult};
+use std::env;
+use std::fs::OpenOptions;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+const DEFAULT_BATCH_SIZE: i64 = 100;
+const DEFAULT_MAX_ATTEMPTS: u32 = 10;
+const RETRY_BASE_SECONDS: i64 = 5;
+const MAX_RETRY_DELAY_SECONDS: i64 = 60 * 60;
+const DEFAULT_POLL_INTERVAL_MILLISECONDS: u64 = 1000;
+
+// delivers outbox events to where other services pick them up. An event is delivered again when
+// its delivery fails, and may be delivered again when the relay stops right after delivering it
+pub trait EventSink: Send {
+    fn deliver(&mut self, event: &OutboxEvent) -> AnyResult<()>;
+}
+
+// where events are relayed to: `stdout`, `file:<path>`, the URL of a webhook, or `webhooks` for the
+// webhook subscriptions of each tenant
+#[derive(Debug, Clone, PartialEq)]
+pub enum SinkTarget {
+    Stdout,
+    File(PathBuf),
+    Webhook(String),
+    Subscriptions,
+}
+
+impl SinkTarget {
+    pub fn open(&self, pool: &DbPool) -> AnyResult<Box<dyn EventSink>> {
+        Ok(match self {
+            SinkTarget::Stdout => Box::new(JsonLinesSink::new(io::stdout())),
+            SinkTarget::File(path) => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .with_context(|| format!("Error opening event file {}", path.display()))?;
+
+                Box::new(JsonLinesSink::new(file))
+            }
+            SinkTarget::Webhook(url) => Box::new(WebhookSink::new(url.clone())),
+            SinkTarget::Subscriptions => Box::new(SubscriptionsSink::new(pool.clone())),
+        })
+    }
+}
+
+impl FromStr for SinkTarget {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> AnyResult<Self> {
+        if value == "stdout" {
+            Ok(SinkTarget::Stdout)
+        } else if value == "webhooks" {
+            Ok(SinkTarget::Subscriptions)
+        } else if let Some(path) = value.strip_prefix("file:") {
+            Ok(SinkTarget::File(PathBuf::from(path)))
+        } else if value.starts_with("http://") || value.starts_with("https://") {
+            Ok(SinkTarget::Webhook(value.to_string()))
+        } else {
+            Err(anyhow!(
+                "unknown event sink '{}', sinks are stdout, file:<path>, webhooks or the URL of a webhook",
+                value
+            ))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RelayConfig {
+    // where events are relayed to, nowhere when this is not set and events pile up in the outbox
+    pub sink: Option<SinkTarget>,
+    // how many events are claimed at once
+    pub batch_size: i64,
+    // how long claimed events are left to the relay that claimed them, long enough for every event
+    // of a batch to time out
+    pub lease: chrono::Duration,
+    // when events that could not be delivered are attempted again, and when they are given up on
+    pub retry_policy: RetryPolicy,
+    // how long the relay waits for new events once it has relayed every pending one
+    pub poll_interval: Duration,
+}
+
+impl RelayConfig {
+    pub fn from_env() -> AnyResult<RelayConfig> {
+        let sink = match env::var("OUTBOX_SINK") {
+            Ok(sink) if !sink.is_empty() => Some(sink.parse().context("Invalid OUTBOX_SINK")?),
+            _ => None,
+        };
+
+        let batch_size = env::var("OUTBOX_BATCH_SIZE")
+            .ok()
+            .and_then(|value| value.parse::<i64>().ok())
+            .filter(|batch_size| *batch_size > 0)
+            .unwrap_or(DEFAULT_BATCH_SIZE);
+        let max_attempts = env::var("OUTBOX_MAX_ATTEMPTS")
+            .ok()
+            .and_then(|value| value.parse::<u32>().ok())
+            .filter(|max_attempts| *max_attempts > 0)
+            .unwrap_or(DEFAULT_MAX_ATTEMPTS);
+
+        Ok(RelayConfig {
+            sink,
+            batch_size,
+            lease: chrono::Duration::seconds(batch_size.saturating_mul(REQUEST_TIMEOUT.as_secs() as i64))
+                + chrono::Duration::minutes(1),
+            retry_policy: RetryPolicy::new(
+                max_attempts,
+                chrono::Duration::seconds(RETRY_BASE_SECONDS),
+                chrono::Duration::seconds(MAX_RETRY_DELAY_SECONDS),
+            ),
+            poll_interval: Duration::from_millis(
+                env::var("OUTBOX_POLL_INTERVAL_MS")
+                    .ok()
+                    .and_then(|value| value.parse::<u64>().ok())
+                    .unwrap_or(DEFAULT_POLL_INTERVAL_MILLISECONDS),
+            ),
+        })
+    }
+}
+
+// relays one batch of due events to `sink`, oldest first. Returns the number of events delivered
+pub fn relay_events(
+    datastore: &mut impl OutboxDatastore,
+    sink: &mut dyn EventSink,
+    config: &RelayConfig,
+) -> AnyResult<usize> {
+    datastore.relay_pending_events(config.batch_size, config.lease, &config.retry_policy, &mut |event| {
+        sink.deliver(event).inspect_err(|error| {
+            if config.retry_policy.delay_after(event.attempts() + 1).is_some() {
+                log::warn!(
+                    "Delivering event {} failed after {} earlier attempts: {:#}",
+                    event.event_id(),
+                    event.attempts(),
+                    error
+                )
+            } else {
+                log::error!(
+                    "Giving up on event {} after {} attempts: {:#}",
+                    event.event_id(),
+                    event.attempts() + 1,
+                    error
+                )
+            }
+        })
+    })
+}
+
+// relays events to the configured sink until the process exits, on a thread of its own. Full
+// batches are followed by the next one right away, anything else waits for the poll interval
+pub fn spawn_relay(pool: DbPool, config: RelayConfig) -> AnyResult<Option<thread::JoinHandle<()>>> {
+    let Some(target) = &config.sink else {
+        return Ok(None);
+    };
+    let mut sink = target.open(&pool)?;
+
+    let relay = thread::Builder::new().name("outbox-relay".to_string()).spawn(move || loop {
+        let relayed = pool
+            .get()
+            .map_err(anyhow::Error::from)
+            .and_then(|mut connection| {
+                relay_events(&mut OutboxRepository::new(&mut connection), sink.as_mut(), &config)
+            });
+
+        match relayed {
+            Ok(delivered) if delivered as i64 == config.batch_size => continue,
+            Ok(_) => {}
+            Err(error) => log::error!("Relaying events failed: {:#}", error),
+        }
+        thread::sleep(config.poll_interval);
+    })?;
+
+    Ok(Some(relay))
+}
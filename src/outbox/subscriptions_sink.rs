@@ -0,0 +1,32 @@
+use crate::DbPool;
+use crate::core::entities::domain_event::OutboxEvent;
+use crate::core::ports::database::webhook_database::WebhookDatastore;
+use crate::datastore::repositories::webhook_repository::WebhookRepository;
+use crate::datastore::tenancy::set_current_tenant;
+use crate::outbox::EventSink;
+use anyhow::Result as AnyResult;
+
+// hands each event to the webhook subscriptions of its tenant that want it, as pending deliveries
+// that the webhook dispatcher posts. The event is delivered once its deliveries are written, so a
+// subscriber that is down holds up only its own deliveries rather than the outbox
+pub struct SubscriptionsSink {
+    pool: DbPool,
+}
+
+impl SubscriptionsSink {
+    pub fn new(pool: DbPool) -> SubscriptionsSink {
+        SubscriptionsSink { pool }
+    }
+}
+
+impl EventSink for SubscriptionsSink {
+    fn deliver(&mut self, event: &OutboxEvent) -> AnyResult<()> {
+        let mut connection = self.pool.get()?;
+        set_current_tenant(&mut connection, event.tenant())?;
+
+        let enqueued = WebhookRepository::new(&mut connection).enqueue_webhook_deliveries(event)?;
+        log::debug!("Event {} is on its way to {} subscriptions", event.event_id(), enqueued);
+
+        Ok(())
+    }
+}
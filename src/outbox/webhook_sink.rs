@@ -0,0 +1,44 @@
+use crate::core::entities::domain_event::OutboxEvent;
+use crate::outbox::EventSink;
+use anyhow::{anyhow, Result as AnyResult};
+use std::time::Duration;
+
+pub const EVENT_ID_HEADER: &str = "X-Event-ID";
+pub const EVENT_TYPE_HEADER: &str = "X-Event-Type";
+
+pub(crate) const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// posts each event as JSON to a URL. Any response other than a 2xx counts as a failed delivery.
+// The event ID is sent in a header as well, for receivers that drop duplicates before reading the
+// body
+pub struct WebhookSink {
+    url: String,
+    agent: ureq::Agent,
+}
+
+impl WebhookSink {
+    pub fn new(url: String) -> WebhookSink {
+        WebhookSink {
+            url,
+            agent: ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).redirects(0).build(),
+        }
+    }
+}
+
+impl EventSink for WebhookSink {
+    fn deliver(&mut self, event: &OutboxEvent) -> AnyResult<()> {
+        let response = self
+            .agent
+            .post(&self.url)
+            .set(EVENT_ID_HEADER, &event.event_id().to_string())
+            .set(EVENT_TYPE_HEADER, event.event().event_type())
+            .send_json(event);
+
+        match response {
+            Ok(response) if (200..300).contains(&response.status()) => Ok(()),
+            Ok(response) => Err(anyhow!("{} answered {}", self.url, response.status())),
+            Err(ureq::Error::Status(status, _)) => Err(anyhow!("{} answered {}", self.url, status)),
+            Err(error) => Err(anyhow!("Error posting to {}: {}", self.url, error)),
+        }
+    }
+}
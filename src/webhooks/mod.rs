@@ -0,0 +1,295 @@
+pub mod receivers;
+
+use crate::DbPool;
+use crate::core::entities::webhook::{
+    sign_webhook_payload, DeliveryAttempt, RetryPolicy, WebhookDelivery, WebhookSubscription,
+};
+use crate::core::ports::database::webhook_database::WebhookDatastore;
+use crate::datastore::repositories::webhook_repository::WebhookRepository;
+use crate::outbox::webhook_sink::{EVENT_ID_HEADER, EVENT_TYPE_HEADER};
+use crate::webhooks::receivers::ReceiverPolicy;
+use anyhow::Result as AnyResult;
+use chrono::Utc;
+use std::env;
+use std::thread;
+use std::time::Duration;
+
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+pub const DELIVERY_ID_HEADER: &str = "X-Webhook-Delivery";
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 8;
+const DEFAULT_RETRY_BASE_SECONDS: i64 = 30;
+const MAX_RETRY_DELAY_SECONDS: i64 = 60 * 60;
+const DEFAULT_BATCH_SIZE: i64 = 50;
+const DEFAULT_POLL_INTERVAL_MILLISECONDS: u64 = 1000;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    pub retry_policy: RetryPolicy,
+    // how many deliveries are claimed at once
+    pub batch_size: i64,
+    // how long claimed deliveries are left to the dispatcher that claimed them, long enough for
+    // every delivery of a batch to time out
+    pub lease: chrono::Duration,
+    // how long the dispatcher waits once no delivery is due
+    pub poll_interval: Duration,
+    pub receiver_policy: ReceiverPolicy,
+}
+
+impl WebhookConfig {
+    pub fn from_env() -> WebhookConfig {
+        let max_attempts = env::var("WEBHOOK_MAX_ATTEMPTS")
+            .ok()
+            .and_then(|value| value.parse::<u32>().ok())
+            .filter(|max_attempts| *max_attempts > 0)
+            .unwrap_or(DEFAULT_MAX_ATTEMPTS);
+        let retry_base_seconds = env::var("WEBHOOK_RETRY_BASE_SECONDS")
+            .ok()
+            .and_then(|value| value.parse::<i64>().ok())
+            .filter(|seconds| *seconds >= 0)
+            .unwrap_or(DEFAULT_RETRY_BASE_SECONDS);
+
+        WebhookConfig {
+            retry_policy: RetryPolicy::new(
+                max_attempts,
+                chrono::Duration::seconds(retry_base_seconds),
+                chrono::Duration::seconds(MAX_RETRY_DELAY_SECONDS.max(retry_base_seconds)),
+            ),
+            batch_size: DEFAULT_BATCH_SIZE,
+            lease: chrono::Duration::seconds(DEFAULT_BATCH_SIZE * REQUEST_TIMEOUT.as_secs() as i64)
+                + chrono::Duration::minutes(1),
+            poll_interval: Duration::from_millis(
+                env::var("WEBHOOK_POLL_INTERVAL_MS")
+                    .ok()
+                    .and_then(|value| value.parse::<u64>().ok())
+                    .unwrap_or(DEFAULT_POLL_INTERVAL_MILLISECONDS),
+            ),
+            receiver_policy: ReceiverPolicy::from_env(),
+        }
+    }
+}
+
+// receivers are resolved with `receiver_policy` as they are posted to, not only when their subscription is checked
+pub fn webhook_agent(receiver_policy: &ReceiverPolicy) -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout(REQUEST_TIMEOUT)
+        .redirects(0)
+        .resolver(receiver_policy.clone())
+        .build()
+}
+
+// posts a delivery's event to its subscription, signed with the subscription's secret. Any response
+// other than a 2xx counts as a failed attempt
+pub fn deliver_webhook(
+    agent: &ureq::Agent,
+    subscription: &WebhookSubscription,
+    delivery: &WebhookDelivery,
+) -> DeliveryAttempt {
+    let body = delivery.payload().to_string();
+    let signature = sign_webhook_payload(subscription.secret(), Utc::now().timestamp(), body.as_bytes());
+
+    let response = agent
+        .post(subscription.url())
+        .set("Content-Type", "application/json")
+        .set(SIGNATURE_HEADER, &signature)
+        .set(DELIVERY_ID_HEADER, &delivery.id().to_string())
+        .set(EVENT_ID_HEADER, &delivery.event_id().to_string())
+        .set(EVENT_TYPE_HEADER, delivery.event_type())
+        .send_string(&body);
+
+    match response {
+        Ok(response) if (200..300).contains(&response.status()) => DeliveryAttempt::Delivered {
+            response_status: response.status(),
+        },
+        Ok(response) => DeliveryAttempt::Failed {
+            response_status: Some(response.status()),
+            error: format!("{} answered {}", subscription.url(), response.status()),
+        },
+        Err(ureq::Error::Status(status, _)) => DeliveryAttempt::Failed {
+            response_status: Some(status),
+            error: format!("{} answered {}", subscription.url(), status),
+        },
+        Err(error) => DeliveryAttempt::Failed {
+            response_status: None,
+            error: format!("Error posting to {}: {}", subscription.url(), error),
+        },
+    }
+}
+
+// attempts one batch of due deliveries. Returns the number of deliveries attempted
+pub fn dispatch_webhooks(
+    datastore: &mut impl WebhookDatastore,
+    agent: &ureq::Agent,
+    config: &WebhookConfig,
+) -> AnyResult<usize> {
+    datastore.dispatch_due_webhook_deliveries(
+        config.batch_size,
+        config.lease,
+        &config.retry_policy,
+        &mut |subscription, delivery| {
+            let attempt = deliver_webhook(agent, subscription, delivery);
+            if let DeliveryAttempt::Failed { error, .. } = &attempt {
+                log::warn!(
+                    "Delivering {} to webhook subscription {} failed after {} earlier attempts: {}",
+                    delivery.event_id(),
+                    subscription.id(),
+                    delivery.attempts(),
+                    error
+                );
+            }
+            attempt
+        },
+    )
+}
+
+// posts due webhook deliveries until the process exits, on a thread of its own. Full batches are
+// followed by the next one right away, anything else waits for the poll interval
+pub fn spawn_webhook_dispatcher(pool: DbPool, config: WebhookConfig) -> AnyResult<thread::JoinHandle<()>> {
+    let agent = webhook_agent(&config.receiver_policy);
+
+    Ok(thread::Builder::new().name("webhook-dispatcher".to_string()).spawn(move || loop {
+        let dispatched = pool
+            .get()
+            .map_err(anyhow::Error::from)
+            .and_then(|mut connection| dispatch_webhooks(&mut WebhookRepository::new(&mut connection), &agent, &config));
+
+        match dispatched {
+            Ok(attempted) if attempted as i64 == config.batch_size => continue,
+            Ok(_) => {}
+            Err(error) => log::error!("Dispatching webhooks failed: {:#}", error),
+        }
+        thread::sleep(config.poll_interval);
+    })?)
+}
+
+#[cfg(test)]
+mod webhooks_tests {
+    use crate::core::entities::ids::WebhookSubscriptionId;
+    use crate::core::entities::webhook::{
+        sign_webhook_payload, DeliveryAttempt, DeliveryStatus, WebhookDelivery, WebhookSubscription,
+    };
+    use crate::webhooks::receivers::ReceiverPolicy;
+    use crate::webhooks::{deliver_webhook, webhook_agent};
+    use chrono::Utc;
+    use serde_json::json;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use uuid::Uuid;
+
+    // answers a single request with `status` and hands back its headers and body
+    fn mock_receiver(status: &'static str) -> (String, thread::JoinHandle<(Vec<String>, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Error binding mock receiver");
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+
+        let receiver = thread::spawn(move || {
+            let (stream, _) = listener.accept().expect("Error accepting request");
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut headers = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim_end().is_empty() {
+                    break;
+                }
+                headers.push(line.trim_end().to_lowercase());
+            }
+            let content_length = headers
+                .iter()
+                .find_map(|header| header.strip_prefix("content-length: "))
+                .map_or(0, |length| length.parse::<usize>().unwrap());
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+
+            let mut stream = stream;
+            write!(stream, "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status).unwrap();
+
+            (headers, String::from_utf8(body).unwrap())
+        });
+
+        (url, receiver)
+    }
+
+    fn delivery_to(url: String) -> (WebhookSubscription, WebhookDelivery) {
+        let subscription_id = WebhookSubscriptionId::try_from(1).unwrap();
+        let subscription = WebhookSubscription::new(
+            subscription_id,
+            url,
+            Vec::new(),
+            "whsec_test".to_string(),
+            true,
+            Utc::now(),
+        );
+        let delivery = WebhookDelivery::new(
+            7,
+            subscription_id,
+            Uuid::now_v7(),
+            "PriceChanged".to_string(),
+            json!({"type": "PriceChanged", "old_cost": 13.23, "new_cost": 11.0}),
+            DeliveryStatus::Pending,
+            0,
+            Utc::now(),
+            None,
+            None,
+            None,
+            None,
+            Utc::now(),
+        );
+
+        (subscription, delivery)
+    }
+
+    #[test]
+    fn test_deliveries_are_posted_with_a_signature_receivers_can_check() {
+        let (url, receiver) = mock_receiver("204 No Content");
+        let (subscription, delivery) = delivery_to(url);
+
+        let agent = webhook_agent(&ReceiverPolicy::allowing(&["127.0.0.1"]));
+        let attempt = deliver_webhook(&agent, &subscription, &delivery);
+        let (headers, body) = receiver.join().unwrap();
+
+        assert_eq!(DeliveryAttempt::Delivered { response_status: 204 }, attempt);
+        assert_eq!(delivery.payload(), &serde_json::from_str::<serde_json::Value>(&body).unwrap());
+        assert!(headers.contains(&"x-event-type: pricechanged".to_string()));
+        assert!(headers.contains(&"x-webhook-delivery: 7".to_string()));
+
+        // the receiver signs the timestamp and body it got with the secret it shares with us
+        let signature = headers
+            .iter()
+            .find_map(|header| header.strip_prefix("x-webhook-signature: "))
+            .expect("The delivery was not signed");
+        let timestamp = signature
+            .strip_prefix("t=")
+            .and_then(|signature| signature.split_once(','))
+            .map(|(timestamp, _)| timestamp.parse::<i64>().unwrap())
+            .unwrap();
+        assert_eq!(sign_webhook_payload("whsec_test", timestamp, body.as_bytes()), signature);
+    }
+
+    #[test]
+    fn test_deliveries_that_are_not_answered_with_a_2xx_fail() {
+        let (url, receiver) = mock_receiver("503 Service Unavailable");
+        let (subscription, delivery) = delivery_to(url);
+
+        let agent = webhook_agent(&ReceiverPolicy::allowing(&["127.0.0.1"]));
+        let attempt = deliver_webhook(&agent, &subscription, &delivery);
+        receiver.join().unwrap();
+
+        assert!(matches!(attempt, DeliveryAttempt::Failed { response_status: Some(503), .. }));
+    }
+
+    #[test]
+    fn test_deliveries_to_internal_addresses_fail_without_being_sent() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let (subscription, delivery) = delivery_to(format!("http://{}/hooks", listener.local_addr().unwrap()));
+
+        let attempt = deliver_webhook(&webhook_agent(&ReceiverPolicy::default()), &subscription, &delivery);
+
+        assert!(matches!(
+            attempt,
+            DeliveryAttempt::Failed { response_status: None, error } if error.contains("WEBHOOK_ALLOWED_HOSTS")
+        ));
+    }
+}
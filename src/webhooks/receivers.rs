@@ -0,0 +1,194 @@
+use anyhow::{anyhow, bail, Result as AnyResult};
+use std::env;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+
+// where webhook receivers may be. Admins of any tenant choose receiver URLs, so the server must not
+// post to itself, to the cloud metadata service at 169.254.169.254 or to anything else on its own
+// network. Hosts resolving to a loopback, private, link-local or otherwise non-public address are
+// refused unless they are listed in `WEBHOOK_ALLOWED_HOSTS`
+#[derive(Debug, Clone, Default)]
+pub struct ReceiverPolicy {
+    allowed_hosts: Vec<String>,
+}
+
+impl ReceiverPolicy {
+    pub fn from_env() -> ReceiverPolicy {
+        let allowed_hosts = env::var("WEBHOOK_ALLOWED_HOSTS").unwrap_or_default();
+
+        ReceiverPolicy::allowing(&allowed_hosts.split(',').collect::<Vec<_>>())
+    }
+
+    // a policy that also lets receivers be on `hosts`, wherever they resolve to
+    pub fn allowing(hosts: &[&str]) -> ReceiverPolicy {
+        ReceiverPolicy {
+            allowed_hosts: hosts
+                .iter()
+                .map(|host| host.trim().trim_start_matches('[').trim_end_matches(']').to_lowercase())
+                .filter(|host| !host.is_empty())
+                .collect(),
+        }
+    }
+
+    fn allows_host(&self, host: &str) -> bool {
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        self.allowed_hosts.iter().any(|allowed_host| allowed_host.eq_ignore_ascii_case(host))
+    }
+
+    // checks a receiver URL as a subscription is created or changed. This resolves its host, so
+    // it blocks
+    pub fn check_url(&self, url: &str) -> AnyResult<()> {
+        let request_url = ureq::post(url).request_url().map_err(|_| anyhow!("'{}' is not a URL", url))?;
+        let default_port = match request_url.scheme() {
+            "http" => 80,
+            "https" => 443,
+            _ => bail!("url must be an http or https URL, not '{}'", url),
+        };
+        let netloc = format!("{}:{}", request_url.host(), request_url.port().unwrap_or(default_port));
+
+        ureq::Resolver::resolve(self, &netloc).map(|_| ()).map_err(|error| anyhow!("url '{}' {}", url, error))
+    }
+}
+
+// the addresses of `host:port` that webhooks may be posted to. The dispatcher's agent resolves with
+// this as well, so a host that resolves elsewhere once its subscription was checked is refused too
+impl ureq::Resolver for ReceiverPolicy {
+    fn resolve(&self, netloc: &str) -> io::Result<Vec<SocketAddr>> {
+        let host = netloc.rsplit_once(':').map_or(netloc, |(host, _)| host);
+        let addresses = netloc
+            .to_socket_addrs()
+            .map_err(|error| io::Error::new(error.kind(), format!("cannot be resolved: {}", error)))?
+            .collect::<Vec<_>>();
+        if self.allows_host(host) {
+            return Ok(addresses);
+        }
+
+        let public_addresses = addresses
+            .into_iter()
+            .filter(|address| is_public_address(address.ip()))
+            .collect::<Vec<_>>();
+        if public_addresses.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!(
+                    "points to a loopback, private or otherwise internal address, which webhooks are not sent to \
+                     unless {} is in WEBHOOK_ALLOWED_HOSTS",
+                    host
+                ),
+            ));
+        }
+
+        Ok(public_addresses)
+    }
+}
+
+fn is_public_address(address: IpAddr) -> bool {
+    match address {
+        IpAddr::V4(address) => is_public_ipv4_address(address),
+        IpAddr::V6(address) => is_public_ipv6_address(address),
+    }
+}
+
+fn is_public_ipv4_address(address: Ipv4Addr) -> bool {
+    let [first, second, third, _] = address.octets();
+
+    !(address.is_unspecified()
+        || address.is_loopback()
+        || address.is_private()
+        || address.is_link_local()
+        || address.is_broadcast()
+        || address.is_documentation()
+        || address.is_multicast()
+        // "this network", shared address space for carrier-grade NAT, IETF protocol assignments,
+        // benchmarking and reserved
+        || first == 0
+        || (first == 100 && (64..128).contains(&second))
+        || (first == 192 && second == 0 && third == 0)
+        || (first == 198 && (18..20).contains(&second))
+        || first >= 240)
+}
+
+fn is_public_ipv6_address(address: Ipv6Addr) -> bool {
+    // IPv4-mapped (::ffff:0:0/96) and NAT64 (64:ff9b::/96) addresses reach the IPv4 address they embed
+    if let Some(mapped_address) = address.to_ipv4_mapped() {
+        return is_public_ipv4_address(mapped_address);
+    }
+    let segments = address.segments();
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        let [_, _, _, _, _, _, _, _, _, _, _, _, a, b, c, d] = address.octets();
+        return is_public_ipv4_address(Ipv4Addr::new(a, b, c, d));
+    }
+
+    !(address.is_unspecified()
+        || address.is_loopback()
+        || address.is_multicast()
+        // unique local (fc00::/7), link-local (fe80::/10) and documentation (2001:db8::/32)
+        || (segments[0] & 0xfe00) == 0xfc00
+        || (segments[0] & 0xffc0) == 0xfe80
+        || (segments[0] == 0x2001 && segments[1] == 0x0db8))
+}
+
+#[cfg(test)]
+mod receivers_tests {
+    use crate::webhooks::receivers::{is_public_address, ReceiverPolicy};
+    use std::net::{IpAddr, SocketAddr};
+    use ureq::Resolver;
+
+    #[test]
+    fn test_only_public_addresses_are_public() {
+        for address in ["93.184.215.14", "8.8.8.8", "2606:4700::1111", "::ffff:8.8.8.8"] {
+            assert!(is_public_address(address.parse::<IpAddr>().unwrap()), "{}", address);
+        }
+        for address in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "255.255.255.255",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "64:ff9b::a9fe:a9fe",
+        ] {
+            assert!(!is_public_address(address.parse::<IpAddr>().unwrap()), "{}", address);
+        }
+    }
+
+    #[test]
+    fn test_receivers_on_internal_addresses_are_refused() {
+        let policy = ReceiverPolicy::default();
+
+        for url in [
+            "http://127.0.0.1:8080/hooks",
+            "http://169.254.169.254/latest/meta-data/",
+            "https://10.0.0.5/hooks",
+            "http://[::1]/hooks",
+            "http://localhost/hooks",
+        ] {
+            let error = policy.check_url(url).unwrap_err().to_string();
+            assert!(error.contains("WEBHOOK_ALLOWED_HOSTS"), "{}: {}", url, error);
+        }
+        assert!(policy.check_url("ftp://93.184.215.14/hooks").is_err());
+        assert!(policy.check_url("not a url").is_err());
+        assert!(policy.check_url("https://93.184.215.14/hooks").is_ok());
+    }
+
+    #[test]
+    fn test_allowed_hosts_may_be_internal() {
+        let policy = ReceiverPolicy::allowing(&[" 127.0.0.1", "", "[::1]", "Localhost"]);
+
+        assert!(policy.check_url("http://127.0.0.1:8080/hooks").is_ok());
+        assert!(policy.check_url("http://[::1]:8080/hooks").is_ok());
+        assert!(policy.check_url("http://localhost/hooks").is_ok());
+        assert!(policy.check_url("http://10.0.0.5/hooks").is_err());
+
+        let address = "127.0.0.1:80".parse::<SocketAddr>().unwrap();
+        assert_eq!(vec![address], policy.resolve("127.0.0.1:80").unwrap());
+        assert!(ReceiverPolicy::default().resolve("127.0.0.1:80").is_err());
+    }
+}